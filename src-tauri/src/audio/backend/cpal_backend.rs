@@ -0,0 +1,392 @@
+//! cpal implementation of [`AudioBackend`]
+//!
+//! Streams are opened with the device's default config. Output streams asking for
+//! [`StreamLatency::Low`] try fixed buffer sizes (256 → 512 → 1024 samples) before
+//! falling back to the device default. F32, I16 and U16 devices are supported; the
+//! callbacks always see f32.
+
+use std::sync::{Arc, Mutex};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{BufferSize, Device, SampleFormat, StreamConfig};
+use tracing::{debug, trace, warn};
+
+use super::{
+    AudioBackend, BackendDevice, BackendStream, CaptureCallback, ErrorCallback, RenderCallback,
+    StreamFormat, StreamLatency,
+};
+use crate::audio::AudioError;
+
+/// Preferred buffer size for low-latency playback.
+/// 256 samples @ 48kHz = ~5.3ms latency per buffer.
+const PREFERRED_BUFFER_SIZE: u32 = 256;
+
+/// Buffer size options for fallback strategy
+const FALLBACK_BUFFER_SIZES: [u32; 3] = [256, 512, 1024];
+
+/// Device sample types the backend converts to and from f32
+trait DeviceSample: Copy {
+    fn to_f32(self) -> f32;
+    fn from_f32(value: f32) -> Self;
+}
+
+impl DeviceSample for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl DeviceSample for i16 {
+    fn to_f32(self) -> f32 {
+        self as f32 / 32768.0
+    }
+
+    fn from_f32(value: f32) -> Self {
+        (value.clamp(-1.0, 1.0) * 32767.0) as i16
+    }
+}
+
+impl DeviceSample for u16 {
+    fn to_f32(self) -> f32 {
+        (self as f32 - 32768.0) / 32768.0
+    }
+
+    fn from_f32(value: f32) -> Self {
+        ((value.clamp(-1.0, 1.0) + 1.0) * 32767.5) as u16
+    }
+}
+
+/// Backend for the default cpal host
+pub struct CpalBackend;
+
+impl CpalBackend {
+    fn output_device(&self, index: usize) -> Result<Device, AudioError> {
+        cpal::default_host()
+            .output_devices()
+            .map_err(|e| AudioError::DeviceEnumeration(e.to_string()))?
+            .nth(index)
+            .ok_or_else(|| AudioError::DeviceNotFound(format!("output {}", index)))
+    }
+
+    fn input_device(&self, index: usize) -> Result<Device, AudioError> {
+        cpal::default_host()
+            .input_devices()
+            .map_err(|e| AudioError::DeviceEnumeration(e.to_string()))?
+            .nth(index)
+            .ok_or_else(|| AudioError::DeviceNotFound(format!("input {}", index)))
+    }
+}
+
+/// Collect device names, marking the one called `default_name`
+///
+/// Devices whose name can't be read keep their slot so indices stay aligned.
+fn list_devices(
+    devices: impl Iterator<Item = Device>,
+    default_name: Option<String>,
+) -> Vec<BackendDevice> {
+    devices
+        .map(|device| {
+            let name = device.name().unwrap_or_default();
+            BackendDevice {
+                is_default: !name.is_empty() && Some(&name) == default_name.as_ref(),
+                name,
+            }
+        })
+        .collect()
+}
+
+fn format_of(config: &cpal::SupportedStreamConfig) -> StreamFormat {
+    StreamFormat {
+        sample_rate: config.sample_rate().0,
+        channels: config.channels(),
+    }
+}
+
+impl AudioBackend for CpalBackend {
+    fn output_devices(&self) -> Result<Vec<BackendDevice>, AudioError> {
+        let host = cpal::default_host();
+        let default_name = host.default_output_device().and_then(|d| d.name().ok());
+        let devices = host
+            .output_devices()
+            .map_err(|e| AudioError::DeviceEnumeration(e.to_string()))?;
+        Ok(list_devices(devices, default_name))
+    }
+
+    fn input_devices(&self) -> Result<Vec<BackendDevice>, AudioError> {
+        let host = cpal::default_host();
+        let default_name = host.default_input_device().and_then(|d| d.name().ok());
+        let devices = host
+            .input_devices()
+            .map_err(|e| AudioError::DeviceEnumeration(e.to_string()))?;
+        Ok(list_devices(devices, default_name))
+    }
+
+    fn output_format(&self, index: usize) -> Result<StreamFormat, AudioError> {
+        let config = self
+            .output_device(index)?
+            .default_output_config()
+            .map_err(|e| AudioError::DeviceConfig(e.to_string()))?;
+        Ok(format_of(&config))
+    }
+
+    fn input_format(&self, index: usize) -> Result<StreamFormat, AudioError> {
+        let config = self
+            .input_device(index)?
+            .default_input_config()
+            .map_err(|e| AudioError::DeviceConfig(e.to_string()))?;
+        Ok(format_of(&config))
+    }
+
+    fn open_output(
+        &self,
+        index: usize,
+        latency: StreamLatency,
+        render: RenderCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError> {
+        let device = self.output_device(index)?;
+        let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+        let config = device
+            .default_output_config()
+            .map_err(|e| AudioError::DeviceConfig(e.to_string()))?;
+
+        // Shared so a failed build attempt doesn't lose the callback
+        let render = Arc::new(Mutex::new(render));
+        let (stream, buffer_size) = match latency {
+            StreamLatency::Low => build_output_with_fallback(&device, &config, &render, &on_error)?,
+            StreamLatency::Default => (
+                build_output(&device, &config, config.config(), &render, &on_error)?,
+                "Default".to_string(),
+            ),
+        };
+
+        stream
+            .play()
+            .map_err(|e| AudioError::StreamStart(e.to_string()))?;
+        debug!(
+            device = %device_name,
+            sample_rate = config.sample_rate().0,
+            channels = config.channels(),
+            sample_format = ?config.sample_format(),
+            buffer_size = %buffer_size,
+            "Output stream started"
+        );
+        Ok(BackendStream::new(stream))
+    }
+
+    fn open_input(
+        &self,
+        index: usize,
+        on_data: CaptureCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError> {
+        let device = self.input_device(index)?;
+        let config = device
+            .default_input_config()
+            .map_err(|e| AudioError::DeviceConfig(e.to_string()))?;
+        open_capture(&device, &config, on_data, on_error)
+    }
+
+    fn open_loopback(
+        &self,
+        index: usize,
+        on_data: CaptureCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError> {
+        // WASAPI captures an output device when an input stream is built on it;
+        // other hosts have no loopback
+        if !cfg!(windows) {
+            return Err(AudioError::StreamBuild(
+                "Recording an output device is only supported on Windows".to_string(),
+            ));
+        }
+        let device = self.output_device(index)?;
+        let config = device
+            .default_output_config()
+            .map_err(|e| AudioError::DeviceConfig(e.to_string()))?;
+        open_capture(&device, &config, on_data, on_error)
+    }
+}
+
+/// Build and start a capture stream in `config`'s sample format
+fn open_capture(
+    device: &Device,
+    config: &cpal::SupportedStreamConfig,
+    on_data: CaptureCallback,
+    on_error: ErrorCallback,
+) -> Result<BackendStream, AudioError> {
+    let stream_config = config.config();
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build_input::<f32>(device, &stream_config, on_data, on_error),
+        SampleFormat::I16 => build_input::<i16>(device, &stream_config, on_data, on_error),
+        SampleFormat::U16 => build_input::<u16>(device, &stream_config, on_data, on_error),
+        _ => return Err(AudioError::UnsupportedFormat),
+    }?;
+
+    stream
+        .play()
+        .map_err(|e| AudioError::StreamStart(e.to_string()))?;
+    Ok(BackendStream::new(stream))
+}
+
+/// Build an output stream with fallback to larger buffer sizes or default config.
+///
+/// Attempts to create a low-latency audio stream by trying multiple buffer sizes
+/// in sequence: 256 → 512 → 1024 samples. If all fixed buffer sizes fail, falls
+/// back to the device's default configuration.
+///
+/// # Returns
+///
+/// Returns a tuple of (Stream, buffer_size_description) on success, or AudioError
+/// if all attempts fail.
+///
+/// # Logging
+///
+/// - Warns if using a fallback buffer size larger than preferred
+/// - Warns if falling back to device default configuration
+fn build_output_with_fallback(
+    device: &Device,
+    config: &cpal::SupportedStreamConfig,
+    render: &Arc<Mutex<RenderCallback>>,
+    on_error: &ErrorCallback,
+) -> Result<(cpal::Stream, String), AudioError> {
+    // Try each buffer size in order
+    for &buffer_size in &FALLBACK_BUFFER_SIZES {
+        let stream_config = StreamConfig {
+            channels: config.channels(),
+            sample_rate: config.sample_rate(),
+            buffer_size: BufferSize::Fixed(buffer_size),
+        };
+
+        match build_output(device, config, stream_config, render, on_error) {
+            Ok(stream) => {
+                if buffer_size != PREFERRED_BUFFER_SIZE {
+                    warn!(
+                        buffer_size = buffer_size,
+                        preferred = PREFERRED_BUFFER_SIZE,
+                        "Using fallback buffer size (preferred size not supported by device)"
+                    );
+                }
+                return Ok((stream, format!("Fixed({})", buffer_size)));
+            }
+            Err(e) => {
+                debug!(
+                    buffer_size = buffer_size,
+                    error = %e,
+                    "Failed to create stream with buffer size, trying next fallback"
+                );
+                continue;
+            }
+        }
+    }
+
+    // Final fallback: use default config
+    warn!("Fixed buffer sizes failed, using device default");
+    let stream = build_output(device, config, config.config(), render, on_error)?;
+
+    Ok((stream, "Default".to_string()))
+}
+
+/// Try to build an output stream in the device's sample format
+fn build_output(
+    device: &Device,
+    config: &cpal::SupportedStreamConfig,
+    stream_config: StreamConfig,
+    render: &Arc<Mutex<RenderCallback>>,
+    on_error: &ErrorCallback,
+) -> Result<cpal::Stream, AudioError> {
+    trace!(
+        sample_format = ?config.sample_format(),
+        buffer_size = ?stream_config.buffer_size,
+        sample_rate = stream_config.sample_rate.0,
+        channels = stream_config.channels,
+        "Attempting stream build"
+    );
+
+    let render = render.clone();
+    let on_error = on_error.clone();
+    match config.sample_format() {
+        SampleFormat::F32 => build_output_typed::<f32>(device, &stream_config, render, on_error),
+        SampleFormat::I16 => build_output_typed::<i16>(device, &stream_config, render, on_error),
+        SampleFormat::U16 => build_output_typed::<u16>(device, &stream_config, render, on_error),
+        _ => Err(AudioError::UnsupportedFormat),
+    }
+}
+
+fn build_output_typed<T>(
+    device: &Device,
+    config: &StreamConfig,
+    render: Arc<Mutex<RenderCallback>>,
+    on_error: ErrorCallback,
+) -> Result<cpal::Stream, AudioError>
+where
+    T: cpal::SizedSample + DeviceSample,
+{
+    // Integer formats are converted after mixing and limiting
+    let mut scratch: Vec<f32> = Vec::new();
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                scratch.resize(data.len(), 0.0);
+                (render.lock().unwrap())(&mut scratch);
+                for (out, &value) in data.iter_mut().zip(scratch.iter()) {
+                    *out = T::from_f32(value);
+                }
+            },
+            move |err| on_error(err.to_string()),
+            None,
+        )
+        .map_err(|e| AudioError::StreamBuild(e.to_string()))
+}
+
+fn build_input<T>(
+    device: &Device,
+    config: &StreamConfig,
+    mut on_data: CaptureCallback,
+    on_error: ErrorCallback,
+) -> Result<cpal::Stream, AudioError>
+where
+    T: cpal::SizedSample + DeviceSample,
+{
+    let mut samples: Vec<f32> = Vec::new();
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                samples.clear();
+                samples.extend(data.iter().map(|&s| s.to_f32()));
+                on_data(&samples);
+            },
+            move |err| on_error(err.to_string()),
+            None,
+        )
+        .map_err(|e| AudioError::StreamBuild(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_formats_round_trip() {
+        assert_eq!(i16::MIN.to_f32(), -1.0);
+        assert!((i16::MAX.to_f32() - 1.0).abs() < 1e-4);
+        assert_eq!(32768u16.to_f32(), 0.0);
+        assert_eq!(0u16.to_f32(), -1.0);
+
+        for &value in &[-1.0f32, -0.5, 0.0, 0.25, 1.0] {
+            assert!((i16::from_f32(value).to_f32() - value).abs() < 1e-3);
+            assert!((u16::from_f32(value).to_f32() - value).abs() < 1e-3);
+            assert_eq!(f32::from_f32(value), value);
+        }
+        // Out-of-range values clamp instead of wrapping
+        assert_eq!(i16::from_f32(2.0), i16::MAX);
+        assert_eq!(u16::from_f32(-2.0), 0);
+    }
+}
@@ -0,0 +1,353 @@
+//! In-memory [`AudioBackend`] for tests
+//!
+//! Nothing runs on its own: tests drive output streams with
+//! [`FakeBackend::render_output`] and feed input streams with
+//! [`FakeBackend::push_input`]. Everything rendered is kept so tests can inspect
+//! what a device would have played, and is also delivered to loopback streams
+//! open on that device.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{
+    AudioBackend, BackendDevice, BackendStream, CaptureCallback, ErrorCallback, RenderCallback,
+    StreamFormat, StreamLatency,
+};
+use crate::audio::AudioError;
+
+struct FakeDevice {
+    name: String,
+    format: StreamFormat,
+}
+
+struct OutputStream {
+    id: u64,
+    render: RenderCallback,
+    on_error: ErrorCallback,
+}
+
+struct InputStream {
+    id: u64,
+    on_data: CaptureCallback,
+    on_error: ErrorCallback,
+}
+
+#[derive(Default)]
+struct State {
+    outputs: Vec<FakeDevice>,
+    inputs: Vec<FakeDevice>,
+    output_streams: HashMap<usize, OutputStream>,
+    input_streams: HashMap<usize, InputStream>,
+    loopback_streams: HashMap<usize, InputStream>,
+    /// Everything rendered per output device
+    rendered: HashMap<usize, Vec<f32>>,
+    next_stream_id: u64,
+}
+
+/// Fake sound hardware with configurable devices
+#[derive(Clone, Default)]
+pub struct FakeBackend {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Clone, Copy)]
+enum StreamKind {
+    Output,
+    Input,
+    Loopback,
+}
+
+/// Removes the stream from the backend when the [`BackendStream`] is dropped
+struct StreamGuard {
+    state: Arc<Mutex<State>>,
+    index: usize,
+    id: u64,
+    kind: StreamKind,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        match self.kind {
+            StreamKind::Output => {
+                if state.output_streams.get(&self.index).map(|s| s.id) == Some(self.id) {
+                    state.output_streams.remove(&self.index);
+                }
+            }
+            StreamKind::Input => remove_input(&mut state.input_streams, self.index, self.id),
+            StreamKind::Loopback => remove_input(&mut state.loopback_streams, self.index, self.id),
+        }
+    }
+}
+
+fn remove_input(streams: &mut HashMap<usize, InputStream>, index: usize, id: u64) {
+    if streams.get(&index).map(|s| s.id) == Some(id) {
+        streams.remove(&index);
+    }
+}
+
+impl FakeBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an output device; returns its index
+    pub fn add_output(&self, name: &str, sample_rate: u32, channels: u16) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.outputs.push(FakeDevice {
+            name: name.to_string(),
+            format: StreamFormat {
+                sample_rate,
+                channels,
+            },
+        });
+        state.outputs.len() - 1
+    }
+
+    /// Add an input device; returns its index
+    pub fn add_input(&self, name: &str, sample_rate: u32, channels: u16) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.inputs.push(FakeDevice {
+            name: name.to_string(),
+            format: StreamFormat {
+                sample_rate,
+                channels,
+            },
+        });
+        state.inputs.len() - 1
+    }
+
+    /// Whether an output stream is open on the device
+    pub fn output_open(&self, index: usize) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .output_streams
+            .contains_key(&index)
+    }
+
+    /// Whether an input stream is open on the device
+    pub fn input_open(&self, index: usize) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .input_streams
+            .contains_key(&index)
+    }
+
+    /// Whether a loopback stream is capturing the output device
+    pub fn loopback_open(&self, index: usize) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .loopback_streams
+            .contains_key(&index)
+    }
+
+    /// Wait up to a second for another thread to open (or close) an output stream
+    pub fn wait_for_output(&self, index: usize, open: bool) -> bool {
+        wait_until(|| self.output_open(index) == open)
+    }
+
+    /// Wait up to a second for another thread to open (or close) an input stream
+    pub fn wait_for_input(&self, index: usize, open: bool) -> bool {
+        wait_until(|| self.input_open(index) == open)
+    }
+
+    /// Pull `frames` frames from the device's output stream
+    ///
+    /// Returns the rendered interleaved samples (silence if no stream is open),
+    /// which a loopback stream on the device also receives.
+    pub fn render_output(&self, index: usize, frames: usize) -> Vec<f32> {
+        let mut state = self.state.lock().unwrap();
+        let channels = state
+            .outputs
+            .get(index)
+            .map_or(1, |d| d.format.channels as usize);
+        let mut buffer = vec![0.0f32; frames * channels];
+        if let Some(stream) = state.output_streams.get_mut(&index) {
+            (stream.render)(&mut buffer);
+        }
+        if let Some(stream) = state.loopback_streams.get_mut(&index) {
+            (stream.on_data)(&buffer);
+        }
+        state
+            .rendered
+            .entry(index)
+            .or_default()
+            .extend_from_slice(&buffer);
+        buffer
+    }
+
+    /// Everything rendered on the device so far
+    pub fn rendered(&self, index: usize) -> Vec<f32> {
+        self.state
+            .lock()
+            .unwrap()
+            .rendered
+            .get(&index)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Deliver interleaved samples to the device's input stream
+    pub fn push_input(&self, index: usize, samples: &[f32]) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(stream) = state.input_streams.get_mut(&index) {
+            (stream.on_data)(samples);
+        }
+    }
+
+    /// Report a stream error on the device, as if it had been unplugged
+    pub fn fail_output(&self, index: usize) {
+        let on_error = self
+            .state
+            .lock()
+            .unwrap()
+            .output_streams
+            .get(&index)
+            .map(|s| s.on_error.clone());
+        if let Some(on_error) = on_error {
+            on_error("device disconnected".to_string());
+        }
+    }
+
+    /// Report a stream error on an input device
+    pub fn fail_input(&self, index: usize) {
+        let on_error = self
+            .state
+            .lock()
+            .unwrap()
+            .input_streams
+            .get(&index)
+            .map(|s| s.on_error.clone());
+        if let Some(on_error) = on_error {
+            on_error("device disconnected".to_string());
+        }
+    }
+
+    fn guard(&self, index: usize, id: u64, kind: StreamKind) -> BackendStream {
+        BackendStream::new(StreamGuard {
+            state: self.state.clone(),
+            index,
+            id,
+            kind,
+        })
+    }
+}
+
+fn wait_until(condition: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    condition()
+}
+
+fn list(devices: &[FakeDevice]) -> Vec<BackendDevice> {
+    devices
+        .iter()
+        .enumerate()
+        .map(|(i, d)| BackendDevice {
+            name: d.name.clone(),
+            is_default: i == 0,
+        })
+        .collect()
+}
+
+fn format(devices: &[FakeDevice], index: usize) -> Result<StreamFormat, AudioError> {
+    devices
+        .get(index)
+        .map(|d| d.format)
+        .ok_or_else(|| AudioError::DeviceNotFound(format!("fake device {}", index)))
+}
+
+impl AudioBackend for FakeBackend {
+    fn output_devices(&self) -> Result<Vec<BackendDevice>, AudioError> {
+        Ok(list(&self.state.lock().unwrap().outputs))
+    }
+
+    fn input_devices(&self) -> Result<Vec<BackendDevice>, AudioError> {
+        Ok(list(&self.state.lock().unwrap().inputs))
+    }
+
+    fn output_format(&self, index: usize) -> Result<StreamFormat, AudioError> {
+        format(&self.state.lock().unwrap().outputs, index)
+    }
+
+    fn input_format(&self, index: usize) -> Result<StreamFormat, AudioError> {
+        format(&self.state.lock().unwrap().inputs, index)
+    }
+
+    fn open_output(
+        &self,
+        index: usize,
+        _latency: StreamLatency,
+        render: RenderCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError> {
+        let mut state = self.state.lock().unwrap();
+        format(&state.outputs, index)?;
+        state.next_stream_id += 1;
+        let id = state.next_stream_id;
+        state.output_streams.insert(
+            index,
+            OutputStream {
+                id,
+                render,
+                on_error,
+            },
+        );
+        drop(state);
+        Ok(self.guard(index, id, StreamKind::Output))
+    }
+
+    fn open_input(
+        &self,
+        index: usize,
+        on_data: CaptureCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError> {
+        let mut state = self.state.lock().unwrap();
+        format(&state.inputs, index)?;
+        state.next_stream_id += 1;
+        let id = state.next_stream_id;
+        state.input_streams.insert(
+            index,
+            InputStream {
+                id,
+                on_data,
+                on_error,
+            },
+        );
+        drop(state);
+        Ok(self.guard(index, id, StreamKind::Input))
+    }
+
+    fn open_loopback(
+        &self,
+        index: usize,
+        on_data: CaptureCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError> {
+        let mut state = self.state.lock().unwrap();
+        format(&state.outputs, index)?;
+        state.next_stream_id += 1;
+        let id = state.next_stream_id;
+        state.loopback_streams.insert(
+            index,
+            InputStream {
+                id,
+                on_data,
+                on_error,
+            },
+        );
+        drop(state);
+        Ok(self.guard(index, id, StreamKind::Loopback))
+    }
+}
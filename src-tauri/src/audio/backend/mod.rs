@@ -0,0 +1,112 @@
+//! Audio backend abstraction
+//!
+//! Everything that talks to sound hardware goes through [`AudioBackend`]: device
+//! enumeration and opening input/output streams. Streams always exchange
+//! interleaved f32 samples at the device's native rate and channel count; the
+//! backend converts to and from the device sample format.
+//!
+//! [`CpalBackend`] is used by the app. Tests use an in-memory fake (see the `fake`
+//! module) that records everything rendered to its output devices and lets tests
+//! feed samples into its input devices, so playback and microphone routing run
+//! without sound hardware.
+
+mod cpal_backend;
+#[cfg(test)]
+pub mod fake;
+
+use std::sync::Arc;
+
+use super::AudioError;
+
+pub use cpal_backend::CpalBackend;
+
+/// Output render callback: fill the interleaved buffer
+pub type RenderCallback = Box<dyn FnMut(&mut [f32]) + Send + 'static>;
+/// Input capture callback: consume an interleaved buffer
+pub type CaptureCallback = Box<dyn FnMut(&[f32]) + Send + 'static>;
+/// Called when a running stream fails (e.g. the device was unplugged)
+pub type ErrorCallback = Arc<dyn Fn(String) + Send + Sync + 'static>;
+
+/// A device as reported by the backend; its position in the list is its index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Native stream format of a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Buffer size preference for an output stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamLatency {
+    /// Small fixed buffers, falling back to larger ones if the device refuses
+    Low,
+    /// Whatever the device prefers
+    Default,
+}
+
+/// Keeps a backend stream running; dropping it stops the stream
+///
+/// Not `Send`: like cpal streams, it must be dropped on the thread that opened it.
+pub struct BackendStream {
+    _inner: Box<dyn std::any::Any>,
+}
+
+impl BackendStream {
+    pub fn new(inner: impl std::any::Any) -> Self {
+        Self {
+            _inner: Box::new(inner),
+        }
+    }
+}
+
+/// Source of audio devices and streams
+pub trait AudioBackend: Send + Sync {
+    /// Output devices in enumeration order (the index is the device's `DeviceId`)
+    fn output_devices(&self) -> Result<Vec<BackendDevice>, AudioError>;
+
+    /// Input devices in enumeration order
+    fn input_devices(&self) -> Result<Vec<BackendDevice>, AudioError>;
+
+    /// Native format of an output device
+    fn output_format(&self, index: usize) -> Result<StreamFormat, AudioError>;
+
+    /// Native format of an input device
+    fn input_format(&self, index: usize) -> Result<StreamFormat, AudioError>;
+
+    /// Open and start an output stream in the device's [`output_format`](Self::output_format)
+    fn open_output(
+        &self,
+        index: usize,
+        latency: StreamLatency,
+        render: RenderCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError>;
+
+    /// Open and start an input stream in the device's [`input_format`](Self::input_format)
+    fn open_input(
+        &self,
+        index: usize,
+        on_data: CaptureCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError>;
+
+    /// Capture what an output device plays (loopback), in the device's
+    /// [`output_format`](Self::output_format)
+    fn open_loopback(
+        &self,
+        index: usize,
+        on_data: CaptureCallback,
+        on_error: ErrorCallback,
+    ) -> Result<BackendStream, AudioError>;
+}
+
+/// The backend used by the app
+pub fn default_backend() -> Arc<dyn AudioBackend> {
+    Arc::new(CpalBackend)
+}
@@ -0,0 +1,956 @@
+//! Per-device output buses
+//!
+//! Every output device gets one long-lived stream fed by a [`BusMixer`]. Playbacks add
+//! [`Voice`]s to the mixer instead of opening their own streams, so overlapping sounds
+//! are summed in one place, optionally ducked while the user speaks, and run through a
+//! look-ahead limiter before reaching the device.
+//!
+//! The mixer is owned by the stream callback and never shared behind a lock. Other
+//! threads control it through a [`BusHandle`]: voice changes travel over a bounded
+//! queue that the callback drains at the start of each block, meters come back
+//! through atomics, and voices the callback is done with are handed back so their
+//! buffers are freed off the audio thread.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tracing::{debug, error, info, warn};
+
+use super::backend::{default_backend, AudioBackend};
+use super::dsp::{Ducker, LookaheadLimiter};
+use super::playback::{create_bus_stream, Voice};
+use super::sidechain::Sidechain;
+use super::{AudioData, AudioError, DeviceId};
+
+/// Default limiter ceiling in dBFS
+pub const DEFAULT_LIMITER_CEILING_DB: f32 = -1.0;
+
+/// Voices one bus can play at once (the voice list never grows on the audio thread)
+const MAX_VOICES: usize = 64;
+
+/// Control messages that can wait for the next block
+const COMMAND_QUEUE: usize = 256;
+
+/// Changes sent from a [`BusHandle`] to the render callback
+enum BusCommand {
+    AddVoice(Voice),
+    RemoveVoice(String),
+    SetCeiling(f32),
+}
+
+/// Meter values published by the render callback
+#[derive(Default)]
+struct BusMeters {
+    /// Highest gain reduction since the last read, as `f32` bits. Reductions are
+    /// never negative, so comparing the bits compares the values.
+    peak_reduction_bits: AtomicU32,
+    active_voices: AtomicUsize,
+}
+
+/// Mixes active voices for one output device and limits the sum
+pub struct BusMixer {
+    voices: Vec<Voice>,
+    /// Ducks the summed voices while the mic sidechain detects speech
+    ducker: Ducker,
+    limiter: LookaheadLimiter,
+    sidechain: Arc<Sidechain>,
+    /// Whether this bus is currently counted as active in the sidechain
+    active: bool,
+    channels: usize,
+    sample_rate: u32,
+    commands: Receiver<BusCommand>,
+    /// Hands finished voices back to be dropped by the control side
+    retired: SyncSender<BusCommand>,
+    handle: BusHandle,
+}
+
+impl BusMixer {
+    pub fn new(
+        sample_rate: u32,
+        channels: usize,
+        ceiling_db: f32,
+        sidechain: Arc<Sidechain>,
+    ) -> Self {
+        let channels = channels.max(1);
+        let config = sidechain.config();
+        let (commands_tx, commands) = mpsc::sync_channel(COMMAND_QUEUE);
+        let (retired, retired_rx) = mpsc::sync_channel(COMMAND_QUEUE);
+        Self {
+            voices: Vec::with_capacity(MAX_VOICES),
+            ducker: Ducker::new(
+                config.board_duck_depth_db,
+                config.attack_ms,
+                config.release_ms,
+                sample_rate,
+                channels,
+            ),
+            limiter: LookaheadLimiter::new(ceiling_db, sample_rate, channels),
+            sidechain,
+            active: false,
+            channels,
+            sample_rate,
+            commands,
+            retired,
+            handle: BusHandle {
+                commands: commands_tx,
+                retired: Arc::new(Mutex::new(retired_rx)),
+                meters: Arc::new(BusMeters::default()),
+                sample_rate,
+            },
+        }
+    }
+
+    /// Handle for controlling this mixer once it has moved into a stream callback
+    pub fn handle(&self) -> BusHandle {
+        self.handle.clone()
+    }
+
+    /// Start playing decoded audio on this bus
+    pub fn add_voice(
+        &mut self,
+        playback_id: String,
+        audio_data: Arc<AudioData>,
+        volume: Arc<Mutex<f32>>,
+        start_frame: Option<usize>,
+        end_frame: Option<usize>,
+    ) {
+        let voice = Voice::new(
+            playback_id,
+            audio_data,
+            volume,
+            start_frame,
+            end_frame,
+            self.sample_rate,
+        );
+        self.push_voice(voice);
+    }
+
+    /// Remove all voices belonging to a playback
+    pub fn remove_voice(&mut self, playback_id: &str) {
+        self.retire_voices(|v| v.playback_id == playback_id);
+    }
+
+    /// Number of voices still playing
+    pub fn active_voices(&self) -> usize {
+        self.voices.len()
+    }
+
+    /// Delay added by the limiter look-ahead, in frames
+    pub fn latency_frames(&self) -> usize {
+        self.limiter.latency_frames()
+    }
+
+    /// Render the next block of interleaved output
+    ///
+    /// Never locks or allocates, so it is safe to call from a stream callback.
+    pub fn render(&mut self, output: &mut [f32]) {
+        while let Ok(command) = self.commands.try_recv() {
+            match command {
+                BusCommand::AddVoice(voice) => self.push_voice(voice),
+                BusCommand::RemoveVoice(playback_id) => {
+                    self.retire_voices(|v| v.playback_id == playback_id);
+                    self.retire(BusCommand::RemoveVoice(playback_id));
+                }
+                BusCommand::SetCeiling(ceiling_db) => self.limiter.set_ceiling_db(ceiling_db),
+            }
+        }
+
+        output.fill(0.0);
+
+        for voice in self.voices.iter_mut() {
+            voice.mix_into(output, self.channels);
+        }
+        self.retire_voices(Voice::is_finished);
+
+        // Publish activity so the mic path can duck, then duck ourselves on speech
+        let active = !self.voices.is_empty();
+        if active != self.active {
+            self.active = active;
+            self.sidechain.set_bus_active(active);
+        }
+        let config = self.sidechain.config();
+        self.ducker.set_params(
+            config.board_duck_depth_db,
+            config.attack_ms,
+            config.release_ms,
+            self.sample_rate,
+        );
+        let keyed = config.board_ducking_enabled && self.sidechain.voice_active(&config);
+        self.ducker.process(output, keyed);
+
+        self.limiter.process_interleaved(output);
+
+        let meters = &self.handle.meters;
+        let reduction = self.limiter.gain_reduction_db().max(0.0);
+        meters
+            .peak_reduction_bits
+            .fetch_max(reduction.to_bits(), Ordering::Relaxed);
+        meters
+            .active_voices
+            .store(self.voices.len(), Ordering::Relaxed);
+    }
+
+    /// Add a voice within the preallocated capacity, turning away any extra
+    fn push_voice(&mut self, voice: Voice) {
+        if self.voices.len() < MAX_VOICES {
+            self.voices.push(voice);
+        } else {
+            self.retire(BusCommand::AddVoice(voice));
+        }
+    }
+
+    /// Take out every voice matching `remove` and hand it back for dropping
+    fn retire_voices(&mut self, remove: impl Fn(&Voice) -> bool) {
+        let mut i = 0;
+        while i < self.voices.len() {
+            if remove(&self.voices[i]) {
+                let voice = self.voices.swap_remove(i);
+                self.retire(BusCommand::AddVoice(voice));
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// Leave freeing to the control side; if its queue is full, free it here
+    fn retire(&self, command: BusCommand) {
+        let _ = self.retired.try_send(command);
+    }
+}
+
+impl Drop for BusMixer {
+    fn drop(&mut self) {
+        if self.active {
+            self.sidechain.set_bus_active(false);
+        }
+    }
+}
+
+/// Controls a [`BusMixer`] from outside its stream callback
+#[derive(Clone)]
+pub struct BusHandle {
+    commands: SyncSender<BusCommand>,
+    retired: Arc<Mutex<Receiver<BusCommand>>>,
+    meters: Arc<BusMeters>,
+    sample_rate: u32,
+}
+
+impl BusHandle {
+    /// Start playing decoded audio on the bus from its next block
+    pub fn add_voice(
+        &self,
+        playback_id: String,
+        audio_data: Arc<AudioData>,
+        volume: Arc<Mutex<f32>>,
+        start_frame: Option<usize>,
+        end_frame: Option<usize>,
+    ) {
+        let voice = Voice::new(
+            playback_id,
+            audio_data,
+            volume,
+            start_frame,
+            end_frame,
+            self.sample_rate,
+        );
+        self.send(BusCommand::AddVoice(voice));
+    }
+
+    /// Remove all voices belonging to a playback
+    pub fn remove_voice(&self, playback_id: &str) {
+        self.send(BusCommand::RemoveVoice(playback_id.to_string()));
+    }
+
+    /// Change the limiter ceiling
+    pub fn set_ceiling_db(&self, ceiling_db: f32) {
+        self.send(BusCommand::SetCeiling(ceiling_db));
+    }
+
+    /// Number of voices playing as of the last rendered block
+    pub fn active_voices(&self) -> usize {
+        self.meters.active_voices.load(Ordering::Relaxed)
+    }
+
+    /// Return the peak gain reduction since the last call and reset it
+    pub fn take_peak_reduction_db(&self) -> f32 {
+        self.drop_retired();
+        f32::from_bits(self.meters.peak_reduction_bits.swap(0, Ordering::Relaxed))
+    }
+
+    /// Whether both handles control the same mixer
+    pub fn same_bus(&self, other: &BusHandle) -> bool {
+        Arc::ptr_eq(&self.meters, &other.meters)
+    }
+
+    fn send(&self, command: BusCommand) {
+        self.drop_retired();
+        match self.commands.try_send(command) {
+            Ok(()) => {}
+            // The callback drains the queue every block, so it only fills up when the
+            // stream has stalled
+            Err(TrySendError::Full(_)) => warn!("Output bus is not keeping up, change dropped"),
+            // Stream closed: nothing left to control
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+
+    /// Free voices the callback has finished with
+    fn drop_retired(&self) {
+        let retired = self.retired.lock().unwrap();
+        while retired.try_recv().is_ok() {}
+    }
+}
+
+/// Gain reduction meter reading for one bus
+#[derive(Debug, Clone, Serialize)]
+pub struct BusMeter {
+    pub device_id: DeviceId,
+    /// Peak gain reduction in dB since the previous reading (0.0 = not limiting)
+    pub gain_reduction_db: f32,
+    pub active_voices: usize,
+}
+
+/// Owns the thread that keeps a device's backend stream alive
+struct OutputBus {
+    mixer: BusHandle,
+    /// Stop flag for our own stream thread (None for attached buses)
+    shutdown: Option<Arc<AtomicBool>>,
+    /// Set when our stream reports an error
+    failed: Arc<AtomicBool>,
+    /// Name of the device the stream was opened on (None for attached buses)
+    device_name: Option<String>,
+}
+
+impl OutputBus {
+    /// Open the bus stream on a dedicated thread (backend streams are not `Send`)
+    fn open(
+        backend: Arc<dyn AudioBackend>,
+        device_id: &DeviceId,
+        ceiling_db: f32,
+        sidechain: Arc<Sidechain>,
+    ) -> Result<Self, AudioError> {
+        let index = device_id.index()?;
+        let device_name = device_id.to_string();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let shutdown_flag = shutdown.clone();
+        let failed = Arc::new(AtomicBool::new(false));
+        let failed_flag = failed.clone();
+        let (ready_tx, ready_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let name = match backend.output_devices() {
+                Ok(devices) => devices.into_iter().nth(index).map(|d| d.name),
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            let Some(name) = name else {
+                let _ = ready_tx.send(Err(AudioError::DeviceNotFound(device_name)));
+                return;
+            };
+
+            let stream =
+                match create_bus_stream(&*backend, index, ceiling_db, sidechain, failed_flag) {
+                    Ok((stream, mixer)) => {
+                        let _ = ready_tx.send(Ok((mixer, name)));
+                        stream
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+
+            while !shutdown_flag.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(50));
+            }
+            drop(stream);
+            debug!(device = %device_name, "Bus stream closed");
+        });
+
+        let (mixer, name) = ready_rx
+            .recv()
+            .map_err(|e| AudioError::StreamBuild(e.to_string()))??;
+
+        Ok(Self {
+            mixer,
+            shutdown: Some(shutdown),
+            failed,
+            device_name: Some(name),
+        })
+    }
+
+    /// Whether the stream broke or `outputs` (device names by index) no longer
+    /// has our device at its ID. Attached buses are never stale.
+    fn is_stale(&self, device_id: &DeviceId, outputs: &[String]) -> bool {
+        let Some(name) = &self.device_name else {
+            return false;
+        };
+        let current = device_id.index().ok().and_then(|i| outputs.get(i));
+        self.failed.load(Ordering::Relaxed) || current != Some(name)
+    }
+}
+
+impl Drop for OutputBus {
+    fn drop(&mut self) {
+        if let Some(shutdown) = &self.shutdown {
+            shutdown.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Lazily opened output buses, one per device
+pub struct BusRegistry {
+    backend: Arc<dyn AudioBackend>,
+    buses: Mutex<HashMap<DeviceId, OutputBus>>,
+    ceiling_db: Mutex<f32>,
+    sidechain: Arc<Sidechain>,
+}
+
+impl BusRegistry {
+    pub fn new(backend: Arc<dyn AudioBackend>, ceiling_db: f32) -> Self {
+        Self {
+            backend,
+            buses: Mutex::new(HashMap::new()),
+            ceiling_db: Mutex::new(ceiling_db),
+            sidechain: Arc::new(Sidechain::default()),
+        }
+    }
+
+    /// Backend the buses open their streams on
+    pub fn backend(&self) -> Arc<dyn AudioBackend> {
+        self.backend.clone()
+    }
+
+    /// Sidechain shared with microphone routing
+    pub fn sidechain(&self) -> Arc<Sidechain> {
+        self.sidechain.clone()
+    }
+
+    /// Get the mixer for a device, opening its stream on first use
+    ///
+    /// The device is opened without holding the bus lock, so playbacks on buses
+    /// that are already open don't wait behind a slow device.
+    pub fn get_or_open(&self, device_id: &DeviceId) -> Result<BusHandle, AudioError> {
+        if let Some(bus) = self.buses.lock().unwrap().get(device_id) {
+            return Ok(bus.mixer.clone());
+        }
+
+        let ceiling_db = self.ceiling_db();
+        let bus = OutputBus::open(
+            self.backend(),
+            device_id,
+            ceiling_db,
+            self.sidechain.clone(),
+        )
+        .map_err(|e| {
+            error!(device_id = %device_id, error = %e, "Failed to open output bus");
+            e
+        })?;
+
+        let mut buses = self.buses.lock().unwrap();
+        if let Some(existing) = buses.get(device_id) {
+            // Another playback opened it meanwhile; dropping ours closes its stream
+            debug!(device_id = %device_id, "Output bus opened concurrently, using the first");
+            return Ok(existing.mixer.clone());
+        }
+        // The ceiling may have changed while the device was opening
+        let current_ceiling_db = self.ceiling_db();
+        if current_ceiling_db != ceiling_db {
+            bus.mixer.set_ceiling_db(current_ceiling_db);
+        }
+        info!(device_id = %device_id, ceiling_db = current_ceiling_db, "Output bus opened");
+
+        let mixer = bus.mixer.clone();
+        buses.insert(device_id.clone(), bus);
+        Ok(mixer)
+    }
+
+    /// Start a playback on several devices at once
+    ///
+    /// Opens every bus first so a device that fails to open leaves nothing playing.
+    /// Returns the mixers the voices were added to.
+    pub fn play(
+        &self,
+        device_ids: &[&DeviceId],
+        playback_id: &str,
+        audio_data: Arc<AudioData>,
+        volume: Arc<Mutex<f32>>,
+        start_frame: Option<usize>,
+        end_frame: Option<usize>,
+    ) -> Result<Vec<BusHandle>, AudioError> {
+        let mixers = device_ids
+            .iter()
+            .map(|device_id| self.get_or_open(device_id))
+            .collect::<Result<Vec<_>, _>>()?;
+        for mixer in &mixers {
+            mixer.add_voice(
+                playback_id.to_string(),
+                audio_data.clone(),
+                volume.clone(),
+                start_frame,
+                end_frame,
+            );
+        }
+        Ok(mixers)
+    }
+
+    /// Register a mixer whose stream is driven elsewhere (e.g. microphone routing).
+    ///
+    /// Replaces any bus already open on the device, closing its stream.
+    pub fn attach(&self, device_id: DeviceId, mixer: BusHandle) {
+        let replaced = self.buses.lock().unwrap().insert(
+            device_id.clone(),
+            OutputBus {
+                mixer,
+                shutdown: None,
+                failed: Arc::new(AtomicBool::new(false)),
+                device_name: None,
+            },
+        );
+        if replaced.is_some() {
+            debug!(device_id = %device_id, "Replaced open output bus with attached mixer");
+        }
+        info!(device_id = %device_id, "Output bus attached");
+    }
+
+    /// Remove an attached mixer, if it is still the one registered for the device
+    pub fn detach(&self, device_id: &DeviceId, mixer: &BusHandle) {
+        let mut buses = self.buses.lock().unwrap();
+        if buses
+            .get(device_id)
+            .is_some_and(|bus| bus.mixer.same_bus(mixer))
+        {
+            buses.remove(device_id);
+            info!(device_id = %device_id, "Output bus detached");
+        }
+    }
+
+    /// Close buses whose stream failed or whose device is no longer at its ID
+    ///
+    /// `outputs` are the current output device names by index. Closed buses reopen
+    /// on the next playback; attached buses are left to their owner.
+    pub fn prune(&self, outputs: &[String]) -> Vec<DeviceId> {
+        let mut buses = self.buses.lock().unwrap();
+        let stale: Vec<DeviceId> = buses
+            .iter()
+            .filter(|(device_id, bus)| bus.is_stale(device_id, outputs))
+            .map(|(device_id, _)| device_id.clone())
+            .collect();
+        for device_id in &stale {
+            buses.remove(device_id);
+            info!(device_id = %device_id, "Closed stale output bus");
+        }
+        stale
+    }
+
+    /// Current limiter ceiling in dBFS
+    pub fn ceiling_db(&self) -> f32 {
+        *self.ceiling_db.lock().unwrap()
+    }
+
+    /// Update the limiter ceiling on all open buses and for future ones
+    pub fn set_ceiling_db(&self, ceiling_db: f32) {
+        *self.ceiling_db.lock().unwrap() = ceiling_db;
+        for bus in self.buses.lock().unwrap().values() {
+            bus.mixer.set_ceiling_db(ceiling_db);
+        }
+    }
+
+    /// Read (and reset) the gain reduction meters of all open buses
+    pub fn meters(&self) -> Vec<BusMeter> {
+        let buses = self.buses.lock().unwrap();
+        let mut meters: Vec<BusMeter> = buses
+            .iter()
+            .map(|(device_id, bus)| BusMeter {
+                device_id: device_id.clone(),
+                gain_reduction_db: bus.mixer.take_peak_reduction_db(),
+                active_voices: bus.mixer.active_voices(),
+            })
+            .collect();
+        meters.sort_by(|a, b| a.device_id.as_str().cmp(b.device_id.as_str()));
+        meters
+    }
+}
+
+impl Default for BusRegistry {
+    fn default() -> Self {
+        Self::new(default_backend(), DEFAULT_LIMITER_CEILING_DB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::backend::fake::FakeBackend;
+    use crate::audio::dsp::{db_to_linear, linear_to_db};
+    use crate::audio::sidechain::DuckingConfig;
+
+    fn fake_registry(backend: &FakeBackend) -> BusRegistry {
+        BusRegistry::new(Arc::new(backend.clone()), DEFAULT_LIMITER_CEILING_DB)
+    }
+
+    fn loud_clip(frames: usize) -> Arc<AudioData> {
+        // Full-scale square wave, stereo
+        let samples = (0..frames)
+            .flat_map(|i| {
+                let v = if (i / 50) % 2 == 0 { 1.0 } else { -1.0 };
+                [v, v]
+            })
+            .collect();
+        Arc::new(AudioData {
+            samples,
+            sample_rate: 48000,
+            channels: 2,
+        })
+    }
+
+    #[test]
+    fn test_mixer_renders_silence_without_voices() {
+        let mut mixer = BusMixer::new(48000, 2, -1.0, Arc::new(Sidechain::default()));
+        let mut output = vec![0.5f32; 512];
+        mixer.render(&mut output);
+        assert!(output.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_overlapping_voices_stay_under_ceiling() {
+        let mut mixer = BusMixer::new(48000, 2, -1.0, Arc::new(Sidechain::default()));
+        // Eight full-volume voices sum to ~1.6 before limiting
+        for i in 0..8 {
+            mixer.add_voice(
+                format!("playback_{}", i),
+                loud_clip(48000),
+                Arc::new(Mutex::new(1.0)),
+                None,
+                None,
+            );
+        }
+
+        let ceiling = db_to_linear(-1.0);
+        let mut output = vec![0.0f32; 512];
+        for _ in 0..50 {
+            mixer.render(&mut output);
+            assert!(output.iter().all(|s| s.abs() <= ceiling + 1e-6));
+        }
+        let handle = mixer.handle();
+        assert!(handle.take_peak_reduction_db() > 3.0);
+        assert_eq!(handle.take_peak_reduction_db(), 0.0);
+    }
+
+    #[test]
+    fn test_finished_voices_are_dropped() {
+        let mut mixer = BusMixer::new(48000, 2, -1.0, Arc::new(Sidechain::default()));
+        mixer.add_voice(
+            "playback_1".to_string(),
+            loud_clip(100),
+            Arc::new(Mutex::new(1.0)),
+            None,
+            None,
+        );
+        let mut output = vec![0.0f32; 512];
+        mixer.render(&mut output);
+        assert_eq!(mixer.active_voices(), 0);
+    }
+
+    #[test]
+    fn test_bus_activity_is_published_to_sidechain() {
+        let sidechain = Arc::new(Sidechain::default());
+        let mut mixer = BusMixer::new(48000, 2, -1.0, sidechain.clone());
+        mixer.add_voice(
+            "playback_1".to_string(),
+            loud_clip(1000),
+            Arc::new(Mutex::new(1.0)),
+            None,
+            None,
+        );
+
+        let mut output = vec![0.0f32; 512];
+        mixer.render(&mut output);
+        assert!(sidechain.board_active());
+
+        // Voice runs out after a few blocks
+        for _ in 0..10 {
+            mixer.render(&mut output);
+        }
+        assert!(!sidechain.board_active());
+    }
+
+    #[test]
+    fn test_board_ducks_while_user_speaks() {
+        let sidechain = Arc::new(Sidechain::new(DuckingConfig {
+            board_ducking_enabled: true,
+            board_duck_depth_db: -12.0,
+            ..DuckingConfig::default()
+        }));
+        let quiet_clip = || {
+            Arc::new(AudioData {
+                samples: vec![0.5; 96000],
+                sample_rate: 48000,
+                channels: 1,
+            })
+        };
+
+        let render_level = |speaking: bool| {
+            sidechain.set_mic_level(if speaking { 0.5 } else { 0.0 });
+            let mut mixer = BusMixer::new(48000, 1, 0.0, sidechain.clone());
+            mixer.add_voice(
+                "playback_1".to_string(),
+                quiet_clip(),
+                Arc::new(Mutex::new(1.0)),
+                None,
+                None,
+            );
+            let mut output = vec![0.0f32; 4800];
+            for _ in 0..5 {
+                mixer.render(&mut output);
+            }
+            output[4799]
+        };
+
+        let normal = render_level(false);
+        let ducked = render_level(true);
+        assert!((linear_to_db(ducked / normal) + 12.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_handle_changes_apply_on_next_block() {
+        let mut mixer = BusMixer::new(48000, 2, -1.0, Arc::new(Sidechain::default()));
+        let handle = mixer.handle();
+        let clip = loud_clip(48000);
+        handle.add_voice(
+            "playback_1".to_string(),
+            clip.clone(),
+            Arc::new(Mutex::new(1.0)),
+            None,
+            None,
+        );
+        assert_eq!(mixer.active_voices(), 0);
+
+        let mut output = vec![0.0f32; 512];
+        mixer.render(&mut output);
+        assert_eq!(handle.active_voices(), 1);
+
+        handle.remove_voice("playback_1");
+        mixer.render(&mut output);
+        assert_eq!(handle.active_voices(), 0);
+
+        // The removed voice is freed by the control side, not the callback
+        assert_eq!(Arc::strong_count(&clip), 2);
+        handle.take_peak_reduction_db();
+        assert_eq!(Arc::strong_count(&clip), 1);
+    }
+
+    #[test]
+    fn test_voices_beyond_capacity_are_turned_away() {
+        let mut mixer = BusMixer::new(48000, 2, -1.0, Arc::new(Sidechain::default()));
+        for i in 0..MAX_VOICES + 1 {
+            mixer.add_voice(
+                format!("playback_{}", i),
+                loud_clip(48000),
+                Arc::new(Mutex::new(1.0)),
+                None,
+                None,
+            );
+        }
+        assert_eq!(mixer.active_voices(), MAX_VOICES);
+    }
+
+    #[test]
+    fn test_attached_bus_is_returned_and_detached() {
+        let registry = fake_registry(&FakeBackend::new());
+        let device_id = DeviceId::from_index(3);
+        let mixer = BusMixer::new(48000, 2, -1.0, registry.sidechain()).handle();
+
+        registry.attach(device_id.clone(), mixer.clone());
+        let found = registry.get_or_open(&device_id).unwrap();
+        assert!(found.same_bus(&mixer));
+        assert_eq!(registry.meters().len(), 1);
+
+        // Detaching with a different mixer is ignored
+        let other = BusMixer::new(48000, 2, -1.0, registry.sidechain()).handle();
+        registry.detach(&device_id, &other);
+        assert_eq!(registry.meters().len(), 1);
+
+        registry.detach(&device_id, &mixer);
+        assert!(registry.meters().is_empty());
+    }
+
+    #[test]
+    fn test_prune_closes_moved_and_failed_buses() {
+        let registry = fake_registry(&FakeBackend::new());
+        let open_bus = |name: &str| {
+            let shutdown = Arc::new(AtomicBool::new(false));
+            let bus = OutputBus {
+                mixer: BusMixer::new(48000, 2, -1.0, registry.sidechain()).handle(),
+                shutdown: Some(shutdown.clone()),
+                failed: Arc::new(AtomicBool::new(false)),
+                device_name: Some(name.to_string()),
+            };
+            (bus, shutdown)
+        };
+
+        let (speakers, speakers_shutdown) = open_bus("Speakers");
+        let (headset, headset_shutdown) = open_bus("Headset");
+        let (hdmi, _) = open_bus("HDMI");
+        let hdmi_failed = hdmi.failed.clone();
+        {
+            let mut buses = registry.buses.lock().unwrap();
+            buses.insert(DeviceId::from_index(0), speakers);
+            buses.insert(DeviceId::from_index(1), headset);
+            buses.insert(DeviceId::from_index(2), hdmi);
+        }
+        let cable = BusMixer::new(48000, 2, -1.0, registry.sidechain()).handle();
+        registry.attach(DeviceId::from_index(3), cable);
+
+        // Nothing changed
+        let outputs: Vec<String> = ["Speakers", "Headset", "HDMI", "CABLE Input"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(registry.prune(&outputs).is_empty());
+
+        // Headset unplugged (HDMI moves to index 1) and the HDMI stream broke
+        hdmi_failed.store(true, Ordering::Relaxed);
+        let outputs: Vec<String> = ["Speakers", "HDMI", "CABLE Input"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut closed = registry.prune(&outputs);
+        closed.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(
+            closed,
+            vec![DeviceId::from_index(1), DeviceId::from_index(2)]
+        );
+
+        assert!(headset_shutdown.load(Ordering::Relaxed));
+        assert!(!speakers_shutdown.load(Ordering::Relaxed));
+        // Attached bus is left alone even though index 3 is gone
+        assert_eq!(registry.meters().len(), 2);
+    }
+
+    #[test]
+    fn test_play_reaches_every_device() {
+        let backend = FakeBackend::new();
+        let speakers = backend.add_output("Speakers", 48000, 2);
+        let cable = backend.add_output("CABLE Input", 44100, 1);
+        let registry = fake_registry(&backend);
+
+        let clip = Arc::new(AudioData {
+            samples: vec![0.5; 48000],
+            sample_rate: 48000,
+            channels: 1,
+        });
+        let mixers = registry
+            .play(
+                &[
+                    &DeviceId::from_index(speakers),
+                    &DeviceId::from_index(cable),
+                ],
+                "playback_1",
+                clip,
+                Arc::new(Mutex::new(1.0)),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(mixers.len(), 2);
+        assert!(backend.output_open(speakers) && backend.output_open(cable));
+
+        // Mono clip lands on the first channel of the stereo device only (the tail
+        // of the block, past the limiter look-ahead)
+        let expected = 0.5 * crate::audio::playback::calculate_scaled_volume(1.0);
+        let rendered = backend.render_output(speakers, 1024);
+        assert!((rendered[2046] - expected).abs() < 1e-4);
+        assert_eq!(rendered[2047], 0.0);
+        let rendered = backend.render_output(cable, 1024);
+        assert!((rendered[1023] - expected).abs() < 1e-4);
+
+        // Stopping removes the voice from both devices
+        for mixer in &mixers {
+            mixer.remove_voice("playback_1");
+        }
+        assert_eq!(backend.render_output(speakers, 1024)[2046], 0.0);
+
+        // Closing the bus stops its stream
+        registry.prune(&[]);
+        assert!(backend.wait_for_output(speakers, false));
+    }
+
+    #[test]
+    fn test_play_fails_without_partial_playback() {
+        let backend = FakeBackend::new();
+        let speakers = backend.add_output("Speakers", 48000, 2);
+        let registry = fake_registry(&backend);
+
+        let result = registry.play(
+            &[&DeviceId::from_index(speakers), &DeviceId::from_index(5)],
+            "playback_1",
+            loud_clip(1000),
+            Arc::new(Mutex::new(1.0)),
+            None,
+            None,
+        );
+        assert!(matches!(result, Err(AudioError::DeviceNotFound(_))));
+        let mixer = registry
+            .get_or_open(&DeviceId::from_index(speakers))
+            .unwrap();
+        backend.render_output(speakers, 512);
+        assert_eq!(mixer.active_voices(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_opens_share_one_bus() {
+        let backend = FakeBackend::new();
+        let speakers = backend.add_output("Speakers", 48000, 2);
+        let registry = Arc::new(fake_registry(&backend));
+        let device_id = DeviceId::from_index(speakers);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let registry = registry.clone();
+                let device_id = device_id.clone();
+                thread::spawn(move || registry.get_or_open(&device_id).unwrap())
+            })
+            .collect();
+        let mixers: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(mixers.iter().all(|m| m.same_bus(&mixers[0])));
+        assert_eq!(registry.meters().len(), 1);
+    }
+
+    #[test]
+    fn test_stream_error_marks_bus_stale() {
+        let backend = FakeBackend::new();
+        let speakers = backend.add_output("Speakers", 48000, 2);
+        let registry = fake_registry(&backend);
+        let device_id = DeviceId::from_index(speakers);
+        registry.get_or_open(&device_id).unwrap();
+
+        let outputs = vec!["Speakers".to_string()];
+        assert!(registry.prune(&outputs).is_empty());
+        backend.fail_output(speakers);
+        assert_eq!(registry.prune(&outputs), vec![device_id]);
+    }
+
+    #[test]
+    fn test_remove_voice_by_playback_id() {
+        let mut mixer = BusMixer::new(48000, 2, -1.0, Arc::new(Sidechain::default()));
+        for id in ["playback_1", "playback_2"] {
+            mixer.add_voice(
+                id.to_string(),
+                loud_clip(48000),
+                Arc::new(Mutex::new(1.0)),
+                None,
+                None,
+            );
+        }
+        mixer.remove_voice("playback_1");
+        assert_eq!(mixer.active_voices(), 1);
+    }
+}
@@ -1,16 +1,27 @@
 //! Audio caching system
 //!
-//! LRU memory cache for decoded audio data to avoid redundant decoding.
+//! Memory cache for decoded audio data to avoid redundant decoding, backed by an
+//! optional on-disk cache that survives restarts.
+//!
+//! Eviction is cost-aware (GreedyDual-Size): entries that were slow to decode for
+//! their size outlive cheap ones, recency still ages everything out, and pinned
+//! files (hotkey-bound sounds) are never evicted.
+//!
+//! Effect chains are rendered once per trim and chain and kept with the decoded
+//! entry they came from, so they are dropped together with it.
 
-use std::collections::HashMap;
-use std::num::NonZeroUsize;
-use std::sync::Arc;
-use std::time::{Instant, SystemTime};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 
 use lru::LruCache;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::decode::decode_audio_file;
+use super::disk_cache::DiskCache;
+use super::dsp::EffectConfig;
+use super::edit::{apply_edits, EditOptions};
+use super::playback::PreparedSound;
 use super::{AudioData, AudioError};
 
 /// Estimated bytes per sample (f32 = 4 bytes)
@@ -19,6 +30,12 @@ const BYTES_PER_SAMPLE: usize = 4;
 /// Default max cache size in bytes (500 MB)
 const DEFAULT_MAX_CACHE_BYTES: usize = 500 * 1024 * 1024;
 
+/// Max number of entries regardless of their size
+const MAX_ENTRIES: usize = 1000;
+
+/// Effect renders kept per file (older trims and chains are dropped first)
+const MAX_RENDERS_PER_FILE: usize = 4;
+
 /// Cache entry with metadata for validation
 struct CacheEntry {
     /// The cached audio data
@@ -27,11 +44,38 @@ struct CacheEntry {
     file_modified: Option<SystemTime>,
     /// Size in bytes (for memory tracking)
     size_bytes: usize,
+    /// Time it took to decode (or load from disk), i.e. the cost of a re-load
+    load_cost: Duration,
+    /// Eviction priority, lowest goes first
+    priority: f64,
+    /// Effect renders of `audio_data`, oldest first
+    rendered: Vec<RenderedEntry>,
+}
+
+/// A file's audio after trim and effects, for one trim and effect chain
+struct RenderedEntry {
+    trim_start_ms: Option<u64>,
+    trim_end_ms: Option<u64>,
+    effects: Vec<EffectConfig>,
+    audio_data: Arc<AudioData>,
+}
+
+impl RenderedEntry {
+    fn matches(
+        &self,
+        trim_start_ms: Option<u64>,
+        trim_end_ms: Option<u64>,
+        effects: &[EffectConfig],
+    ) -> bool {
+        self.trim_start_ms == trim_start_ms
+            && self.trim_end_ms == trim_end_ms
+            && self.effects == effects
+    }
 }
 
-/// LRU cache for decoded audio data
+/// Memory cache for decoded audio data
 pub struct AudioCache {
-    /// LRU cache mapping file paths to decoded audio
+    /// Entries in recency order (used to break priority ties)
     cache: LruCache<String, CacheEntry>,
     /// Current total memory usage in bytes
     current_bytes: usize,
@@ -39,17 +83,26 @@ pub struct AudioCache {
     max_bytes: usize,
     /// Track file modification times for invalidation
     file_times: HashMap<String, SystemTime>,
+    /// Persistent cache consulted before decoding
+    disk: Option<Arc<DiskCache>>,
+    /// Files that are never evicted
+    pinned: HashSet<String>,
+    /// Priority of the last eviction; new and re-used entries start above it
+    inflation: f64,
+    /// Bumped whenever cached audio goes stale, so in-flight decodes aren't stored
+    generation: u64,
+    hits: u64,
+    misses: u64,
+    disk_hits: u64,
+    evictions: u64,
 }
 
 impl AudioCache {
     /// Create a new audio cache with specified max memory in MB
     pub fn new(max_memory_mb: usize) -> Self {
-        // Use NonZeroUsize for LRU cache capacity (number of entries, not bytes)
-        // We'll track bytes ourselves and evict when needed
-        let capacity = NonZeroUsize::new(1000).unwrap(); // Max 1000 entries
-
         Self {
-            cache: LruCache::new(capacity),
+            // Entry count is capped in make_space so pinned entries survive
+            cache: LruCache::unbounded(),
             current_bytes: 0,
             max_bytes: if max_memory_mb > 0 {
                 max_memory_mb * 1024 * 1024
@@ -57,9 +110,40 @@ impl AudioCache {
                 DEFAULT_MAX_CACHE_BYTES
             },
             file_times: HashMap::new(),
+            disk: None,
+            pinned: HashSet::new(),
+            inflation: 0.0,
+            generation: 0,
+            hits: 0,
+            misses: 0,
+            disk_hits: 0,
+            evictions: 0,
         }
     }
 
+    /// Replace the persistent cache (None disables it)
+    ///
+    /// Memory entries are dropped when the pre-resample rate changes so sounds
+    /// aren't served at a stale rate.
+    pub fn set_disk_cache(&mut self, disk: Option<DiskCache>) {
+        let old_rate = self.disk.as_ref().and_then(|disk| disk.sample_rate());
+        let new_rate = disk.as_ref().and_then(DiskCache::sample_rate);
+        if old_rate != new_rate {
+            self.drop_entries();
+        }
+        self.disk = disk.map(Arc::new);
+    }
+
+    /// Replace the set of files that are never evicted
+    pub fn set_pinned(&mut self, file_paths: impl IntoIterator<Item = String>) {
+        self.pinned = file_paths.into_iter().collect();
+    }
+
+    /// Whether the memory budget has space left (preloading stops when it doesn't)
+    pub fn has_room(&self) -> bool {
+        self.current_bytes < self.max_bytes
+    }
+
     /// Estimate memory size of audio data
     fn estimate_size(audio_data: &AudioData) -> usize {
         audio_data.samples.len() * BYTES_PER_SAMPLE
@@ -79,46 +163,231 @@ impl AudioCache {
         }
     }
 
+    /// Priority of an entry used now: load cost (µs) per KB on top of the inflation
+    fn priority(&self, load_cost: Duration, size_bytes: usize) -> f64 {
+        let size_kb = (size_bytes as f64 / 1024.0).max(1.0);
+        self.inflation + load_cost.as_micros() as f64 / size_kb
+    }
+
     /// Evict entries until we have enough space for new entry
     fn make_space(&mut self, needed_bytes: usize) {
-        while self.current_bytes + needed_bytes > self.max_bytes {
-            // LRU pop removes least recently used entry
-            if let Some((path, entry)) = self.cache.pop_lru() {
-                self.current_bytes = self.current_bytes.saturating_sub(entry.size_bytes);
-                self.file_times.remove(&path);
-                debug!(
-                    cache = "eviction",
-                    evicted_path = %path,
-                    freed_bytes = entry.size_bytes,
-                    current_bytes = self.current_bytes,
-                    max_bytes = self.max_bytes,
-                    "Cache eviction (LRU)"
-                );
-            } else {
-                // Cache is empty, nothing more to evict
+        while self.current_bytes + needed_bytes > self.max_bytes || self.cache.len() >= MAX_ENTRIES
+        {
+            // Lowest priority first; ties go to the least recently used
+            let victim = self
+                .cache
+                .iter()
+                .rev()
+                .filter(|(path, _)| !self.pinned.contains(*path))
+                .fold(
+                    None,
+                    |lowest: Option<(&String, f64)>, (path, entry)| match lowest {
+                        Some((_, priority)) if priority <= entry.priority => lowest,
+                        _ => Some((path, entry.priority)),
+                    },
+                )
+                .map(|(path, _)| path.clone());
+
+            // Only pinned entries left
+            let Some(path) = victim else { break };
+            let Some(entry) = self.cache.pop(&path) else {
                 break;
-            }
+            };
+            self.current_bytes = self.current_bytes.saturating_sub(entry.size_bytes);
+            self.file_times.remove(&path);
+            self.inflation = self.inflation.max(entry.priority);
+            self.evictions += 1;
+            debug!(
+                cache = "eviction",
+                evicted_path = %path,
+                freed_bytes = entry.size_bytes,
+                load_cost_ms = entry.load_cost.as_millis(),
+                current_bytes = self.current_bytes,
+                max_bytes = self.max_bytes,
+                "Cache eviction"
+            );
         }
     }
 
     /// Get cached audio or decode and cache
-    pub fn get_or_decode(&mut self, file_path: &str) -> Result<Arc<AudioData>, AudioError> {
+    ///
+    /// The lock is only held to look up and to insert, never while decoding, so a
+    /// slow decode (e.g. a preload) doesn't hold up other sounds.
+    pub fn get_or_decode(
+        cache: &Mutex<Self>,
+        file_path: &str,
+    ) -> Result<Arc<AudioData>, AudioError> {
         let start = Instant::now();
 
-        // Check if we have a valid cached version
-        if let Some(entry) = self.cache.get(file_path) {
-            if Self::is_cache_valid(entry, file_path) {
-                // Cache hit - return the cached data
-                let duration_us = start.elapsed().as_micros();
+        let (disk, generation) = {
+            let mut guard = cache.lock().unwrap();
+            if let Some(audio_data) = guard.lookup(file_path) {
                 debug!(
                     cache = "hit",
                     file_path = %file_path,
-                    duration_us = duration_us,
+                    duration_us = start.elapsed().as_micros(),
                     "Audio cache hit"
                 );
-                return Ok(entry.audio_data.clone());
-            } else {
-                // Cache invalid - remove it (will be replaced below)
+                return Ok(audio_data);
+            }
+            (guard.disk.clone(), guard.generation)
+        };
+
+        // Read before decoding, so a change during the decode invalidates the entry
+        let file_modified = Self::get_file_modified(file_path);
+        let (audio_data, from_disk) = load_or_decode(disk.as_deref(), file_path)?;
+        let load_cost = start.elapsed();
+
+        let mut guard = cache.lock().unwrap();
+        if from_disk {
+            guard.disk_hits += 1;
+        }
+        let audio_data = guard.insert(
+            file_path,
+            Arc::new(audio_data),
+            file_modified,
+            load_cost,
+            generation,
+        );
+
+        debug!(
+            cache = "stored",
+            file_path = %file_path,
+            from_disk = from_disk,
+            duration_ms = load_cost.as_millis(),
+            "Audio decoded and cached"
+        );
+        Ok(audio_data)
+    }
+
+    /// Trim decoded audio and render its effect chain, reusing an earlier render
+    ///
+    /// `audio_data` is what [`AudioCache::get_or_decode`] returned for the file.
+    /// Renders are only reused while that decode is still the cached one, so a
+    /// changed or evicted file is rendered afresh. The lock isn't held while
+    /// rendering.
+    pub fn prepare(
+        cache: &Mutex<Self>,
+        file_path: &str,
+        audio_data: Arc<AudioData>,
+        trim_start_ms: Option<u64>,
+        trim_end_ms: Option<u64>,
+        effects: &[EffectConfig],
+    ) -> PreparedSound {
+        // Without effects the trim is applied during playback, nothing to render
+        if effects.is_empty() {
+            return PreparedSound::new(audio_data, trim_start_ms, trim_end_ms, effects);
+        }
+
+        let start = Instant::now();
+        let reused = cache
+            .lock()
+            .unwrap()
+            .lookup_render(file_path, &audio_data, |render| {
+                render.matches(trim_start_ms, trim_end_ms, effects)
+            });
+        if let Some(rendered) = reused {
+            debug!(
+                cache = "render_hit",
+                file_path = %file_path,
+                duration_us = start.elapsed().as_micros(),
+                "Effect render reused"
+            );
+            return PreparedSound {
+                audio_data: rendered,
+                start_frame: None,
+                end_frame: None,
+            };
+        }
+
+        let prepared = PreparedSound::new(audio_data.clone(), trim_start_ms, trim_end_ms, effects);
+        let render_cost = start.elapsed();
+        cache.lock().unwrap().store_render(
+            file_path,
+            &audio_data,
+            RenderedEntry {
+                trim_start_ms,
+                trim_end_ms,
+                effects: effects.to_vec(),
+                audio_data: prepared.audio_data.clone(),
+            },
+            render_cost,
+        );
+        debug!(
+            cache = "render_stored",
+            file_path = %file_path,
+            duration_ms = render_cost.as_millis(),
+            "Effect chain rendered and cached"
+        );
+        prepared
+    }
+
+    /// Render of the cached decode `source` that satisfies `matches`
+    fn lookup_render(
+        &mut self,
+        file_path: &str,
+        source: &Arc<AudioData>,
+        matches: impl Fn(&RenderedEntry) -> bool,
+    ) -> Option<Arc<AudioData>> {
+        let entry = self.cache.get(file_path)?;
+        if !Arc::ptr_eq(&entry.audio_data, source) {
+            return None;
+        }
+        let rendered = entry.rendered.iter().find(|render| matches(render))?;
+        Some(rendered.audio_data.clone())
+    }
+
+    /// Keep a render with its decoded entry, if that is still the one cached
+    fn store_render(
+        &mut self,
+        file_path: &str,
+        source: &Arc<AudioData>,
+        render: RenderedEntry,
+        render_cost: Duration,
+    ) {
+        let Some(entry) = self
+            .cache
+            .peek_mut(file_path)
+            .filter(|entry| Arc::ptr_eq(&entry.audio_data, source))
+        else {
+            return;
+        };
+
+        let mut freed_bytes = 0;
+        if entry.rendered.len() >= MAX_RENDERS_PER_FILE {
+            freed_bytes = Self::estimate_size(&entry.rendered.remove(0).audio_data);
+        }
+        let added_bytes = Self::estimate_size(&render.audio_data);
+        entry.rendered.push(render);
+        entry.size_bytes = entry.size_bytes + added_bytes - freed_bytes;
+        // The entry now also saves a re-render
+        entry.load_cost += render_cost;
+        self.current_bytes = self.current_bytes + added_bytes - freed_bytes;
+
+        if self.current_bytes > self.max_bytes {
+            self.make_space(0);
+        }
+    }
+
+    /// Valid cached audio for a file, counting the hit or miss
+    fn lookup(&mut self, file_path: &str) -> Option<Arc<AudioData>> {
+        let valid = self
+            .cache
+            .peek(file_path)
+            .map(|entry| Self::is_cache_valid(entry, file_path));
+
+        match valid {
+            Some(true) => {
+                self.hits += 1;
+                let entry = self.cache.peek(file_path)?;
+                let priority = self.priority(entry.load_cost, entry.size_bytes);
+                // get_mut also marks the entry as recently used
+                let entry = self.cache.get_mut(file_path)?;
+                entry.priority = priority;
+                return Some(entry.audio_data.clone());
+            }
+            Some(false) => {
+                // Cache invalid - remove it (will be replaced by the caller)
                 if let Some(removed) = self.cache.pop(file_path) {
                     self.current_bytes = self.current_bytes.saturating_sub(removed.size_bytes);
                     self.file_times.remove(file_path);
@@ -130,75 +399,126 @@ impl AudioCache {
                     );
                 }
             }
+            None => {}
         }
+        self.misses += 1;
+        None
+    }
 
-        // Cache miss - decode the file
-        debug!(
-            cache = "miss",
-            file_path = %file_path,
-            "Cache miss, decoding audio"
-        );
-        let audio_data = decode_audio_file(file_path)?;
-        let audio_data = Arc::new(audio_data);
+    /// Store freshly decoded audio, unless the cache changed underneath the decode
+    fn insert(
+        &mut self,
+        file_path: &str,
+        audio_data: Arc<AudioData>,
+        file_modified: Option<SystemTime>,
+        load_cost: Duration,
+        generation: u64,
+    ) -> Arc<AudioData> {
+        if generation != self.generation {
+            return audio_data;
+        }
+        // Another thread decoded the same file meanwhile
+        if let Some(existing) = self.cache.get(file_path) {
+            return existing.audio_data.clone();
+        }
 
         // Calculate size and make space if needed
         let size_bytes = Self::estimate_size(&audio_data);
         self.make_space(size_bytes);
 
-        // Get file modification time for future validation
-        let file_modified = Self::get_file_modified(file_path);
-
-        // Create cache entry
         let entry = CacheEntry {
             audio_data: audio_data.clone(),
             file_modified,
             size_bytes,
+            load_cost,
+            priority: self.priority(load_cost, size_bytes),
+            rendered: Vec::new(),
         };
-
-        // Store in cache
         self.cache.put(file_path.to_string(), entry);
         self.current_bytes += size_bytes;
 
         if let Some(time) = file_modified {
             self.file_times.insert(file_path.to_string(), time);
         }
-
-        let duration_ms = start.elapsed().as_millis();
-        debug!(
-            cache = "stored",
-            file_path = %file_path,
-            size_bytes = size_bytes,
-            duration_ms = duration_ms,
-            "Audio decoded and cached"
-        );
-
-        Ok(audio_data)
+        audio_data
     }
 
-    /// Clear the entire cache
-    pub fn clear(&mut self) {
+    /// Drop memory entries and discard decodes in flight
+    fn drop_entries(&mut self) {
         self.cache.clear();
         self.file_times.clear();
         self.current_bytes = 0;
+        self.generation += 1;
+    }
+
+    /// Clear the entire cache, including the persistent one
+    pub fn clear(&mut self) {
+        self.drop_entries();
+        if let Some(disk) = &self.disk {
+            disk.clear();
+        }
     }
 
     /// Get cache statistics
-    pub fn stats(&self) -> CacheStats {
+    ///
+    /// The persistent cache is measured after releasing the lock, since that
+    /// scans its directory.
+    pub fn stats(cache: &Mutex<Self>) -> CacheStats {
+        let (stats, disk) = {
+            let guard = cache.lock().unwrap();
+            (guard.memory_stats(), guard.disk.clone())
+        };
+        let usage = disk.map(|disk| disk.usage()).unwrap_or_default();
+        CacheStats {
+            disk_entries: usage.entries,
+            disk_bytes: usage.bytes,
+            ..stats
+        }
+    }
+
+    /// Statistics of the memory cache (persistent cache usage left at zero)
+    fn memory_stats(&self) -> CacheStats {
         CacheStats {
             entries: self.cache.len(),
             memory_bytes: self.current_bytes,
             max_memory_bytes: self.max_bytes,
             memory_mb: self.current_bytes / (1024 * 1024),
             max_memory_mb: self.max_bytes / (1024 * 1024),
+            disk_entries: 0,
+            disk_bytes: 0,
+            pinned_entries: self
+                .cache
+                .iter()
+                .filter(|(path, _)| self.pinned.contains(*path))
+                .count(),
+            hits: self.hits,
+            misses: self.misses,
+            disk_hits: self.disk_hits,
+            evictions: self.evictions,
         }
     }
 
     /// Invalidate a specific file from cache
-    pub fn invalidate(&mut self, file_path: &str) {
+    ///
+    /// Persistent entries are removed after releasing the lock.
+    pub fn invalidate(cache: &Mutex<Self>, file_path: &str) {
+        let disk = {
+            let mut guard = cache.lock().unwrap();
+            guard.forget(file_path);
+            guard.disk.clone()
+        };
+        if let Some(disk) = disk {
+            disk.invalidate(file_path);
+        }
+    }
+
+    /// Drop a file from memory and discard its decodes in flight
+    fn forget(&mut self, file_path: &str) {
         if let Some(entry) = self.cache.pop(file_path) {
             self.current_bytes = self.current_bytes.saturating_sub(entry.size_bytes);
             self.file_times.remove(file_path);
         }
+        self.generation += 1;
     }
 }
 
@@ -208,6 +528,45 @@ impl Default for AudioCache {
     }
 }
 
+/// Read the persistent cache, or decode (and pre-resample) and store the result
+///
+/// Returns whether the audio came from the persistent cache.
+fn load_or_decode(
+    disk: Option<&DiskCache>,
+    file_path: &str,
+) -> Result<(AudioData, bool), AudioError> {
+    if let Some(audio_data) = disk.and_then(|disk| disk.load(file_path)) {
+        debug!(
+            cache = "disk_hit",
+            file_path = %file_path,
+            "Audio loaded from disk cache"
+        );
+        return Ok((audio_data, true));
+    }
+
+    // Cache miss - decode the file
+    debug!(
+        cache = "miss",
+        file_path = %file_path,
+        "Cache miss, decoding audio"
+    );
+    let mut audio_data = decode_audio_file(file_path)?;
+
+    if let Some(disk) = disk {
+        if let Some(sample_rate) = disk.sample_rate() {
+            let options = EditOptions {
+                sample_rate: Some(sample_rate),
+                ..Default::default()
+            };
+            audio_data = apply_edits(&audio_data, &options);
+        }
+        if let Err(e) = disk.store(file_path, &audio_data) {
+            warn!(file_path = %file_path, error = %e, "Failed to write disk cache entry");
+        }
+    }
+    Ok((audio_data, false))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,8 +601,7 @@ mod tests {
 
     #[test]
     fn test_cache_stats_initial() {
-        let cache = AudioCache::new(100);
-        let stats = cache.stats();
+        let stats = AudioCache::stats(&Mutex::new(AudioCache::new(100)));
         assert_eq!(stats.entries, 0);
         assert_eq!(stats.memory_bytes, 0);
         assert_eq!(stats.max_memory_mb, 100);
@@ -258,6 +616,9 @@ mod tests {
             audio_data: audio,
             file_modified: None,
             size_bytes: 4000,
+            load_cost: Duration::ZERO,
+            priority: 0.0,
+            rendered: Vec::new(),
         };
         cache.cache.put("test.mp3".to_string(), entry);
         cache.current_bytes = 4000;
@@ -276,22 +637,83 @@ mod tests {
             audio_data: audio,
             file_modified: None,
             size_bytes: 4000,
+            load_cost: Duration::ZERO,
+            priority: 0.0,
+            rendered: Vec::new(),
         };
         cache.cache.put("test.mp3".to_string(), entry);
         cache.current_bytes = 4000;
 
-        cache.invalidate("test.mp3");
+        let cache = Mutex::new(cache);
+        AudioCache::invalidate(&cache, "test.mp3");
 
+        let cache = cache.into_inner().unwrap();
         assert_eq!(cache.cache.len(), 0);
         assert_eq!(cache.current_bytes, 0);
     }
 
     #[test]
     fn test_cache_invalidate_nonexistent() {
-        let mut cache = AudioCache::new(100);
+        let cache = Mutex::new(AudioCache::new(100));
         // Should not panic
-        cache.invalidate("nonexistent.mp3");
-        assert_eq!(cache.cache.len(), 0);
+        AudioCache::invalidate(&cache, "nonexistent.mp3");
+        assert_eq!(cache.lock().unwrap().cache.len(), 0);
+    }
+
+    #[test]
+    fn test_disk_cache_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("tone.wav");
+        crate::audio::write_audio(
+            &create_test_audio(4800),
+            &source,
+            crate::audio::ExportFormat::Wav,
+        )
+        .unwrap();
+        let source = source.to_string_lossy().to_string();
+        let disk = || Some(DiskCache::new(dir.path().join("decoded"), 10).unwrap());
+
+        let cache = Mutex::new(AudioCache::new(10));
+        cache.lock().unwrap().set_disk_cache(disk());
+        let decoded = AudioCache::get_or_decode(&cache, &source).unwrap();
+        assert_eq!(AudioCache::stats(&cache).disk_entries, 1);
+
+        // A fresh memory cache (next app start) is served from disk
+        let restarted = Mutex::new(AudioCache::new(10));
+        restarted.lock().unwrap().set_disk_cache(disk());
+        let loaded = AudioCache::get_or_decode(&restarted, &source).unwrap();
+        assert_eq!(loaded.samples, decoded.samples);
+        assert_eq!(AudioCache::stats(&restarted).disk_hits, 1);
+
+        AudioCache::invalidate(&restarted, &source);
+        assert_eq!(AudioCache::stats(&restarted).disk_entries, 0);
+    }
+
+    #[test]
+    fn test_disk_cache_pre_resamples() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("tone.wav");
+        crate::audio::write_audio(
+            &create_test_audio(9600),
+            &source,
+            crate::audio::ExportFormat::Wav,
+        )
+        .unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        let cache = Mutex::new(AudioCache::new(10));
+        let decoded = AudioCache::get_or_decode(&cache, &source).unwrap();
+        assert_eq!(decoded.sample_rate, 48000);
+
+        // Changing the rate drops memory entries decoded at the old rate
+        let disk = DiskCache::new(dir.path().join("decoded"), 10)
+            .unwrap()
+            .with_sample_rate(Some(44100));
+        cache.lock().unwrap().set_disk_cache(Some(disk));
+        assert_eq!(AudioCache::stats(&cache).entries, 0);
+        let resampled = AudioCache::get_or_decode(&cache, &source).unwrap();
+        assert_eq!(resampled.sample_rate, 44100);
+        assert_eq!(resampled.samples.len(), 4410 * 2);
     }
 
     #[test]
@@ -305,6 +727,9 @@ mod tests {
             audio_data: audio1,
             file_modified: None,
             size_bytes: 500 * 1024,
+            load_cost: Duration::ZERO,
+            priority: 0.0,
+            rendered: Vec::new(),
         };
         cache.cache.put("first.mp3".to_string(), entry1);
         cache.current_bytes = 500 * 1024;
@@ -315,6 +740,9 @@ mod tests {
             audio_data: audio2,
             file_modified: None,
             size_bytes: 500 * 1024,
+            load_cost: Duration::ZERO,
+            priority: 0.0,
+            rendered: Vec::new(),
         };
         cache.cache.put("second.mp3".to_string(), entry2);
         cache.current_bytes = 1024 * 1024;
@@ -325,6 +753,163 @@ mod tests {
         assert!(cache.current_bytes <= one_mb);
         assert!(cache.cache.get("first.mp3").is_none());
     }
+
+    /// Insert a 400 KB entry that took `cost_ms` to decode
+    fn insert_entry(cache: &mut AudioCache, path: &str, cost_ms: u64) {
+        let audio = Arc::new(create_test_audio(100 * 1024));
+        let generation = cache.generation;
+        cache.insert(
+            path,
+            audio,
+            None,
+            Duration::from_millis(cost_ms),
+            generation,
+        );
+    }
+
+    #[test]
+    fn test_eviction_prefers_cheap_entries() {
+        let mut cache = AudioCache::new(1);
+        insert_entry(&mut cache, "slow.mp3", 50);
+        insert_entry(&mut cache, "fast.wav", 1);
+
+        // The slow decode is older but costs more to redo
+        insert_entry(&mut cache, "new.wav", 1);
+        assert!(cache.cache.contains("slow.mp3"));
+        assert!(!cache.cache.contains("fast.wav"));
+        assert_eq!(cache.memory_stats().evictions, 1);
+    }
+
+    #[test]
+    fn test_eviction_ages_unused_entries() {
+        let mut cache = AudioCache::new(1);
+        insert_entry(&mut cache, "slow.mp3", 5);
+
+        // Evictions raise the floor until the idle slow entry goes too
+        for i in 0..10 {
+            insert_entry(&mut cache, &format!("fast{}.wav", i), 4);
+        }
+        assert!(!cache.cache.contains("slow.mp3"));
+        assert!(cache.current_bytes <= cache.max_bytes);
+    }
+
+    #[test]
+    fn test_pinned_entries_are_never_evicted() {
+        let mut cache = AudioCache::new(1);
+        cache.set_pinned(["bound.mp3".to_string()]);
+        insert_entry(&mut cache, "bound.mp3", 0);
+        for i in 0..5 {
+            insert_entry(&mut cache, &format!("other{}.wav", i), 100);
+        }
+        assert!(cache.cache.contains("bound.mp3"));
+        assert_eq!(cache.memory_stats().pinned_entries, 1);
+
+        // With only pinned entries left the budget may be exceeded
+        cache.set_pinned(
+            ["bound.mp3", "other4.wav"]
+                .iter()
+                .map(|path| path.to_string()),
+        );
+        insert_entry(&mut cache, "big.wav", 0);
+        assert!(cache.cache.contains("bound.mp3"));
+        assert!(cache.cache.contains("other4.wav"));
+        assert!(!cache.has_room());
+    }
+
+    #[test]
+    fn test_stale_decode_is_not_stored() {
+        let mut cache = AudioCache::new(10);
+        let generation = cache.generation;
+        cache.forget("a.wav");
+
+        let audio = Arc::new(create_test_audio(100));
+        let returned = cache.insert("a.wav", audio.clone(), None, Duration::ZERO, generation);
+        assert!(Arc::ptr_eq(&returned, &audio));
+        assert_eq!(cache.cache.len(), 0);
+    }
+
+    #[test]
+    fn test_effect_renders_are_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("tone.wav");
+        crate::audio::write_audio(
+            &create_test_audio(4800),
+            &source,
+            crate::audio::ExportFormat::Wav,
+        )
+        .unwrap();
+        let source = source.to_string_lossy().to_string();
+        let louder = [EffectConfig::Gain { gain_db: 6.0 }];
+        let quieter = [EffectConfig::Gain { gain_db: -6.0 }];
+
+        let cache = Mutex::new(AudioCache::new(10));
+        let prepare = |trim_end_ms: Option<u64>, effects: &[EffectConfig]| {
+            let decoded = AudioCache::get_or_decode(&cache, &source).unwrap();
+            AudioCache::prepare(&cache, &source, decoded, None, trim_end_ms, effects).audio_data
+        };
+
+        let first = prepare(None, &louder);
+        assert!(Arc::ptr_eq(&first, &prepare(None, &louder)));
+        assert!(!Arc::ptr_eq(&first, &prepare(None, &quieter)));
+        assert!(!Arc::ptr_eq(&first, &prepare(Some(25), &louder)));
+        // The decode, two full-length renders and a half-length one
+        assert_eq!(
+            AudioCache::stats(&cache).memory_bytes,
+            (3 * 4800 + 2400) * BYTES_PER_SAMPLE
+        );
+
+        // A fresh decode is rendered afresh
+        AudioCache::invalidate(&cache, &source);
+        assert!(!Arc::ptr_eq(&first, &prepare(None, &louder)));
+    }
+
+    #[test]
+    fn test_renders_per_file_are_capped() {
+        let mut cache = AudioCache::new(10);
+        insert_entry(&mut cache, "a.wav", 0);
+        let source = cache.cache.peek("a.wav").unwrap().audio_data.clone();
+        for i in 0..MAX_RENDERS_PER_FILE + 2 {
+            let render = RenderedEntry {
+                trim_start_ms: Some(i as u64),
+                trim_end_ms: None,
+                effects: Vec::new(),
+                audio_data: Arc::new(create_test_audio(100)),
+            };
+            cache.store_render("a.wav", &source, render, Duration::ZERO);
+        }
+
+        let entry = cache.cache.peek("a.wav").unwrap();
+        assert_eq!(entry.rendered.len(), MAX_RENDERS_PER_FILE);
+        assert_eq!(entry.rendered[0].trim_start_ms, Some(2));
+        let expected = (100 * 1024 + MAX_RENDERS_PER_FILE * 100) * BYTES_PER_SAMPLE;
+        assert_eq!(entry.size_bytes, expected);
+        assert_eq!(cache.current_bytes, expected);
+    }
+
+    #[test]
+    fn test_hit_and_miss_counters() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("tone.wav");
+        crate::audio::write_audio(
+            &create_test_audio(4800),
+            &source,
+            crate::audio::ExportFormat::Wav,
+        )
+        .unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        let cache = Mutex::new(AudioCache::new(10));
+        let first = AudioCache::get_or_decode(&cache, &source).unwrap();
+        let second = AudioCache::get_or_decode(&cache, &source).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(AudioCache::get_or_decode(&cache, "missing.wav").is_err());
+
+        let stats = AudioCache::stats(&cache);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.disk_hits, 0);
+        assert_eq!(stats.entries, 1);
+    }
 }
 
 /// Cache statistics for monitoring
@@ -340,4 +925,18 @@ pub struct CacheStats {
     pub memory_mb: usize,
     /// Maximum memory limit in MB
     pub max_memory_mb: usize,
+    /// Number of entries in the persistent cache
+    pub disk_entries: usize,
+    /// Disk usage of the persistent cache in bytes
+    pub disk_bytes: u64,
+    /// Number of cached entries that are pinned (never evicted)
+    pub pinned_entries: usize,
+    /// Lookups served from memory
+    pub hits: u64,
+    /// Lookups that had to load from disk or decode
+    pub misses: u64,
+    /// Misses served by the persistent cache instead of decoding
+    pub disk_hits: u64,
+    /// Entries evicted to make room
+    pub evictions: u64,
 }
@@ -0,0 +1,456 @@
+//! Persistent decode cache
+//!
+//! Stores decoded PCM on disk so sounds don't have to be decoded again after a
+//! restart. Entries are keyed by source path, modification time and size (plus
+//! the pre-resample rate, if any) and read back through a memory map.
+//!
+//! File layout (little endian): 8-byte magic, sample rate (u32), channels (u16),
+//! 2 bytes padding, frame count (u64), then interleaved f32 samples.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use super::{AudioData, AudioError};
+
+/// Magic bytes and format version of cache files
+const MAGIC: &[u8; 8] = b"SDPCM\0\0\x01";
+
+/// Size of the header before the samples
+const HEADER_LEN: usize = 24;
+
+/// Extension of cache files
+const EXTENSION: &str = "pcm";
+
+/// Numbers temp files so concurrent stores of one entry don't share a file
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Default disk cache size cap in MB
+pub const DEFAULT_DISK_CACHE_MB: u32 = 1024;
+
+/// Disk usage of the decode cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskCacheUsage {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// Directory of decoded audio files with a size cap
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Pre-resample decoded audio to this rate before storing it
+    sample_rate: Option<u32>,
+}
+
+impl DiskCache {
+    /// Open (and create) a cache directory holding at most `max_mb` MB
+    pub fn new(dir: PathBuf, max_mb: u32) -> Result<Self, AudioError> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            max_bytes: max_mb as u64 * 1024 * 1024,
+            sample_rate: None,
+        })
+    }
+
+    /// Store audio resampled to `sample_rate` (e.g. the output device rate)
+    pub fn with_sample_rate(mut self, sample_rate: Option<u32>) -> Self {
+        self.sample_rate = sample_rate.filter(|&rate| rate > 0);
+        self
+    }
+
+    /// Rate stored audio is resampled to, if any
+    pub fn sample_rate(&self) -> Option<u32> {
+        self.sample_rate
+    }
+
+    /// Load the cached decode of a file, if present and the file is unchanged
+    pub fn load(&self, file_path: &str) -> Option<AudioData> {
+        let entry_path = self.entry_path(file_path)?;
+        // Opened writable so the last-use time can be updated
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .open(&entry_path)
+            .ok()?;
+
+        // Safety: entries are only ever replaced by renaming a new file over
+        // them, never modified in place, so the mapping stays valid.
+        let map = match unsafe { Mmap::map(&file) } {
+            Ok(map) => map,
+            Err(e) => {
+                warn!(path = %entry_path.display(), error = %e, "Failed to map decode cache entry");
+                return None;
+            }
+        };
+
+        match parse_entry(&map) {
+            Some(audio_data) => {
+                // Touch the entry so eviction drops the least recently used first
+                let _ = file.set_modified(SystemTime::now());
+                Some(audio_data)
+            }
+            None => {
+                warn!(path = %entry_path.display(), "Corrupt decode cache entry, removing");
+                drop(map);
+                drop(file);
+                let _ = fs::remove_file(&entry_path);
+                None
+            }
+        }
+    }
+
+    /// Write the decode of a file, replacing older versions of it
+    pub fn store(&self, file_path: &str, audio_data: &AudioData) -> Result<(), AudioError> {
+        let Some(entry_path) = self.entry_path(file_path) else {
+            return Ok(());
+        };
+        let bytes = HEADER_LEN as u64 + audio_data.samples.len() as u64 * 4;
+        if bytes > self.max_bytes {
+            return Ok(());
+        }
+
+        // Write next to the entry and rename, so readers never see a partial file.
+        // The same file can be decoded twice at once (preload and trigger).
+        let temp_path = entry_path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result =
+            write_entry(&temp_path, audio_data).and_then(|()| fs::rename(&temp_path, &entry_path));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+
+        self.remove_versions(file_path, Some(&entry_path));
+        self.enforce_cap();
+        debug!(
+            file_path = %file_path,
+            bytes = bytes,
+            "Decode stored in disk cache"
+        );
+        Ok(())
+    }
+
+    /// Remove every cached version of a file
+    pub fn invalidate(&self, file_path: &str) {
+        self.remove_versions(file_path, None);
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) {
+        for (path, _, _) in self.entries() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Number and total size of the stored entries
+    pub fn usage(&self) -> DiskCacheUsage {
+        self.entries()
+            .iter()
+            .fold(DiskCacheUsage::default(), |usage, (_, bytes, _)| {
+                DiskCacheUsage {
+                    entries: usage.entries + 1,
+                    bytes: usage.bytes + bytes,
+                }
+            })
+    }
+
+    /// Cache file for the current version of a source file (None if it can't be read)
+    fn entry_path(&self, file_path: &str) -> Option<PathBuf> {
+        let metadata = fs::metadata(file_path).ok()?;
+        let modified = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+
+        let mut version = Sha256::new();
+        version.update(modified.to_le_bytes());
+        version.update(metadata.len().to_le_bytes());
+        version.update(self.sample_rate.unwrap_or(0).to_le_bytes());
+        Some(self.dir.join(format!(
+            "{}-{}.{}",
+            path_key(file_path),
+            hex_prefix(&version.finalize()),
+            EXTENSION
+        )))
+    }
+
+    /// Remove the entries of a file except `keep`
+    fn remove_versions(&self, file_path: &str, keep: Option<&Path>) {
+        let prefix = format!("{}-", path_key(file_path));
+        for (path, _, _) in self.entries() {
+            let stale = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix));
+            if stale && Some(path.as_path()) != keep {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    /// Evict least recently used entries until the cache fits its cap
+    fn enforce_cap(&self) {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, bytes, _)| bytes).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+
+        entries.sort_by_key(|(_, _, used)| *used);
+        for (path, bytes, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total -= bytes;
+                debug!(path = %path.display(), freed_bytes = bytes, "Disk cache eviction");
+            }
+        }
+    }
+
+    /// Cache files with their size and last use
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(read_dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        read_dir
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some(EXTENSION))
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                let used = metadata.modified().unwrap_or(UNIX_EPOCH);
+                Some((path, metadata.len(), used))
+            })
+            .collect()
+    }
+}
+
+/// Stable short key of a source path
+fn path_key(file_path: &str) -> String {
+    hex_prefix(&Sha256::digest(file_path.as_bytes()))
+}
+
+/// First 8 bytes of a digest as hex
+fn hex_prefix(digest: &[u8]) -> String {
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn write_entry(path: &Path, audio_data: &AudioData) -> std::io::Result<()> {
+    let channels = audio_data.channels.max(1);
+    let frames = (audio_data.samples.len() / channels as usize) as u64;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&audio_data.sample_rate.to_le_bytes())?;
+    writer.write_all(&channels.to_le_bytes())?;
+    writer.write_all(&[0, 0])?;
+    writer.write_all(&frames.to_le_bytes())?;
+    for sample in &audio_data.samples[..frames as usize * channels as usize] {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()
+}
+
+fn parse_entry(bytes: &[u8]) -> Option<AudioData> {
+    let header = bytes.get(..HEADER_LEN)?;
+    if &header[..8] != MAGIC {
+        return None;
+    }
+    let sample_rate = u32::from_le_bytes(header[8..12].try_into().ok()?);
+    let channels = u16::from_le_bytes(header[12..14].try_into().ok()?);
+    let frames = u64::from_le_bytes(header[16..24].try_into().ok()?);
+
+    let body = &bytes[HEADER_LEN..];
+    let sample_count = (frames as usize).checked_mul(channels as usize)?;
+    if sample_rate == 0 || channels == 0 || body.len() != sample_count.checked_mul(4)? {
+        return None;
+    }
+
+    let samples = body
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    Some(AudioData {
+        samples,
+        sample_rate,
+        channels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(samples: usize) -> AudioData {
+        AudioData {
+            samples: (0..samples).map(|i| (i as f32 * 0.01).sin()).collect(),
+            sample_rate: 44100,
+            channels: 2,
+        }
+    }
+
+    fn source(dir: &Path, name: &str, contents: &[u8]) -> String {
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_store_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("cache"), 10).unwrap();
+        let file = source(dir.path(), "a.mp3", b"mp3");
+
+        assert!(cache.load(&file).is_none());
+        let original = audio(2000);
+        cache.store(&file, &original).unwrap();
+
+        let loaded = cache.load(&file).unwrap();
+        assert_eq!(loaded.samples, original.samples);
+        assert_eq!(loaded.sample_rate, 44100);
+        assert_eq!(loaded.channels, 2);
+        assert_eq!(
+            cache.usage(),
+            DiskCacheUsage {
+                entries: 1,
+                bytes: HEADER_LEN as u64 + 2000 * 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_concurrent_stores_of_one_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = std::sync::Arc::new(DiskCache::new(dir.path().join("cache"), 10).unwrap());
+        let file = source(dir.path(), "a.mp3", b"mp3");
+        let original = audio(20000);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let (cache, file, original) = (cache.clone(), file.clone(), original.clone());
+                std::thread::spawn(move || cache.store(&file, &original))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap().unwrap();
+        }
+
+        assert_eq!(cache.load(&file).unwrap().samples, original.samples);
+        // Only the entry is left, no temp files
+        assert_eq!(fs::read_dir(dir.path().join("cache")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_changed_source_misses_and_replaces_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("cache"), 10).unwrap();
+        let file = source(dir.path(), "a.mp3", b"mp3");
+        cache.store(&file, &audio(100)).unwrap();
+
+        // Size change alters the key even if the mtime resolution is coarse
+        fs::write(&file, b"longer mp3").unwrap();
+        assert!(cache.load(&file).is_none());
+
+        cache.store(&file, &audio(200)).unwrap();
+        assert_eq!(cache.usage().entries, 1);
+        assert_eq!(cache.load(&file).unwrap().samples.len(), 200);
+    }
+
+    #[test]
+    fn test_sample_rate_is_part_of_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = source(dir.path(), "a.mp3", b"mp3");
+        let plain = DiskCache::new(dir.path().join("cache"), 10).unwrap();
+        plain.store(&file, &audio(100)).unwrap();
+
+        let resampled = DiskCache::new(dir.path().join("cache"), 10)
+            .unwrap()
+            .with_sample_rate(Some(48000));
+        assert!(resampled.load(&file).is_none());
+    }
+
+    #[test]
+    fn test_cap_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("cache"), 1).unwrap();
+        let a = source(dir.path(), "a.mp3", b"a");
+        let b = source(dir.path(), "b.mp3", b"b");
+        let c = source(dir.path(), "c.mp3", b"c");
+
+        // Three 400 KB entries don't fit in 1 MB
+        let big = audio(100 * 1024);
+        cache.store(&a, &big).unwrap();
+        cache.store(&b, &big).unwrap();
+        let old = SystemTime::now() - std::time::Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(cache.entry_path(&a).unwrap())
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        File::options()
+            .write(true)
+            .open(cache.entry_path(&b).unwrap())
+            .unwrap()
+            .set_modified(old - std::time::Duration::from_secs(60))
+            .unwrap();
+        // Loading a marks it as recently used, so b is evicted
+        assert!(cache.load(&a).is_some());
+        cache.store(&c, &big).unwrap();
+
+        assert!(cache.usage().bytes <= 1024 * 1024);
+        assert!(cache.load(&a).is_some());
+        assert!(cache.load(&b).is_none());
+        assert!(cache.load(&c).is_some());
+
+        // Entries larger than the whole cache are not stored
+        cache.store(&a, &audio(300 * 1024)).unwrap();
+        assert!(cache.usage().bytes <= 1024 * 1024);
+    }
+
+    #[test]
+    fn test_corrupt_entry_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("cache"), 10).unwrap();
+        let file = source(dir.path(), "a.mp3", b"mp3");
+        cache.store(&file, &audio(100)).unwrap();
+
+        let entry = cache.entry_path(&file).unwrap();
+        let bytes = fs::read(&entry).unwrap();
+        fs::write(&entry, &bytes[..bytes.len() - 3]).unwrap();
+
+        assert!(cache.load(&file).is_none());
+        assert!(!entry.exists());
+    }
+
+    #[test]
+    fn test_invalidate_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("cache"), 10).unwrap();
+        let a = source(dir.path(), "a.mp3", b"a");
+        let b = source(dir.path(), "b.mp3", b"b");
+        cache.store(&a, &audio(100)).unwrap();
+        cache.store(&b, &audio(100)).unwrap();
+
+        cache.invalidate(&a);
+        assert!(cache.load(&a).is_none());
+        assert!(cache.load(&b).is_some());
+
+        cache.clear();
+        assert_eq!(cache.usage(), DiskCacheUsage::default());
+    }
+}
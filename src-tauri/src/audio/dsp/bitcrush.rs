@@ -0,0 +1,77 @@
+//! Bitcrusher (bit depth reduction and sample-rate decimation)
+
+use super::Effect;
+
+/// Lo-fi effect for meme sounds
+pub struct Bitcrusher {
+    /// Quantization step size
+    step: f32,
+    /// Hold each sampled frame for this many frames
+    downsample: usize,
+    channels: usize,
+}
+
+impl Bitcrusher {
+    /// Create a bitcrusher with 1-16 bits and a 1-64x downsample factor
+    pub fn new(bits: u8, downsample: u32, channels: usize) -> Self {
+        let bits = bits.clamp(1, 16) as i32;
+        Self {
+            step: 2.0 / 2f32.powi(bits),
+            downsample: downsample.clamp(1, 64) as usize,
+            channels: channels.max(1),
+        }
+    }
+}
+
+impl Effect for Bitcrusher {
+    fn name(&self) -> &'static str {
+        "bitcrusher"
+    }
+
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        let mut held = vec![0.0f32; self.channels];
+        for (index, frame) in samples.chunks_mut(self.channels).enumerate() {
+            if index % self.downsample == 0 {
+                for (ch, sample) in frame.iter().enumerate() {
+                    held[ch] = ((sample / self.step).round() * self.step).clamp(-1.0, 1.0);
+                }
+            }
+            frame.copy_from_slice(&held[..frame.len()]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_bitcrusher_quantizes() {
+        let mut samples = sine(440.0, 48000, 4800, 1, 0.9);
+        Bitcrusher::new(2, 1, 1).process(&mut samples);
+
+        // 2 bits => step 0.5, so only multiples of 0.5 remain
+        for s in &samples {
+            assert!(((s / 0.5).round() * 0.5 - s).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_bitcrusher_downsample_holds_frames() {
+        let mut samples: Vec<f32> = (0..16).map(|i| i as f32 / 32.0).collect();
+        Bitcrusher::new(16, 4, 2).process(&mut samples);
+
+        // Stereo frames 0-3 all hold frame 0, frames 4-7 hold frame 4
+        assert_eq!(&samples[0..8], &[samples[0], samples[1]].repeat(4)[..]);
+        assert_eq!(&samples[8..16], &[samples[8], samples[9]].repeat(4)[..]);
+        assert_ne!(samples[0], samples[8]);
+    }
+
+    #[test]
+    fn test_bitcrusher_preserves_length() {
+        let mut samples = sine(440.0, 48000, 1000, 2, 0.5);
+        Bitcrusher::new(8, 3, 2).process(&mut samples);
+        assert_eq!(samples.len(), 2000);
+    }
+}
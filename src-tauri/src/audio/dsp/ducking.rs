@@ -0,0 +1,188 @@
+//! Envelope following and ducking for sidechain control
+//!
+//! [`EnvelopeFollower`] turns a signal into a smoothed level used to detect speech
+//! or activity. [`Ducker`] lowers a signal by a fixed depth while a key is active,
+//! ramping with separate attack and release times so the gain never jumps.
+
+use super::db_to_linear;
+use super::dynamics::time_coeff;
+
+/// Peak envelope follower with separate attack and release
+pub struct EnvelopeFollower {
+    attack_coeff: f32,
+    release_coeff: f32,
+    channels: usize,
+    envelope: f32,
+}
+
+impl EnvelopeFollower {
+    pub fn new(attack_ms: f32, release_ms: f32, sample_rate: u32, channels: usize) -> Self {
+        Self {
+            attack_coeff: time_coeff(attack_ms.clamp(0.0, 1000.0), sample_rate),
+            release_coeff: time_coeff(release_ms.clamp(1.0, 5000.0), sample_rate),
+            channels: channels.max(1),
+            envelope: 0.0,
+        }
+    }
+
+    /// Feed an interleaved buffer and return the envelope after its last frame
+    pub fn process(&mut self, samples: &[f32]) -> f32 {
+        for frame in samples.chunks(self.channels) {
+            let peak = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            let coeff = if peak > self.envelope {
+                self.attack_coeff
+            } else {
+                self.release_coeff
+            };
+            self.envelope = peak + coeff * (self.envelope - peak);
+        }
+        self.envelope
+    }
+}
+
+/// Gain stage that ducks a signal while a key is active
+pub struct Ducker {
+    depth: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+    channels: usize,
+    gain: f32,
+}
+
+impl Ducker {
+    /// Create a ducker that attenuates by `depth_db` (negative) while keyed
+    pub fn new(
+        depth_db: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        sample_rate: u32,
+        channels: usize,
+    ) -> Self {
+        let mut ducker = Self {
+            depth: 1.0,
+            attack_coeff: 0.0,
+            release_coeff: 0.0,
+            channels: channels.max(1),
+            gain: 1.0,
+        };
+        ducker.set_params(depth_db, attack_ms, release_ms, sample_rate);
+        ducker
+    }
+
+    /// Update depth and timing without resetting the current gain
+    pub fn set_params(&mut self, depth_db: f32, attack_ms: f32, release_ms: f32, sample_rate: u32) {
+        self.depth = db_to_linear(depth_db.clamp(-60.0, 0.0));
+        self.attack_coeff = time_coeff(attack_ms.clamp(0.0, 1000.0), sample_rate);
+        self.release_coeff = time_coeff(release_ms.clamp(1.0, 5000.0), sample_rate);
+    }
+
+    /// Current gain (1.0 = not ducking)
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Apply the ducking gain to an interleaved buffer in place
+    pub fn process(&mut self, samples: &mut [f32], keyed: bool) {
+        let (target, coeff) = if keyed {
+            (self.depth, self.attack_coeff)
+        } else {
+            (1.0, self.release_coeff)
+        };
+
+        // Fast path: settled at unity and nothing to do
+        if !keyed && self.gain >= 0.9999 {
+            self.gain = 1.0;
+            return;
+        }
+
+        for frame in samples.chunks_mut(self.channels) {
+            self.gain = target + coeff * (self.gain - target);
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::linear_to_db;
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_envelope_tracks_level() {
+        let mut follower = EnvelopeFollower::new(1.0, 100.0, 48000, 1);
+        let level = follower.process(&sine(440.0, 48000, 4800, 1, 0.5));
+        // Slow release holds close to the peak between cycles
+        assert!(level > 0.45 && level <= 0.5);
+    }
+
+    #[test]
+    fn test_envelope_releases_on_silence() {
+        let mut follower = EnvelopeFollower::new(1.0, 50.0, 48000, 2);
+        follower.process(&sine(440.0, 48000, 4800, 2, 0.5));
+        // 250ms of silence is five release time constants
+        let level = follower.process(&vec![0.0; 12000 * 2]);
+        assert!(level < 0.5 * 0.01);
+    }
+
+    #[test]
+    fn test_envelope_attack_is_gradual() {
+        let mut follower = EnvelopeFollower::new(10.0, 100.0, 48000, 1);
+        // One time constant of a constant level reaches ~63%
+        let level = follower.process(&vec![1.0; 480]);
+        assert!((level - 0.632).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_ducker_passthrough_when_idle() {
+        let input = sine(440.0, 48000, 4800, 2, 0.5);
+        let mut samples = input.clone();
+        let mut ducker = Ducker::new(-12.0, 10.0, 200.0, 48000, 2);
+        ducker.process(&mut samples, false);
+        assert_eq!(samples, input);
+        assert_eq!(ducker.gain(), 1.0);
+    }
+
+    #[test]
+    fn test_ducker_reaches_depth_while_keyed() {
+        let mut samples = vec![1.0f32; 48000];
+        let mut ducker = Ducker::new(-12.0, 10.0, 200.0, 48000, 1);
+        ducker.process(&mut samples, true);
+
+        assert!((linear_to_db(ducker.gain()) + 12.0).abs() < 0.1);
+        assert!((linear_to_db(samples[47999]) + 12.0).abs() < 0.1);
+        // The first samples are still close to unity (no click)
+        assert!(samples[0] > 0.99);
+    }
+
+    #[test]
+    fn test_ducker_releases_after_key() {
+        let mut ducker = Ducker::new(-12.0, 5.0, 50.0, 48000, 1);
+        ducker.process(&mut vec![1.0f32; 4800], true);
+        assert!(ducker.gain() < 0.3);
+
+        let mut tail = vec![1.0f32; 24000];
+        ducker.process(&mut tail, false);
+        assert!(ducker.gain() > 0.99);
+        // Release ramps up monotonically
+        assert!(tail.windows(2).all(|w| w[1] >= w[0]));
+    }
+
+    #[test]
+    fn test_ducker_streaming_matches_single_block() {
+        let input = sine(440.0, 48000, 9600, 2, 0.8);
+
+        let mut whole = input.clone();
+        Ducker::new(-9.0, 10.0, 100.0, 48000, 2).process(&mut whole, true);
+
+        let mut blocks = input.clone();
+        let mut ducker = Ducker::new(-9.0, 10.0, 100.0, 48000, 2);
+        for chunk in blocks.chunks_mut(480 * 2) {
+            ducker.process(chunk, true);
+        }
+
+        assert_eq!(whole, blocks);
+    }
+}
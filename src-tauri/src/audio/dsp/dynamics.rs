@@ -0,0 +1,134 @@
+//! Dynamics processing (compressor/limiter)
+
+use super::{db_to_linear, linear_to_db, Effect};
+
+/// Time constant to one-pole smoothing coefficient
+pub(crate) fn time_coeff(time_ms: f32, sample_rate: u32) -> f32 {
+    let samples = (time_ms.max(0.01) / 1000.0) * sample_rate as f32;
+    (-1.0 / samples).exp()
+}
+
+/// Feed-forward peak compressor with stereo-linked detection.
+///
+/// All channels of a frame share one gain value so the stereo image does not
+/// shift when only one side is loud. A very high ratio turns it into a limiter.
+pub struct Compressor {
+    threshold_db: f32,
+    /// 1 - 1/ratio, the fraction of overshoot that is removed
+    slope: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+    makeup: f32,
+    channels: usize,
+    /// Current smoothed gain reduction in dB (positive = attenuating)
+    reduction_db: f32,
+}
+
+impl Compressor {
+    pub fn new(
+        threshold_db: f32,
+        ratio: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        makeup_db: f32,
+        sample_rate: u32,
+        channels: usize,
+    ) -> Self {
+        let ratio = ratio.clamp(1.0, 100.0);
+        Self {
+            threshold_db: threshold_db.clamp(-60.0, 0.0),
+            slope: 1.0 - 1.0 / ratio,
+            attack_coeff: time_coeff(attack_ms.clamp(0.0, 500.0), sample_rate),
+            release_coeff: time_coeff(release_ms.clamp(1.0, 5000.0), sample_rate),
+            makeup: db_to_linear(makeup_db.clamp(0.0, 24.0)),
+            channels: channels.max(1),
+            reduction_db: 0.0,
+        }
+    }
+
+    /// Current gain reduction in dB (0.0 when idle)
+    #[allow(dead_code)]
+    pub fn reduction_db(&self) -> f32 {
+        self.reduction_db
+    }
+
+    /// Compress an interleaved buffer in place
+    pub fn process_interleaved(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_mut(self.channels) {
+            let level = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            let over_db = linear_to_db(level) - self.threshold_db;
+            let target = if over_db > 0.0 {
+                over_db * self.slope
+            } else {
+                0.0
+            };
+
+            let coeff = if target > self.reduction_db {
+                self.attack_coeff
+            } else {
+                self.release_coeff
+            };
+            self.reduction_db = target + coeff * (self.reduction_db - target);
+
+            let gain = db_to_linear(-self.reduction_db) * self.makeup;
+            for sample in frame.iter_mut() {
+                *sample *= gain;
+            }
+        }
+    }
+}
+
+impl Effect for Compressor {
+    fn name(&self) -> &'static str {
+        "compressor"
+    }
+
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        self.process_interleaved(samples);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_quiet_signal_untouched() {
+        let input = sine(440.0, 48000, 4800, 2, 0.1); // -20 dBFS
+        let mut output = input.clone();
+        Compressor::new(-6.0, 4.0, 5.0, 50.0, 0.0, 48000, 2).process(&mut output);
+        for (a, b) in input.iter().zip(output.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_loud_signal_reduced_by_ratio() {
+        // 0 dBFS peak, -12 dB threshold, 4:1 => 12 dB over becomes 3 dB over
+        let mut samples = sine(440.0, 48000, 48000, 1, 1.0);
+        let mut comp = Compressor::new(-12.0, 4.0, 1.0, 100.0, 0.0, 48000, 1);
+        comp.process(&mut samples);
+        let settled = peak(&samples[24000..]);
+        assert!(
+            (linear_to_db(settled) + 9.0).abs() < 1.0,
+            "Expected ~-9 dBFS, got {}",
+            linear_to_db(settled)
+        );
+        assert!(comp.reduction_db() > 0.0);
+    }
+
+    #[test]
+    fn test_high_ratio_limits() {
+        let mut samples = sine(440.0, 48000, 48000, 2, 1.0);
+        Compressor::new(-6.0, 100.0, 0.0, 100.0, 0.0, 48000, 2).process(&mut samples);
+        assert!(linear_to_db(peak(&samples[4800..])) < -5.5);
+    }
+
+    #[test]
+    fn test_makeup_gain_applied() {
+        let mut samples = sine(440.0, 48000, 4800, 1, 0.1);
+        Compressor::new(-6.0, 4.0, 5.0, 50.0, 6.0, 48000, 1).process(&mut samples);
+        assert!((peak(&samples) - 0.1 * db_to_linear(6.0)).abs() < 0.01);
+    }
+}
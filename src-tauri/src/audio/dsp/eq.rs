@@ -0,0 +1,218 @@
+//! Biquad filters (shelf, peaking EQ and high-pass)
+//!
+//! Coefficients follow the RBJ Audio EQ Cookbook.
+
+use std::f32::consts::PI;
+
+use super::Effect;
+
+/// Normalized biquad coefficients (a0 = 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiquadCoeffs {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl BiquadCoeffs {
+    /// Low shelf with a slope of 1
+    pub fn low_shelf(sample_rate: u32, frequency_hz: f32, gain_db: f32) -> Self {
+        let (a, cos, alpha) = shelf_params(sample_rate, frequency_hz, gain_db);
+        let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+        Self::normalize(
+            a * ((a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+            a * ((a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha),
+            (a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+            (a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha,
+        )
+    }
+
+    /// High shelf with a slope of 1
+    pub fn high_shelf(sample_rate: u32, frequency_hz: f32, gain_db: f32) -> Self {
+        let (a, cos, alpha) = shelf_params(sample_rate, frequency_hz, gain_db);
+        let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+        Self::normalize(
+            a * ((a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+            a * ((a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha),
+            (a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos),
+            (a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha,
+        )
+    }
+
+    /// Peaking EQ band
+    pub fn peak(sample_rate: u32, frequency_hz: f32, gain_db: f32, q: f32) -> Self {
+        let a = 10f32.powf(gain_db.clamp(-24.0, 24.0) / 40.0);
+        let w0 = omega(sample_rate, frequency_hz);
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q.clamp(0.1, 20.0));
+        Self::normalize(
+            1.0 + alpha * a,
+            -2.0 * cos,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos,
+            1.0 - alpha / a,
+        )
+    }
+
+    /// Second-order high-pass (Q = 0.707 gives a Butterworth response)
+    pub fn high_pass(sample_rate: u32, frequency_hz: f32, q: f32) -> Self {
+        let w0 = omega(sample_rate, frequency_hz);
+        let cos = w0.cos();
+        let alpha = w0.sin() / (2.0 * q.clamp(0.1, 20.0));
+        Self::normalize(
+            (1.0 + cos) / 2.0,
+            -(1.0 + cos),
+            (1.0 + cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        )
+    }
+
+    fn normalize(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+/// Angular frequency, with the corner kept safely below Nyquist
+fn omega(sample_rate: u32, frequency_hz: f32) -> f32 {
+    let nyquist = sample_rate as f32 / 2.0;
+    2.0 * PI * frequency_hz.clamp(10.0, nyquist * 0.95) / sample_rate as f32
+}
+
+/// Shared shelf parameters: (A, cos(w0), alpha) for slope S = 1
+fn shelf_params(sample_rate: u32, frequency_hz: f32, gain_db: f32) -> (f32, f32, f32) {
+    let a = 10f32.powf(gain_db.clamp(-24.0, 24.0) / 40.0);
+    let w0 = omega(sample_rate, frequency_hz);
+    let alpha = w0.sin() / 2.0 * std::f32::consts::SQRT_2;
+    (a, w0.cos(), alpha)
+}
+
+/// Multi-channel biquad filter (direct form I, independent state per channel)
+pub struct Biquad {
+    coeffs: BiquadCoeffs,
+    /// Per-channel history: [x1, x2, y1, y2]
+    state: Vec<[f32; 4]>,
+}
+
+impl Biquad {
+    pub fn new(coeffs: BiquadCoeffs, channels: usize) -> Self {
+        Self {
+            coeffs,
+            state: vec![[0.0; 4]; channels.max(1)],
+        }
+    }
+
+    /// Filter a single sample on the given channel
+    #[inline]
+    pub fn process_sample(&mut self, channel: usize, x: f32) -> f32 {
+        let c = &self.coeffs;
+        let s = &mut self.state[channel];
+        let y = c.b0 * x + c.b1 * s[0] + c.b2 * s[1] - c.a1 * s[2] - c.a2 * s[3];
+        *s = [x, s[0], y, s[2]];
+        y
+    }
+
+    /// Filter an interleaved buffer in place
+    pub fn process_interleaved(&mut self, samples: &mut [f32]) {
+        let channels = self.state.len();
+        for frame in samples.chunks_mut(channels) {
+            for (ch, sample) in frame.iter_mut().enumerate() {
+                *sample = self.process_sample(ch, *sample);
+            }
+        }
+    }
+}
+
+impl Effect for Biquad {
+    fn name(&self) -> &'static str {
+        "biquad"
+    }
+
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        self.process_interleaved(samples);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::super::{db_to_linear, linear_to_db};
+    use super::*;
+
+    /// Steady-state gain in dB of a filter for a sine at `freq`
+    fn response_db(coeffs: BiquadCoeffs, freq: f32) -> f32 {
+        let input = sine(freq, 48000, 48000, 1, 0.5);
+        let mut output = input.clone();
+        Biquad::new(coeffs, 1).process(&mut output);
+        // Skip the first 100ms to ignore the filter's settling time
+        linear_to_db(rms(&output[4800..]) / rms(&input[4800..]))
+    }
+
+    #[test]
+    fn test_low_shelf_boosts_lows_only() {
+        let coeffs = BiquadCoeffs::low_shelf(48000, 200.0, 6.0);
+        assert!((response_db(coeffs, 40.0) - 6.0).abs() < 0.5);
+        assert!(response_db(coeffs, 5000.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_high_shelf_cuts_highs_only() {
+        let coeffs = BiquadCoeffs::high_shelf(48000, 4000.0, -9.0);
+        assert!((response_db(coeffs, 16000.0) + 9.0).abs() < 0.75);
+        assert!(response_db(coeffs, 100.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_peak_boosts_center_frequency() {
+        let coeffs = BiquadCoeffs::peak(48000, 1000.0, 12.0, 1.0);
+        assert!((response_db(coeffs, 1000.0) - 12.0).abs() < 0.5);
+        assert!(response_db(coeffs, 50.0).abs() < 1.0);
+        assert!(response_db(coeffs, 15000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_high_pass_removes_rumble() {
+        let coeffs = BiquadCoeffs::high_pass(48000, 100.0, std::f32::consts::FRAC_1_SQRT_2);
+        // 12 dB/octave: two octaves below the corner is ~24 dB down
+        assert!(response_db(coeffs, 25.0) < -20.0);
+        assert!((response_db(coeffs, 100.0) + 3.0).abs() < 0.5);
+        assert!(response_db(coeffs, 2000.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_zero_gain_is_transparent() {
+        let coeffs = BiquadCoeffs::peak(48000, 1000.0, 0.0, 1.0);
+        let input = sine(1000.0, 48000, 4800, 2, 0.5);
+        let mut output = input.clone();
+        Biquad::new(coeffs, 2).process(&mut output);
+        for (a, b) in input.iter().zip(output.iter()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_channels_filtered_independently() {
+        // Left carries signal, right is silent: right must stay silent
+        let mut samples: Vec<f32> = sine(100.0, 48000, 4800, 1, 0.5)
+            .into_iter()
+            .flat_map(|s| [s, 0.0])
+            .collect();
+        Biquad::new(BiquadCoeffs::low_shelf(48000, 200.0, 12.0), 2).process(&mut samples);
+        assert!(samples.iter().skip(1).step_by(2).all(|&s| s == 0.0));
+        assert!(peak(&samples) > 0.5 * db_to_linear(6.0));
+    }
+}
@@ -0,0 +1,57 @@
+//! Static gain effect
+
+use super::{db_to_linear, Effect};
+
+/// Gain adjustment in decibels
+pub struct Gain {
+    factor: f32,
+}
+
+impl Gain {
+    /// Create a gain stage, clamped to -60..+24 dB
+    pub fn new(gain_db: f32) -> Self {
+        Self {
+            factor: db_to_linear(gain_db.clamp(-60.0, 24.0)),
+        }
+    }
+}
+
+impl Effect for Gain {
+    fn name(&self) -> &'static str {
+        "gain"
+    }
+
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        for sample in samples.iter_mut() {
+            *sample *= self.factor;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_gain_boost() {
+        let mut samples = sine(440.0, 48000, 4800, 2, 0.25);
+        Gain::new(6.0).process(&mut samples);
+        assert!((peak(&samples) - 0.25 * db_to_linear(6.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_gain_unity() {
+        let original = sine(440.0, 48000, 480, 1, 0.5);
+        let mut samples = original.clone();
+        Gain::new(0.0).process(&mut samples);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_gain_clamped() {
+        let mut samples = vec![0.001];
+        Gain::new(200.0).process(&mut samples);
+        assert!((samples[0] - 0.001 * db_to_linear(24.0)).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,170 @@
+//! Noise gate with hysteresis and hold
+//!
+//! The gate opens when the signal rises above the open threshold and only closes
+//! once it has stayed below a lower close threshold for the hold time. The gap
+//! between the thresholds stops the gate from chattering on signals hovering
+//! around a single threshold, such as the tail of a word.
+
+use super::db_to_linear;
+use super::dynamics::time_coeff;
+
+/// Attack time of the level detector
+const DETECTOR_ATTACK_MS: f32 = 0.5;
+/// Release time of the level detector
+const DETECTOR_RELEASE_MS: f32 = 20.0;
+/// Time for the gate gain to open fully
+const OPEN_MS: f32 = 1.0;
+/// Attenuation applied while the gate is closed
+const FLOOR_DB: f32 = -80.0;
+
+/// Stereo-linked noise gate for interleaved audio
+pub struct NoiseGate {
+    open_threshold: f32,
+    close_threshold: f32,
+    hold_frames: usize,
+    detector_attack: f32,
+    detector_release: f32,
+    open_coeff: f32,
+    close_coeff: f32,
+    floor: f32,
+    channels: usize,
+    envelope: f32,
+    is_open: bool,
+    /// Frames spent below the close threshold while open
+    below_frames: usize,
+    gain: f32,
+}
+
+impl NoiseGate {
+    /// Create a gate opening at `threshold_db` and closing `hysteresis_db` below it
+    pub fn new(
+        threshold_db: f32,
+        hysteresis_db: f32,
+        hold_ms: f32,
+        release_ms: f32,
+        sample_rate: u32,
+        channels: usize,
+    ) -> Self {
+        let threshold_db = threshold_db.clamp(-90.0, 0.0);
+        let hysteresis_db = hysteresis_db.clamp(0.0, 30.0);
+        Self {
+            open_threshold: db_to_linear(threshold_db),
+            close_threshold: db_to_linear(threshold_db - hysteresis_db),
+            hold_frames: (hold_ms.clamp(0.0, 2000.0) / 1000.0 * sample_rate as f32) as usize,
+            detector_attack: time_coeff(DETECTOR_ATTACK_MS, sample_rate),
+            detector_release: time_coeff(DETECTOR_RELEASE_MS, sample_rate),
+            open_coeff: time_coeff(OPEN_MS, sample_rate),
+            close_coeff: time_coeff(release_ms.clamp(1.0, 5000.0), sample_rate),
+            floor: db_to_linear(FLOOR_DB),
+            channels: channels.max(1),
+            envelope: 0.0,
+            is_open: false,
+            below_frames: 0,
+            gain: db_to_linear(FLOOR_DB),
+        }
+    }
+
+    /// Whether the gate is currently open
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Gate an interleaved buffer in place
+    pub fn process_interleaved(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_mut(self.channels) {
+            let peak = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            let coeff = if peak > self.envelope {
+                self.detector_attack
+            } else {
+                self.detector_release
+            };
+            self.envelope = peak + coeff * (self.envelope - peak);
+
+            if self.is_open {
+                if self.envelope < self.close_threshold {
+                    self.below_frames += 1;
+                    if self.below_frames > self.hold_frames {
+                        self.is_open = false;
+                    }
+                } else {
+                    self.below_frames = 0;
+                }
+            } else if self.envelope > self.open_threshold {
+                self.is_open = true;
+                self.below_frames = 0;
+            }
+
+            let (target, coeff) = if self.is_open {
+                (1.0, self.open_coeff)
+            } else {
+                (self.floor, self.close_coeff)
+            };
+            self.gain = target + coeff * (self.gain - target);
+
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_gate_passes_loud_signal() {
+        let input = sine(440.0, 48000, 9600, 1, 0.5);
+        let mut output = input.clone();
+        let mut gate = NoiseGate::new(-40.0, 6.0, 50.0, 100.0, 48000, 1);
+        gate.process_interleaved(&mut output);
+
+        assert!(gate.is_open());
+        // After the short opening ramp the tone passes unchanged
+        assert!((rms(&output[4800..]) - rms(&input[4800..])).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_gate_silences_quiet_noise() {
+        let mut samples = noise(48000, 2, db_to_linear(-55.0), 7);
+        let mut gate = NoiseGate::new(-40.0, 6.0, 50.0, 100.0, 48000, 2);
+        gate.process_interleaved(&mut samples);
+
+        assert!(!gate.is_open());
+        assert!(peak(&samples) < db_to_linear(-100.0));
+    }
+
+    #[test]
+    fn test_hysteresis_keeps_gate_open_between_thresholds() {
+        let mut gate = NoiseGate::new(-20.0, 10.0, 10.0, 50.0, 48000, 1);
+        // Open with a loud tone, then drop to -25 dB (between close and open)
+        gate.process_interleaved(&mut sine(440.0, 48000, 4800, 1, db_to_linear(-10.0)));
+        assert!(gate.is_open());
+        gate.process_interleaved(&mut sine(440.0, 48000, 24000, 1, db_to_linear(-25.0)));
+        assert!(gate.is_open());
+
+        // A fresh gate does not open at the same level
+        let mut closed = NoiseGate::new(-20.0, 10.0, 10.0, 50.0, 48000, 1);
+        closed.process_interleaved(&mut sine(440.0, 48000, 24000, 1, db_to_linear(-25.0)));
+        assert!(!closed.is_open());
+    }
+
+    #[test]
+    fn test_gate_closes_after_hold() {
+        let mut gate = NoiseGate::new(-40.0, 6.0, 100.0, 20.0, 48000, 1);
+        gate.process_interleaved(&mut sine(440.0, 48000, 4800, 1, 0.5));
+
+        // Silence shorter than the hold time keeps the gate open
+        gate.process_interleaved(&mut vec![0.0; 2400]);
+        assert!(gate.is_open());
+
+        // Well past detector release + hold it closes and fades out
+        let mut tail = vec![0.0f32; 24000];
+        gate.process_interleaved(&mut tail);
+        assert!(!gate.is_open());
+        let mut probe = sine(440.0, 48000, 4800, 1, 0.005);
+        gate.process_interleaved(&mut probe);
+        assert!(peak(&probe[2400..]) < 1e-4);
+    }
+}
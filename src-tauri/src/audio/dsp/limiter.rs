@@ -0,0 +1,229 @@
+//! Look-ahead brickwall limiter
+//!
+//! Delays the signal by a short look-ahead window so gain can be reduced before a
+//! peak arrives. The gain curve is the sliding minimum of the required gain, smoothed
+//! by a moving average of the same length, which guarantees no output sample exceeds
+//! the ceiling while avoiding clicks from instant gain steps.
+
+use std::collections::VecDeque;
+
+use super::dynamics::time_coeff;
+use super::{db_to_linear, linear_to_db};
+
+/// Default look-ahead time in milliseconds
+pub const DEFAULT_LOOKAHEAD_MS: f32 = 2.0;
+/// Default release time in milliseconds
+pub const DEFAULT_RELEASE_MS: f32 = 80.0;
+
+/// Streaming look-ahead limiter for interleaved audio
+pub struct LookaheadLimiter {
+    ceiling: f32,
+    channels: usize,
+    lookahead: usize,
+    release_coeff: f32,
+    /// Delay line for the audio (lookahead frames, interleaved)
+    delay: Vec<f32>,
+    delay_pos: usize,
+    /// Monotonic deque of (frame index, required gain) for the sliding minimum
+    min_window: VecDeque<(u64, f32)>,
+    /// Ring of sliding-minimum values for the moving average
+    avg_ring: Vec<f32>,
+    avg_pos: usize,
+    avg_sum: f64,
+    frame_index: u64,
+    /// Current gain after release smoothing
+    envelope: f32,
+}
+
+impl LookaheadLimiter {
+    pub fn new(ceiling_db: f32, sample_rate: u32, channels: usize) -> Self {
+        Self::with_timing(
+            ceiling_db,
+            DEFAULT_LOOKAHEAD_MS,
+            DEFAULT_RELEASE_MS,
+            sample_rate,
+            channels,
+        )
+    }
+
+    pub fn with_timing(
+        ceiling_db: f32,
+        lookahead_ms: f32,
+        release_ms: f32,
+        sample_rate: u32,
+        channels: usize,
+    ) -> Self {
+        let channels = channels.max(1);
+        let lookahead = ((lookahead_ms.max(0.1) / 1000.0) * sample_rate as f32).ceil() as usize;
+        let lookahead = lookahead.max(1);
+
+        Self {
+            ceiling: db_to_linear(ceiling_db.clamp(-24.0, 0.0)),
+            channels,
+            lookahead,
+            release_coeff: time_coeff(release_ms.max(1.0), sample_rate),
+            delay: vec![0.0; lookahead * channels],
+            delay_pos: 0,
+            min_window: VecDeque::with_capacity(lookahead + 1),
+            avg_ring: vec![1.0; lookahead],
+            avg_pos: 0,
+            avg_sum: lookahead as f64,
+            frame_index: 0,
+            envelope: 1.0,
+        }
+    }
+
+    /// Change the ceiling without resetting the limiter state
+    pub fn set_ceiling_db(&mut self, ceiling_db: f32) {
+        self.ceiling = db_to_linear(ceiling_db.clamp(-24.0, 0.0));
+    }
+
+    /// Current gain reduction in dB (0.0 when not limiting)
+    pub fn gain_reduction_db(&self) -> f32 {
+        -linear_to_db(self.envelope).min(0.0)
+    }
+
+    /// Latency introduced by the look-ahead, in frames
+    #[allow(dead_code)]
+    pub fn latency_frames(&self) -> usize {
+        self.lookahead
+    }
+
+    /// Limit an interleaved buffer in place (output is delayed by the look-ahead)
+    pub fn process_interleaved(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_mut(self.channels) {
+            // Gain needed so this incoming frame stays under the ceiling
+            let peak = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            let required = if peak > self.ceiling {
+                self.ceiling / peak
+            } else {
+                1.0
+            };
+
+            // Sliding minimum over the last `lookahead + 1` frames, so the window
+            // always covers the frame leaving the delay line
+            while let Some(&(_, g)) = self.min_window.back() {
+                if g >= required {
+                    self.min_window.pop_back();
+                } else {
+                    break;
+                }
+            }
+            self.min_window.push_back((self.frame_index, required));
+            while let Some(&(idx, _)) = self.min_window.front() {
+                if idx + (self.lookahead as u64) < self.frame_index {
+                    self.min_window.pop_front();
+                } else {
+                    break;
+                }
+            }
+            let held = self.min_window.front().map_or(1.0, |&(_, g)| g);
+
+            // Moving average of the held minimum (same window length)
+            self.avg_sum += held as f64 - self.avg_ring[self.avg_pos] as f64;
+            self.avg_ring[self.avg_pos] = held;
+            self.avg_pos = (self.avg_pos + 1) % self.lookahead;
+            let smoothed = (self.avg_sum / self.lookahead as f64) as f32;
+
+            // Instant attack (already anticipated by the look-ahead), smooth release
+            self.envelope = if smoothed < self.envelope {
+                smoothed
+            } else {
+                smoothed + self.release_coeff * (self.envelope - smoothed)
+            };
+
+            // Swap the incoming frame with the delayed one and apply the gain
+            let offset = self.delay_pos * self.channels;
+            for (ch, sample) in frame.iter_mut().enumerate() {
+                let delayed = self.delay[offset + ch];
+                self.delay[offset + ch] = *sample;
+                // Hard clamp guards against float rounding right at the ceiling
+                *sample = (delayed * self.envelope).clamp(-self.ceiling, self.ceiling);
+            }
+            self.delay_pos = (self.delay_pos + 1) % self.lookahead;
+            self.frame_index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_quiet_signal_passes_delayed() {
+        let input = sine(440.0, 48000, 4800, 2, 0.25);
+        let mut output = input.clone();
+        let mut limiter = LookaheadLimiter::new(-1.0, 48000, 2);
+        let latency = limiter.latency_frames();
+        limiter.process_interleaved(&mut output);
+
+        for i in 0..(4800 - latency) * 2 {
+            assert!((output[i + latency * 2] - input[i]).abs() < 1e-6);
+        }
+        assert_eq!(limiter.gain_reduction_db(), 0.0);
+    }
+
+    #[test]
+    fn test_never_exceeds_ceiling() {
+        // Sum of three loud overlapping clips peaks around 2.4
+        let a = sine(220.0, 48000, 48000, 2, 0.8);
+        let b = sine(330.0, 48000, 48000, 2, 0.8);
+        let c = sine(550.0, 48000, 48000, 2, 0.8);
+        let mut mix: Vec<f32> = (0..a.len()).map(|i| a[i] + b[i] + c[i]).collect();
+        assert!(peak(&mix) > 2.0);
+
+        let mut limiter = LookaheadLimiter::new(-1.0, 48000, 2);
+        limiter.process_interleaved(&mut mix);
+
+        assert!(peak(&mix) <= db_to_linear(-1.0) + 1e-6);
+        assert!(limiter.gain_reduction_db() > 0.0);
+    }
+
+    #[test]
+    fn test_transient_is_caught_without_clamping() {
+        // A single-sample spike must already be attenuated by the gain curve
+        let mut samples = vec![0.1f32; 4800];
+        samples[2400] = 1.0;
+        let mut limiter = LookaheadLimiter::with_timing(-6.0, 2.0, 50.0, 48000, 1);
+        let latency = limiter.latency_frames();
+
+        let mut output = samples.clone();
+        limiter.process_interleaved(&mut output);
+
+        let limited = output[2400 + latency];
+        assert!((limited - db_to_linear(-6.0)).abs() < 1e-3);
+        // Gain ramps down ahead of the spike instead of stepping
+        let before = output[2400 + latency - latency / 2];
+        assert!(before < 0.1 && before > 0.05);
+    }
+
+    #[test]
+    fn test_releases_after_peak() {
+        let mut samples = sine(440.0, 48000, 4800, 1, 1.0);
+        samples.extend(sine(440.0, 48000, 48000, 1, 0.1));
+        let mut limiter = LookaheadLimiter::with_timing(-6.0, 2.0, 20.0, 48000, 1);
+        limiter.process_interleaved(&mut samples);
+
+        // Long after the loud part ends the quiet part comes back to full level
+        assert!((peak(&samples[40000..]) - 0.1).abs() < 0.005);
+        assert!(limiter.gain_reduction_db() < 0.1);
+    }
+
+    #[test]
+    fn test_streaming_matches_single_block() {
+        let input = sine(440.0, 48000, 9600, 2, 1.5);
+
+        let mut whole = input.clone();
+        LookaheadLimiter::new(-1.0, 48000, 2).process_interleaved(&mut whole);
+
+        let mut blocks = input.clone();
+        let mut limiter = LookaheadLimiter::new(-1.0, 48000, 2);
+        for chunk in blocks.chunks_mut(256 * 2) {
+            limiter.process_interleaved(chunk);
+        }
+
+        assert_eq!(whole, blocks);
+    }
+}
@@ -0,0 +1,392 @@
+//! Non-destructive DSP effects
+//!
+//! Per-sound effect chains that are applied when a sound is rendered for playback.
+//! The source file is never modified; the chain is stored alongside the sound in
+//! `sounds.json` and re-applied every time the decoded audio is rendered.
+//!
+//! Each effect implements the [`Effect`] trait. Effects may change the buffer length
+//! (speed, reverb tail), so they operate on the whole interleaved buffer at once.
+
+mod bitcrush;
+mod ducking;
+mod dynamics;
+mod eq;
+mod gain;
+mod gate;
+mod limiter;
+mod reverb;
+mod time;
+
+pub use bitcrush::Bitcrusher;
+pub use ducking::{Ducker, EnvelopeFollower};
+pub use dynamics::Compressor;
+pub use eq::{Biquad, BiquadCoeffs};
+pub use gain::Gain;
+pub use gate::NoiseGate;
+pub use limiter::LookaheadLimiter;
+pub use reverb::Reverb;
+pub use time::{PitchShift, Speed};
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::debug;
+
+use super::AudioData;
+
+/// A single audio effect operating on interleaved f32 samples
+pub trait Effect: Send {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Process an interleaved buffer, possibly changing its length.
+    ///
+    /// The buffer always holds whole frames for the channel count the effect
+    /// was built with, and must still do so when this returns.
+    fn process(&mut self, samples: &mut Vec<f32>);
+}
+
+/// Persisted configuration for one effect in a sound's chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EffectConfig {
+    /// Gain in decibels
+    Gain { gain_db: f32 },
+    /// Pitch shift in semitones (duration is preserved)
+    PitchShift { semitones: f32 },
+    /// Playback speed multiplier (tape-style, pitch follows speed)
+    Speed { factor: f32 },
+    /// Low shelf EQ
+    LowShelf { frequency_hz: f32, gain_db: f32 },
+    /// High shelf EQ
+    HighShelf { frequency_hz: f32, gain_db: f32 },
+    /// Peaking EQ band
+    Peak {
+        frequency_hz: f32,
+        gain_db: f32,
+        q: f32,
+    },
+    /// Compressor (use a high ratio for limiting)
+    Compressor {
+        threshold_db: f32,
+        ratio: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        makeup_db: f32,
+    },
+    /// Reverb with room size and damping in 0.0-1.0, mix as wet proportion
+    Reverb {
+        room_size: f32,
+        damping: f32,
+        mix: f32,
+    },
+    /// Bit depth reduction and sample-and-hold downsampling
+    Bitcrusher { bits: u8, downsample: u32 },
+}
+
+impl EffectConfig {
+    /// Build a processing instance for the given stream format.
+    ///
+    /// Parameters are clamped to safe ranges, so any persisted value can be built.
+    pub fn build(&self, sample_rate: u32, channels: usize) -> Box<dyn Effect> {
+        match *self {
+            EffectConfig::Gain { gain_db } => Box::new(Gain::new(gain_db)),
+            EffectConfig::PitchShift { semitones } => {
+                Box::new(PitchShift::new(semitones, sample_rate, channels))
+            }
+            EffectConfig::Speed { factor } => Box::new(Speed::new(factor, channels)),
+            EffectConfig::LowShelf {
+                frequency_hz,
+                gain_db,
+            } => Box::new(Biquad::new(
+                BiquadCoeffs::low_shelf(sample_rate, frequency_hz, gain_db),
+                channels,
+            )),
+            EffectConfig::HighShelf {
+                frequency_hz,
+                gain_db,
+            } => Box::new(Biquad::new(
+                BiquadCoeffs::high_shelf(sample_rate, frequency_hz, gain_db),
+                channels,
+            )),
+            EffectConfig::Peak {
+                frequency_hz,
+                gain_db,
+                q,
+            } => Box::new(Biquad::new(
+                BiquadCoeffs::peak(sample_rate, frequency_hz, gain_db, q),
+                channels,
+            )),
+            EffectConfig::Compressor {
+                threshold_db,
+                ratio,
+                attack_ms,
+                release_ms,
+                makeup_db,
+            } => Box::new(Compressor::new(
+                threshold_db,
+                ratio,
+                attack_ms,
+                release_ms,
+                makeup_db,
+                sample_rate,
+                channels,
+            )),
+            EffectConfig::Reverb {
+                room_size,
+                damping,
+                mix,
+            } => Box::new(Reverb::new(room_size, damping, mix, sample_rate, channels)),
+            EffectConfig::Bitcrusher { bits, downsample } => {
+                Box::new(Bitcrusher::new(bits, downsample, channels))
+            }
+        }
+    }
+}
+
+/// Ordered chain of effects applied one after another
+pub struct EffectChain {
+    effects: Vec<Box<dyn Effect>>,
+}
+
+impl EffectChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self {
+            effects: Vec::new(),
+        }
+    }
+
+    /// Build a chain from persisted effect configs
+    pub fn from_configs(configs: &[EffectConfig], sample_rate: u32, channels: usize) -> Self {
+        Self {
+            effects: configs
+                .iter()
+                .map(|config| config.build(sample_rate, channels))
+                .collect(),
+        }
+    }
+
+    /// Append a custom effect to the end of the chain
+    #[allow(dead_code)]
+    pub fn push(&mut self, effect: Box<dyn Effect>) {
+        self.effects.push(effect);
+    }
+
+    /// Run every effect over the buffer in order
+    pub fn process(&mut self, samples: &mut Vec<f32>) {
+        for effect in self.effects.iter_mut() {
+            let start = Instant::now();
+            effect.process(samples);
+            debug!(
+                effect = effect.name(),
+                duration_us = start.elapsed().as_micros(),
+                samples = samples.len(),
+                "Effect applied"
+            );
+        }
+    }
+}
+
+impl Default for EffectChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Render the given frame range of decoded audio through an effect chain.
+///
+/// Trimming happens before the chain so time-based effects (speed, reverb tail)
+/// see only the audible part of the sound. The result starts at frame 0 and
+/// should be played without any further trim.
+pub fn render_effects(
+    audio_data: &AudioData,
+    start_frame: Option<usize>,
+    end_frame: Option<usize>,
+    effects: &[EffectConfig],
+) -> AudioData {
+    let start = Instant::now();
+    let channels = audio_data.channels as usize;
+    let total_frames = audio_data.samples.len() / channels;
+    let end = end_frame.unwrap_or(total_frames).min(total_frames);
+    let begin = start_frame.unwrap_or(0).min(end);
+
+    let mut samples = audio_data.samples[begin * channels..end * channels].to_vec();
+    EffectChain::from_configs(effects, audio_data.sample_rate, channels).process(&mut samples);
+
+    debug!(
+        effect_count = effects.len(),
+        input_frames = end - begin,
+        output_frames = samples.len() / channels,
+        duration_ms = start.elapsed().as_millis(),
+        "Effect chain rendered"
+    );
+
+    AudioData {
+        samples,
+        sample_rate: audio_data.sample_rate,
+        channels: audio_data.channels,
+    }
+}
+
+/// Convert decibels to a linear amplitude factor
+#[inline]
+pub fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Convert a linear amplitude factor to decibels
+#[inline]
+pub fn linear_to_db(linear: f32) -> f32 {
+    20.0 * linear.max(1e-9).log10()
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    /// Generate an interleaved sine wave with the same signal on every channel
+    pub fn sine(freq: f32, sample_rate: u32, frames: usize, channels: usize, amp: f32) -> Vec<f32> {
+        let mut out = Vec::with_capacity(frames * channels);
+        for i in 0..frames {
+            let v = amp * (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin();
+            for _ in 0..channels {
+                out.push(v);
+            }
+        }
+        out
+    }
+
+    /// Deterministic white noise in [-amp, amp] (simple LCG, seeded)
+    pub fn noise(frames: usize, channels: usize, amp: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed.wrapping_mul(747796405).wrapping_add(2891336453);
+        (0..frames * channels)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                amp * ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    /// Peak absolute amplitude of a buffer
+    pub fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |m, s| m.max(s.abs()))
+    }
+
+    /// RMS level of a buffer
+    pub fn rms(samples: &[f32]) -> f32 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// Estimate the frequency of a mono signal from positive-going zero crossings
+    pub fn zero_crossing_freq(samples: &[f32], sample_rate: u32) -> f32 {
+        let crossings = samples
+            .windows(2)
+            .filter(|w| w[0] <= 0.0 && w[1] > 0.0)
+            .count();
+        crossings as f32 * sample_rate as f32 / samples.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_db_linear_roundtrip() {
+        assert!((db_to_linear(0.0) - 1.0).abs() < 1e-6);
+        assert!((db_to_linear(-6.0) - 0.501).abs() < 0.001);
+        assert!((linear_to_db(db_to_linear(-12.0)) + 12.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_empty_chain_is_passthrough() {
+        let audio = AudioData {
+            samples: sine(440.0, 48000, 4800, 2, 0.5),
+            sample_rate: 48000,
+            channels: 2,
+        };
+        let rendered = render_effects(&audio, None, None, &[]);
+        assert_eq!(rendered.samples, audio.samples);
+    }
+
+    #[test]
+    fn test_render_applies_trim_before_chain() {
+        let audio = AudioData {
+            samples: sine(440.0, 48000, 48000, 1, 0.5),
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let rendered = render_effects(
+            &audio,
+            Some(12000),
+            Some(36000),
+            &[EffectConfig::Speed { factor: 2.0 }],
+        );
+        // 24000 trimmed frames played at double speed
+        assert!((rendered.samples.len() as i64 - 12000).abs() <= 1);
+    }
+
+    #[test]
+    fn test_chain_applies_in_order() {
+        let audio = AudioData {
+            samples: sine(440.0, 48000, 4800, 1, 0.5),
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let rendered = render_effects(
+            &audio,
+            None,
+            None,
+            &[
+                EffectConfig::Gain { gain_db: -6.0 },
+                EffectConfig::Gain { gain_db: -6.0 },
+            ],
+        );
+        let expected = 0.5 * db_to_linear(-12.0);
+        assert!((peak(&rendered.samples) - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_effect_config_serde_roundtrip() {
+        let chain = vec![
+            EffectConfig::Gain { gain_db: 3.0 },
+            EffectConfig::PitchShift { semitones: -5.0 },
+            EffectConfig::Peak {
+                frequency_hz: 1000.0,
+                gain_db: 6.0,
+                q: 0.7,
+            },
+            EffectConfig::Bitcrusher {
+                bits: 4,
+                downsample: 8,
+            },
+        ];
+        let json = serde_json::to_string(&chain).unwrap();
+        assert!(json.contains(r#""type":"pitch_shift""#));
+        let deserialized: Vec<EffectConfig> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, chain);
+    }
+
+    #[test]
+    fn test_custom_effect_can_be_pushed() {
+        struct Invert;
+        impl Effect for Invert {
+            fn name(&self) -> &'static str {
+                "invert"
+            }
+            fn process(&mut self, samples: &mut Vec<f32>) {
+                for s in samples.iter_mut() {
+                    *s = -*s;
+                }
+            }
+        }
+
+        let mut chain = EffectChain::new();
+        chain.push(Box::new(Invert));
+        let mut samples = vec![0.25, -0.5];
+        chain.process(&mut samples);
+        assert_eq!(samples, vec![-0.25, 0.5]);
+    }
+}
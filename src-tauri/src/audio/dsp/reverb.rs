@@ -0,0 +1,176 @@
+//! Algorithmic reverb (Schroeder/Freeverb-style comb + allpass network)
+
+use super::Effect;
+
+/// Comb delay lengths in samples at 44.1 kHz
+const COMB_TUNING: [usize; 4] = [1116, 1188, 1277, 1356];
+/// Allpass delay lengths in samples at 44.1 kHz
+const ALLPASS_TUNING: [usize; 2] = [556, 441];
+/// Extra delay for odd channels to decorrelate left/right
+const STEREO_SPREAD: usize = 23;
+/// Input attenuation so the comb bank does not clip
+const FIXED_GAIN: f32 = 0.03;
+
+/// Feedback comb filter with one-pole lowpass damping in the loop
+struct Comb {
+    buffer: Vec<f32>,
+    pos: usize,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len.max(1)],
+            pos: 0,
+            filter_store: 0.0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32, feedback: f32, damp: f32) -> f32 {
+        let output = self.buffer[self.pos];
+        self.filter_store = output * (1.0 - damp) + self.filter_store * damp;
+        self.buffer[self.pos] = input + self.filter_store * feedback;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// Schroeder allpass diffuser
+struct Allpass {
+    buffer: Vec<f32>,
+    pos: usize,
+}
+
+impl Allpass {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len.max(1)],
+            pos: 0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.pos];
+        self.buffer[self.pos] = input + buffered * 0.5;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        buffered - input
+    }
+}
+
+/// Reverb effect; appends a tail so the decay is not cut off at the end of the sound
+pub struct Reverb {
+    combs: Vec<Vec<Comb>>,
+    allpasses: Vec<Vec<Allpass>>,
+    feedback: f32,
+    damp: f32,
+    mix: f32,
+    channels: usize,
+    tail_frames: usize,
+}
+
+impl Reverb {
+    pub fn new(room_size: f32, damping: f32, mix: f32, sample_rate: u32, channels: usize) -> Self {
+        let room_size = room_size.clamp(0.0, 1.0);
+        let channels = channels.max(1);
+        let scale = sample_rate as f32 / 44100.0;
+        let scaled = |len: usize, ch: usize| {
+            let spread = if ch % 2 == 1 { STEREO_SPREAD } else { 0 };
+            ((len + spread) as f32 * scale) as usize
+        };
+
+        Self {
+            combs: (0..channels)
+                .map(|ch| {
+                    COMB_TUNING
+                        .iter()
+                        .map(|&len| Comb::new(scaled(len, ch)))
+                        .collect()
+                })
+                .collect(),
+            allpasses: (0..channels)
+                .map(|ch| {
+                    ALLPASS_TUNING
+                        .iter()
+                        .map(|&len| Allpass::new(scaled(len, ch)))
+                        .collect()
+                })
+                .collect(),
+            feedback: 0.7 + room_size * 0.28,
+            damp: damping.clamp(0.0, 1.0) * 0.4,
+            mix: mix.clamp(0.0, 1.0),
+            channels,
+            // Bigger rooms ring longer: 0.5s to 2s of tail
+            tail_frames: ((0.5 + room_size * 1.5) * sample_rate as f32) as usize,
+        }
+    }
+}
+
+impl Effect for Reverb {
+    fn name(&self) -> &'static str {
+        "reverb"
+    }
+
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        if self.mix <= 0.0 {
+            return;
+        }
+
+        samples.resize(samples.len() + self.tail_frames * self.channels, 0.0);
+
+        for frame in samples.chunks_mut(self.channels) {
+            for (ch, sample) in frame.iter_mut().enumerate() {
+                let input = *sample * FIXED_GAIN;
+                let mut wet: f32 = self.combs[ch]
+                    .iter_mut()
+                    .map(|comb| comb.process(input, self.feedback, self.damp))
+                    .sum();
+                for allpass in self.allpasses[ch].iter_mut() {
+                    wet = allpass.process(wet);
+                }
+                *sample = *sample * (1.0 - self.mix) + wet * self.mix;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_reverb_adds_tail() {
+        let mut samples = sine(440.0, 48000, 4800, 2, 0.5);
+        let original_len = samples.len();
+        Reverb::new(0.5, 0.5, 0.3, 48000, 2).process(&mut samples);
+
+        assert!(samples.len() > original_len);
+        assert_eq!(samples.len() % 2, 0);
+        // The tail carries decaying energy after the dry signal ended
+        assert!(rms(&samples[original_len..original_len + 9600]) > 1e-4);
+    }
+
+    #[test]
+    fn test_reverb_impulse_decays() {
+        let mut samples = vec![0.0f32; 48000];
+        samples[0] = 1.0;
+        Reverb::new(0.3, 0.5, 1.0, 48000, 1).process(&mut samples);
+
+        let early = rms(&samples[2000..12000]);
+        let late = rms(&samples[samples.len() - 10000..]);
+        assert!(early > 0.0);
+        assert!(late < early, "Tail should decay ({} vs {})", late, early);
+        assert!(samples.iter().all(|s| s.is_finite()));
+    }
+
+    #[test]
+    fn test_reverb_dry_mix_is_passthrough() {
+        let original = sine(440.0, 48000, 4800, 1, 0.5);
+        let mut samples = original.clone();
+        Reverb::new(0.8, 0.2, 0.0, 48000, 1).process(&mut samples);
+        assert_eq!(samples, original);
+    }
+}
@@ -0,0 +1,226 @@
+//! Time-domain effects: speed change and pitch shift
+
+use std::f32::consts::PI;
+
+use super::Effect;
+
+/// Resample interleaved audio by a speed factor using linear interpolation.
+///
+/// A factor of 2.0 halves the length (and doubles the pitch).
+fn resample(samples: &[f32], channels: usize, factor: f64) -> Vec<f32> {
+    let frames = samples.len() / channels;
+    if frames == 0 {
+        return Vec::new();
+    }
+
+    let out_frames = (frames as f64 / factor).floor() as usize;
+    let mut out = Vec::with_capacity(out_frames * channels);
+
+    for i in 0..out_frames {
+        let pos = i as f64 * factor;
+        let idx = pos as usize;
+        let frac = (pos - idx as f64) as f32;
+        let next = (idx + 1).min(frames - 1);
+        for ch in 0..channels {
+            let s1 = samples[idx * channels + ch];
+            let s2 = samples[next * channels + ch];
+            out.push(s1 + (s2 - s1) * frac);
+        }
+    }
+
+    out
+}
+
+/// Overlap-add time stretch without changing pitch.
+///
+/// A stretch of 2.0 doubles the length. Grains are Hann-windowed and the output
+/// is normalized by the summed window so the level stays constant.
+fn ola_stretch(samples: &[f32], channels: usize, stretch: f64, window: usize) -> Vec<f32> {
+    let frames = samples.len() / channels;
+    if frames == 0 {
+        return Vec::new();
+    }
+
+    let synthesis_hop = window / 4;
+    let analysis_hop = synthesis_hop as f64 / stretch;
+    let out_frames = (frames as f64 * stretch).round() as usize;
+
+    let hann: Vec<f32> = (0..window)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / window as f32).cos())
+        .collect();
+
+    let mut out = vec![0.0f32; (out_frames + window) * channels];
+    let mut norm = vec![0.0f32; out_frames + window];
+
+    let mut grain = 0usize;
+    loop {
+        let out_pos = grain * synthesis_hop;
+        if out_pos >= out_frames {
+            break;
+        }
+        let in_pos = (grain as f64 * analysis_hop) as usize;
+
+        for (i, &w) in hann.iter().enumerate() {
+            let src = in_pos + i;
+            if src >= frames {
+                break;
+            }
+            let dst = out_pos + i;
+            for ch in 0..channels {
+                out[dst * channels + ch] += samples[src * channels + ch] * w;
+            }
+            norm[dst] += w;
+        }
+        grain += 1;
+    }
+
+    out.truncate(out_frames * channels);
+    for (frame, &n) in out.chunks_mut(channels).zip(norm.iter()) {
+        if n > 1e-3 {
+            for sample in frame.iter_mut() {
+                *sample /= n;
+            }
+        }
+    }
+
+    out
+}
+
+/// Tape-style speed change: faster playback is shorter and higher pitched
+pub struct Speed {
+    factor: f64,
+    channels: usize,
+}
+
+impl Speed {
+    /// Create a speed change clamped to 0.25x-4x
+    pub fn new(factor: f32, channels: usize) -> Self {
+        Self {
+            factor: factor.clamp(0.25, 4.0) as f64,
+            channels: channels.max(1),
+        }
+    }
+}
+
+impl Effect for Speed {
+    fn name(&self) -> &'static str {
+        "speed"
+    }
+
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        if (self.factor - 1.0).abs() < f64::EPSILON {
+            return;
+        }
+        *samples = resample(samples, self.channels, self.factor);
+    }
+}
+
+/// Pitch shift that preserves duration.
+///
+/// Stretches the audio by the pitch ratio with overlap-add, then resamples it
+/// back to the original length, which moves the pitch by that ratio.
+pub struct PitchShift {
+    ratio: f64,
+    channels: usize,
+    window: usize,
+}
+
+impl PitchShift {
+    /// Create a pitch shift clamped to +/- 24 semitones
+    pub fn new(semitones: f32, sample_rate: u32, channels: usize) -> Self {
+        let semitones = semitones.clamp(-24.0, 24.0) as f64;
+        Self {
+            ratio: 2f64.powf(semitones / 12.0),
+            channels: channels.max(1),
+            // ~40ms grains: long enough for low voices, short enough to avoid echo
+            window: ((sample_rate as usize / 25).max(64) / 4) * 4,
+        }
+    }
+}
+
+impl Effect for PitchShift {
+    fn name(&self) -> &'static str {
+        "pitch_shift"
+    }
+
+    fn process(&mut self, samples: &mut Vec<f32>) {
+        if (self.ratio - 1.0).abs() < 1e-6 {
+            return;
+        }
+        let stretched = ola_stretch(samples, self.channels, self.ratio, self.window);
+        *samples = resample(&stretched, self.channels, self.ratio);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_util::*;
+    use super::*;
+
+    #[test]
+    fn test_speed_double_halves_length_and_doubles_pitch() {
+        let mut samples = sine(440.0, 48000, 48000, 1, 0.5);
+        Speed::new(2.0, 1).process(&mut samples);
+
+        assert_eq!(samples.len(), 24000);
+        let freq = zero_crossing_freq(&samples, 48000);
+        assert!(
+            (freq - 880.0).abs() < 10.0,
+            "Expected ~880 Hz, got {}",
+            freq
+        );
+    }
+
+    #[test]
+    fn test_speed_half_doubles_length() {
+        let mut samples = sine(440.0, 48000, 4800, 2, 0.5);
+        Speed::new(0.5, 2).process(&mut samples);
+        assert_eq!(samples.len(), 4800 * 2 * 2);
+    }
+
+    #[test]
+    fn test_speed_unity_is_passthrough() {
+        let original = sine(440.0, 48000, 4800, 2, 0.5);
+        let mut samples = original.clone();
+        Speed::new(1.0, 2).process(&mut samples);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_pitch_shift_octave_up_keeps_duration() {
+        let mut samples = sine(220.0, 48000, 48000, 1, 0.5);
+        PitchShift::new(12.0, 48000, 1).process(&mut samples);
+
+        assert!((samples.len() as i64 - 48000).abs() < 100);
+        let freq = zero_crossing_freq(&samples[4800..43200], 48000);
+        assert!(
+            (freq - 440.0).abs() < 25.0,
+            "Expected ~440 Hz, got {}",
+            freq
+        );
+    }
+
+    #[test]
+    fn test_pitch_shift_down_keeps_duration() {
+        let mut samples = sine(880.0, 48000, 48000, 2, 0.5);
+        PitchShift::new(-12.0, 48000, 2).process(&mut samples);
+
+        assert!((samples.len() as i64 - 96000).abs() < 200);
+        let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+        let freq = zero_crossing_freq(&left[4800..43200], 48000);
+        assert!(
+            (freq - 440.0).abs() < 25.0,
+            "Expected ~440 Hz, got {}",
+            freq
+        );
+    }
+
+    #[test]
+    fn test_pitch_shift_keeps_level() {
+        let mut samples = sine(440.0, 48000, 48000, 1, 0.5);
+        let before = rms(&samples);
+        PitchShift::new(5.0, 48000, 1).process(&mut samples);
+        let after = rms(&samples[4800..43200]);
+        assert!((after / before) > 0.6 && (after / before) < 1.2);
+    }
+}
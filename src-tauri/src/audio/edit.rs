@@ -0,0 +1,195 @@
+//! Destructive edits baked into new audio
+//!
+//! Unlike effect chains (see [`dsp`](super::dsp)), these edits produce audio that
+//! replaces the sound's file: playback then decodes only what is heard instead of
+//! decoding the whole file and skipping the trimmed parts every time.
+
+use std::time::Instant;
+
+use tracing::debug;
+
+use super::dsp::db_to_linear;
+use super::playback::lerp_sample;
+use super::AudioData;
+
+/// Peaks below this are treated as silence and never normalized
+const SILENCE_PEAK: f32 = 1e-6;
+
+/// Edits to bake into a processed copy of a sound
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditOptions {
+    pub trim_start_ms: Option<u64>,
+    pub trim_end_ms: Option<u64>,
+    /// Scale so the loudest sample hits this level in dBFS
+    pub normalize_peak_db: Option<f32>,
+    /// Resample to this rate (e.g. the output device rate)
+    pub sample_rate: Option<u32>,
+}
+
+/// Apply trim, normalization and rate conversion, in that order
+pub fn apply_edits(audio_data: &AudioData, options: &EditOptions) -> AudioData {
+    let start = Instant::now();
+    let mut edited = trim(audio_data, options.trim_start_ms, options.trim_end_ms);
+    if let Some(peak_db) = options.normalize_peak_db {
+        normalize(&mut edited.samples, peak_db);
+    }
+    if let Some(sample_rate) = options.sample_rate {
+        edited = resample(&edited, sample_rate);
+    }
+
+    debug!(
+        input_frames = audio_data.samples.len() / audio_data.channels.max(1) as usize,
+        output_frames = edited.samples.len() / edited.channels.max(1) as usize,
+        sample_rate = edited.sample_rate,
+        duration_ms = start.elapsed().as_millis(),
+        "Edits applied"
+    );
+    edited
+}
+
+/// Copy out the frames between the trim points (milliseconds, as stored on sounds)
+fn trim(audio_data: &AudioData, start_ms: Option<u64>, end_ms: Option<u64>) -> AudioData {
+    let channels = audio_data.channels.max(1) as usize;
+    let total_frames = audio_data.samples.len() / channels;
+    let to_frame = |ms: u64| ((ms as f64 / 1000.0) * audio_data.sample_rate as f64) as usize;
+    let end = end_ms.map_or(total_frames, to_frame).min(total_frames);
+    let begin = start_ms.map_or(0, to_frame).min(end);
+
+    AudioData {
+        samples: audio_data.samples[begin * channels..end * channels].to_vec(),
+        sample_rate: audio_data.sample_rate,
+        channels: audio_data.channels,
+    }
+}
+
+/// Scale samples so the peak sits at `peak_db` dBFS (silence is left alone)
+fn normalize(samples: &mut [f32], peak_db: f32) {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak < SILENCE_PEAK {
+        return;
+    }
+    let gain = db_to_linear(peak_db.min(0.0)) / peak;
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+}
+
+/// Linear-interpolation rate conversion, the same interpolation playback uses
+fn resample(audio_data: &AudioData, sample_rate: u32) -> AudioData {
+    let channels = audio_data.channels.max(1) as usize;
+    let in_frames = audio_data.samples.len() / channels;
+    if sample_rate == 0 || sample_rate == audio_data.sample_rate || in_frames == 0 {
+        return AudioData {
+            samples: audio_data.samples.clone(),
+            sample_rate: audio_data.sample_rate,
+            channels: audio_data.channels,
+        };
+    }
+
+    let step = audio_data.sample_rate as f64 / sample_rate as f64;
+    let out_frames = (in_frames as f64 / step).round() as usize;
+    let mut samples = Vec::with_capacity(out_frames * channels);
+    for frame in 0..out_frames {
+        let position = frame as f64 * step;
+        let index = (position as usize).min(in_frames - 1);
+        let next = (index + 1).min(in_frames - 1);
+        let frac = (position - index as f64) as f32;
+        for ch in 0..channels {
+            samples.push(lerp_sample(
+                audio_data.samples[index * channels + ch],
+                audio_data.samples[next * channels + ch],
+                frac,
+            ));
+        }
+    }
+
+    AudioData {
+        samples,
+        sample_rate,
+        channels: audio_data.channels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::dsp::test_util::{peak, sine, zero_crossing_freq};
+
+    fn tone(frames: usize, channels: u16, amp: f32) -> AudioData {
+        AudioData {
+            samples: sine(440.0, 48000, frames, channels as usize, amp),
+            sample_rate: 48000,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_no_edits_is_identity() {
+        let audio = tone(4800, 2, 0.5);
+        let edited = apply_edits(&audio, &EditOptions::default());
+        assert_eq!(edited.samples, audio.samples);
+        assert_eq!(edited.sample_rate, 48000);
+    }
+
+    #[test]
+    fn test_trim_keeps_frames_between_points() {
+        let audio = tone(48000, 2, 0.5);
+        let options = EditOptions {
+            trim_start_ms: Some(250),
+            trim_end_ms: Some(750),
+            ..Default::default()
+        };
+        let edited = apply_edits(&audio, &options);
+        assert_eq!(edited.samples.len(), 24000 * 2);
+        assert_eq!(edited.samples[..], audio.samples[12000 * 2..36000 * 2]);
+
+        // Out-of-range trims clamp to the audio
+        let options = EditOptions {
+            trim_start_ms: Some(2000),
+            trim_end_ms: Some(5000),
+            ..Default::default()
+        };
+        assert!(apply_edits(&audio, &options).samples.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_sets_peak() {
+        let options = EditOptions {
+            normalize_peak_db: Some(-1.0),
+            ..Default::default()
+        };
+        let edited = apply_edits(&tone(4800, 1, 0.1), &options);
+        assert!((peak(&edited.samples) - db_to_linear(-1.0)).abs() < 1e-4);
+
+        // Positive targets are capped at full scale; silence stays silent
+        let options = EditOptions {
+            normalize_peak_db: Some(6.0),
+            ..Default::default()
+        };
+        assert!(peak(&apply_edits(&tone(4800, 1, 0.1), &options).samples) <= 1.0 + 1e-6);
+        let silence = AudioData {
+            samples: vec![0.0; 480],
+            sample_rate: 48000,
+            channels: 1,
+        };
+        assert!(apply_edits(&silence, &options)
+            .samples
+            .iter()
+            .all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_resample_preserves_duration_and_pitch() {
+        let options = EditOptions {
+            sample_rate: Some(44100),
+            ..Default::default()
+        };
+        let edited = apply_edits(&tone(48000, 2, 0.5), &options);
+        assert_eq!(edited.sample_rate, 44100);
+        assert_eq!(edited.channels, 2);
+        assert_eq!(edited.samples.len(), 44100 * 2);
+
+        let left: Vec<f32> = edited.samples.iter().step_by(2).copied().collect();
+        assert!((zero_crossing_freq(&left, 44100) - 440.0).abs() < 2.0);
+    }
+}
@@ -49,6 +49,12 @@ pub enum AudioError {
 
     #[error("Device not found: {0}")]
     DeviceNotFound(String),
+
+    #[error("Failed to encode audio: {0}")]
+    Encode(String),
+
+    #[error("Speech synthesis failed: {0}")]
+    Synthesis(String),
 }
 
 /// Convert AudioError to String for Tauri commands
@@ -63,7 +69,7 @@ mod tests {
     use super::*;
     use std::io::{Error as IoError, ErrorKind};
 
-    // ========== Display trait tests for all 15 error variants ==========
+    // ========== Display trait tests for all 17 error variants ==========
 
     #[test]
     fn test_display_file_open() {
@@ -182,6 +188,22 @@ mod tests {
         assert!(msg.contains("Speakers (High Definition Audio)"));
     }
 
+    #[test]
+    fn test_display_encode() {
+        let err = AudioError::Encode("block size too large".to_string());
+        let msg = err.to_string();
+        assert!(msg.contains("Failed to encode audio"));
+        assert!(msg.contains("block size too large"));
+    }
+
+    #[test]
+    fn test_display_synthesis() {
+        let err = AudioError::Synthesis("espeak-ng not found".to_string());
+        let msg = err.to_string();
+        assert!(msg.contains("Speech synthesis failed"));
+        assert!(msg.contains("espeak-ng not found"));
+    }
+
     // ========== From<io::Error> conversion test ==========
 
     #[test]
@@ -0,0 +1,490 @@
+//! Offline rendering and export of sounds
+//!
+//! [`render_offline`] runs decoded audio through the same path as live playback
+//! (effect chain, [`Voice`](super::playback::Voice) volume curve, bus limiter) but
+//! pulls the bus as fast as possible instead of from a device callback. The bus runs
+//! at the sound's own sample rate and channel count, so no resampling happens.
+//! [`write_audio`] encodes the result as WAV (16-bit PCM), FLAC or Ogg Vorbis.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::num::{NonZeroU32, NonZeroU8};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use flacenc::bitsink::{BitSink, ByteSink};
+use flacenc::component::{BitRepr, StreamInfo};
+use flacenc::error::Verify;
+use flacenc::source::{Fill, FrameBuf};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::bus::BusMixer;
+use super::cache::AudioCache;
+use super::dsp::{render_effects, EffectConfig};
+use super::sidechain::Sidechain;
+use super::{AudioData, AudioError};
+
+/// Frames rendered per bus pull
+const RENDER_BLOCK_FRAMES: usize = 1024;
+
+/// Frames handed to the Vorbis encoder at a time (libvorbis recommends ~1024)
+const VORBIS_BLOCK_FRAMES: usize = 1024;
+
+/// Playback ID of the voice used for offline rendering
+const EXPORT_PLAYBACK_ID: &str = "export";
+
+/// File format for exported sounds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// 16-bit PCM WAV
+    #[default]
+    Wav,
+    /// 16-bit FLAC (lossless)
+    Flac,
+    /// Ogg Vorbis (lossy, default quality)
+    Ogg,
+}
+
+impl ExportFormat {
+    /// File extension without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+            Self::Ogg => "ogg",
+        }
+    }
+
+    /// Format matching a file's extension, if it is one we can write
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "wav" => Some(Self::Wav),
+            "flac" => Some(Self::Flac),
+            "ogg" | "oga" => Some(Self::Ogg),
+            _ => None,
+        }
+    }
+}
+
+/// Per-sound playback settings applied when rendering
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    /// Playback volume (0.0-1.0), as passed to `play_dual_output`
+    pub volume: f32,
+    pub trim_start_ms: Option<u64>,
+    pub trim_end_ms: Option<u64>,
+    pub effects: Vec<EffectConfig>,
+    /// Bus limiter ceiling in dBFS
+    pub limiter_ceiling_db: f32,
+}
+
+/// Summary of a finished export
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSummary {
+    pub output_path: String,
+    pub format: ExportFormat,
+    pub duration_ms: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Render audio exactly as a playback with `settings` would sound
+pub fn render_offline(audio_data: Arc<AudioData>, settings: &RenderSettings) -> AudioData {
+    let sample_rate = audio_data.sample_rate;
+    let channels = audio_data.channels.max(1) as usize;
+    let to_frame = |ms: u64| ((ms as f64 / 1000.0) * sample_rate as f64) as usize;
+    let start_frame = settings.trim_start_ms.map(to_frame);
+    let end_frame = settings.trim_end_ms.map(to_frame);
+
+    // Same order as playback: the effect chain renders the trimmed audio once
+    let (audio_data, start_frame, end_frame) = if settings.effects.is_empty() {
+        (audio_data, start_frame, end_frame)
+    } else {
+        let rendered = render_effects(&audio_data, start_frame, end_frame, &settings.effects);
+        (Arc::new(rendered), None, None)
+    };
+    // Measured after the effects, which can change the length (speed, reverb tail)
+    let total_frames = audio_data.samples.len() / channels;
+    let end = end_frame.unwrap_or(total_frames).min(total_frames);
+    let frames = end.saturating_sub(start_frame.unwrap_or(0));
+
+    // A private sidechain: nothing else is playing, so nothing ducks the render
+    let mut mixer = BusMixer::new(
+        sample_rate,
+        channels,
+        settings.limiter_ceiling_db,
+        Arc::new(Sidechain::default()),
+    );
+    mixer.add_voice(
+        EXPORT_PLAYBACK_ID.to_string(),
+        audio_data,
+        Arc::new(Mutex::new(settings.volume)),
+        start_frame,
+        end_frame,
+    );
+
+    // Pull until the limiter's look-ahead has flushed the last frame
+    let latency = mixer.latency_frames();
+    let mut samples = Vec::with_capacity((frames + latency) * channels);
+    let mut block = vec![0.0f32; RENDER_BLOCK_FRAMES * channels];
+    while samples.len() < (frames + latency) * channels {
+        mixer.render(&mut block);
+        samples.extend_from_slice(&block);
+    }
+    samples.drain(..latency * channels);
+    samples.truncate(frames * channels);
+
+    AudioData {
+        samples,
+        sample_rate,
+        channels: channels as u16,
+    }
+}
+
+/// Encode interleaved audio to a file
+pub fn write_audio(
+    audio_data: &AudioData,
+    path: &Path,
+    format: ExportFormat,
+) -> Result<(), AudioError> {
+    if audio_data.samples.is_empty() {
+        return Err(AudioError::NoData);
+    }
+    match format {
+        ExportFormat::Wav => write_wav(audio_data, path),
+        ExportFormat::Flac => write_flac(audio_data, path),
+        ExportFormat::Ogg => write_ogg(audio_data, path),
+    }
+}
+
+/// Render a sound file with its playback settings and write it to `output_path`
+///
+/// Decoding goes through the shared cache, so exporting a sound that was just
+/// played doesn't decode it again.
+pub fn export_sound_file(
+    cache: &Mutex<AudioCache>,
+    file_path: &str,
+    settings: &RenderSettings,
+    output_path: &Path,
+    format: ExportFormat,
+) -> Result<ExportSummary, AudioError> {
+    let start = Instant::now();
+    let audio_data = AudioCache::get_or_decode(cache, file_path)?;
+    let rendered = render_offline(audio_data, settings);
+    write_audio(&rendered, output_path, format)?;
+
+    let frames = rendered.samples.len() / rendered.channels as usize;
+    let summary = ExportSummary {
+        output_path: output_path.to_string_lossy().into_owned(),
+        format,
+        duration_ms: (frames as u64 * 1000) / rendered.sample_rate as u64,
+        sample_rate: rendered.sample_rate,
+        channels: rendered.channels,
+    };
+    info!(
+        source = %file_path,
+        output = %summary.output_path,
+        format = ?format,
+        frames = frames,
+        duration_ms = start.elapsed().as_millis(),
+        "Sound exported"
+    );
+    Ok(summary)
+}
+
+fn encode_error(error: impl std::fmt::Display) -> AudioError {
+    AudioError::Encode(error.to_string())
+}
+
+#[inline]
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * 32767.0).round() as i16
+}
+
+fn write_wav(audio_data: &AudioData, path: &Path) -> Result<(), AudioError> {
+    let spec = hound::WavSpec {
+        channels: audio_data.channels,
+        sample_rate: audio_data.sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).map_err(encode_error)?;
+    for &sample in &audio_data.samples {
+        writer.write_sample(to_i16(sample)).map_err(encode_error)?;
+    }
+    writer.finalize().map_err(encode_error)
+}
+
+fn write_flac(audio_data: &AudioData, path: &Path) -> Result<(), AudioError> {
+    let channels = audio_data.channels as usize;
+    let samples: Vec<i32> = audio_data
+        .samples
+        .iter()
+        .map(|&s| to_i16(s) as i32)
+        .collect();
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| encode_error(e))?;
+    let mut info =
+        StreamInfo::new(audio_data.sample_rate as usize, channels, 16).map_err(encode_error)?;
+
+    // Frames are encoded one by one instead of with `encode_with_fixed_block_size`,
+    // which pads the last block to full size and so lengthens the sound
+    let mut frames = Vec::new();
+    for (frame_number, block) in samples.chunks(config.block_size * channels).enumerate() {
+        let mut framebuf =
+            FrameBuf::with_size(channels, config.block_size).map_err(encode_error)?;
+        framebuf.resize(block.len() / channels);
+        framebuf.fill_interleaved(block).map_err(encode_error)?;
+        let frame = flacenc::encode_fixed_size_frame(&config, &framebuf, frame_number, &info)
+            .map_err(|e| encode_error(format!("{:?}", e)))?;
+        info.update_frame_info(&frame);
+        frames.push(frame);
+    }
+    // The short last block doesn't count towards the stream's block size
+    info.set_block_sizes(config.block_size, config.block_size)
+        .map_err(encode_error)?;
+
+    let mut sink = ByteSink::new();
+    let info_len = (info.count_bits() / 8) as u32;
+    let mut header = b"fLaC".to_vec();
+    // Last-metadata-block flag, STREAMINFO type, then the 24-bit block length
+    header.push(0x80);
+    header.extend_from_slice(&info_len.to_be_bytes()[1..]);
+    sink.write_bytes_aligned(&header).map_err(encode_error)?;
+    info.write(&mut sink).map_err(encode_error)?;
+    for frame in &frames {
+        frame.write(&mut sink).map_err(encode_error)?;
+    }
+    std::fs::write(path, sink.as_slice()).map_err(encode_error)?;
+    Ok(())
+}
+
+fn write_ogg(audio_data: &AudioData, path: &Path) -> Result<(), AudioError> {
+    let channels = audio_data.channels as usize;
+    let sample_rate = NonZeroU32::new(audio_data.sample_rate)
+        .ok_or_else(|| encode_error("sample rate is zero"))?;
+    let channel_count =
+        NonZeroU8::new(audio_data.channels as u8).ok_or_else(|| encode_error("no channels"))?;
+
+    let sink = BufWriter::new(File::create(path).map_err(encode_error)?);
+    let mut encoder = vorbis_rs::VorbisEncoderBuilder::new(sample_rate, channel_count, sink)
+        .map_err(encode_error)?
+        .build()
+        .map_err(encode_error)?;
+
+    // Vorbis takes planar blocks
+    let mut planar = vec![Vec::with_capacity(VORBIS_BLOCK_FRAMES); channels];
+    for block in audio_data.samples.chunks(VORBIS_BLOCK_FRAMES * channels) {
+        for (ch, plane) in planar.iter_mut().enumerate() {
+            plane.clear();
+            plane.extend(block.iter().skip(ch).step_by(channels));
+        }
+        encoder.encode_audio_block(&planar).map_err(encode_error)?;
+    }
+    encoder.finish().map_err(encode_error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decode::decode_audio_file;
+    use crate::audio::dsp::db_to_linear;
+    use crate::audio::dsp::test_util::{peak, rms};
+    use crate::audio::playback::calculate_scaled_volume;
+    use std::path::PathBuf;
+
+    fn get_fixture_path(filename: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(filename)
+    }
+
+    fn settings(volume: f32) -> RenderSettings {
+        RenderSettings {
+            volume,
+            trim_start_ms: None,
+            trim_end_ms: None,
+            effects: Vec::new(),
+            limiter_ceiling_db: -1.0,
+        }
+    }
+
+    fn constant(value: f32, frames: usize, channels: u16) -> Arc<AudioData> {
+        Arc::new(AudioData {
+            samples: vec![value; frames * channels as usize],
+            sample_rate: 48000,
+            channels,
+        })
+    }
+
+    #[test]
+    fn test_render_applies_playback_volume_curve() {
+        let rendered = render_offline(constant(0.5, 4800, 2), &settings(0.25));
+
+        assert_eq!(rendered.samples.len(), 4800 * 2);
+        assert_eq!((rendered.sample_rate, rendered.channels), (48000, 2));
+        // Output is aligned: the limiter look-ahead has been removed
+        let expected = 0.5 * calculate_scaled_volume(0.25);
+        assert!((rendered.samples[0] - expected).abs() < 1e-4);
+        // The last frame is the interpolation guard frame, as in playback
+        let body = &rendered.samples[..rendered.samples.len() - 2];
+        assert!(body.iter().all(|s| (s - expected).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_render_applies_trim() {
+        let mut s = settings(1.0);
+        s.trim_start_ms = Some(100);
+        s.trim_end_ms = Some(350);
+        let rendered = render_offline(constant(0.5, 48000, 1), &s);
+        assert_eq!(rendered.samples.len(), 12000);
+    }
+
+    #[test]
+    fn test_render_runs_effects_and_limiter() {
+        let mut s = settings(1.0);
+        s.effects = vec![EffectConfig::Gain { gain_db: 30.0 }];
+        let rendered = render_offline(constant(0.5, 48000, 1), &s);
+        assert_eq!(rendered.samples.len(), 48000);
+        assert!(peak(&rendered.samples) <= db_to_linear(-1.0) + 1e-6);
+    }
+
+    #[test]
+    fn test_render_keeps_reverb_tail() {
+        let mut s = settings(1.0);
+        s.effects = vec![EffectConfig::Reverb {
+            room_size: 0.8,
+            damping: 0.5,
+            mix: 0.5,
+        }];
+        let audio = constant(0.1, 48000, 2);
+        let expected = render_effects(&audio, None, None, &s.effects);
+        assert!(expected.samples.len() > 48000 * 2);
+
+        let rendered = render_offline(audio, &s);
+        assert_eq!(rendered.samples.len(), expected.samples.len());
+    }
+
+    #[test]
+    fn test_render_keeps_slowed_down_length() {
+        let mut s = settings(1.0);
+        s.effects = vec![EffectConfig::Speed { factor: 0.5 }];
+        let rendered = render_offline(constant(0.1, 48000, 1), &s);
+        // One second of audio at half speed plays for two
+        assert!((rendered.samples.len() as i64 - 96000).abs() <= 480);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.WAV")),
+            Some(ExportFormat::Wav)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.flac")),
+            Some(ExportFormat::Flac)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("a.ogg")),
+            Some(ExportFormat::Ogg)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("a.mp3")), None);
+        assert_eq!(ExportFormat::from_path(Path::new("a")), None);
+    }
+
+    #[test]
+    fn test_write_rejects_empty_audio() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = AudioData {
+            samples: Vec::new(),
+            sample_rate: 48000,
+            channels: 2,
+        };
+        let result = write_audio(&empty, &dir.path().join("empty.wav"), ExportFormat::Wav);
+        assert!(matches!(result, Err(AudioError::NoData)));
+    }
+
+    #[test]
+    fn test_flac_keeps_short_last_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tail.flac");
+        // Two full 4096-frame blocks and a 5-frame tail
+        let audio = constant(0.25, 4096 * 2 + 5, 2);
+        write_audio(&audio, &path, ExportFormat::Flac).unwrap();
+
+        let decoded = decode_audio_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(decoded.samples.len(), audio.samples.len());
+        assert!(decoded.samples.iter().all(|s| (s - 0.25).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_export_fixtures_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Mutex::new(AudioCache::new(50));
+        let mut s = settings(1.0);
+        s.trim_start_ms = Some(100);
+        s.trim_end_ms = Some(600);
+
+        for fixture in ["test_mono.mp3", "test_stereo.ogg", "test_stereo.m4a"] {
+            let source = get_fixture_path(fixture);
+            let source = source.to_str().unwrap();
+            let expected = render_offline(AudioCache::get_or_decode(&cache, source).unwrap(), &s);
+            let expected_frames = expected.samples.len() / expected.channels as usize;
+            assert_eq!(expected_frames, expected.sample_rate as usize / 2);
+
+            for format in [ExportFormat::Wav, ExportFormat::Flac, ExportFormat::Ogg] {
+                let output = dir
+                    .path()
+                    .join(format!("{}.{}", fixture, format.extension()));
+                let summary = export_sound_file(&cache, source, &s, &output, format).unwrap();
+                assert_eq!(summary.duration_ms, 500);
+
+                let decoded = decode_audio_file(output.to_str().unwrap()).unwrap();
+                assert_eq!(decoded.channels, expected.channels);
+                assert_eq!(decoded.sample_rate, expected.sample_rate);
+                let frames = decoded.samples.len() / decoded.channels as usize;
+                let level_db = 20.0 * (rms(&decoded.samples) / rms(&expected.samples)).log10();
+
+                if format == ExportFormat::Ogg {
+                    // Lossy: allow codec padding and a small level change
+                    assert!(
+                        frames.abs_diff(expected_frames) < 2048,
+                        "{} {:?}",
+                        fixture,
+                        format
+                    );
+                    assert!(
+                        level_db.abs() < 1.0,
+                        "{} {:?}: {} dB",
+                        fixture,
+                        format,
+                        level_db
+                    );
+                } else {
+                    assert_eq!(frames, expected_frames, "{} {:?}", fixture, format);
+                    assert!(
+                        level_db.abs() < 0.1,
+                        "{} {:?}: {} dB",
+                        fixture,
+                        format,
+                        level_db
+                    );
+                    // 16-bit quantization only
+                    let max_error = decoded
+                        .samples
+                        .iter()
+                        .zip(&expected.samples)
+                        .fold(0.0f32, |max, (a, b)| max.max((a - b).abs()));
+                    assert!(max_error < 1e-4, "{} {:?}: {}", fixture, format, max_error);
+                }
+            }
+        }
+    }
+}
@@ -5,8 +5,18 @@
 use std::collections::HashMap;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
+use std::thread;
 
+use tracing::debug;
+
+use super::backend::AudioBackend;
+use super::bus::{BusMeter, BusRegistry};
 use super::cache::{AudioCache, CacheStats};
+use super::disk_cache::DiskCache;
+use super::dsp::EffectConfig;
+use super::playback::PreparedSound;
+use super::sidechain::DuckingConfig;
+use super::{AudioData, AudioError};
 
 /// State of an active sound playback
 #[derive(Clone, Debug)]
@@ -35,10 +45,12 @@ pub struct AudioManager {
     stop_senders: Arc<Mutex<HashMap<String, Sender<()>>>>,
     /// Counter for generating unique playback IDs
     playback_counter: Arc<Mutex<u64>>,
-    /// LRU cache for decoded audio data
+    /// Cache for decoded audio data
     cache: Arc<Mutex<AudioCache>>,
     /// Active sound_id -> SoundState mapping for policy enforcement
     active_sounds: Arc<Mutex<HashMap<String, SoundState>>>,
+    /// Per-device output buses (mixing + limiting)
+    buses: Arc<BusRegistry>,
 }
 
 impl AudioManager {
@@ -48,6 +60,7 @@ impl AudioManager {
             playback_counter: Arc::new(Mutex::new(0)),
             cache: Arc::new(Mutex::new(AudioCache::default())),
             active_sounds: Arc::new(Mutex::new(HashMap::new())),
+            buses: Arc::new(BusRegistry::default()),
         }
     }
 
@@ -58,6 +71,7 @@ impl AudioManager {
             playback_counter: Arc::new(Mutex::new(0)),
             cache: Arc::new(Mutex::new(AudioCache::new(max_memory_mb))),
             active_sounds: Arc::new(Mutex::new(HashMap::new())),
+            buses: Arc::new(BusRegistry::default()),
         }
     }
 
@@ -71,9 +85,95 @@ impl AudioManager {
         self.cache.lock().unwrap().clear();
     }
 
+    /// Use a persistent decode cache (None disables it)
+    pub fn set_disk_cache(&self, disk: Option<DiskCache>) {
+        self.cache.lock().unwrap().set_disk_cache(disk);
+    }
+
+    /// Keep these files cached no matter what (hotkey-bound sounds)
+    pub fn set_pinned_files(&self, file_paths: Vec<String>) {
+        self.cache.lock().unwrap().set_pinned(file_paths);
+    }
+
+    /// Get cached audio or decode it (the cache lock isn't held while decoding)
+    pub fn get_or_decode(&self, file_path: &str) -> Result<Arc<AudioData>, AudioError> {
+        AudioCache::get_or_decode(&self.cache, file_path)
+    }
+
+    /// Trim decoded audio and apply its effect chain, reusing a cached render
+    pub fn prepare(
+        &self,
+        file_path: &str,
+        audio_data: Arc<AudioData>,
+        trim_start_ms: Option<u64>,
+        trim_end_ms: Option<u64>,
+        effects: &[EffectConfig],
+    ) -> PreparedSound {
+        AudioCache::prepare(
+            &self.cache,
+            file_path,
+            audio_data,
+            trim_start_ms,
+            trim_end_ms,
+            effects,
+        )
+    }
+
+    /// Decode files into the cache on a background thread, in order
+    ///
+    /// Stops early once the memory budget is full so preloading never evicts
+    /// what it just loaded. Triggers meanwhile aren't blocked: decoding happens
+    /// outside the cache lock.
+    pub fn preload(&self, file_paths: Vec<String>) {
+        let cache = self.cache.clone();
+        thread::spawn(move || {
+            let mut loaded = 0;
+            for path in &file_paths {
+                if !cache.lock().unwrap().has_room() {
+                    debug!(
+                        loaded,
+                        remaining = file_paths.len() - loaded,
+                        "Preload stopped: cache full"
+                    );
+                    return;
+                }
+                if AudioCache::get_or_decode(&cache, path).is_ok() {
+                    debug!("Preloaded: {}", path);
+                }
+                loaded += 1;
+            }
+            debug!(loaded, "Preload complete");
+        });
+    }
+
     /// Get cache statistics
     pub fn cache_stats(&self) -> CacheStats {
-        self.cache.lock().unwrap().stats()
+        AudioCache::stats(&self.cache)
+    }
+
+    /// Get a clone of the output bus registry for use in spawned threads
+    pub fn get_buses(&self) -> Arc<BusRegistry> {
+        self.buses.clone()
+    }
+
+    /// Audio backend used for device enumeration and streams
+    pub fn backend(&self) -> Arc<dyn AudioBackend> {
+        self.buses.backend()
+    }
+
+    /// Set the limiter ceiling (dBFS) on all output buses
+    pub fn set_limiter_ceiling_db(&self, ceiling_db: f32) {
+        self.buses.set_ceiling_db(ceiling_db);
+    }
+
+    /// Apply new ducking settings (takes effect on the next audio callback)
+    pub fn set_ducking_config(&self, config: DuckingConfig) {
+        self.buses.sidechain().set_config(config);
+    }
+
+    /// Read the gain reduction meters of all open output buses
+    pub fn limiter_meters(&self) -> Vec<BusMeter> {
+        self.buses.meters()
     }
 
     /// Generate a unique playback ID
@@ -295,6 +395,13 @@ mod tests {
         assert_eq!(playing.playback_id(), "pb_2");
     }
 
+    #[test]
+    fn test_limiter_meters_empty_without_playback() {
+        let manager = AudioManager::new();
+        manager.set_limiter_ceiling_db(-3.0);
+        assert!(manager.limiter_meters().is_empty());
+    }
+
     #[test]
     fn test_cache_clear() {
         let manager = AudioManager::new();
@@ -1,20 +1,47 @@
 //! Audio module for Sonic Deck
 //!
-//! Provides dual-output audio routing with cpal-based playback and caching.
+//! Provides dual-output audio routing with backend-agnostic playback and caching.
 
+mod backend;
+mod bus;
 mod cache;
 mod decode;
 mod device;
+mod disk_cache;
+mod dsp;
+mod edit;
 mod error;
+mod export;
 mod manager;
+mod monitor;
 mod playback;
+mod sidechain;
 mod waveform;
 
-pub use cache::CacheStats;
+#[cfg(test)]
+pub use backend::fake::FakeBackend;
+pub use backend::{AudioBackend, BackendDevice, ErrorCallback, StreamLatency};
+pub use bus::{BusHandle, BusMeter, BusMixer, BusRegistry, DEFAULT_LIMITER_CEILING_DB};
+pub use cache::{AudioCache, CacheStats};
+#[cfg(test)]
+pub use decode::decode_audio_file;
 pub use device::enumerate_devices;
+pub use disk_cache::{DiskCache, DEFAULT_DISK_CACHE_MB};
+#[cfg(test)]
+pub(crate) use dsp::test_util;
+pub use dsp::{
+    db_to_linear, render_effects, Biquad, BiquadCoeffs, Compressor, Ducker, EffectConfig,
+    EnvelopeFollower, LookaheadLimiter, NoiseGate,
+};
+pub use edit::{apply_edits, EditOptions};
 pub use error::AudioError;
+pub use export::{
+    export_sound_file, render_offline, write_audio, ExportFormat, ExportSummary, RenderSettings,
+};
 pub use manager::{AudioManager, SoundState};
-pub use playback::create_playback_stream;
+pub use monitor::{DeviceChange, DeviceMonitor, DeviceProvider, DeviceRole, DeviceSnapshot};
+pub use playback::PreparedSound;
+pub use sidechain::{DuckingConfig, VOICE_DETECT_ATTACK_MS, VOICE_DETECT_RELEASE_MS};
 pub use waveform::{generate_peaks, WaveformData};
 
 use serde::{Deserialize, Serialize};
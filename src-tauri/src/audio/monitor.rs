@@ -0,0 +1,438 @@
+//! Audio device hot-plug monitoring
+//!
+//! [`DeviceId`]s are enumeration indices, so they shift whenever a device is plugged
+//! in or removed. [`DeviceMonitor`] remembers the devices the app is configured to
+//! use by name and compares each new [`DeviceSnapshot`] against them, reporting which
+//! were lost, which came back and which only moved to another index. The caller
+//! applies the resulting [`DeviceChange`]s (closing stale buses, updating settings,
+//! restarting microphone routing).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use super::{AudioBackend, AudioError, BackendDevice, DeviceId};
+
+/// Device names by enumeration index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceSnapshot {
+    pub outputs: Vec<String>,
+    pub inputs: Vec<String>,
+}
+
+impl DeviceSnapshot {
+    fn names(&self, kind: DeviceKind) -> &[String] {
+        match kind {
+            DeviceKind::Output => &self.outputs,
+            DeviceKind::Input => &self.inputs,
+        }
+    }
+
+    /// Name of the device at an ID
+    fn name_of(&self, kind: DeviceKind, device_id: &DeviceId) -> Option<&str> {
+        let index = device_id.index().ok()?;
+        self.names(kind).get(index).map(String::as_str)
+    }
+
+    /// Find a device by name, preferring `hint` when several devices share the name
+    fn find(&self, kind: DeviceKind, name: &str, hint: &DeviceId) -> Option<DeviceId> {
+        if self.name_of(kind, hint) == Some(name) {
+            return Some(hint.clone());
+        }
+        self.names(kind)
+            .iter()
+            .position(|n| n == name)
+            .map(DeviceId::from_index)
+    }
+}
+
+/// Source of device snapshots (the audio backend in the app, a mock in tests)
+pub trait DeviceProvider: Send {
+    fn snapshot(&self) -> Result<DeviceSnapshot, AudioError>;
+}
+
+impl DeviceProvider for Arc<dyn AudioBackend> {
+    fn snapshot(&self) -> Result<DeviceSnapshot, AudioError> {
+        // Unnamed devices keep their slot so indices match `DeviceId`s
+        let names = |devices: Vec<BackendDevice>| devices.into_iter().map(|d| d.name).collect();
+        Ok(DeviceSnapshot {
+            outputs: names(self.output_devices()?),
+            inputs: names(self.input_devices()?),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeviceKind {
+    Output,
+    Input,
+}
+
+/// What a watched device is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceRole {
+    /// Output the user listens on
+    Monitor,
+    /// Output sent to the voice chat (usually CABLE Input)
+    Broadcast,
+    /// Routed microphone
+    Microphone,
+}
+
+impl DeviceRole {
+    fn kind(self) -> DeviceKind {
+        match self {
+            Self::Monitor | Self::Broadcast => DeviceKind::Output,
+            Self::Microphone => DeviceKind::Input,
+        }
+    }
+}
+
+/// A change to a watched device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeviceChange {
+    /// The device disappeared
+    Lost {
+        role: DeviceRole,
+        name: String,
+        device_id: DeviceId,
+    },
+    /// The device is back, possibly under a different ID
+    Restored {
+        role: DeviceRole,
+        name: String,
+        device_id: DeviceId,
+    },
+    /// Still present, but other devices shifted its ID
+    Moved {
+        role: DeviceRole,
+        name: String,
+        from: DeviceId,
+        to: DeviceId,
+    },
+}
+
+impl DeviceChange {
+    pub fn role(&self) -> DeviceRole {
+        match self {
+            Self::Lost { role, .. } | Self::Restored { role, .. } | Self::Moved { role, .. } => {
+                *role
+            }
+        }
+    }
+
+    /// The device's current ID, if it is present
+    pub fn current_id(&self) -> Option<&DeviceId> {
+        match self {
+            Self::Lost { .. } => None,
+            Self::Restored { device_id, .. } => Some(device_id),
+            Self::Moved { to, .. } => Some(to),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Watch {
+    name: String,
+    /// Current ID while present, last known ID while missing
+    device_id: DeviceId,
+    present: bool,
+}
+
+/// Tracks watched devices across snapshots
+pub struct DeviceMonitor {
+    snapshot: DeviceSnapshot,
+    watches: HashMap<DeviceRole, Watch>,
+}
+
+impl DeviceMonitor {
+    pub fn new(snapshot: DeviceSnapshot) -> Self {
+        Self {
+            snapshot,
+            watches: HashMap::new(),
+        }
+    }
+
+    /// Most recent snapshot
+    pub fn snapshot(&self) -> &DeviceSnapshot {
+        &self.snapshot
+    }
+
+    /// Set the device configured for a role
+    ///
+    /// The name is looked up in the latest snapshot. Passing the ID the role already
+    /// tracks keeps its state, so a missing device stays watched until it returns.
+    /// `None`, or an ID with no device behind it, stops watching the role.
+    pub fn watch(&mut self, role: DeviceRole, device_id: Option<&DeviceId>) {
+        let Some(device_id) = device_id else {
+            self.watches.remove(&role);
+            return;
+        };
+        if self
+            .watches
+            .get(&role)
+            .is_some_and(|w| w.device_id == *device_id)
+        {
+            return;
+        }
+
+        match self.snapshot.name_of(role.kind(), device_id) {
+            Some(name) => {
+                self.watches.insert(
+                    role,
+                    Watch {
+                        name: name.to_string(),
+                        device_id: device_id.clone(),
+                        present: true,
+                    },
+                );
+            }
+            None => {
+                self.watches.remove(&role);
+            }
+        }
+    }
+
+    /// Whether the device for a role is currently missing
+    pub fn is_missing(&self, role: DeviceRole) -> bool {
+        self.watches.get(&role).is_some_and(|w| !w.present)
+    }
+
+    /// Compare a new snapshot against the watched devices
+    ///
+    /// Returns `None` when the device lists are unchanged.
+    pub fn update(&mut self, snapshot: DeviceSnapshot) -> Option<Vec<DeviceChange>> {
+        if snapshot == self.snapshot {
+            return None;
+        }
+
+        let mut changes = Vec::new();
+        for (&role, watch) in self.watches.iter_mut() {
+            let found = snapshot.find(role.kind(), &watch.name, &watch.device_id);
+            let name = watch.name.clone();
+            match (watch.present, found) {
+                (true, Some(id)) if id == watch.device_id => {}
+                (true, Some(id)) => {
+                    let from = std::mem::replace(&mut watch.device_id, id.clone());
+                    changes.push(DeviceChange::Moved {
+                        role,
+                        name,
+                        from,
+                        to: id,
+                    });
+                }
+                (true, None) => {
+                    watch.present = false;
+                    changes.push(DeviceChange::Lost {
+                        role,
+                        name,
+                        device_id: watch.device_id.clone(),
+                    });
+                }
+                (false, Some(id)) => {
+                    watch.present = true;
+                    watch.device_id = id.clone();
+                    changes.push(DeviceChange::Restored {
+                        role,
+                        name,
+                        device_id: id,
+                    });
+                }
+                (false, None) => {}
+            }
+        }
+
+        // Stable order for callers and tests
+        changes.sort_by_key(|c| c.role() as u8);
+        self.snapshot = snapshot;
+        Some(changes)
+    }
+
+    /// Take a snapshot from `provider` and [`update`](Self::update) with it
+    pub fn poll(
+        &mut self,
+        provider: &dyn DeviceProvider,
+    ) -> Result<Option<Vec<DeviceChange>>, AudioError> {
+        Ok(self.update(provider.snapshot()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Provider whose device lists the test edits between polls
+    #[derive(Clone, Default)]
+    struct MockProvider {
+        snapshot: Arc<Mutex<DeviceSnapshot>>,
+        fail: Arc<Mutex<bool>>,
+    }
+
+    impl MockProvider {
+        fn new(outputs: &[&str], inputs: &[&str]) -> Self {
+            let provider = Self::default();
+            provider.set(outputs, inputs);
+            provider
+        }
+
+        fn set(&self, outputs: &[&str], inputs: &[&str]) {
+            *self.snapshot.lock().unwrap() = DeviceSnapshot {
+                outputs: outputs.iter().map(|s| s.to_string()).collect(),
+                inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            };
+        }
+    }
+
+    impl DeviceProvider for MockProvider {
+        fn snapshot(&self) -> Result<DeviceSnapshot, AudioError> {
+            if *self.fail.lock().unwrap() {
+                return Err(AudioError::DeviceEnumeration("mock failure".to_string()));
+            }
+            Ok(self.snapshot.lock().unwrap().clone())
+        }
+    }
+
+    fn monitor_for(provider: &MockProvider) -> DeviceMonitor {
+        DeviceMonitor::new(provider.snapshot().unwrap())
+    }
+
+    #[test]
+    fn test_unchanged_devices_report_nothing() {
+        let provider = MockProvider::new(&["Speakers", "Headset"], &["Mic"]);
+        let mut monitor = monitor_for(&provider);
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(1)));
+
+        assert_eq!(monitor.poll(&provider).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unwatched_change_still_reports_list_change() {
+        let provider = MockProvider::new(&["Speakers"], &[]);
+        let mut monitor = monitor_for(&provider);
+
+        provider.set(&["Speakers", "HDMI"], &[]);
+        assert_eq!(monitor.poll(&provider).unwrap(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_headset_unplug_and_return_at_new_index() {
+        let provider = MockProvider::new(&["Speakers", "Headset", "CABLE Input"], &[]);
+        let mut monitor = monitor_for(&provider);
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(1)));
+        monitor.watch(DeviceRole::Broadcast, Some(&DeviceId::from_index(2)));
+
+        // Headset unplugged: CABLE Input shifts down one index
+        provider.set(&["Speakers", "CABLE Input"], &[]);
+        let changes = monitor.poll(&provider).unwrap().unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                DeviceChange::Lost {
+                    role: DeviceRole::Monitor,
+                    name: "Headset".to_string(),
+                    device_id: DeviceId::from_index(1),
+                },
+                DeviceChange::Moved {
+                    role: DeviceRole::Broadcast,
+                    name: "CABLE Input".to_string(),
+                    from: DeviceId::from_index(2),
+                    to: DeviceId::from_index(1),
+                },
+            ]
+        );
+        assert!(monitor.is_missing(DeviceRole::Monitor));
+
+        // Settings still hold the old ID; re-watching it must not forget the headset
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(1)));
+        assert!(monitor.is_missing(DeviceRole::Monitor));
+
+        // Plugged back in, enumerated last this time
+        provider.set(&["Speakers", "CABLE Input", "Headset"], &[]);
+        let changes = monitor.poll(&provider).unwrap().unwrap();
+        assert_eq!(
+            changes,
+            vec![DeviceChange::Restored {
+                role: DeviceRole::Monitor,
+                name: "Headset".to_string(),
+                device_id: DeviceId::from_index(2),
+            }]
+        );
+        assert!(!monitor.is_missing(DeviceRole::Monitor));
+    }
+
+    #[test]
+    fn test_microphone_watched_among_inputs() {
+        let provider = MockProvider::new(&["Headset"], &["USB Mic", "Headset Mic"]);
+        let mut monitor = monitor_for(&provider);
+        monitor.watch(DeviceRole::Microphone, Some(&DeviceId::from_index(0)));
+
+        provider.set(&["Headset"], &["Headset Mic"]);
+        let changes = monitor.poll(&provider).unwrap().unwrap();
+        assert_eq!(changes[0].role(), DeviceRole::Microphone);
+        assert_eq!(changes[0].current_id(), None);
+
+        provider.set(&["Headset"], &["Headset Mic", "USB Mic"]);
+        let changes = monitor.poll(&provider).unwrap().unwrap();
+        assert_eq!(changes[0].current_id(), Some(&DeviceId::from_index(1)));
+    }
+
+    #[test]
+    fn test_duplicate_names_keep_current_index() {
+        let provider = MockProvider::new(&["USB Audio", "USB Audio"], &[]);
+        let mut monitor = monitor_for(&provider);
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(1)));
+
+        provider.set(&["USB Audio", "USB Audio", "HDMI"], &[]);
+        assert_eq!(monitor.poll(&provider).unwrap(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_watch_changes_and_clears() {
+        let provider = MockProvider::new(&["Speakers", "Headset"], &[]);
+        let mut monitor = monitor_for(&provider);
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(1)));
+
+        // User picks another device: the old one is no longer watched
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(0)));
+        provider.set(&["Speakers"], &[]);
+        assert_eq!(monitor.poll(&provider).unwrap(), Some(vec![]));
+
+        // IDs without a device and `None` both stop watching
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(7)));
+        provider.set(&[], &[]);
+        assert_eq!(monitor.poll(&provider).unwrap(), Some(vec![]));
+        monitor.watch(DeviceRole::Broadcast, None);
+        assert!(!monitor.is_missing(DeviceRole::Broadcast));
+    }
+
+    #[test]
+    fn test_provider_error_keeps_state() {
+        let provider = MockProvider::new(&["Headset"], &[]);
+        let mut monitor = monitor_for(&provider);
+        monitor.watch(DeviceRole::Monitor, Some(&DeviceId::from_index(0)));
+
+        *provider.fail.lock().unwrap() = true;
+        assert!(monitor.poll(&provider).is_err());
+        assert_eq!(monitor.snapshot().outputs, vec!["Headset".to_string()]);
+
+        *provider.fail.lock().unwrap() = false;
+        assert_eq!(monitor.poll(&provider).unwrap(), None);
+    }
+
+    #[test]
+    fn test_change_serializes_with_kind_tag() {
+        let change = DeviceChange::Lost {
+            role: DeviceRole::Monitor,
+            name: "Headset".to_string(),
+            device_id: DeviceId::from_index(1),
+        };
+        assert_eq!(
+            serde_json::to_string(&change).unwrap(),
+            r#"{"kind":"lost","role":"monitor","name":"Headset","device_id":"device_1"}"#
+        );
+    }
+}
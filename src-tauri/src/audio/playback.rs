@@ -1,500 +1,206 @@
-//! Audio playback stream creation and sample writing
+//! Audio playback: output stream creation and voice rendering
 //!
-//! Handles cpal stream creation with sample rate conversion using linear interpolation.
+//! Each output device gets one long-lived stream that pulls from a [`BusMixer`].
+//! Individual playbacks are [`Voice`]s that read their decoded audio with sample rate
+//! conversion (linear interpolation) and are summed into the bus.
 
-use cpal::traits::{DeviceTrait, StreamTrait};
-use cpal::{BufferSize, Device, SampleRate, Stream, StreamConfig};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use tracing::{debug, error, info, trace, warn};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info};
 
+use super::backend::{AudioBackend, BackendStream, StreamLatency};
+use super::bus::{BusHandle, BusMixer};
+use super::dsp::{render_effects, EffectConfig};
+use super::sidechain::Sidechain;
 use super::{AudioData, AudioError};
 
-/// Preferred buffer size for low-latency playback.
-/// 256 samples @ 48kHz = ~5.3ms latency per buffer.
-const PREFERRED_BUFFER_SIZE: u32 = 256;
-
-/// Create and start a playback stream on a specific device
-pub fn create_playback_stream(
-    device: &Device,
-    audio_data: Arc<AudioData>,
-    volume: Arc<Mutex<f32>>,
-    start_frame: Option<usize>,
-    end_frame: Option<usize>,
-) -> Result<Stream, AudioError> {
-    let start = Instant::now();
-    let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
-
-    debug!(device = %device_name, "Creating playback stream");
-
-    let supported_config = device
-        .default_output_config()
-        .map_err(|e| AudioError::DeviceConfig(e.to_string()))?;
-
-    let output_sample_rate = supported_config.sample_rate().0;
-    let channels = supported_config.channels() as usize;
-    let sample_format = supported_config.sample_format();
-
-    // Create low-latency stream config with fixed buffer size
-    let stream_config = StreamConfig {
-        channels: supported_config.channels(),
-        sample_rate: SampleRate(output_sample_rate),
-        buffer_size: BufferSize::Fixed(PREFERRED_BUFFER_SIZE),
-    };
-
-    // Log channel mapping for multi-channel devices
-    if channels > audio_data.channels as usize {
-        warn!(
-            "Device has {} output channels, audio has {} channels - extra channels will be silent",
-            channels, audio_data.channels
-        );
-    }
-
-    // Initialize sample index to start_frame (or 0)
-    let start_idx = start_frame.unwrap_or(0) as f64;
-    let sample_index = Arc::new(Mutex::new(start_idx));
-
-    // Calculate end frame (or use full length)
-    let max_frames = audio_data.samples.len() / audio_data.channels as usize;
-    let end_idx = end_frame.unwrap_or(max_frames);
-    let end_frame_arc = Arc::new(end_idx);
-
-    // Calculate sample rate ratio for resampling
-    let rate_ratio = audio_data.sample_rate as f64 / output_sample_rate as f64;
-
-    // Log if resampling is occurring (quality impact)
-    if audio_data.sample_rate != output_sample_rate {
-        info!(
-            audio_sample_rate = audio_data.sample_rate,
-            output_sample_rate = output_sample_rate,
-            rate_ratio = format!("{:.4}", rate_ratio),
-            "Sample rate conversion active"
-        );
-    }
-
-    // Try to build stream with low-latency config, fallback to default if it fails
-    let (stream, used_buffer_size) = build_stream_with_fallback(
-        device,
-        sample_format,
-        &stream_config,
-        &supported_config,
-        audio_data,
-        sample_index,
-        volume,
-        end_frame_arc,
-        channels,
-        rate_ratio,
-    )?;
-
-    stream
-        .play()
-        .map_err(|e| AudioError::StreamStart(e.to_string()))?;
-
-    let duration_ms = start.elapsed().as_millis();
-    info!(
-        device = %device_name,
-        sample_rate = output_sample_rate,
-        channels = channels,
-        buffer_size = ?used_buffer_size,
-        sample_format = ?sample_format,
-        duration_ms = duration_ms,
-        "Playback stream created and started"
-    );
-
-    Ok(stream)
+/// Decoded audio ready for the buses: trim converted to frames, effects rendered
+pub struct PreparedSound {
+    pub audio_data: Arc<AudioData>,
+    pub start_frame: Option<usize>,
+    pub end_frame: Option<usize>,
 }
 
-/// Buffer size options for fallback strategy
-const FALLBACK_BUFFER_SIZES: [u32; 3] = [256, 512, 1024];
-
-/// Build output stream with fallback to larger buffer sizes or default config.
-///
-/// Attempts to create a low-latency audio stream by trying multiple buffer sizes
-/// in sequence: 256 → 512 → 1024 samples. If all fixed buffer sizes fail, falls
-/// back to the device's default configuration.
-///
-/// # Arguments
-///
-/// * `device` - The audio output device
-/// * `sample_format` - Sample format (F32, I16, or U16)
-/// * `low_latency_config` - Preferred low-latency stream configuration
-/// * `default_config` - Device's default configuration (fallback)
-/// * `audio_data` - Decoded audio samples
-/// * `sample_index` - Current playback position
-/// * `volume` - Playback volume (0.0-1.0)
-/// * `end_frame` - End frame for trimmed playback
-/// * `channels` - Number of output channels
-/// * `rate_ratio` - Sample rate conversion ratio
-///
-/// # Returns
-///
-/// Returns a tuple of (Stream, buffer_size_description) on success, or AudioError
-/// if all attempts fail.
-///
-/// # Logging
-///
-/// - Warns if using a fallback buffer size larger than preferred
-/// - Warns if falling back to device default configuration
-#[allow(clippy::too_many_arguments)]
-fn build_stream_with_fallback(
-    device: &Device,
-    sample_format: cpal::SampleFormat,
-    low_latency_config: &StreamConfig,
-    default_config: &cpal::SupportedStreamConfig,
-    audio_data: Arc<AudioData>,
-    sample_index: Arc<Mutex<f64>>,
-    volume: Arc<Mutex<f32>>,
-    end_frame: Arc<usize>,
-    channels: usize,
-    rate_ratio: f64,
-) -> Result<(Stream, String), AudioError> {
-    // Try each buffer size in order
-    for &buffer_size in &FALLBACK_BUFFER_SIZES {
-        let config = StreamConfig {
-            channels: low_latency_config.channels,
-            sample_rate: low_latency_config.sample_rate,
-            buffer_size: BufferSize::Fixed(buffer_size),
-        };
-
-        match try_build_stream(
-            device,
-            sample_format,
-            &config,
-            audio_data.clone(),
-            sample_index.clone(),
-            volume.clone(),
-            end_frame.clone(),
-            channels,
-            rate_ratio,
-        ) {
-            Ok(stream) => {
-                if buffer_size != PREFERRED_BUFFER_SIZE {
-                    warn!(
-                        buffer_size = buffer_size,
-                        preferred = PREFERRED_BUFFER_SIZE,
-                        "Using fallback buffer size (preferred size not supported by device)"
-                    );
-                }
-                return Ok((stream, format!("Fixed({})", buffer_size)));
+impl PreparedSound {
+    /// Apply a sound's trim (in ms) and effect chain to its decoded audio
+    pub fn new(
+        audio_data: Arc<AudioData>,
+        trim_start_ms: Option<u64>,
+        trim_end_ms: Option<u64>,
+        effects: &[EffectConfig],
+    ) -> Self {
+        let sample_rate = audio_data.sample_rate as f64;
+        let to_frame = |ms: u64| ((ms as f64 / 1000.0) * sample_rate) as usize;
+        let start_frame = trim_start_ms.map(to_frame);
+        let end_frame = trim_end_ms.map(to_frame);
+
+        // Render the effect chain once; trim is baked into the rendered buffer
+        if effects.is_empty() {
+            Self {
+                audio_data,
+                start_frame,
+                end_frame,
             }
-            Err(e) => {
-                debug!(
-                    buffer_size = buffer_size,
-                    error = %e,
-                    "Failed to create stream with buffer size, trying next fallback"
-                );
-                continue;
+        } else {
+            let rendered = render_effects(&audio_data, start_frame, end_frame, effects);
+            Self {
+                audio_data: Arc::new(rendered),
+                start_frame: None,
+                end_frame: None,
             }
         }
     }
 
-    // Final fallback: use default config
-    warn!("Fixed buffer sizes failed, using device default");
-    let stream = try_build_stream(
-        device,
-        sample_format,
-        &default_config.clone().into(),
-        audio_data,
-        sample_index,
-        volume,
-        end_frame,
-        channels,
-        rate_ratio,
-    )?;
-
-    Ok((stream, "Default".to_string()))
+    /// How long the (trimmed) sound plays
+    pub fn duration(&self) -> Duration {
+        let total_frames = self.audio_data.samples.len() / self.audio_data.channels.max(1) as usize;
+        let frames = self
+            .end_frame
+            .unwrap_or(total_frames)
+            .min(total_frames)
+            .saturating_sub(self.start_frame.unwrap_or(0));
+        Duration::from_secs_f64(frames as f64 / self.audio_data.sample_rate as f64)
+    }
 }
 
-/// Try to build a stream with the given configuration.
-///
-/// Attempts to create a cpal output stream with the specified configuration.
-/// Handles three sample formats (F32, I16, U16) and sets up audio callbacks
-/// with volume control, sample rate conversion, and multi-channel support.
-///
-/// # Arguments
-///
-/// * `device` - The audio output device
-/// * `sample_format` - Sample format to use (F32, I16, or U16)
-/// * `config` - Stream configuration (sample rate, channels, buffer size)
-/// * `audio_data` - Decoded audio samples
-/// * `sample_index` - Current playback position (shared, mutable)
-/// * `volume` - Playback volume (shared, mutable, 0.0-1.0)
-/// * `end_frame` - End frame for trimmed playback
-/// * `channels` - Number of output channels
-/// * `rate_ratio` - Sample rate conversion ratio
-///
-/// # Returns
-///
-/// Returns the created Stream on success, or AudioError if:
-/// - The sample format is unsupported
-/// - The stream build fails (device busy, invalid config, etc.)
-///
-/// # Audio Processing
-///
-/// The audio callback performs:
-/// - Linear interpolation for sample rate conversion
-/// - Volume scaling with square root curve
-/// - Multi-channel mapping (silences extra output channels)
-#[allow(clippy::too_many_arguments)]
-fn try_build_stream(
-    device: &Device,
-    sample_format: cpal::SampleFormat,
-    config: &StreamConfig,
+/// A single playback reading from decoded audio into a bus
+pub struct Voice {
+    /// Playback ID this voice belongs to
+    pub playback_id: String,
     audio_data: Arc<AudioData>,
-    sample_index: Arc<Mutex<f64>>,
-    volume: Arc<Mutex<f32>>,
-    end_frame: Arc<usize>,
-    channels: usize,
+    /// Current read position in source frames (fractional for resampling)
+    position: f64,
+    /// Last frame to play (exclusive)
+    end_frame: usize,
+    /// Source frames to advance per output frame
     rate_ratio: f64,
-) -> Result<Stream, AudioError> {
-    trace!(
-        sample_format = ?sample_format,
-        buffer_size = ?config.buffer_size,
-        sample_rate = config.sample_rate.0,
-        channels = config.channels,
-        "Attempting stream build"
-    );
-
-    let stream = match sample_format {
-        cpal::SampleFormat::F32 => device
-            .build_output_stream(
-                config,
-                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                    let vol = *volume.lock().unwrap();
-                    write_audio_f32(
-                        data,
-                        &audio_data,
-                        &sample_index,
-                        vol,
-                        channels,
-                        rate_ratio,
-                        *end_frame,
-                    );
-                },
-                |err| error!("Stream error: {}", err),
-                None,
-            )
-            .map_err(|e| AudioError::StreamBuild(e.to_string())),
-        cpal::SampleFormat::I16 => device
-            .build_output_stream(
-                config,
-                move |data: &mut [i16], _: &cpal::OutputCallbackInfo| {
-                    let vol = *volume.lock().unwrap();
-                    write_audio_i16(
-                        data,
-                        &audio_data,
-                        &sample_index,
-                        vol,
-                        channels,
-                        rate_ratio,
-                        *end_frame,
-                    );
-                },
-                |err| error!("Stream error: {}", err),
-                None,
-            )
-            .map_err(|e| AudioError::StreamBuild(e.to_string())),
-        cpal::SampleFormat::U16 => device
-            .build_output_stream(
-                config,
-                move |data: &mut [u16], _: &cpal::OutputCallbackInfo| {
-                    let vol = *volume.lock().unwrap();
-                    write_audio_u16(
-                        data,
-                        &audio_data,
-                        &sample_index,
-                        vol,
-                        channels,
-                        rate_ratio,
-                        *end_frame,
-                    );
-                },
-                |err| error!("Stream error: {}", err),
-                None,
-            )
-            .map_err(|e| AudioError::StreamBuild(e.to_string())),
-        _ => return Err(AudioError::UnsupportedFormat),
-    }?;
-
-    debug!(sample_format = ?sample_format, "Stream built successfully");
-    Ok(stream)
+    /// Shared volume (0.0-1.0) for dynamic control
+    volume: Arc<Mutex<f32>>,
+    finished: bool,
 }
 
-/// Write audio data to f32 output buffer with resampling (linear interpolation)
-fn write_audio_f32(
-    output: &mut [f32],
-    audio_data: &AudioData,
-    sample_index: &Arc<Mutex<f64>>,
-    volume: f32,
-    output_channels: usize,
-    rate_ratio: f64,
-    end_frame: usize,
-) {
-    let mut index = sample_index.lock().unwrap();
-    let input_channels = audio_data.channels as usize;
-    let max_frame = end_frame.min(audio_data.samples.len() / input_channels) as f64;
-
-    // Apply square root volume curve with base attenuation
-    // Base multiplier of 0.2 for safe default volume (20% of full amplitude)
-    let scaled_volume = volume.sqrt() * 0.2;
-
-    for frame in output.chunks_mut(output_channels) {
-        if *index >= max_frame - 1.0 {
-            // End of audio - silence
-            for sample in frame.iter_mut() {
-                *sample = 0.0;
-            }
-            continue;
+impl Voice {
+    /// Create a voice for a bus running at `output_sample_rate`
+    pub fn new(
+        playback_id: String,
+        audio_data: Arc<AudioData>,
+        volume: Arc<Mutex<f32>>,
+        start_frame: Option<usize>,
+        end_frame: Option<usize>,
+        output_sample_rate: u32,
+    ) -> Self {
+        let max_frames = audio_data.samples.len() / audio_data.channels as usize;
+        let rate_ratio = audio_data.sample_rate as f64 / output_sample_rate as f64;
+
+        // Log if resampling is occurring (quality impact)
+        if audio_data.sample_rate != output_sample_rate {
+            info!(
+                audio_sample_rate = audio_data.sample_rate,
+                output_sample_rate = output_sample_rate,
+                rate_ratio = format!("{:.4}", rate_ratio),
+                "Sample rate conversion active"
+            );
         }
 
-        // Linear interpolation between samples
-        let frame_idx = *index as usize;
-        let frac = *index - frame_idx as f64; // Fractional part for interpolation
-
-        for (ch, sample) in frame.iter_mut().enumerate() {
-            // Only map audio to channels that exist in input
-            // Extra output channels (e.g., center, LFE, surround in 5.1/7.1) get silence
-            // This prevents audio artifacts on multi-channel devices like Razer 7.1 headsets
-            if ch >= input_channels {
-                *sample = 0.0;
-                continue;
-            }
-
-            let idx1 = frame_idx * input_channels + ch;
-            let idx2 = (frame_idx + 1) * input_channels + ch;
-
-            if idx2 < audio_data.samples.len() {
-                // Linear interpolation: value = sample1 + (sample2 - sample1) * frac
-                let sample1 = audio_data.samples[idx1];
-                let sample2 = audio_data.samples[idx2];
-                *sample = (sample1 + (sample2 - sample1) * frac as f32) * scaled_volume;
-            } else if idx1 < audio_data.samples.len() {
-                *sample = audio_data.samples[idx1] * scaled_volume;
-            } else {
-                *sample = 0.0;
-            }
+        Self {
+            playback_id,
+            position: start_frame.unwrap_or(0) as f64,
+            end_frame: end_frame.unwrap_or(max_frames).min(max_frames),
+            audio_data,
+            rate_ratio,
+            volume,
+            finished: false,
         }
+    }
 
-        *index += rate_ratio;
+    /// Whether the voice has played to its end frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
     }
-}
 
-/// Write audio data to i16 output buffer with resampling (linear interpolation)
-fn write_audio_i16(
-    output: &mut [i16],
-    audio_data: &AudioData,
-    sample_index: &Arc<Mutex<f64>>,
-    volume: f32,
-    output_channels: usize,
-    rate_ratio: f64,
-    end_frame: usize,
-) {
-    let mut index = sample_index.lock().unwrap();
-    let input_channels = audio_data.channels as usize;
-    let max_frame = end_frame.min(audio_data.samples.len() / input_channels) as f64;
-
-    // Apply square root volume curve with base attenuation
-    // Base multiplier of 0.2 for safe default volume (20% of full amplitude)
-    let scaled_volume = volume.sqrt() * 0.2;
-
-    for frame in output.chunks_mut(output_channels) {
-        if *index >= max_frame - 1.0 {
-            // End of audio - silence
-            for sample in frame.iter_mut() {
-                *sample = 0;
+    /// Add this voice's next frames to an interleaved output buffer
+    pub fn mix_into(&mut self, output: &mut [f32], output_channels: usize) {
+        let audio_data = &*self.audio_data;
+        let input_channels = audio_data.channels as usize;
+        let max_frame = self.end_frame as f64;
+        let scaled_volume = calculate_scaled_volume(*self.volume.lock().unwrap());
+
+        for frame in output.chunks_mut(output_channels) {
+            if self.position >= max_frame - 1.0 {
+                // End of audio - nothing more to add
+                self.finished = true;
+                return;
             }
-            continue;
-        }
 
-        // Linear interpolation between samples
-        let frame_idx = *index as usize;
-        let frac = *index - frame_idx as f64;
+            // Linear interpolation between samples
+            let frame_idx = self.position as usize;
+            let frac = (self.position - frame_idx as f64) as f32;
 
-        for (ch, sample) in frame.iter_mut().enumerate() {
             // Only map audio to channels that exist in input
             // Extra output channels (e.g., center, LFE, surround in 5.1/7.1) get silence
             // This prevents audio artifacts on multi-channel devices like Razer 7.1 headsets
-            if ch >= input_channels {
-                *sample = 0;
-                continue;
+            for (ch, sample) in frame.iter_mut().enumerate().take(input_channels) {
+                let idx1 = frame_idx * input_channels + ch;
+                let idx2 = (frame_idx + 1) * input_channels + ch;
+
+                let value = if idx2 < audio_data.samples.len() {
+                    lerp_sample(audio_data.samples[idx1], audio_data.samples[idx2], frac)
+                } else if idx1 < audio_data.samples.len() {
+                    audio_data.samples[idx1]
+                } else {
+                    0.0
+                };
+                *sample += value * scaled_volume;
             }
 
-            let idx1 = frame_idx * input_channels + ch;
-            let idx2 = (frame_idx + 1) * input_channels + ch;
-
-            let value = if idx2 < audio_data.samples.len() {
-                let sample1 = audio_data.samples[idx1];
-                let sample2 = audio_data.samples[idx2];
-                (sample1 + (sample2 - sample1) * frac as f32) * scaled_volume
-            } else if idx1 < audio_data.samples.len() {
-                audio_data.samples[idx1] * scaled_volume
-            } else {
-                0.0
-            };
-            *sample = (value * 32767.0) as i16;
+            self.position += self.rate_ratio;
         }
-
-        *index += rate_ratio;
     }
 }
 
-/// Write audio data to u16 output buffer with resampling (linear interpolation)
-fn write_audio_u16(
-    output: &mut [u16],
-    audio_data: &AudioData,
-    sample_index: &Arc<Mutex<f64>>,
-    volume: f32,
-    output_channels: usize,
-    rate_ratio: f64,
-    end_frame: usize,
-) {
-    let mut index = sample_index.lock().unwrap();
-    let input_channels = audio_data.channels as usize;
-    let max_frame = end_frame.min(audio_data.samples.len() / input_channels) as f64;
-
-    // Apply square root volume curve with base attenuation
-    // Base multiplier of 0.2 for safe default volume (20% of full amplitude)
-    let scaled_volume = volume.sqrt() * 0.2;
-
-    for frame in output.chunks_mut(output_channels) {
-        if *index >= max_frame - 1.0 {
-            // End of audio - silence
-            for sample in frame.iter_mut() {
-                *sample = 32768;
-            }
-            continue;
-        }
-
-        // Linear interpolation between samples
-        let frame_idx = *index as usize;
-        let frac = *index - frame_idx as f64;
+/// Create and start the bus output stream on a specific device.
+///
+/// Returns the running stream together with a handle to the mixer it pulls from. The
+/// mixer is created for the device's native sample rate and channel count and is
+/// owned by the callback. `failed` is set
+/// when the stream reports an error (e.g. the device was unplugged).
+pub fn create_bus_stream(
+    backend: &dyn AudioBackend,
+    index: usize,
+    limiter_ceiling_db: f32,
+    sidechain: Arc<Sidechain>,
+    failed: Arc<AtomicBool>,
+) -> Result<(BackendStream, BusHandle), AudioError> {
+    let start = Instant::now();
+    debug!(device = index, "Creating bus stream");
+
+    let format = backend.output_format(index)?;
+    let mut mixer = BusMixer::new(
+        format.sample_rate,
+        format.channels as usize,
+        limiter_ceiling_db,
+        sidechain,
+    );
+    let handle = mixer.handle();
 
-        for (ch, sample) in frame.iter_mut().enumerate() {
-            // Only map audio to channels that exist in input
-            // Extra output channels (e.g., center, LFE, surround in 5.1/7.1) get silence
-            // This prevents audio artifacts on multi-channel devices like Razer 7.1 headsets
-            if ch >= input_channels {
-                *sample = 32768; // Silence for u16 (mid-point)
-                continue;
-            }
+    let render = Box::new(move |data: &mut [f32]| mixer.render(data));
+    // Flag the bus so the device monitor can reopen it
+    let on_error = Arc::new(move |err: String| {
+        error!("Stream error: {}", err);
+        failed.store(true, Ordering::Relaxed);
+    });
+    let stream = backend.open_output(index, StreamLatency::Low, render, on_error)?;
 
-            let idx1 = frame_idx * input_channels + ch;
-            let idx2 = (frame_idx + 1) * input_channels + ch;
-
-            let value = if idx2 < audio_data.samples.len() {
-                let sample1 = audio_data.samples[idx1];
-                let sample2 = audio_data.samples[idx2];
-                (sample1 + (sample2 - sample1) * frac as f32) * scaled_volume
-            } else if idx1 < audio_data.samples.len() {
-                audio_data.samples[idx1] * scaled_volume
-            } else {
-                0.0
-            };
-            *sample = ((value + 1.0) * 32767.5) as u16;
-        }
+    info!(
+        device = index,
+        sample_rate = format.sample_rate,
+        channels = format.channels,
+        duration_ms = start.elapsed().as_millis(),
+        "Bus stream created and started"
+    );
 
-        *index += rate_ratio;
-    }
+    Ok((stream, handle))
 }
 
 /// Calculate scaled volume with square root curve and base attenuation.
@@ -508,7 +214,6 @@ fn write_audio_u16(
 /// # Returns
 /// Scaled volume value (0.0 to 0.2 range)
 #[inline]
-#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn calculate_scaled_volume(volume: f32) -> f32 {
     volume.sqrt() * 0.2
 }
@@ -523,7 +228,6 @@ pub(crate) fn calculate_scaled_volume(volume: f32) -> f32 {
 /// # Returns
 /// Interpolated sample value
 #[inline]
-#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) fn lerp_sample(sample1: f32, sample2: f32, frac: f32) -> f32 {
     sample1 + (sample2 - sample1) * frac
 }
@@ -608,4 +312,112 @@ mod tests {
         let result = lerp_sample(5.0, 5.0, 0.7);
         assert!((result - 5.0).abs() < 0.0001);
     }
+
+    // Voice rendering tests
+    fn test_voice(samples: Vec<f32>, sample_rate: u32, channels: u16, output_rate: u32) -> Voice {
+        Voice::new(
+            "playback_1".to_string(),
+            Arc::new(AudioData {
+                samples,
+                sample_rate,
+                channels,
+            }),
+            Arc::new(Mutex::new(1.0)),
+            None,
+            None,
+            output_rate,
+        )
+    }
+
+    #[test]
+    fn test_voice_mixes_additively() {
+        let mut voice = test_voice(vec![0.5; 100], 48000, 1, 48000);
+        let mut output = vec![0.1f32; 10];
+        voice.mix_into(&mut output, 1);
+
+        let expected = 0.1 + 0.5 * calculate_scaled_volume(1.0);
+        assert!(output.iter().all(|s| (s - expected).abs() < 0.0001));
+    }
+
+    #[test]
+    fn test_voice_silences_extra_output_channels() {
+        let mut voice = test_voice(vec![1.0; 200], 48000, 2, 48000);
+        // 6-channel (5.1) output: only the first two channels receive audio
+        let mut output = vec![0.0f32; 6 * 4];
+        voice.mix_into(&mut output, 6);
+
+        for frame in output.chunks(6) {
+            assert!(frame[0] > 0.0 && frame[1] > 0.0);
+            assert!(frame[2..].iter().all(|&s| s == 0.0));
+        }
+    }
+
+    #[test]
+    fn test_voice_finishes_at_end_frame() {
+        let mut voice = Voice::new(
+            "playback_1".to_string(),
+            Arc::new(AudioData {
+                samples: vec![1.0; 1000],
+                sample_rate: 48000,
+                channels: 1,
+            }),
+            Arc::new(Mutex::new(1.0)),
+            Some(10),
+            Some(20),
+            48000,
+        );
+        let mut output = vec![0.0f32; 64];
+        voice.mix_into(&mut output, 1);
+
+        assert!(voice.is_finished());
+        // 10 trimmed frames minus the interpolation guard frame
+        assert_eq!(output.iter().filter(|&&s| s != 0.0).count(), 9);
+    }
+
+    #[test]
+    fn test_voice_resamples_to_output_rate() {
+        // 24 kHz source on a 48 kHz bus advances half a frame per output frame
+        let mut voice = test_voice(
+            (0..100).map(|i| i as f32 / 100.0).collect(),
+            24000,
+            1,
+            48000,
+        );
+        let mut output = vec![0.0f32; 4];
+        voice.mix_into(&mut output, 1);
+
+        let scale = calculate_scaled_volume(1.0);
+        assert!((output[1] / scale - 0.005).abs() < 0.0001);
+        assert!((output[2] / scale - 0.01).abs() < 0.0001);
+    }
+    fn one_second_ramp() -> Arc<AudioData> {
+        Arc::new(AudioData {
+            samples: (0..96000).map(|i| (i / 2) as f32 / 48000.0).collect(),
+            sample_rate: 48000,
+            channels: 2,
+        })
+    }
+
+    #[test]
+    fn test_prepared_sound_keeps_trim_without_effects() {
+        let sound = PreparedSound::new(one_second_ramp(), Some(250), Some(750), &[]);
+        assert_eq!(sound.start_frame, Some(12000));
+        assert_eq!(sound.end_frame, Some(36000));
+        assert_eq!(sound.audio_data.samples.len(), 96000);
+        assert_eq!(sound.duration(), Duration::from_millis(500));
+
+        // An end past the audio is capped
+        let sound = PreparedSound::new(one_second_ramp(), None, Some(5000), &[]);
+        assert_eq!(sound.duration(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_prepared_sound_bakes_trim_into_effects() {
+        let effects = [EffectConfig::Speed { factor: 2.0 }];
+        let sound = PreparedSound::new(one_second_ramp(), Some(250), Some(750), &effects);
+        assert_eq!((sound.start_frame, sound.end_frame), (None, None));
+        // Half a second of trimmed audio at double speed
+        let millis = sound.duration().as_millis();
+        assert!((240..=260).contains(&millis), "{}", millis);
+    }
 }
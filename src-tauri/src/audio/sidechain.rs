@@ -0,0 +1,242 @@
+//! Shared sidechain state between soundboard playback and microphone routing
+//!
+//! Output buses publish whether any soundboard voice is playing, and the microphone
+//! routing thread publishes the current mic level. Each side reads the other to decide
+//! whether to duck its own signal.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+use super::dsp::{db_to_linear, linear_to_db};
+
+/// Persisted ducking configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DuckingConfig {
+    /// Lower the routed microphone while soundboard audio plays
+    pub mic_ducking_enabled: bool,
+    /// Microphone attenuation while ducked, in dB (negative)
+    pub mic_duck_depth_db: f32,
+    /// Lower soundboard playback while the user is speaking
+    pub board_ducking_enabled: bool,
+    /// Soundboard attenuation while ducked, in dB (negative)
+    pub board_duck_depth_db: f32,
+    /// Mic level above which the user counts as speaking, in dBFS
+    pub voice_threshold_db: f32,
+    /// Time to reach full ducking depth
+    pub attack_ms: f32,
+    /// Time to recover after the key signal stops
+    pub release_ms: f32,
+}
+
+impl Default for DuckingConfig {
+    fn default() -> Self {
+        Self {
+            mic_ducking_enabled: false,
+            mic_duck_depth_db: -12.0,
+            board_ducking_enabled: false,
+            board_duck_depth_db: -9.0,
+            voice_threshold_db: -40.0,
+            attack_ms: 10.0,
+            release_ms: 300.0,
+        }
+    }
+}
+
+/// Attack time of the mic level detector (speech onsets should be caught quickly)
+pub const VOICE_DETECT_ATTACK_MS: f32 = 2.0;
+/// Release time of the mic level detector (bridges gaps between words)
+pub const VOICE_DETECT_RELEASE_MS: f32 = 250.0;
+
+/// Ducking configuration readable from audio callbacks without locking
+///
+/// Fields are published one by one, so a reader racing `store` may see a mix of
+/// the old and new values for one block.
+struct AtomicDuckingConfig {
+    mic_ducking_enabled: AtomicBool,
+    mic_duck_depth_db: AtomicU32,
+    board_ducking_enabled: AtomicBool,
+    board_duck_depth_db: AtomicU32,
+    voice_threshold_db: AtomicU32,
+    attack_ms: AtomicU32,
+    release_ms: AtomicU32,
+}
+
+impl AtomicDuckingConfig {
+    fn new(config: &DuckingConfig) -> Self {
+        let f = |v: f32| AtomicU32::new(v.to_bits());
+        Self {
+            mic_ducking_enabled: AtomicBool::new(config.mic_ducking_enabled),
+            mic_duck_depth_db: f(config.mic_duck_depth_db),
+            board_ducking_enabled: AtomicBool::new(config.board_ducking_enabled),
+            board_duck_depth_db: f(config.board_duck_depth_db),
+            voice_threshold_db: f(config.voice_threshold_db),
+            attack_ms: f(config.attack_ms),
+            release_ms: f(config.release_ms),
+        }
+    }
+
+    fn load(&self) -> DuckingConfig {
+        let f = |v: &AtomicU32| f32::from_bits(v.load(Ordering::Relaxed));
+        DuckingConfig {
+            mic_ducking_enabled: self.mic_ducking_enabled.load(Ordering::Relaxed),
+            mic_duck_depth_db: f(&self.mic_duck_depth_db),
+            board_ducking_enabled: self.board_ducking_enabled.load(Ordering::Relaxed),
+            board_duck_depth_db: f(&self.board_duck_depth_db),
+            voice_threshold_db: f(&self.voice_threshold_db),
+            attack_ms: f(&self.attack_ms),
+            release_ms: f(&self.release_ms),
+        }
+    }
+
+    fn store(&self, config: &DuckingConfig) {
+        let f = |a: &AtomicU32, v: f32| a.store(v.to_bits(), Ordering::Relaxed);
+        self.mic_ducking_enabled
+            .store(config.mic_ducking_enabled, Ordering::Relaxed);
+        f(&self.mic_duck_depth_db, config.mic_duck_depth_db);
+        self.board_ducking_enabled
+            .store(config.board_ducking_enabled, Ordering::Relaxed);
+        f(&self.board_duck_depth_db, config.board_duck_depth_db);
+        f(&self.voice_threshold_db, config.voice_threshold_db);
+        f(&self.attack_ms, config.attack_ms);
+        f(&self.release_ms, config.release_ms);
+    }
+}
+
+/// Cross-thread sidechain signals and ducking configuration
+pub struct Sidechain {
+    config: AtomicDuckingConfig,
+    /// Number of output buses that currently have at least one voice
+    active_buses: AtomicUsize,
+    /// Latest mic envelope level (f32 bits)
+    mic_level: AtomicU32,
+}
+
+impl Sidechain {
+    pub fn new(config: DuckingConfig) -> Self {
+        Self {
+            config: AtomicDuckingConfig::new(&config),
+            active_buses: AtomicUsize::new(0),
+            mic_level: AtomicU32::new(0.0f32.to_bits()),
+        }
+    }
+
+    /// Current ducking configuration (lock-free, safe on the audio thread)
+    pub fn config(&self) -> DuckingConfig {
+        self.config.load()
+    }
+
+    /// Replace the ducking configuration
+    pub fn set_config(&self, config: DuckingConfig) {
+        self.config.store(&config);
+    }
+
+    /// Mark one output bus as having started or stopped playing voices
+    pub fn set_bus_active(&self, active: bool) {
+        if active {
+            self.active_buses.fetch_add(1, Ordering::Relaxed);
+        } else {
+            // Never wrap below zero if a bus reports inactive twice
+            let _ = self
+                .active_buses
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+        }
+    }
+
+    /// Whether any soundboard voice is currently playing
+    pub fn board_active(&self) -> bool {
+        self.active_buses.load(Ordering::Relaxed) > 0
+    }
+
+    /// Publish the current mic envelope level (linear)
+    pub fn set_mic_level(&self, level: f32) {
+        self.mic_level.store(level.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Latest mic envelope level (linear)
+    pub fn mic_level(&self) -> f32 {
+        f32::from_bits(self.mic_level.load(Ordering::Relaxed))
+    }
+
+    /// Whether the mic level is above the voice threshold
+    pub fn voice_active(&self, config: &DuckingConfig) -> bool {
+        self.mic_level() > db_to_linear(config.voice_threshold_db)
+    }
+
+    /// Latest mic level in dBFS (for logging and UI)
+    #[allow(dead_code)]
+    pub fn mic_level_db(&self) -> f32 {
+        linear_to_db(self.mic_level())
+    }
+}
+
+impl Default for Sidechain {
+    fn default() -> Self {
+        Self::new(DuckingConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_active_counts_buses() {
+        let sidechain = Sidechain::default();
+        assert!(!sidechain.board_active());
+
+        sidechain.set_bus_active(true);
+        sidechain.set_bus_active(true);
+        sidechain.set_bus_active(false);
+        assert!(sidechain.board_active());
+
+        sidechain.set_bus_active(false);
+        assert!(!sidechain.board_active());
+    }
+
+    #[test]
+    fn test_bus_inactive_does_not_underflow() {
+        let sidechain = Sidechain::default();
+        sidechain.set_bus_active(false);
+        sidechain.set_bus_active(true);
+        assert!(sidechain.board_active());
+    }
+
+    #[test]
+    fn test_voice_active_threshold() {
+        let sidechain = Sidechain::default();
+        let config = DuckingConfig::default();
+
+        sidechain.set_mic_level(db_to_linear(-50.0));
+        assert!(!sidechain.voice_active(&config));
+
+        sidechain.set_mic_level(db_to_linear(-20.0));
+        assert!(sidechain.voice_active(&config));
+        assert!((sidechain.mic_level_db() + 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_set_config_round_trips() {
+        let sidechain = Sidechain::default();
+        let config = DuckingConfig {
+            mic_ducking_enabled: true,
+            mic_duck_depth_db: -18.0,
+            board_ducking_enabled: true,
+            board_duck_depth_db: -6.0,
+            voice_threshold_db: -35.0,
+            attack_ms: 5.0,
+            release_ms: 500.0,
+        };
+        sidechain.set_config(config.clone());
+        assert_eq!(sidechain.config(), config);
+    }
+
+    #[test]
+    fn test_ducking_config_partial_json_uses_defaults() {
+        let config: DuckingConfig =
+            serde_json::from_str(r#"{"board_ducking_enabled": true}"#).unwrap();
+        assert!(config.board_ducking_enabled);
+        assert!(!config.mic_ducking_enabled);
+        assert_eq!(config.release_ms, 300.0);
+    }
+}
@@ -0,0 +1,73 @@
+//! Commands from other processes, run by the app
+
+use std::sync::Arc;
+
+use tauri::Manager;
+use tracing::{error, info};
+
+use super::args::CliCommand;
+use super::ipc::{self, CliHandler, IpcServer};
+use super::library;
+use crate::audio::AudioManager;
+use crate::commands;
+use crate::media;
+use crate::window;
+use crate::AppState;
+
+/// Keeps the IPC server running (managed Tauri state)
+pub struct IpcState {
+    _server: IpcServer,
+}
+
+struct AppCliHandler {
+    app: tauri::AppHandle,
+}
+
+impl CliHandler for AppCliHandler {
+    fn execute(&self, command: CliCommand) -> Result<String, String> {
+        let state = self.app.state::<AppState>();
+        match command {
+            CliCommand::Play { sound } => {
+                let sound_id = library::find_sound(&state.read_sounds(), &sound)?
+                    .id
+                    .clone();
+                let (sound, _) = commands::play_library_sound(&self.app, &sound_id, 1.0)?;
+                Ok(format!("Playing {}", sound.name))
+            }
+            CliCommand::StopAll => {
+                self.app.state::<AudioManager>().stop_all();
+                Ok("Stopped all sounds".to_string())
+            }
+            CliCommand::List => Ok(library::format_list(&state.read_sounds())),
+            CliCommand::Import { dir } => {
+                // Copied files are unreferenced until the library is saved
+                let _pending = media::protect_new_files();
+                let added = state.update_sounds(|sounds| {
+                    library::import_dir(sounds, &dir, |file| {
+                        commands::library_file_path(file.to_string_lossy().into_owned(), &state)
+                    })
+                })?;
+                if added > 0 {
+                    state.sounds_changed(&self.app);
+                }
+                info!("Imported {} sounds from {}", added, dir.display());
+                Ok(format!("Imported {} sounds from {}", added, dir.display()))
+            }
+            CliCommand::Show => {
+                window::show_main_window(&self.app);
+                Ok(String::new())
+            }
+        }
+    }
+}
+
+/// Accept commands from the command line and later launches
+pub fn start_ipc_server(app: &tauri::AppHandle) {
+    let handler = Arc::new(AppCliHandler { app: app.clone() });
+    match IpcServer::start(&ipc::instance_socket_id(), handler) {
+        Ok(server) => {
+            app.manage(IpcState { _server: server });
+        }
+        Err(e) => error!("Command-line control unavailable: {}", e),
+    }
+}
@@ -0,0 +1,161 @@
+//! Command-line arguments
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Usage text for `--help` and argument errors
+pub const USAGE: &str = "\
+Usage: sonic-deck [--debug] [--daemon | COMMAND]
+
+Without a command, starts SonicDeck (or shows the running window).
+With --daemon, starts without the window: hotkeys, routing and the tray
+keep running, and the window opens from the tray when needed.
+
+Commands:
+  play <SOUND>    Play a sound by name (or ID)
+  stop-all        Stop all playing sounds
+  list            List the sounds in the library
+  import <DIR>    Add the audio files in a folder to the library
+
+Commands go to the running instance; if none is running they run on their own.";
+
+/// Something to do, from the command line or another instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum CliCommand {
+    Play {
+        sound: String,
+    },
+    StopAll,
+    List,
+    Import {
+        dir: PathBuf,
+    },
+    /// Bring the window forward (sent when the app is launched again)
+    Show,
+}
+
+/// Parsed command line
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub debug: bool,
+    pub help: bool,
+    /// Start without creating the window
+    pub daemon: bool,
+    /// None starts the app
+    pub command: Option<CliCommand>,
+}
+
+/// Parse the arguments (without the program name)
+pub fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs::default();
+    let mut words = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--debug" => parsed.debug = true,
+            "-h" | "--help" => parsed.help = true,
+            "--daemon" => parsed.daemon = true,
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            word => words.push(word),
+        }
+    }
+
+    parsed.command = match words.as_slice() {
+        [] => None,
+        ["play", sound @ ..] if !sound.is_empty() => Some(CliCommand::Play {
+            // Quoting is optional: `play Air Horn`
+            sound: sound.join(" "),
+        }),
+        ["stop-all"] => Some(CliCommand::StopAll),
+        ["list"] => Some(CliCommand::List),
+        // The running instance may have another working directory
+        ["import", dir] => Some(CliCommand::Import {
+            dir: std::path::absolute(dir).map_err(|e| format!("Invalid folder {}: {}", dir, e))?,
+        }),
+        ["play"] => return Err("Missing sound name".to_string()),
+        ["import", ..] => return Err("Expected one folder to import".to_string()),
+        [command @ ("stop-all" | "list"), ..] => {
+            return Err(format!("'{}' takes no arguments", command))
+        }
+        [command, ..] => return Err(format!("Unknown command: {}", command)),
+    };
+    if parsed.daemon && parsed.command.is_some() {
+        return Err("--daemon starts the app and takes no command".to_string());
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        parse_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+        let args = parse(&["--debug"]).unwrap();
+        assert!(args.debug);
+        assert!(args.command.is_none());
+
+        assert_eq!(
+            parse(&["play", "Airhorn"]).unwrap().command,
+            Some(CliCommand::Play {
+                sound: "Airhorn".to_string()
+            })
+        );
+        assert_eq!(
+            parse(&["--debug", "play", "Air", "Horn"]).unwrap().command,
+            Some(CliCommand::Play {
+                sound: "Air Horn".to_string()
+            })
+        );
+        assert_eq!(
+            parse(&["stop-all"]).unwrap().command,
+            Some(CliCommand::StopAll)
+        );
+        assert_eq!(parse(&["list"]).unwrap().command, Some(CliCommand::List));
+        assert!(parse(&["--help"]).unwrap().help);
+
+        let args = parse(&["--daemon", "--debug"]).unwrap();
+        assert!(args.daemon && args.debug);
+        assert!(args.command.is_none());
+    }
+
+    #[test]
+    fn test_import_dir_is_absolute() {
+        let Some(CliCommand::Import { dir }) = parse(&["import", "sounds"]).unwrap().command else {
+            panic!("expected an import");
+        };
+        assert!(dir.is_absolute());
+        assert!(dir.ends_with("sounds"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["play"]).is_err());
+        assert!(parse(&["import"]).is_err());
+        assert!(parse(&["import", "a", "b"]).is_err());
+        assert!(parse(&["dance"]).is_err());
+        assert!(parse(&["list", "extra"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["--daemon", "list"]).is_err());
+    }
+
+    #[test]
+    fn test_command_wire_format() {
+        let json = serde_json::to_string(&CliCommand::StopAll).unwrap();
+        assert_eq!(json, r#"{"command":"stop_all"}"#);
+        let command: CliCommand =
+            serde_json::from_str(r#"{"command":"play","sound":"Airhorn"}"#).unwrap();
+        assert_eq!(
+            command,
+            CliCommand::Play {
+                sound: "Airhorn".to_string()
+            }
+        );
+    }
+}
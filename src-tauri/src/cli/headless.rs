@@ -0,0 +1,147 @@
+//! One-shot commands when no instance is running
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tracing::info;
+
+use super::args::CliCommand;
+use super::library;
+use crate::audio::AudioManager;
+use crate::media;
+use crate::state::AppState;
+use crate::storage::StorageRoot;
+use crate::tts;
+/// Time for the output buffers to drain after the last sample
+const PLAYBACK_TAIL: Duration = Duration::from_millis(250);
+
+/// Runs commands against the state in the app data folder
+struct Headless {
+    state: AppState,
+}
+
+impl Headless {
+    fn execute(&self, command: CliCommand) -> Result<String, String> {
+        match command {
+            CliCommand::Play { sound } => self.play(&sound),
+            CliCommand::StopAll => Ok("SonicDeck is not running, nothing is playing".to_string()),
+            CliCommand::List => Ok(library::format_list(&self.state.read_sounds())),
+            CliCommand::Import { dir } => self.import(&dir),
+            CliCommand::Show => Err("SonicDeck is not running".to_string()),
+        }
+    }
+
+    /// Play on the configured devices and wait until the sound ends
+    fn play(&self, query: &str) -> Result<String, String> {
+        let settings = self.state.read_settings().clone();
+        let sound = library::find_sound(&self.state.read_sounds(), query)?.clone();
+        let monitor = settings
+            .monitor_device_id
+            .ok_or("No monitor device configured")?;
+        let broadcast = settings
+            .broadcast_device_id
+            .ok_or("No broadcast device configured")?;
+
+        let manager = AudioManager::new();
+        manager.set_limiter_ceiling_db(settings.limiter_ceiling_db);
+        if let Some(speech) = &sound.tts {
+            let engine = tts::engine(&settings.tts);
+            tts::ensure_rendered(engine.as_ref(), speech, Path::new(&sound.file_path))?;
+        }
+        let data = manager
+            .get_or_decode(&sound.file_path)
+            .map_err(|e| e.to_string())?;
+        let prepared = manager.prepare(
+            &sound.file_path,
+            data,
+            sound.trim_start_ms,
+            sound.trim_end_ms,
+            &sound.effects,
+        );
+        let duration = prepared.duration();
+        let volume = sound
+            .volume
+            .unwrap_or(settings.default_volume)
+            .clamp(0.0, 1.0);
+
+        manager
+            .get_buses()
+            .play(
+                &[&monitor, &broadcast],
+                "cli",
+                prepared.audio_data,
+                Arc::new(Mutex::new(volume)),
+                prepared.start_frame,
+                prepared.end_frame,
+            )
+            .map_err(|e| format!("Failed to open output device: {}", e))?;
+        info!("Playing {} headless", sound.name);
+        thread::sleep(duration + PLAYBACK_TAIL);
+        Ok(format!("Played {}", sound.name))
+    }
+
+    fn import(&self, dir: &Path) -> Result<String, String> {
+        let copy_sounds = self.state.read_settings().copy_sounds_to_library;
+        let media_dir = if copy_sounds {
+            Some(self.state.storage().media_dir()?)
+        } else {
+            None
+        };
+
+        // Copied files are unreferenced until the library is saved
+        let _pending = media::protect_new_files();
+        let added = self.state.update_sounds(|library| {
+            library::import_dir(library, dir, |file| match &media_dir {
+                Some(media_dir) => media::import_file(file, media_dir)
+                    .map(|stored| stored.to_string_lossy().into_owned()),
+                None => Ok(file.to_string_lossy().into_owned()),
+            })
+        })?;
+        Ok(format!("Imported {} sounds from {}", added, dir.display()))
+    }
+}
+
+/// Run a command without the app (no instance is running)
+pub fn run_headless(command: CliCommand) -> Result<String, String> {
+    let state = AppState::load(StorageRoot::user_default()?)?;
+    Headless { state }.execute(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_import_and_list_without_app() {
+        let temp = TempDir::new().unwrap();
+        let headless = Headless {
+            state: AppState::load_from_dir(temp.path().join("data")).unwrap(),
+        };
+        assert_eq!(headless.execute(CliCommand::List).unwrap(), "");
+
+        let dir = temp.path().join("Memes");
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(dir.join("Bruh.mp3"), b"").unwrap();
+        let output = headless
+            .execute(CliCommand::Import { dir: dir.clone() })
+            .unwrap();
+        assert!(output.starts_with("Imported 1 sounds"));
+
+        assert_eq!(headless.execute(CliCommand::List).unwrap(), "Bruh\tMemes");
+        // Persisted for the app's next start
+        let library = headless.state.storage().load_sounds().unwrap();
+        assert_eq!(
+            library.sounds[0].file_path,
+            dir.join("Bruh.mp3").to_string_lossy()
+        );
+
+        assert!(headless
+            .execute(CliCommand::Play {
+                sound: "Missing".to_string()
+            })
+            .is_err());
+    }
+}
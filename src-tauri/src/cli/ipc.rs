@@ -0,0 +1,323 @@
+//! Single-instance IPC over a local socket (a named pipe on Windows)
+//!
+//! A client writes one JSON command per connection and reads one JSON
+//! response line back.
+
+use std::io::{self, BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use interprocess::local_socket::prelude::*;
+#[cfg(unix)]
+use interprocess::local_socket::GenericFilePath;
+#[cfg(not(unix))]
+use interprocess::local_socket::GenericNamespaced;
+use interprocess::local_socket::{ListenerNonblockingMode, ListenerOptions, Name, Stream};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use super::args::CliCommand;
+
+/// How often the accept loop checks for shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs commands received from other processes
+pub trait CliHandler: Send + Sync + 'static {
+    /// Run a command, returning the text to print
+    fn execute(&self, command: CliCommand) -> Result<String, String>;
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    ok: bool,
+    /// Output on success, the error otherwise
+    output: String,
+}
+
+/// Socket name of this user's instance
+pub fn instance_socket_id() -> String {
+    let user = std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default();
+    let user: String = user
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    format!("sonic-deck-{}", user)
+}
+
+/// A socket file in a private directory on Unix (abstract sockets on Linux
+/// are open to every local user), a named pipe on Windows
+fn socket_name(id: &str) -> io::Result<Name<'static>> {
+    #[cfg(unix)]
+    return socket_path(id)?.to_fs_name::<GenericFilePath>();
+    #[cfg(not(unix))]
+    return format!("{}.sock", id).to_ns_name::<GenericNamespaced>();
+}
+
+#[cfg(unix)]
+fn socket_path(id: &str) -> io::Result<PathBuf> {
+    Ok(private_dir()?.join(format!("{}.sock", id)))
+}
+
+/// `$XDG_RUNTIME_DIR` (per-user, 0700), else a 0700 directory in the temp dir
+#[cfg(unix)]
+fn private_dir() -> io::Result<PathBuf> {
+    use std::fs::{self, DirBuilder};
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    if let Some(dir) = dirs::runtime_dir() {
+        return Ok(dir);
+    }
+    let dir = std::env::temp_dir().join(instance_socket_id());
+    match DirBuilder::new().mode(0o700).create(&dir) {
+        Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+        _ => {}
+    }
+    // Don't trust a directory (or symlink) others can get into
+    let metadata = fs::symlink_metadata(&dir)?;
+    if !metadata.is_dir() || metadata.permissions().mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not private", dir.display()),
+        ));
+    }
+    Ok(dir)
+}
+
+/// Send a command to the instance listening on `id`
+///
+/// Returns None if no instance is listening.
+pub fn send(id: &str, command: &CliCommand) -> Option<Result<String, String>> {
+    let stream = Stream::connect(socket_name(id).ok()?).ok()?;
+    Some(exchange(stream, command))
+}
+
+/// Whether an instance is listening on `id` (without sending a command)
+pub fn is_listening(id: &str) -> bool {
+    socket_name(id).is_ok_and(|name| Stream::connect(name).is_ok())
+}
+
+fn exchange(stream: Stream, command: &CliCommand) -> Result<String, String> {
+    let lost = |e: io::Error| format!("Lost connection to SonicDeck: {}", e);
+    let request = serde_json::to_string(command).map_err(|e| e.to_string())?;
+    (&stream)
+        .write_all(format!("{}\n", request).as_bytes())
+        .map_err(lost)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).map_err(lost)?;
+    let response: Response = serde_json::from_str(&line)
+        .map_err(|e| format!("Invalid response from SonicDeck: {}", e))?;
+    if response.ok {
+        Ok(response.output)
+    } else {
+        Err(response.output)
+    }
+}
+
+/// Listens for commands from other processes (stops when dropped)
+pub struct IpcServer {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl IpcServer {
+    /// Listen on `id`; fails if another instance already does
+    pub fn start(id: &str, handler: Arc<dyn CliHandler>) -> Result<Self, String> {
+        let name = socket_name(id).map_err(|e| format!("Invalid socket name {}: {}", id, e))?;
+        // A crashed instance leaves its socket file behind
+        #[cfg(unix)]
+        if !is_listening(id) {
+            if let Ok(path) = socket_path(id) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        let listener = ListenerOptions::new()
+            .name(name)
+            .nonblocking(ListenerNonblockingMode::Accept)
+            .create_sync()
+            .map_err(|e| format!("Failed to listen on {}: {}", id, e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let thread = thread::Builder::new()
+            .name("cli-ipc".to_string())
+            .spawn(move || {
+                while !stopped.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok(stream) => {
+                            let handler = handler.clone();
+                            // A slow command (import) doesn't hold up the next one
+                            thread::spawn(move || serve(stream, handler.as_ref()));
+                        }
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                            thread::sleep(POLL_INTERVAL)
+                        }
+                        Err(e) => {
+                            warn!("IPC accept failed: {}", e);
+                            thread::sleep(POLL_INTERVAL);
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start IPC thread: {}", e))?;
+
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Answer one connection
+fn serve(stream: Stream, handler: &dyn CliHandler) {
+    let mut line = String::new();
+    if let Err(e) = BufReader::new(&stream).read_line(&mut line) {
+        debug!("IPC read failed: {}", e);
+        return;
+    }
+    let result = serde_json::from_str::<CliCommand>(&line)
+        .map_err(|e| format!("Invalid command: {}", e))
+        .and_then(|command| {
+            debug!("IPC command: {:?}", command);
+            handler.execute(command)
+        });
+    let response = match result {
+        Ok(output) => Response { ok: true, output },
+        Err(output) => Response { ok: false, output },
+    };
+    let json = serde_json::to_string(&response).unwrap_or_default();
+    if let Err(e) = (&stream).write_all(format!("{}\n", json).as_bytes()) {
+        debug!("IPC write failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records commands and answers like a small library
+    #[derive(Default)]
+    struct MockHandler {
+        received: Mutex<Vec<CliCommand>>,
+    }
+
+    impl CliHandler for MockHandler {
+        fn execute(&self, command: CliCommand) -> Result<String, String> {
+            self.received.lock().unwrap().push(command.clone());
+            match command {
+                CliCommand::Play { sound } if sound == "Airhorn" => {
+                    Ok("Playing Airhorn".to_string())
+                }
+                CliCommand::Play { sound } => Err(format!("Sound not found: {}", sound)),
+                CliCommand::List => Ok("Airhorn\nRimshot".to_string()),
+                _ => Ok(String::new()),
+            }
+        }
+    }
+
+    fn unique_id(test: &str) -> String {
+        format!("sonic-deck-test-{}-{}", test, std::process::id())
+    }
+
+    #[test]
+    fn test_commands_round_trip() {
+        let id = unique_id("round-trip");
+        let handler = Arc::new(MockHandler::default());
+        let _server = IpcServer::start(&id, handler.clone()).unwrap();
+
+        let play = CliCommand::Play {
+            sound: "Airhorn".to_string(),
+        };
+        assert_eq!(send(&id, &play), Some(Ok("Playing Airhorn".to_string())));
+        assert_eq!(
+            send(&id, &CliCommand::List),
+            Some(Ok("Airhorn\nRimshot".to_string()))
+        );
+        assert_eq!(
+            send(
+                &id,
+                &CliCommand::Play {
+                    sound: "Nope".to_string()
+                }
+            ),
+            Some(Err("Sound not found: Nope".to_string()))
+        );
+        assert_eq!(send(&id, &CliCommand::StopAll), Some(Ok(String::new())));
+
+        let received = handler.received.lock().unwrap();
+        assert_eq!(received.len(), 4);
+        assert_eq!(received[0], play);
+        assert_eq!(received[3], CliCommand::StopAll);
+    }
+
+    #[test]
+    fn test_no_instance_running() {
+        let id = unique_id("none");
+        assert_eq!(send(&id, &CliCommand::Show), None);
+        assert!(!is_listening(&id));
+
+        // Dropping the server frees the name
+        let server = IpcServer::start(&id, Arc::new(MockHandler::default())).unwrap();
+        assert!(send(&id, &CliCommand::Show).is_some());
+        assert!(is_listening(&id));
+        drop(server);
+        assert_eq!(send(&id, &CliCommand::Show), None);
+    }
+
+    #[test]
+    fn test_second_server_is_refused() {
+        let id = unique_id("single");
+        let _first = IpcServer::start(&id, Arc::new(MockHandler::default())).unwrap();
+        assert!(IpcServer::start(&id, Arc::new(MockHandler::default())).is_err());
+    }
+
+    #[test]
+    fn test_invalid_request_gets_an_error() {
+        let id = unique_id("invalid");
+        let _server = IpcServer::start(&id, Arc::new(MockHandler::default())).unwrap();
+
+        let stream = Stream::connect(socket_name(&id).unwrap()).unwrap();
+        (&stream).write_all(b"{\"command\":\"dance\"}\n").unwrap();
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line).unwrap();
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert!(!response.ok);
+        assert!(response.output.starts_with("Invalid command"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_is_a_private_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let id = unique_id("private");
+        let path = socket_path(&id).unwrap();
+        let mode = std::fs::metadata(path.parent().unwrap())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o077, 0);
+
+        // A leftover file from a crashed instance doesn't block the next one
+        std::fs::write(&path, "").unwrap();
+        let _server = IpcServer::start(&id, Arc::new(MockHandler::default())).unwrap();
+        assert!(path.exists());
+        assert!(is_listening(&id));
+    }
+}
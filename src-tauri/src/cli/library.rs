@@ -0,0 +1,180 @@
+//! Library operations shared by the running app and headless runs
+
+use std::fs;
+use std::path::Path;
+
+use crate::sounds::{self, Sound, SoundLibrary};
+
+/// File types the app can add (as in the add-sound dialog)
+const AUDIO_EXTENSIONS: [&str; 5] = ["mp3", "wav", "ogg", "m4a", "flac"];
+
+/// Find a sound by name (case-insensitive) or ID
+pub fn find_sound<'a>(library: &'a SoundLibrary, query: &str) -> Result<&'a Sound, String> {
+    library
+        .sounds
+        .iter()
+        .find(|sound| sound.name.eq_ignore_ascii_case(query))
+        .or_else(|| {
+            library
+                .sounds
+                .iter()
+                .find(|sound| sound.id.as_str() == query)
+        })
+        .ok_or_else(|| format!("Sound not found: {}", query))
+}
+
+/// One line per sound: `Name<TAB>Category`, in category order
+pub fn format_list(library: &SoundLibrary) -> String {
+    let mut categories: Vec<_> = library.categories.iter().collect();
+    categories.sort_by_key(|category| category.sort_order);
+    categories
+        .iter()
+        .flat_map(|category| {
+            library
+                .sounds
+                .iter()
+                .filter(move |sound| sound.category_id == category.id)
+                .map(move |sound| format!("{}\t{}", sound.name, category.name))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Add a folder's audio files to a category named after the folder
+///
+/// `store` returns the path to save for a file (copying it into the media
+/// folder when that's enabled). Files already in the library are skipped.
+/// Returns the number of sounds added.
+pub fn import_dir(
+    library: &mut SoundLibrary,
+    dir: &Path,
+    store: impl Fn(&Path) -> Result<String, String>,
+) -> Result<usize, String> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                    })
+        })
+        .collect();
+    files.sort();
+
+    let folder = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Imported".to_string());
+    let category_id = match library.categories.iter().find(|c| c.name == folder) {
+        Some(category) => category.id.clone(),
+        None if files.is_empty() => return Ok(0),
+        None => sounds::add_category(library, folder, None).id,
+    };
+
+    let mut added = 0;
+    for file in files {
+        let original = file.to_string_lossy();
+        if library
+            .sounds
+            .iter()
+            .any(|sound| sound.file_path == original)
+        {
+            continue;
+        }
+        let file_path = store(&file)?;
+        if library
+            .sounds
+            .iter()
+            .any(|sound| sound.file_path == file_path)
+        {
+            continue;
+        }
+        let name = file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        sounds::add_sound(library, name, file_path, category_id.clone(), None, None);
+        added += 1;
+    }
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn library_with(names: &[&str]) -> SoundLibrary {
+        let mut library = SoundLibrary::default();
+        let category_id = library.categories[0].id.clone();
+        for name in names {
+            sounds::add_sound(
+                &mut library,
+                name.to_string(),
+                format!("/sounds/{}.mp3", name),
+                category_id.clone(),
+                None,
+                None,
+            );
+        }
+        library
+    }
+
+    #[test]
+    fn test_find_sound_by_name_or_id() {
+        let library = library_with(&["Airhorn", "Rimshot"]);
+        assert_eq!(find_sound(&library, "airhorn").unwrap().name, "Airhorn");
+        let id = library.sounds[1].id.as_str().to_string();
+        assert_eq!(find_sound(&library, &id).unwrap().name, "Rimshot");
+        assert!(find_sound(&library, "Air").is_err());
+    }
+
+    #[test]
+    fn test_format_list() {
+        let mut library = library_with(&["Airhorn"]);
+        let memes = sounds::add_category(&mut library, "Memes".to_string(), None);
+        sounds::add_sound(
+            &mut library,
+            "Bruh".to_string(),
+            "/sounds/bruh.mp3".to_string(),
+            memes.id,
+            None,
+            None,
+        );
+        assert_eq!(format_list(&library), "Airhorn\tGeneral\nBruh\tMemes");
+    }
+
+    #[test]
+    fn test_import_dir() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("Stream Sounds");
+        fs::create_dir(&dir).unwrap();
+        for file in ["b.MP3", "a.wav", "notes.txt"] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+        fs::create_dir(dir.join("nested.mp3")).unwrap();
+
+        let mut library = SoundLibrary::default();
+        let store = |path: &Path| Ok(path.to_string_lossy().into_owned());
+        assert_eq!(import_dir(&mut library, &dir, store).unwrap(), 2);
+        assert_eq!(library.categories[1].name, "Stream Sounds");
+        let names: Vec<_> = library.sounds.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(library
+            .sounds
+            .iter()
+            .all(|sound| sound.category_id == library.categories[1].id));
+
+        // Importing again adds nothing new and reuses the category
+        fs::write(dir.join("c.ogg"), b"").unwrap();
+        assert_eq!(import_dir(&mut library, &dir, store).unwrap(), 1);
+        assert_eq!(library.categories.len(), 2);
+        assert_eq!(library.sounds.len(), 3);
+
+        assert!(import_dir(&mut library, &temp.path().join("missing"), store).is_err());
+    }
+}
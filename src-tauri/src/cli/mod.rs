@@ -0,0 +1,39 @@
+//! Command-line control and single-instance IPC
+//!
+//! `sonic-deck play "Airhorn"`, `stop-all`, `list` and `import <dir>` are
+//! forwarded to the running instance over a local socket (a named pipe on
+//! Windows). Without a running instance they run headless. Launching the app
+//! again shows the running window instead of starting a second one, and
+//! `--daemon` starts it without the window.
+
+mod app;
+mod args;
+mod headless;
+mod ipc;
+mod library;
+
+pub use app::start_ipc_server;
+pub use args::{parse_args, CliArgs, CliCommand, USAGE};
+pub use headless::run_headless;
+
+/// Forward a command to the running instance (None if there is none)
+pub fn send_to_running_instance(command: &CliCommand) -> Option<Result<String, String>> {
+    ipc::send(&ipc::instance_socket_id(), command)
+}
+
+/// Whether another instance is already running
+pub fn is_instance_running() -> bool {
+    ipc::is_listening(&ipc::instance_socket_id())
+}
+
+/// Write output to the console the app was started from
+///
+/// Release builds on Windows have no console of their own.
+pub fn attach_parent_console() {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        // Fails when not started from a console, where there's nothing to print to
+        let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+    }
+}
@@ -5,23 +5,30 @@
 //! - Dual-output playback
 //! - Playback control (play, stop)
 //! - Audio cache management
+//! - Limiter metering
 //! - Waveform generation
+//! - Sound export
 
+use std::path::Path;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use cpal::traits::HostTrait;
-use tauri::{Emitter, State};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, State};
 use tracing::{debug, error, info};
 
 use crate::audio::{
-    self, AudioDevice, AudioManager, CacheStats, DeviceId, SoundState, WaveformData,
+    self, AudioCache, AudioDevice, AudioManager, BusMeter, CacheStats, DeviceId, EffectConfig,
+    ExportFormat, ExportSummary, RenderSettings, SoundState, WaveformData,
 };
+use crate::sounds::{Sound, SoundId};
+use crate::tts::{self, TtsSpeech};
+use crate::AppState;
 
 /// Playback progress event payload
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, Serialize)]
 struct PlaybackProgress {
     playback_id: String,
     elapsed_ms: u64,
@@ -29,14 +36,46 @@ struct PlaybackProgress {
     progress_pct: u8,
 }
 
+/// Playback state of a library sound (ordered from least to most active)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundPlayState {
+    #[default]
+    Idle,
+    /// Triggered but not audible yet
+    Decoding,
+    Playing,
+}
+
+/// Payload of the `sound-state-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundStateChange {
+    pub sound_id: SoundId,
+    pub state: SoundPlayState,
+}
+
+/// Tell listeners (control surfaces, OBS, Twitch) that a library sound changed state
+fn emit_sound_state(app_handle: &tauri::AppHandle, sound_id: &str, state: SoundPlayState) {
+    if sound_id.is_empty() {
+        return;
+    }
+    let change = SoundStateChange {
+        sound_id: SoundId::from_string(sound_id.to_string()),
+        state,
+    };
+    if let Err(e) = app_handle.emit("sound-state-changed", change) {
+        error!("Failed to emit sound state event: {}", e);
+    }
+}
+
 /// Lists all available output audio devices on the system
 #[tauri::command]
-pub fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
-    audio::enumerate_devices().map_err(Into::into)
+pub fn list_audio_devices(manager: State<'_, AudioManager>) -> Result<Vec<AudioDevice>, String> {
+    audio::enumerate_devices(&*manager.backend()).map_err(Into::into)
 }
 
 /// Result of play_dual_output indicating what action was taken
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PlaybackResult {
     /// The playback ID (if playback started)
     pub playback_id: Option<String>,
@@ -110,9 +149,29 @@ pub fn play_dual_output(
         } else {
             // No current playback, register as decoding
             manager.register_sound_decoding(sound_id.clone(), playback_id.clone());
+            emit_sound_state(&app_handle, &sound_id, SoundPlayState::Decoding);
         }
     }
 
+    // Look up the sound's effect chain (rendered on the playback thread) and,
+    // for text-to-speech sounds, what to say if the render has gone missing
+    let (effects, speech): (Vec<EffectConfig>, Option<TtsSpeech>) = if sound_id.is_empty() {
+        (Vec::new(), None)
+    } else {
+        app_handle
+            .state::<AppState>()
+            .read_sounds()
+            .sounds
+            .iter()
+            .find(|s| s.id.as_str() == sound_id)
+            .map(|s| (s.effects.clone(), s.tts.clone()))
+            .unwrap_or_default()
+    };
+    let speech = speech.map(|speech| {
+        let engine = tts::engine(&app_handle.state::<AppState>().read_settings().tts);
+        (engine, speech)
+    });
+
     // Create stop channel
     let (stop_tx, stop_rx) = mpsc::channel();
 
@@ -127,6 +186,7 @@ pub fn play_dual_output(
     let manager_inner = manager.get_stop_senders();
     let active_sounds = manager.get_active_sounds();
     let cache = manager.get_cache();
+    let buses = manager.get_buses();
     let sound_id_clone = sound_id.clone();
     let old_playback_to_stop = stopped_playback_id.clone();
 
@@ -147,13 +207,21 @@ pub fn play_dual_output(
                     if let Some(state) = sounds.get(sound_id) {
                         if state.playback_id() == playback_id {
                             sounds.remove(sound_id);
+                            emit_sound_state(&app_handle, sound_id, SoundPlayState::Idle);
                         }
                     }
                 }
             };
 
         // Get audio from cache or decode (cache handles the logic)
-        let audio_data = match cache.lock().unwrap().get_or_decode(&file_path) {
+        let rendered = match &speech {
+            Some((engine, speech)) => {
+                tts::ensure_rendered(engine.as_ref(), speech, Path::new(&file_path))
+            }
+            None => Ok(()),
+        };
+        let audio_data = match rendered.and_then(|()| AudioCache::get_or_decode(&cache, &file_path))
+        {
             Ok(data) => data, // Already Arc<AudioData>
             Err(e) => {
                 error!("Failed to decode audio: {}", e);
@@ -178,36 +246,29 @@ pub fn play_dual_output(
             error!("Failed to emit decode complete event: {}", e);
         }
 
-        // This thread owns the streams - no Send issues!
-        let host = cpal::default_host();
-
-        let enum_start = Instant::now();
-        let output_devices: Vec<_> = match host.output_devices() {
-            Ok(devices) => devices.collect(),
-            Err(e) => {
-                error!("Failed to enumerate devices: {}", e);
-                cleanup_early(
-                    &manager_inner,
-                    &active_sounds,
-                    &playback_id_clone,
-                    &sound_id_clone,
-                );
-                return;
-            }
-        };
-
-        let enum_duration = enum_start.elapsed().as_millis();
-        debug!(
-            duration_ms = enum_duration,
-            device_count = output_devices.len(),
-            "Device enumeration complete"
+        // Trim and render the effect chain (rendered once per chain and cached)
+        let prepared = AudioCache::prepare(
+            &cache,
+            &file_path,
+            audio_data,
+            trim_start_ms,
+            trim_end_ms,
+            &effects,
         );
-
-        // Parse device indices
-        let (idx1, idx2) = match (device_id_1.index(), device_id_2.index()) {
-            (Ok(i1), Ok(i2)) => (i1, i2),
-            _ => {
-                let error_msg = format!("Invalid device IDs: {} / {}", device_id_1, device_id_2);
+        let duration = prepared.duration();
+
+        // Add a voice to the bus of each device with shared volume state and trim
+        let mixers = match buses.play(
+            &[&device_id_1, &device_id_2],
+            &playback_id_clone,
+            prepared.audio_data,
+            volume_state,
+            prepared.start_frame,
+            prepared.end_frame,
+        ) {
+            Ok(mixers) => mixers,
+            Err(e) => {
+                let error_msg = format!("Failed to open output device: {}", e);
                 error!("{}", error_msg);
                 if let Err(e) = app_handle.emit("audio-device-error", error_msg) {
                     error!("Failed to emit device error event: {}", e);
@@ -222,72 +283,13 @@ pub fn play_dual_output(
             }
         };
 
-        let (Some(device_1), Some(device_2)) = (output_devices.get(idx1), output_devices.get(idx2))
-        else {
-            error!("Devices not found at indices {} and {}", idx1, idx2);
-            cleanup_early(
-                &manager_inner,
-                &active_sounds,
-                &playback_id_clone,
-                &sound_id_clone,
-            );
-            return;
-        };
-
-        // Calculate trim frames from milliseconds
-        let sample_rate = audio_data.sample_rate;
-        let start_frame =
-            trim_start_ms.map(|ms| ((ms as f64 / 1000.0) * sample_rate as f64) as usize);
-        let end_frame = trim_end_ms.map(|ms| ((ms as f64 / 1000.0) * sample_rate as f64) as usize);
-
-        // Create streams with shared volume state and trim parameters
-        let stream_1 = match audio::create_playback_stream(
-            device_1,
-            audio_data.clone(),
-            volume_state.clone(),
-            start_frame,
-            end_frame,
-        ) {
-            Ok(s) => s,
-            Err(e) => {
-                error!("Failed to create stream 1: {}", e);
-                cleanup_early(
-                    &manager_inner,
-                    &active_sounds,
-                    &playback_id_clone,
-                    &sound_id_clone,
-                );
-                return;
-            }
-        };
-
-        let stream_2 = match audio::create_playback_stream(
-            device_2,
-            audio_data.clone(),
-            volume_state.clone(),
-            start_frame,
-            end_frame,
-        ) {
-            Ok(s) => s,
-            Err(e) => {
-                error!("Failed to create stream 2: {}", e);
-                cleanup_early(
-                    &manager_inner,
-                    &active_sounds,
-                    &playback_id_clone,
-                    &sound_id_clone,
-                );
-                return;
-            }
-        };
-
-        // Streams created successfully - NOW the sound is audible!
+        // Voices added - NOW the sound is audible!
         let streams_ready_elapsed = thread_start.elapsed().as_millis();
         info!(
             playback_id = %playback_id_clone,
             sound_id = %sound_id_clone,
             streams_ready_ms = streams_ready_elapsed,
-            "Audio streams created and playing"
+            "Voices added to output buses and playing"
         );
 
         // Stop the old playback NOW (seamless transition, no audio gap)
@@ -315,18 +317,12 @@ pub fn play_dual_output(
                         "Sound {} now playing (playback {})",
                         sound_id_clone, playback_id_clone
                     );
+                    emit_sound_state(&app_handle, &sound_id_clone, SoundPlayState::Playing);
                 }
             }
         }
 
-        // Calculate duration (with trim)
-        let total_frames = audio_data.samples.len() / audio_data.channels as usize;
-        let actual_start = start_frame.unwrap_or(0);
-        let actual_end = end_frame.unwrap_or(total_frames);
-        let trimmed_frames = actual_end.saturating_sub(actual_start);
-
-        let duration_secs = trimmed_frames as f64 / audio_data.sample_rate as f64;
-        let total_sleep_ms = (duration_secs * 1000.0) as u64;
+        let total_sleep_ms = duration.as_millis() as u64;
 
         // Wait for completion or stop signal, emitting progress events
         let check_interval = Duration::from_millis(10); // 10ms for fast stop response
@@ -362,9 +358,10 @@ pub fn play_dual_output(
             }
         }
 
-        // Clean up
-        drop(stream_1);
-        drop(stream_2);
+        // Clean up (no-op if the voices already played to the end)
+        for mixer in &mixers {
+            mixer.remove_voice(&playback_id_clone);
+        }
 
         let total_duration_ms = thread_start.elapsed().as_millis();
         debug!(
@@ -391,6 +388,7 @@ pub fn play_dual_output(
             if let Some(state) = sounds.get(&sound_id_clone) {
                 if state.playback_id() == playback_id_clone {
                     sounds.remove(&sound_id_clone);
+                    emit_sound_state(&app_handle, &sound_id_clone, SoundPlayState::Idle);
                 }
             }
         }
@@ -409,6 +407,51 @@ pub fn play_dual_output(
     })
 }
 
+/// Play a library sound on the configured monitor and broadcast devices
+///
+/// Used by global hotkeys and remote triggers: the sound's own volume (or the
+/// default volume) and trim apply, as when it's played from the board. `gain`
+/// scales that volume (MIDI velocity; 1.0 otherwise).
+pub fn play_library_sound(
+    app_handle: &tauri::AppHandle,
+    sound_id: &SoundId,
+    gain: f32,
+) -> Result<(Sound, PlaybackResult), String> {
+    let app_state = app_handle.state::<AppState>();
+
+    let sound = app_state
+        .read_sounds()
+        .sounds
+        .iter()
+        .find(|s| s.id == *sound_id)
+        .cloned()
+        .ok_or_else(|| format!("Sound not found: {}", sound_id.as_str()))?;
+
+    let (device1, device2, default_volume) = {
+        let settings = app_state.read_settings();
+        (
+            settings.monitor_device_id.clone(),
+            settings.broadcast_device_id.clone(),
+            settings.default_volume,
+        )
+    };
+    let device1 = device1.ok_or("No monitor device configured")?;
+    let device2 = device2.ok_or("No broadcast device configured")?;
+
+    let result = play_dual_output(
+        sound.file_path.clone(),
+        device1,
+        device2,
+        sound.volume.unwrap_or(default_volume) * gain,
+        sound.trim_start_ms,
+        sound.trim_end_ms,
+        Some(sound.id.as_str().to_owned()),
+        app_handle.state::<AudioManager>(),
+        app_handle.clone(),
+    )?;
+    Ok((sound, result))
+}
+
 /// Stops all currently playing audio
 #[tauri::command]
 pub fn stop_all_audio(manager: State<'_, AudioManager>) -> Result<(), String> {
@@ -439,6 +482,12 @@ pub fn get_cache_stats(manager: State<'_, AudioManager>) -> Result<CacheStats, S
     Ok(manager.cache_stats())
 }
 
+/// Get limiter gain reduction meters for all open output buses
+#[tauri::command]
+pub fn get_limiter_meters(manager: State<'_, AudioManager>) -> Result<Vec<BusMeter>, String> {
+    Ok(manager.limiter_meters())
+}
+
 /// Get waveform data for an audio file
 #[tauri::command]
 pub fn get_waveform(
@@ -448,9 +497,6 @@ pub fn get_waveform(
 ) -> Result<WaveformData, String> {
     // Use cache to get or decode the audio
     let audio_data = manager
-        .get_cache()
-        .lock()
-        .unwrap()
         .get_or_decode(&file_path)
         .map_err(|e| e.to_string())?;
 
@@ -463,18 +509,48 @@ pub fn get_waveform(
 /// Call this when switching categories to ensure sounds are ready
 #[tauri::command]
 pub fn preload_sounds(file_paths: Vec<String>, manager: State<'_, AudioManager>) {
-    let cache = manager.get_cache();
+    manager.preload(file_paths);
+}
 
-    // Spawn background thread to preload without blocking UI
-    thread::spawn(move || {
-        for path in file_paths {
-            let mut cache_guard = cache.lock().unwrap();
-            if cache_guard.get_or_decode(&path).is_ok() {
-                debug!("Preloaded: {}", path);
-            }
-            // Release lock between files to not block playback
-            drop(cache_guard);
-        }
-        debug!("Preload complete");
-    });
+/// Render a sound with its trim, volume and effects and write it to a file
+///
+/// The format defaults to the one matching `output_path`'s extension, or WAV.
+#[tauri::command]
+pub fn export_sound(
+    sound_id: String,
+    output_path: String,
+    format: Option<ExportFormat>,
+    manager: State<'_, AudioManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<ExportSummary, String> {
+    let state = app_handle.state::<AppState>();
+    let sound = state
+        .read_sounds()
+        .sounds
+        .iter()
+        .find(|s| s.id.as_str() == sound_id)
+        .cloned()
+        .ok_or_else(|| format!("Sound not found: {}", sound_id))?;
+    let default_volume = state.read_settings().default_volume;
+
+    let output_path = Path::new(&output_path);
+    let format = format
+        .or_else(|| ExportFormat::from_path(output_path))
+        .unwrap_or_default();
+    let settings = RenderSettings {
+        volume: sound.volume.unwrap_or(default_volume).clamp(0.0, 1.0),
+        trim_start_ms: sound.trim_start_ms,
+        trim_end_ms: sound.trim_end_ms,
+        effects: sound.effects,
+        limiter_ceiling_db: manager.get_buses().ceiling_db(),
+    };
+
+    audio::export_sound_file(
+        &manager.get_cache(),
+        &sound.file_path,
+        &settings,
+        output_path,
+        format,
+    )
+    .map_err(Into::into)
 }
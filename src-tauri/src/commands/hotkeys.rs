@@ -38,6 +38,14 @@ pub fn register_hotkey(
         current.clone()
     };
 
+    // The push-to-talk hotkey can't also trigger a sound
+    if state.read_settings().microphone_gate_hotkey.as_deref() == Some(hotkey.as_str()) {
+        return Err(format!(
+            "Hotkey '{}' is already used for microphone push-to-talk",
+            hotkey
+        ));
+    }
+
     // Add mapping (checks for duplicates)
     hotkeys::add_mapping(&mut mappings, hotkey.clone(), sound_id.clone())?;
 
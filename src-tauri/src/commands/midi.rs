@@ -0,0 +1,43 @@
+//! MIDI input and learn mode commands
+
+use tauri::State;
+
+use crate::midi::{self, MidiState, MidiStatus, MidiTrigger};
+use crate::sounds::SoundId;
+use crate::AppState;
+
+/// List the available MIDI input ports
+#[tauri::command]
+pub fn list_midi_inputs() -> Result<Vec<String>, String> {
+    midi::list_inputs()
+}
+
+/// Get the MIDI input connection status
+#[tauri::command]
+pub fn get_midi_status(midi: State<'_, MidiState>) -> MidiStatus {
+    midi.status()
+}
+
+/// Bind the next MIDI note or button to a sound ("midi-learned" is emitted)
+#[tauri::command]
+pub fn start_midi_learn(sound_id: SoundId, midi: State<'_, MidiState>) -> Result<(), String> {
+    midi.start_learn(sound_id)
+}
+
+/// Leave MIDI learn mode
+#[tauri::command]
+pub fn cancel_midi_learn(midi: State<'_, MidiState>) {
+    midi.cancel_learn();
+}
+
+/// Remove a MIDI binding
+#[tauri::command]
+pub fn remove_midi_binding(
+    trigger: MidiTrigger,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    state.update_hotkeys(|mappings| mappings.midi.remove(&trigger))?;
+    state.hotkeys_changed(&app_handle);
+    Ok(())
+}
@@ -4,21 +4,39 @@
 //! - `audio`: Audio playback, device management, caching, waveforms
 //! - `settings`: App settings and autostart configuration
 //! - `hotkeys`: Global hotkey registration and management
-//! - `sounds`: Sound library and category management
+//! - `sounds`: Sound library and category management, destructive edits
 //! - `logs`: Log file access and management
+//! - `midi`: MIDI input selection and learn mode
+//! - `obs`: OBS WebSocket connection status
+//! - `recorder`: Recording and instant replay into new sounds
+//! - `remote`: Remote-control API status and token
+//! - `tts`: Text-to-speech sounds and voices
+//! - `twitch`: Twitch chat status and kill switch
 //! - `vbcable`: VB-Cable detection and default device management
 
 pub mod audio;
 pub mod hotkeys;
 pub mod logs;
+pub mod midi;
+pub mod obs;
+pub mod recorder;
+pub mod remote;
 pub mod settings;
 pub mod sounds;
+pub mod tts;
+pub mod twitch;
 pub mod vbcable;
 
 // Re-export all commands for easy access in lib.rs
 pub use audio::*;
 pub use hotkeys::*;
 pub use logs::*;
+pub use midi::*;
+pub use obs::*;
+pub use recorder::*;
+pub use remote::*;
 pub use settings::*;
 pub use sounds::*;
+pub use tts::*;
+pub use twitch::*;
 pub use vbcable::*;
@@ -0,0 +1,11 @@
+//! OBS integration commands
+
+use tauri::State;
+
+use crate::obs::{ObsState, ObsStatus};
+
+/// Get the OBS WebSocket connection status
+#[tauri::command]
+pub fn get_obs_status(obs: State<'_, ObsState>) -> ObsStatus {
+    obs.status()
+}
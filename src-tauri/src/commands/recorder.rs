@@ -0,0 +1,75 @@
+//! Recorder commands: record or replay, then save as a new sound
+
+use tauri::State;
+
+use crate::audio::AudioManager;
+use crate::media;
+use crate::recorder::{self, RecordMode, RecordSource, Recorder, RecorderStatus, SourceInfo};
+use crate::sounds::{self, CategoryId, Sound};
+use crate::AppState;
+
+/// List input devices and output devices (loopback) to record from
+#[tauri::command]
+pub fn list_recording_sources(manager: State<'_, AudioManager>) -> Vec<SourceInfo> {
+    recorder::list_sources(&*manager.backend())
+}
+
+/// Start recording, or start the instant replay buffer
+#[tauri::command]
+pub fn start_recording(
+    source: RecordSource,
+    mode: RecordMode,
+    recorder: State<'_, Recorder>,
+) -> Result<(), String> {
+    recorder.start(source, mode)
+}
+
+/// Get the running recorder session (None if idle)
+#[tauri::command]
+pub fn get_recorder_status(recorder: State<'_, Recorder>) -> Option<RecorderStatus> {
+    recorder.status()
+}
+
+/// Stop the recorder and discard what it captured
+#[tauri::command]
+pub fn stop_recording(recorder: State<'_, Recorder>) {
+    recorder.stop();
+}
+
+/// Save the recording (or the replay window) as a new sound
+///
+/// Ends a recording once the sound is saved; instant replay keeps running.
+#[tauri::command]
+pub fn save_recording(
+    name: String,
+    category_id: CategoryId,
+    recorder: State<'_, Recorder>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Sound, String> {
+    let mut library = {
+        let current = state.read_sounds();
+        current.clone()
+    };
+    if !library.categories.iter().any(|c| c.id == category_id) {
+        return Err(format!("Category not found: {}", category_id.as_str()));
+    }
+
+    let audio = recorder.audio()?;
+    let media_dir = state.storage().media_dir()?;
+    // The stored file is unreferenced until the library is saved
+    let _pending = media::protect_new_files();
+    let stored = media::store_recording(&audio, &media_dir)?;
+
+    let sound = sounds::add_sound(
+        &mut library,
+        name,
+        stored.to_string_lossy().into_owned(),
+        category_id,
+        None,
+        None,
+    );
+    state.update_and_save_sounds(&app_handle, library)?;
+    recorder.saved();
+    Ok(sound)
+}
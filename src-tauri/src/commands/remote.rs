@@ -0,0 +1,28 @@
+//! Remote-control API commands
+
+use crate::remote::{self, RemoteState, RemoteStatus};
+use crate::AppState;
+use tauri::{Emitter, State};
+
+/// Get whether the remote API is running and where
+#[tauri::command]
+pub fn get_remote_api_status(remote: State<'_, RemoteState>) -> Result<RemoteStatus, String> {
+    Ok(remote.status())
+}
+
+/// Replace the remote API token (disconnects clients using the old one)
+#[tauri::command]
+pub fn regenerate_remote_api_token(
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let token = remote::generate_token()?;
+    let config = state.update_settings(|settings| {
+        settings.remote_api.token = token;
+        settings.remote_api.clone()
+    })?;
+
+    remote::apply_settings(&app_handle, &config);
+    let _ = app_handle.emit("settings-changed", ());
+    Ok(config.token)
+}
@@ -1,8 +1,16 @@
 //! Application settings and autostart management commands
 
-use crate::settings::{self, AppSettings};
-use crate::AppState;
-use tauri::State;
+use crate::audio::DiskCache;
+use crate::midi;
+use crate::obs;
+use crate::remote;
+use crate::settings::AppSettings;
+use crate::storage::StorageRoot;
+use crate::twitch;
+use crate::vbcable;
+use crate::{AppState, AudioManager};
+use tauri::{Emitter, State};
+use tracing::warn;
 
 /// Load application settings from in-memory state
 #[tauri::command]
@@ -14,17 +22,104 @@ pub fn load_settings(state: State<'_, AppState>) -> Result<AppSettings, String>
 /// Save application settings to state and disk
 #[tauri::command]
 pub fn save_settings(
-    settings: AppSettings,
+    mut settings: AppSettings,
     state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    state.update_and_save_settings(&app_handle, settings)
+    manager.set_limiter_ceiling_db(settings.limiter_ceiling_db);
+    manager.set_ducking_config(settings.ducking.clone());
+    vbcable::set_routing_gains(
+        settings.microphone_routing_mic_gain_db,
+        settings.microphone_routing_board_gain_db,
+    );
+    vbcable::set_mic_processing(settings.microphone_processing.clone());
+    // Changing the mode releases the gate key, so leave a held key alone otherwise
+    if state.read_settings().microphone_gate_mode != settings.microphone_gate_mode {
+        vbcable::set_gate_mode(settings.microphone_gate_mode);
+    }
+
+    let decode_cache_changed = {
+        let current = state.read_settings();
+        current.decode_cache_max_mb != settings.decode_cache_max_mb
+            || current.decode_cache_resample != settings.decode_cache_resample
+            || (settings.decode_cache_resample
+                && current.monitor_device_id != settings.monitor_device_id)
+    };
+    if decode_cache_changed {
+        apply_decode_cache_settings(&settings, &manager, state.storage());
+    }
+
+    // Enabling the remote API for the first time creates its token
+    let token_generated = settings.remote_api.enabled && settings.remote_api.token.is_empty();
+    if token_generated {
+        settings.remote_api.token = remote::generate_token()?;
+    }
+    let remote_config = settings.remote_api.clone();
+    let remote_changed = state.read_settings().remote_api != remote_config;
+    let midi_port = settings.midi_input_port.clone();
+    let midi_changed = state.read_settings().midi_input_port != midi_port;
+    // Actions and triggers are read when used; only reconnect for connection changes
+    let obs_config = settings.obs.clone();
+    let obs_changed = !state.read_settings().obs.same_connection(&obs_config);
+    let twitch_config = settings.twitch.clone();
+    let twitch_changed = !state.read_settings().twitch.same_connection(&twitch_config);
+
+    state.save_settings(settings)?;
+    if remote_changed {
+        remote::apply_settings(&app_handle, &remote_config);
+    }
+    if midi_changed {
+        midi::apply_settings(&app_handle, midi_port.as_deref());
+    }
+    if obs_changed {
+        obs::apply_settings(&app_handle, &obs_config);
+    }
+    if twitch_changed {
+        twitch::apply_settings(&app_handle, &twitch_config);
+    }
+    if token_generated {
+        // The UI reloads settings to show the new token
+        let _ = app_handle.emit("settings-changed", ());
+    }
+    Ok(())
+}
+
+/// Configure the persistent decode cache from settings
+///
+/// Failures only disable the disk cache; playback falls back to decoding.
+pub fn apply_decode_cache_settings(
+    settings: &AppSettings,
+    manager: &AudioManager,
+    storage: &StorageRoot,
+) {
+    if settings.decode_cache_max_mb == 0 {
+        manager.set_disk_cache(None);
+        return;
+    }
+
+    // Pre-resample to the monitor device rate; other devices still convert at playback
+    let sample_rate = settings
+        .monitor_device_id
+        .as_ref()
+        .filter(|_| settings.decode_cache_resample)
+        .and_then(|device_id| device_id.index().ok())
+        .and_then(|index| manager.backend().output_format(index).ok())
+        .map(|format| format.sample_rate);
+
+    match DiskCache::new(storage.decode_cache_dir(), settings.decode_cache_max_mb) {
+        Ok(disk) => manager.set_disk_cache(Some(disk.with_sample_rate(sample_rate))),
+        Err(e) => {
+            warn!("Failed to open decode cache: {}", e);
+            manager.set_disk_cache(None);
+        }
+    }
 }
 
 /// Get the settings file path (for debugging/info)
 #[tauri::command]
-pub fn get_settings_file_path(app_handle: tauri::AppHandle) -> Result<String, String> {
-    let path = settings::get_settings_path(&app_handle)?;
+pub fn get_settings_file_path(state: State<'_, AppState>) -> Result<String, String> {
+    let path = state.storage().settings_path();
     Ok(path.to_string_lossy().to_string())
 }
 
@@ -1,8 +1,11 @@
 //! Sound library and category management commands
 
+use crate::audio::{AudioCache, AudioManager, EffectConfig};
 use crate::hotkeys;
+use crate::media::{self, MediaUsage};
 use crate::sounds::{self, Category, CategoryId, Sound, SoundId, SoundLibrary};
 use crate::AppState;
+use std::path::Path;
 use tauri::State;
 use tracing::{info, warn};
 
@@ -24,6 +27,9 @@ pub fn add_sound(
     state: State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<Sound, String> {
+    // A copied file is unreferenced until the library is saved
+    let _pending = media::protect_new_files();
+    let file_path = library_file_path(file_path, &state)?;
     let mut library = {
         let current = state.read_sounds();
         current.clone()
@@ -47,8 +53,11 @@ pub fn update_sound(
     trim_start_ms: Option<u64>,
     trim_end_ms: Option<u64>,
     state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
     app_handle: tauri::AppHandle,
 ) -> Result<Sound, String> {
+    let pending = media::protect_new_files();
+    let file_path = library_file_path(file_path, &state)?;
     let mut library = {
         let current = state.read_sounds();
         current.clone()
@@ -69,6 +78,9 @@ pub fn update_sound(
     )?;
 
     state.update_and_save_sounds(&app_handle, library)?;
+    // Collection waits for this guard, so release it first
+    drop(pending);
+    remove_unused_media(&state, &manager);
     Ok(sound)
 }
 
@@ -98,11 +110,120 @@ pub fn toggle_favorite(
     Ok(updated_sound)
 }
 
+/// Replace the effect chain of a sound (applied at render time, file is untouched)
+#[tauri::command]
+pub fn set_sound_effects(
+    sound_id: SoundId,
+    effects: Vec<EffectConfig>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Sound, String> {
+    let mut library = {
+        let current = state.read_sounds();
+        current.clone()
+    };
+
+    let sound = sounds::set_effects(&mut library, &sound_id, effects)?;
+    state.update_and_save_sounds(&app_handle, library)?;
+    Ok(sound)
+}
+
+/// Bake a sound's trim into a processed copy in the media folder and play that
+///
+/// The copy can also be peak-normalized and converted to the sample rate of the
+/// broadcast device (or the monitor device if no broadcast device is set). The
+/// original file is kept and restored by `revert_sound_edits`.
+#[tauri::command]
+pub fn apply_sound_edits(
+    sound_id: SoundId,
+    normalize_peak_db: Option<f32>,
+    match_device_rate: bool,
+    state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<Sound, String> {
+    let mut library = {
+        let current = state.read_sounds();
+        current.clone()
+    };
+
+    let sample_rate = if match_device_rate {
+        let device_id = {
+            let settings = state.read_settings();
+            settings
+                .broadcast_device_id
+                .clone()
+                .or_else(|| settings.monitor_device_id.clone())
+                .ok_or("No output device selected")?
+        };
+        Some(
+            manager
+                .backend()
+                .output_format(device_id.index()?)?
+                .sample_rate,
+        )
+    } else {
+        None
+    };
+
+    let sound = library
+        .sounds
+        .iter_mut()
+        .find(|s| s.id == sound_id)
+        .ok_or_else(|| format!("Sound not found: {}", sound_id.as_str()))?;
+    let audio_data = manager.get_or_decode(&sound.file_path)?;
+
+    let media_dir = state.storage().media_dir()?;
+    // The processed copy is unreferenced until the library is saved
+    let pending = media::protect_new_files();
+    media::apply_edits(
+        sound,
+        &audio_data,
+        normalize_peak_db,
+        sample_rate,
+        &media_dir,
+    )?;
+    let updated_sound = sound.clone();
+    state.update_and_save_sounds(&app_handle, library)?;
+    drop(pending);
+
+    // Re-editing leaves the previous processed copy unused
+    remove_unused_media(&state, &manager);
+    Ok(updated_sound)
+}
+
+/// Point an edited sound back at its original file and trim
+#[tauri::command]
+pub fn revert_sound_edits(
+    sound_id: SoundId,
+    state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<Sound, String> {
+    let mut library = {
+        let current = state.read_sounds();
+        current.clone()
+    };
+
+    let sound = library
+        .sounds
+        .iter_mut()
+        .find(|s| s.id == sound_id)
+        .ok_or_else(|| format!("Sound not found: {}", sound_id.as_str()))?;
+    media::revert_edits(sound)?;
+    let updated_sound = sound.clone();
+    state.update_and_save_sounds(&app_handle, library)?;
+
+    remove_unused_media(&state, &manager);
+    Ok(updated_sound)
+}
+
 /// Delete a sound from the library and remove associated hotkeys
 #[tauri::command]
 pub fn delete_sound(
     sound_id: SoundId,
     state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     // First, find and remove any hotkeys associated with this sound
@@ -151,6 +272,7 @@ pub fn delete_sound(
 
     sounds::delete_sound(&mut library, &sound_id)?;
     state.update_and_save_sounds(&app_handle, library)?;
+    remove_unused_media(&state, &manager);
 
     Ok(())
 }
@@ -199,6 +321,7 @@ pub fn delete_category(
     category_id: CategoryId,
     move_sounds_to: Option<CategoryId>,
     state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     let mut library = {
@@ -208,5 +331,55 @@ pub fn delete_category(
 
     sounds::delete_category(&mut library, &category_id, move_sounds_to)?;
     state.update_and_save_sounds(&app_handle, library)?;
+    remove_unused_media(&state, &manager);
     Ok(())
 }
+
+/// Get disk usage of the managed media folder
+#[tauri::command]
+pub fn get_media_storage_usage(state: State<'_, AppState>) -> Result<MediaUsage, String> {
+    let media_dir = state.storage().media_dir()?;
+    media::storage_usage(&state.read_sounds(), &media_dir)
+}
+
+/// Delete files in the media folder that no sound uses; returns how many were removed
+#[tauri::command]
+pub fn collect_unused_media(
+    state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
+) -> Result<usize, String> {
+    collect_media_garbage(&state, &manager)
+}
+
+/// Path to store for a sound's file: a copy in the media folder when the
+/// library manages files, otherwise the user's path
+pub(crate) fn library_file_path(file_path: String, state: &AppState) -> Result<String, String> {
+    if !state.read_settings().copy_sounds_to_library {
+        return Ok(file_path);
+    }
+    let media_dir = state.storage().media_dir()?;
+    let stored = media::import_file(Path::new(&file_path), &media_dir)?;
+    Ok(stored.to_string_lossy().into_owned())
+}
+
+/// Delete unreferenced media and drop it from the decode cache
+fn collect_media_garbage(state: &AppState, manager: &AudioManager) -> Result<usize, String> {
+    let media_dir = state.storage().media_dir()?;
+    let lock = media::lock_for_collection();
+    let removed = media::collect_garbage(&state.read_sounds(), &media_dir, &lock)?;
+
+    let cache = manager.get_cache();
+    for path in &removed {
+        AudioCache::invalidate(&cache, &path.to_string_lossy());
+    }
+    Ok(removed.len())
+}
+
+/// Garbage-collect the media folder after the library changed
+///
+/// The library is already saved at this point, so failures are only logged.
+pub(crate) fn remove_unused_media(state: &AppState, manager: &AudioManager) {
+    if let Err(e) = collect_media_garbage(state, manager) {
+        warn!("Failed to remove unused media: {}", e);
+    }
+}
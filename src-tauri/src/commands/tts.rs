@@ -0,0 +1,83 @@
+//! Text-to-speech sound commands
+
+use super::sounds::remove_unused_media;
+use crate::audio::AudioManager;
+use crate::media;
+use crate::sounds::{self, CategoryId, Sound, SoundId};
+use crate::tts::{self, TtsSpeech, TtsVoice};
+use crate::AppState;
+use tauri::State;
+
+/// List the voices of the configured engine
+#[tauri::command]
+pub fn list_tts_voices(state: State<'_, AppState>) -> Result<Vec<TtsVoice>, String> {
+    let engine = tts::engine(&state.read_settings().tts);
+    Ok(engine.voices()?)
+}
+
+/// Add a sound that speaks a text
+#[tauri::command]
+pub fn add_tts_sound(
+    name: String,
+    speech: TtsSpeech,
+    category_id: CategoryId,
+    icon: Option<String>,
+    volume: Option<f32>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Sound, String> {
+    let speech = speech.normalized()?;
+    // Garbage collection would see the render as unused until the library is saved
+    let _pending = media::protect_new_files();
+    let file_path = render_speech(&speech, &state)?;
+    let mut library = {
+        let current = state.read_sounds();
+        current.clone()
+    };
+
+    let sound = sounds::add_sound(
+        &mut library,
+        name,
+        file_path.clone(),
+        category_id,
+        icon,
+        volume,
+    );
+    let sound = sounds::set_speech(&mut library, &sound.id, speech, file_path)?;
+    state.update_and_save_sounds(&app_handle, library)?;
+    Ok(sound)
+}
+
+/// Change what a text-to-speech sound says
+#[tauri::command]
+pub fn update_tts_sound(
+    sound_id: SoundId,
+    speech: TtsSpeech,
+    state: State<'_, AppState>,
+    manager: State<'_, AudioManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<Sound, String> {
+    let speech = speech.normalized()?;
+    // Until the sound points at it, the new render looks unused
+    let pending = media::protect_new_files();
+    let file_path = render_speech(&speech, &state)?;
+    let mut library = {
+        let current = state.read_sounds();
+        current.clone()
+    };
+
+    let sound = sounds::set_speech(&mut library, &sound_id, speech, file_path)?;
+    state.update_and_save_sounds(&app_handle, library)?;
+    drop(pending);
+    // The previous render is unreferenced now
+    remove_unused_media(&state, &manager);
+    Ok(sound)
+}
+
+/// Render speech into the media folder with the configured engine
+fn render_speech(speech: &TtsSpeech, state: &AppState) -> Result<String, String> {
+    let engine = tts::engine(&state.read_settings().tts);
+    let media_dir = state.storage().media_dir()?;
+    let path = tts::render(engine.as_ref(), speech, &media_dir)?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,17 @@
+//! Twitch chat integration commands
+
+use tauri::State;
+
+use crate::twitch::{self, TwitchState, TwitchStatus};
+
+/// Get the Twitch chat connection and trigger status
+#[tauri::command]
+pub fn get_twitch_status(twitch: State<'_, TwitchState>) -> TwitchStatus {
+    twitch.status()
+}
+
+/// Pause chat triggers (clearing the queue) or resume them
+#[tauri::command]
+pub fn set_twitch_paused(paused: bool, app_handle: tauri::AppHandle) {
+    twitch::set_paused(&app_handle, paused);
+}
@@ -2,16 +2,19 @@
 
 use crate::vbcable::{
     activate_comm_mode, cleanup_temp_files, deactivate_comm_mode, detect_vb_cable, disable_routing,
-    enable_routing, get_routing_status, install_vbcable, is_comm_mode_active, list_capture_devices,
-    uninstall_vbcable, wait_for_vb_cable, DefaultDeviceManager, RestoreResult, SavedDefaults,
+    enable_routing, get_routing_stats, get_routing_status, install_vbcable, is_comm_mode_active,
+    list_capture_devices, set_gate_mode, uninstall_vbcable, wait_for_vb_cable,
+    DefaultDeviceManager, MicGateMode, RestoreResult, RoutingStats, RoutingStatus, SavedDefaults,
     VbCableStatus,
 };
+use crate::{AppState, AudioManager};
+use tauri::State;
 use tracing::info;
 
 /// Check if VB-Cable is installed and get its status
 #[tauri::command]
-pub fn check_vb_cable_status() -> VbCableStatus {
-    if let Some(info) = detect_vb_cable() {
+pub fn check_vb_cable_status(manager: State<'_, AudioManager>) -> VbCableStatus {
+    if let Some(info) = detect_vb_cable(&*manager.backend()) {
         VbCableStatus::Installed { info }
     } else {
         VbCableStatus::NotInstalled
@@ -22,8 +25,8 @@ pub fn check_vb_cable_status() -> VbCableStatus {
 ///
 /// Returns the device name for use in device selection dropdowns.
 #[tauri::command]
-pub fn get_vb_cable_device_name() -> Option<String> {
-    detect_vb_cable().map(|info| info.output_device)
+pub fn get_vb_cable_device_name(manager: State<'_, AudioManager>) -> Option<String> {
+    detect_vb_cable(&*manager.backend()).map(|info| info.output_device)
 }
 
 /// Save the current default audio device
@@ -99,9 +102,9 @@ pub fn restore_all_default_devices(saved: SavedDefaults) -> RestoreResult {
 /// Uses active retry logic that polls for the device up to 5 times with 1s delays.
 /// Returns early as soon as device is detected, or None if not detected after retries.
 #[tauri::command]
-pub fn wait_for_vb_cable_device() -> Option<String> {
+pub fn wait_for_vb_cable_device(manager: State<'_, AudioManager>) -> Option<String> {
     info!("Waiting for VB-Cable device with retry logic...");
-    wait_for_vb_cable().map(|info| info.output_device)
+    wait_for_vb_cable(&*manager.backend()).map(|info| info.output_device)
 }
 
 // ============================================================================
@@ -113,9 +116,9 @@ pub fn wait_for_vb_cable_device() -> Option<String> {
 /// Returns a list of (device_id, display_name) tuples.
 /// Excludes VB-Cable devices since we only want physical microphones.
 #[tauri::command]
-pub fn list_microphones() -> Vec<(String, String)> {
+pub fn list_microphones(manager: State<'_, AudioManager>) -> Vec<(String, String)> {
     info!("Listing available microphones");
-    list_capture_devices()
+    list_capture_devices(&*manager.backend())
 }
 
 /// Enable microphone routing to CABLE Input
@@ -123,9 +126,12 @@ pub fn list_microphones() -> Vec<(String, String)> {
 /// Routes audio from the specified microphone to VB-Cable's CABLE Input device.
 /// This allows the user's voice to be heard on Discord while using VB-Cable.
 #[tauri::command]
-pub fn enable_microphone_routing(microphone_id: String) -> Result<(), String> {
+pub fn enable_microphone_routing(
+    microphone_id: String,
+    manager: State<'_, AudioManager>,
+) -> Result<(), String> {
     info!("Enabling microphone routing for device: {}", microphone_id);
-    enable_routing(&microphone_id)
+    enable_routing(&microphone_id, manager.get_buses())
 }
 
 /// Disable microphone routing
@@ -139,12 +145,68 @@ pub fn disable_microphone_routing() -> Result<(), String> {
 
 /// Get microphone routing status
 ///
-/// Returns the device ID of the currently routed microphone, or None if not active.
+/// Returns the routed microphone and its push-to-talk gate state, or None if not active.
 #[tauri::command]
-pub fn get_microphone_routing_status() -> Option<String> {
+pub fn get_microphone_routing_status() -> Option<RoutingStatus> {
     get_routing_status()
 }
 
+/// Configure push-to-talk/push-to-mute for the routed microphone
+///
+/// Registers `hotkey` as a global shortcut (replacing the previous gate hotkey),
+/// applies the mode immediately and persists both in settings.
+#[tauri::command]
+pub fn set_microphone_gate(
+    mode: MicGateMode,
+    hotkey: Option<String>,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    if mode != MicGateMode::AlwaysOn && hotkey.is_none() {
+        return Err("Push-to-talk and push-to-mute need a hotkey".to_string());
+    }
+    if let Some(key) = &hotkey {
+        if state.read_hotkeys().mappings.contains_key(key) {
+            return Err(format!("Hotkey '{}' is already assigned to a sound", key));
+        }
+    }
+
+    let old_hotkey = state.read_settings().microphone_gate_hotkey.clone();
+    if old_hotkey != hotkey {
+        if let Some(key) = &hotkey {
+            let shortcut = key
+                .parse::<tauri_plugin_global_shortcut::Shortcut>()
+                .map_err(|e| format!("Failed to parse hotkey '{}': {}", key, e))?;
+            app_handle
+                .global_shortcut()
+                .register(shortcut)
+                .map_err(|e| format!("Failed to register hotkey: {}", e))?;
+        }
+        if let Some(old) = &old_hotkey {
+            if let Ok(shortcut) = old.parse::<tauri_plugin_global_shortcut::Shortcut>() {
+                let _ = app_handle.global_shortcut().unregister(shortcut);
+            }
+        }
+    }
+
+    info!("Microphone gate: {:?} (hotkey: {:?})", mode, hotkey);
+    set_gate_mode(mode);
+    state.update_settings(|settings| {
+        settings.microphone_gate_mode = mode;
+        settings.microphone_gate_hotkey = hotkey;
+    })
+}
+
+/// Get microphone routing buffer statistics
+///
+/// Returns underrun/overrun counters and current latency, or None if not active.
+#[tauri::command]
+pub fn get_microphone_routing_stats() -> Option<RoutingStats> {
+    get_routing_stats()
+}
+
 // ============================================================================
 // VB-Cable Uninstall Command
 // ============================================================================
@@ -0,0 +1,182 @@
+//! Audio device hot-plug handling
+//!
+//! A background thread polls the device lists through [`DeviceMonitor`]. When a
+//! configured device moves or comes back it updates the saved device IDs, reopens
+//! output buses and restarts microphone routing, then tells the frontend with an
+//! `audio-devices-changed` event.
+
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+use tracing::{debug, error, info, warn};
+
+use crate::audio::{DeviceChange, DeviceId, DeviceMonitor, DeviceProvider, DeviceRole};
+use crate::{vbcable, AppState, AudioManager};
+
+/// How often the device lists are enumerated
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Payload of the `audio-devices-changed` event
+#[derive(Debug, Clone, Serialize)]
+struct DevicesChangedEvent {
+    /// Changes to the configured devices (empty if only other devices changed)
+    changes: Vec<DeviceChange>,
+}
+
+/// Start the device monitor thread
+pub fn start(app: &tauri::AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let provider = app.state::<AudioManager>().backend();
+        let mut monitor = match provider.snapshot() {
+            Ok(snapshot) => DeviceMonitor::new(snapshot),
+            Err(e) => {
+                error!("Device monitor disabled: {}", e);
+                return;
+            }
+        };
+        info!("Device monitor started");
+
+        loop {
+            watch_configured_devices(&app, &mut monitor);
+
+            match monitor.poll(&provider) {
+                Ok(Some(changes)) => apply_changes(&app, &monitor, changes),
+                Ok(None) => {}
+                Err(e) => debug!("Device poll failed: {}", e),
+            }
+
+            // Streams that errored or whose device moved reopen on the next playback
+            let buses = app.state::<AudioManager>().get_buses();
+            buses.prune(&monitor.snapshot().outputs);
+
+            // Routing can also break without the device list changing
+            if vbcable::routing_failed() && !monitor.is_missing(DeviceRole::Microphone) {
+                warn!("Microphone routing failed, restarting");
+                restart_routing(&app, &monitor);
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Point the monitor at the devices currently saved in settings
+fn watch_configured_devices(app: &tauri::AppHandle, monitor: &mut DeviceMonitor) {
+    let state = app.state::<AppState>();
+    let settings = state.read_settings();
+    monitor.watch(DeviceRole::Monitor, settings.monitor_device_id.as_ref());
+    monitor.watch(DeviceRole::Broadcast, settings.broadcast_device_id.as_ref());
+    let microphone = settings
+        .microphone_routing_device_id
+        .as_deref()
+        .and_then(parse_device_id);
+    monitor.watch(DeviceRole::Microphone, microphone.as_ref());
+}
+
+/// Parse a microphone ID ("device_3") as stored in settings
+fn parse_device_id(id: &str) -> Option<DeviceId> {
+    id.strip_prefix("device_")
+        .and_then(|index| index.parse().ok())
+        .map(DeviceId::from_index)
+}
+
+fn apply_changes(app: &tauri::AppHandle, monitor: &DeviceMonitor, changes: Vec<DeviceChange>) {
+    let state = app.state::<AppState>();
+    let mut remapped = Vec::new();
+    let mut routing_affected = false;
+
+    for change in &changes {
+        match change {
+            DeviceChange::Lost { role, name, .. } => {
+                warn!("{:?} device disconnected: {}", role, name)
+            }
+            DeviceChange::Restored {
+                role,
+                name,
+                device_id,
+            } => info!("{:?} device reconnected: {} ({})", role, name, device_id),
+            DeviceChange::Moved { role, name, to, .. } => {
+                debug!("{:?} device {} is now {}", role, name, to)
+            }
+        }
+
+        if let Some(device_id) = change.current_id() {
+            remapped.push((change.role(), device_id.clone()));
+        }
+        routing_affected |= matches!(
+            change.role(),
+            DeviceRole::Microphone | DeviceRole::Broadcast
+        );
+    }
+
+    // Keep the saved IDs pointing at the same physical devices
+    if !remapped.is_empty() {
+        let saved = state.update_settings(|settings| {
+            for (role, device_id) in remapped {
+                match role {
+                    DeviceRole::Monitor => settings.monitor_device_id = Some(device_id),
+                    DeviceRole::Broadcast => settings.broadcast_device_id = Some(device_id),
+                    DeviceRole::Microphone => {
+                        settings.microphone_routing_device_id = Some(device_id.to_string())
+                    }
+                }
+            }
+        });
+        if let Err(e) = saved {
+            error!("Failed to save remapped devices: {}", e);
+        }
+    }
+
+    // The old streams are dead; stop routing and start over on the new IDs
+    let routing_enabled = state.read_settings().microphone_routing_enabled;
+    if routing_affected && routing_enabled {
+        restart_routing(app, monitor);
+    }
+
+    // Reopen output buses right away so the next sound plays without delay
+    let buses = app.state::<AudioManager>().get_buses();
+    buses.prune(&monitor.snapshot().outputs);
+    for change in &changes {
+        if let (DeviceRole::Monitor | DeviceRole::Broadcast, Some(device_id)) =
+            (change.role(), change.current_id())
+        {
+            if let Err(e) = buses.get_or_open(device_id) {
+                error!("Failed to reopen output bus {}: {}", device_id, e);
+            }
+        }
+    }
+
+    if let Err(e) = app.emit("audio-devices-changed", DevicesChangedEvent { changes }) {
+        error!("Failed to emit devices changed event: {}", e);
+    }
+}
+
+/// Stop microphone routing and start it again on the saved microphone, if present
+fn restart_routing(app: &tauri::AppHandle, monitor: &DeviceMonitor) {
+    if let Err(e) = vbcable::disable_routing() {
+        error!("Failed to stop microphone routing: {}", e);
+    }
+    if monitor.is_missing(DeviceRole::Microphone) {
+        info!("Microphone routing paused until the microphone returns");
+        return;
+    }
+
+    let state = app.state::<AppState>();
+    let settings = state.read_settings();
+    let (enabled, device_id) = (
+        settings.microphone_routing_enabled,
+        settings.microphone_routing_device_id.clone(),
+    );
+    drop(settings);
+
+    if let (true, Some(device_id)) = (enabled, device_id) {
+        let buses = app.state::<AudioManager>().get_buses();
+        match vbcable::enable_routing(&device_id, buses) {
+            Ok(()) => info!("Microphone routing restarted on {}", device_id),
+            Err(e) => error!("Failed to restart microphone routing: {}", e),
+        }
+    }
+}
@@ -2,41 +2,28 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use tauri::Manager;
+use std::path::Path;
 
+use crate::midi::MidiMappings;
 use crate::SoundId;
 
 /// Hotkey mappings: keyboard shortcut string -> sound ID
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HotkeyMappings {
     pub mappings: HashMap<String, SoundId>,
+    /// MIDI note/CC triggers
+    #[serde(default)]
+    pub midi: MidiMappings,
 }
 
-/// Get the path to the hotkeys file
-pub fn get_hotkeys_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_local_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    // Ensure directory exists
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-
-    Ok(app_data_dir.join("hotkeys.json"))
-}
-
-/// Load hotkey mappings from disk
-pub fn load(app_handle: &tauri::AppHandle) -> Result<HotkeyMappings, String> {
-    let hotkeys_path = get_hotkeys_path(app_handle)?;
-
+/// Load a hotkeys file (empty mappings if it doesn't exist)
+pub fn load_from_path(hotkeys_path: &Path) -> Result<HotkeyMappings, String> {
     if !hotkeys_path.exists() {
         // Return default empty mappings if file doesn't exist
         return Ok(HotkeyMappings::default());
     }
 
-    let content = std::fs::read_to_string(&hotkeys_path)
+    let content = std::fs::read_to_string(hotkeys_path)
         .map_err(|e| format!("Failed to read hotkeys file: {}", e))?;
 
     let mappings: HotkeyMappings =
@@ -45,14 +32,12 @@ pub fn load(app_handle: &tauri::AppHandle) -> Result<HotkeyMappings, String> {
     Ok(mappings)
 }
 
-/// Save hotkey mappings to disk (atomic write)
-pub fn save(mappings: &HotkeyMappings, app_handle: &tauri::AppHandle) -> Result<(), String> {
-    let hotkeys_path = get_hotkeys_path(app_handle)?;
-
+/// Save a hotkeys file (atomic write)
+pub fn save_to_path(mappings: &HotkeyMappings, hotkeys_path: &Path) -> Result<(), String> {
     let json = serde_json::to_string_pretty(mappings)
         .map_err(|e| format!("Failed to serialize hotkeys: {}", e))?;
 
-    crate::persistence::atomic_write(&hotkeys_path, &json)?;
+    crate::persistence::atomic_write(hotkeys_path, &json)?;
 
     tracing::debug!("Hotkey mappings saved to {:?}", hotkeys_path);
     Ok(())
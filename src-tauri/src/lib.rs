@@ -3,22 +3,41 @@
 //! Rust backend with dual-output audio routing (cpal-based implementation).
 
 mod audio;
+mod cli;
 mod commands;
+#[cfg(desktop)]
+mod devices;
 mod hotkeys;
+mod media;
+mod midi;
+mod obs;
 mod persistence;
+mod recorder;
+mod remote;
 mod settings;
 mod sounds;
 mod state;
+mod storage;
 mod tray;
+mod tts;
+mod twitch;
 mod vbcable;
+mod window;
 
 use tauri::Manager;
 use tracing::{error, info};
 
-pub use audio::{AudioDevice, AudioManager, CacheStats, DeviceId, WaveformData};
+pub use audio::{
+    AudioDevice, AudioManager, BusMeter, CacheStats, DeviceId, EffectConfig, WaveformData,
+};
+pub use cli::{
+    attach_parent_console, is_instance_running, parse_args, run_headless, send_to_running_instance,
+    CliArgs, CliCommand, USAGE,
+};
 pub use settings::AppSettings;
 pub use sounds::{Category, CategoryId, Sound, SoundId, SoundLibrary};
 pub use state::AppState;
+pub use storage::StorageRoot;
 // ============================================================================
 // GLOBAL SHORTCUT HANDLING
 // ============================================================================
@@ -82,6 +101,24 @@ fn handle_global_shortcut(
         event.state
     );
 
+    // Push-to-talk/push-to-mute key: track both press and release
+    {
+        let settings = app.state::<AppState>().read_settings();
+        let is_gate_key = settings
+            .microphone_gate_hotkey
+            .as_deref()
+            .and_then(|hotkey| {
+                hotkey
+                    .parse::<tauri_plugin_global_shortcut::Shortcut>()
+                    .ok()
+            })
+            .is_some_and(|gate| gate == *shortcut);
+        if is_gate_key {
+            vbcable::set_gate_key_held(event.state == ShortcutState::Pressed);
+            return;
+        }
+    }
+
     // Only handle pressed state
     if event.state != ShortcutState::Pressed {
         tracing::debug!("Ignoring non-pressed state: {:?}", event.state);
@@ -120,66 +157,9 @@ fn handle_global_shortcut(
     };
     drop(mappings); // Release read lock early
 
-    // Read sound library from in-memory state
-    let library = app_state.read_sounds();
-
-    // Find the sound
-    let sound = match library.sounds.iter().find(|s| s.id == sound_id) {
-        Some(s) => s.clone(),
-        None => {
-            tracing::warn!(
-                "Sound not found for hotkey: {} -> {:?}",
-                hotkey_str,
-                sound_id
-            );
-            return;
-        }
-    };
-    drop(library); // Release read lock early
-
-    // Read settings from in-memory state
-    let settings = app_state.read_settings();
-    let monitor_device = settings.monitor_device_id.clone();
-    let broadcast_device = settings.broadcast_device_id.clone();
-    let default_volume = settings.default_volume;
-    drop(settings); // Release read lock early
-
-    // Get device IDs
-    let device1 = match monitor_device {
-        Some(id) => id,
-        None => {
-            tracing::warn!("No monitor device configured");
-            return;
-        }
-    };
-
-    let device2 = match broadcast_device {
-        Some(id) => id,
-        None => {
-            tracing::warn!("No broadcast device configured");
-            return;
-        }
-    };
-
-    // Determine volume
-    let volume = sound.volume.unwrap_or(default_volume);
-
-    // Get audio manager from state
-    let manager = app.state::<AudioManager>();
-
-    // Trigger playback
-    match commands::play_dual_output(
-        sound.file_path.clone(),
-        device1,
-        device2,
-        volume,
-        sound.trim_start_ms,
-        sound.trim_end_ms,
-        Some(sound.id.as_str().to_owned()),
-        manager,
-        app.clone(),
-    ) {
-        Ok(result) => match result.action.as_str() {
+    // Trigger playback on the configured devices
+    match commands::play_library_sound(app, &sound_id, 1.0) {
+        Ok((sound, result)) => match result.action.as_str() {
             "ignored" => {
                 tracing::debug!(
                     "Hotkey '{}' ignored - sound '{}' already playing",
@@ -216,7 +196,7 @@ fn handle_global_shortcut(
 fn register_saved_hotkeys(app: &tauri::AppHandle) -> Result<(), String> {
     use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-    let mappings = hotkeys::load(app)?;
+    let mappings = app.state::<AppState>().read_hotkeys().clone();
 
     for (hotkey, sound_id) in &mappings.mappings {
         if let Ok(shortcut) = hotkey.parse::<tauri_plugin_global_shortcut::Shortcut>() {
@@ -236,14 +216,36 @@ fn register_saved_hotkeys(app: &tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Register the saved microphone push-to-talk hotkey on app startup
+#[cfg(desktop)]
+fn register_mic_gate_hotkey(app: &tauri::AppHandle) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let settings = app.state::<AppState>().read_settings();
+    let Some(hotkey) = settings.microphone_gate_hotkey.clone() else {
+        return Ok(());
+    };
+    drop(settings);
+
+    let shortcut = hotkey
+        .parse::<tauri_plugin_global_shortcut::Shortcut>()
+        .map_err(|e| format!("Failed to parse push-to-talk hotkey '{}': {}", hotkey, e))?;
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("Failed to register push-to-talk hotkey: {}", e))?;
+    tracing::info!("Registered push-to-talk hotkey: {}", hotkey);
+    Ok(())
+}
+
 /// Clean up orphaned hotkeys (hotkeys for sounds that no longer exist)
 #[cfg(desktop)]
 fn cleanup_orphaned_hotkeys(app: &tauri::AppHandle) -> Result<(), String> {
     use std::collections::HashSet;
     use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-    let mut mappings = hotkeys::load(app)?;
-    let library = sounds::load(app)?;
+    let state = app.state::<AppState>();
+    let mut mappings = state.read_hotkeys().clone();
+    let library = state.read_sounds().clone();
 
     // Create set of valid sound IDs
     let valid_ids: HashSet<_> = library.sounds.iter().map(|s| &s.id).collect();
@@ -260,6 +262,7 @@ fn cleanup_orphaned_hotkeys(app: &tauri::AppHandle) -> Result<(), String> {
     }
 
     // Remove orphaned hotkeys
+    let changed = !orphaned.is_empty();
     for hotkey in orphaned {
         hotkeys::remove_mapping(&mut mappings, &hotkey)?;
         if let Ok(shortcut) = hotkey.parse::<tauri_plugin_global_shortcut::Shortcut>() {
@@ -268,8 +271,8 @@ fn cleanup_orphaned_hotkeys(app: &tauri::AppHandle) -> Result<(), String> {
     }
 
     // Save cleaned mappings
-    if !mappings.mappings.is_empty() {
-        hotkeys::save(&mappings, app)?;
+    if changed {
+        state.update_and_save_hotkeys(app, mappings)?;
     }
 
     Ok(())
@@ -281,6 +284,18 @@ fn cleanup_orphaned_hotkeys(app: &tauri::AppHandle) -> Result<(), String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    run_app(false);
+}
+
+/// Run without the window: hotkeys, mic routing, the tray and IPC only
+///
+/// The window can still be opened from the tray (or `sonic-deck` again);
+/// closing it frees the webview but keeps the app running.
+pub fn run_daemon() {
+    run_app(true);
+}
+
+fn run_app(daemon: bool) {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
@@ -291,7 +306,9 @@ pub fn run() {
             commands::stop_all_audio,
             commands::stop_playback,
             commands::clear_audio_cache,
+            commands::export_sound,
             commands::get_cache_stats,
+            commands::get_limiter_meters,
             commands::preload_sounds,
             commands::get_logs_path,
             commands::read_logs,
@@ -312,6 +329,11 @@ pub fn run() {
             commands::add_sound,
             commands::update_sound,
             commands::toggle_favorite,
+            commands::set_sound_effects,
+            commands::apply_sound_edits,
+            commands::revert_sound_edits,
+            commands::get_media_storage_usage,
+            commands::collect_unused_media,
             commands::delete_sound,
             commands::add_category,
             commands::update_category,
@@ -332,6 +354,8 @@ pub fn run() {
             commands::enable_microphone_routing,
             commands::disable_microphone_routing,
             commands::get_microphone_routing_status,
+            commands::get_microphone_routing_stats,
+            commands::set_microphone_gate,
             // VB-Cable uninstall command
             commands::start_vb_cable_uninstall,
             // Sound settings command
@@ -340,17 +364,88 @@ pub fn run() {
             commands::activate_vbcable_comm_mode,
             commands::deactivate_vbcable_comm_mode,
             commands::is_vbcable_comm_mode_active,
+            // Remote-control API commands
+            commands::get_remote_api_status,
+            commands::regenerate_remote_api_token,
+            // MIDI trigger commands
+            commands::list_midi_inputs,
+            commands::get_midi_status,
+            commands::start_midi_learn,
+            commands::cancel_midi_learn,
+            commands::remove_midi_binding,
+            // OBS integration commands
+            commands::get_obs_status,
+            // Twitch chat commands
+            commands::get_twitch_status,
+            commands::set_twitch_paused,
+            // Text-to-speech commands
+            commands::list_tts_voices,
+            commands::add_tts_sound,
+            commands::update_tts_sound,
+            // Recorder commands
+            commands::list_recording_sources,
+            commands::start_recording,
+            commands::get_recorder_status,
+            commands::stop_recording,
+            commands::save_recording,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             // Initialize app state (load all data from disk once at startup)
-            let app_state = AppState::load(app.handle())?;
+            let app_state = AppState::load(StorageRoot::from_app(app.handle())?)?;
 
-            // Initialize audio manager
+            // Initialize audio manager with the saved limiter ceiling
             let audio_manager = AudioManager::new();
+            let settings = app_state.read_settings();
+            audio_manager.set_limiter_ceiling_db(settings.limiter_ceiling_db);
+            audio_manager.set_ducking_config(settings.ducking.clone());
+            vbcable::set_routing_gains(
+                settings.microphone_routing_mic_gain_db,
+                settings.microphone_routing_board_gain_db,
+            );
+            vbcable::set_mic_processing(settings.microphone_processing.clone());
+            vbcable::set_gate_mode(settings.microphone_gate_mode);
+            commands::apply_decode_cache_settings(&settings, &audio_manager, app_state.storage());
+            drop(settings);
 
             // Register state managers
+            let remote_api = app_state.read_settings().remote_api.clone();
+            let midi_port = app_state.read_settings().midi_input_port.clone();
+            let obs_config = app_state.read_settings().obs.clone();
+            let twitch_config = app_state.read_settings().twitch.clone();
+            let recorder = recorder::Recorder::new(audio_manager.backend());
             app.manage(app_state);
             app.manage(audio_manager);
+            app.manage(recorder);
+            app.manage(remote::RemoteState::default());
+            app.manage(midi::MidiState::default());
+            app.manage(obs::ObsState::default());
+            app.manage(twitch::TwitchState::default());
+
+            // Remote-control API (opt-in)
+            remote::forward_events(app.handle());
+            remote::apply_settings(app.handle(), &remote_api);
+
+            // MIDI triggers from the selected input
+            midi::apply_settings(app.handle(), midi_port.as_deref());
+
+            // OBS scene switching, overlays and scene triggers
+            obs::forward_sound_events(app.handle());
+            obs::apply_settings(app.handle(), &obs_config);
+
+            // Twitch chat commands and channel-point redemptions
+            twitch::forward_sound_events(app.handle());
+            twitch::apply_settings(app.handle(), &twitch_config);
+
+            // Commands from the command line and later launches
+            cli::start_ipc_server(app.handle());
+
+            // Pin hotkey-bound sounds and warm them (then favorites) in the background
+            {
+                let state = app.state::<AppState>();
+                let manager = app.state::<AudioManager>();
+                manager.set_pinned_files(state.hotkey_bound_files());
+                manager.preload(state.startup_preload_files());
+            }
 
             #[cfg(desktop)]
             {
@@ -404,25 +499,18 @@ pub fn run() {
                 if let Err(e) = register_saved_hotkeys(app.handle()) {
                     error!("Failed to register saved hotkeys: {}", e);
                 }
+                if let Err(e) = register_mic_gate_hotkey(app.handle()) {
+                    error!("{}", e);
+                }
+
+                // Watch for unplugged and returning audio devices
+                devices::start(app.handle());
 
                 // Initialize system tray
                 if let Err(e) = tray::init(app.handle()) {
                     error!("Failed to initialize system tray: {}", e);
                 }
 
-                // Optionally start minimized (read from in-memory state)
-                let state = app.state::<AppState>();
-                let settings = state.read_settings();
-                let start_minimized = settings.start_minimized;
-                drop(settings);
-
-                if start_minimized {
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.hide();
-                        info!("Started minimized to tray");
-                    }
-                }
-
                 // Auto-enable microphone routing if it was enabled in settings
                 let state = app.state::<AppState>();
                 let settings = state.read_settings();
@@ -433,7 +521,8 @@ pub fn run() {
                 if mic_routing_enabled {
                     if let Some(device_id) = mic_device_id {
                         info!("Auto-enabling microphone routing for device: {}", device_id);
-                        if let Err(e) = vbcable::enable_routing(&device_id) {
+                        let buses = app.state::<AudioManager>().get_buses();
+                        if let Err(e) = vbcable::enable_routing(&device_id, buses) {
                             error!("Failed to auto-enable microphone routing: {}", e);
                         }
 
@@ -446,9 +535,20 @@ pub fn run() {
                 }
             }
 
+            // Create the window unless running as a daemon, hidden if starting minimized
+            if daemon {
+                info!("Daemon mode: running without the window");
+            } else {
+                let start_minimized = app.state::<AppState>().read_settings().start_minimized;
+                window::create_main_window(app.handle(), !start_minimized)?;
+                if start_minimized {
+                    info!("Started minimized to tray");
+                }
+            }
+
             Ok(())
         })
-        .on_window_event(|window, event| {
+        .on_window_event(move |window, event| {
             // Handle window close button based on minimize_to_tray setting
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let app = window.app_handle();
@@ -457,7 +557,8 @@ pub fn run() {
                 let minimize_to_tray = settings.minimize_to_tray;
                 drop(settings);
 
-                if minimize_to_tray {
+                // A daemon frees the webview on close; the app keeps running
+                if minimize_to_tray && !daemon {
                     // Minimize to tray instead of closing
                     api.prevent_close();
                     let _ = window.hide();
@@ -470,7 +571,17 @@ pub fn run() {
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app, event| {
+        .run(move |_app, event| {
+            // Closing the last window doesn't quit a daemon (Quit from the tray does)
+            if let tauri::RunEvent::ExitRequested {
+                code: None, api, ..
+            } = &event
+            {
+                if daemon {
+                    api.prevent_exit();
+                }
+            }
+
             if let tauri::RunEvent::Exit = event {
                 // Deactivate VB-Cable communications mode on exit
                 info!("App exiting - deactivating VB-Cable communications mode");
@@ -81,17 +81,64 @@ fn setup_logging(debug_mode: bool) -> tracing_appender::non_blocking::WorkerGuar
 }
 
 fn main() {
-    // Parse CLI arguments for --debug flag
-    let args: Vec<String> = std::env::args().collect();
-    let debug_mode = args.iter().any(|arg| arg == "--debug");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match sonic_deck::parse_args(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            sonic_deck::attach_parent_console();
+            eprintln!("{}\n\n{}", e, sonic_deck::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if cli.help {
+        sonic_deck::attach_parent_console();
+        println!("{}", sonic_deck::USAGE);
+        return;
+    }
+
+    // Commands go to the running instance, or run on their own
+    if let Some(command) = cli.command {
+        sonic_deck::attach_parent_console();
+        let result = sonic_deck::send_to_running_instance(&command).unwrap_or_else(|| {
+            let _log_guard = setup_logging(cli.debug);
+            sonic_deck::run_headless(command)
+        });
+        match result {
+            Ok(output) => {
+                if !output.is_empty() {
+                    println!("{}", output);
+                }
+                return;
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Single instance: launching again shows the running window
+    if cli.daemon {
+        if sonic_deck::is_instance_running() {
+            sonic_deck::attach_parent_console();
+            eprintln!("SonicDeck is already running");
+            std::process::exit(1);
+        }
+    } else if sonic_deck::send_to_running_instance(&sonic_deck::CliCommand::Show).is_some() {
+        return;
+    }
 
     // Keep guard alive for the entire application lifetime
     // This ensures logs are flushed when the app exits
-    let _log_guard = setup_logging(debug_mode);
+    let _log_guard = setup_logging(cli.debug);
 
-    if debug_mode {
+    if cli.debug {
         tracing::info!("Debug mode enabled via --debug flag");
     }
 
-    sonic_deck::run();
+    if cli.daemon {
+        sonic_deck::run_daemon();
+    } else {
+        sonic_deck::run();
+    }
 }
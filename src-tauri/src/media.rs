@@ -0,0 +1,575 @@
+//! Managed, content-addressed media folder
+//!
+//! Files in `media/` in the app data directory are named after the SHA-256 of
+//! their contents (`<hash>.<ext>`), so identical files are stored once. These kinds
+//! of files end up here:
+//!
+//! - Imported sounds, when `copy_sounds_to_library` is enabled: the user's file is
+//!   copied in so moving or deleting the original doesn't break the board.
+//! - Processed copies written by applying edits (trim baked in, optionally
+//!   normalized and resampled). The sound's previous file and trim are kept on the
+//!   sound so the edits can be reverted.
+//! - Text-to-speech renders (see `tts`). These are the exception to content
+//!   addressing: the hash is of the engine, voice, rate and text, so the same
+//!   speech is found again without synthesising it.
+//! - Recordings saved from the recorder, as WAV.
+//!
+//! Files may be shared between sounds, so nothing is deleted directly: after the
+//! library changes, [`collect_garbage`] removes the files no sound refers to. A
+//! new file is unreferenced until its sound is saved, so storing one holds
+//! [`protect_new_files`] until then.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use crate::audio::{self, AudioData, EditOptions, ExportFormat};
+use crate::sounds::{OriginalSource, Sound, SoundLibrary};
+
+/// Name of the managed media folder inside the app data directory
+const MEDIA_DIR_NAME: &str = "media";
+
+/// Length of a hex-encoded SHA-256 digest
+const HASH_LEN: usize = 64;
+
+/// Numbers temp files so concurrent stores never share one
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Shared while new files wait for their sound to be saved, exclusive while
+/// garbage is collected
+static NEW_FILES: RwLock<()> = RwLock::new(());
+
+/// Keeps garbage collection waiting while held
+pub struct NewFileGuard {
+    _guard: RwLockReadGuard<'static, ()>,
+}
+
+/// Exclusive hold on the media folder for [`collect_garbage`]
+pub struct CollectionLock {
+    _guard: RwLockWriteGuard<'static, ()>,
+}
+
+/// Protect files stored from now on until the guard is dropped
+///
+/// Hold it from storing a file until the library referring to it is saved.
+pub fn protect_new_files() -> NewFileGuard {
+    NewFileGuard {
+        _guard: NEW_FILES.read().unwrap_or_else(PoisonError::into_inner),
+    }
+}
+
+/// Wait until no new file is waiting for its sound, then keep new ones out
+///
+/// Read the library to collect against only after this returns.
+pub fn lock_for_collection() -> CollectionLock {
+    CollectionLock {
+        _guard: NEW_FILES.write().unwrap_or_else(PoisonError::into_inner),
+    }
+}
+
+/// Disk usage of the media folder
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MediaUsage {
+    /// Files in the media folder
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Files no sound refers to (freed by garbage collection)
+    pub unreferenced_count: usize,
+    pub unreferenced_bytes: u64,
+}
+
+/// Get the media folder of an app data directory, creating it if needed
+pub fn media_dir_in(app_data_dir: &Path) -> Result<PathBuf, String> {
+    let media_dir = app_data_dir.join(MEDIA_DIR_NAME);
+
+    fs::create_dir_all(&media_dir)
+        .map_err(|e| format!("Failed to create media directory: {}", e))?;
+
+    Ok(media_dir)
+}
+
+/// Whether a file lives in the managed media folder
+pub fn is_managed(path: &Path, media_dir: &Path) -> bool {
+    path.parent() == Some(media_dir)
+}
+
+/// Copy a file into the media folder, returning the stored path
+///
+/// Files already in the media folder are returned as-is, and a file whose
+/// contents are already stored is not copied again.
+pub fn import_file(source: &Path, media_dir: &Path) -> Result<PathBuf, String> {
+    if is_managed(source, media_dir) {
+        return Ok(source.to_path_buf());
+    }
+
+    let hash =
+        hash_file(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let target = media_dir.join(stored_name(&hash, source));
+    if target.exists() {
+        debug!(source = %source.display(), target = %target.display(), "Media already stored");
+        return Ok(target);
+    }
+
+    // Copy under a temporary name so a half-written file never has a hash name
+    let temp = temp_path(media_dir, "import");
+    fs::copy(source, &temp).map_err(|e| format!("Failed to copy sound into library: {}", e))?;
+    fs::rename(&temp, &target).map_err(|e| format!("Failed to store sound: {}", e))?;
+
+    info!(source = %source.display(), target = %target.display(), "Sound copied into library");
+    Ok(target)
+}
+
+/// Write a processed copy of a sound and point the sound at it
+///
+/// `audio_data` is the decoded audio of the sound's current file. The sound's trim
+/// is baked in and cleared. Re-editing an edited sound keeps the first original.
+pub fn apply_edits(
+    sound: &mut Sound,
+    audio_data: &AudioData,
+    normalize_peak_db: Option<f32>,
+    sample_rate: Option<u32>,
+    media_dir: &Path,
+) -> Result<(), String> {
+    let options = EditOptions {
+        trim_start_ms: sound.trim_start_ms,
+        trim_end_ms: sound.trim_end_ms,
+        normalize_peak_db,
+        sample_rate,
+    };
+    let edited = audio::apply_edits(audio_data, &options);
+
+    let temp = temp_path(media_dir, "edit");
+    audio::write_audio(&edited, &temp, ExportFormat::Wav).map_err(|e| e.to_string())?;
+    let stored = store_temp(&temp, ExportFormat::Wav.extension(), media_dir)?;
+
+    if sound.original.is_none() {
+        sound.original = Some(OriginalSource {
+            file_path: sound.file_path.clone(),
+            trim_start_ms: sound.trim_start_ms,
+            trim_end_ms: sound.trim_end_ms,
+        });
+    }
+    sound.file_path = stored.to_string_lossy().into_owned();
+    sound.trim_start_ms = None;
+    sound.trim_end_ms = None;
+
+    info!(
+        sound_id = %sound.id.as_str(),
+        output = %sound.file_path,
+        normalize_peak_db = ?normalize_peak_db,
+        sample_rate = edited.sample_rate,
+        "Edits applied to sound"
+    );
+    Ok(())
+}
+
+/// Write recorded audio into the media folder as WAV
+pub fn store_recording(audio_data: &AudioData, media_dir: &Path) -> Result<PathBuf, String> {
+    let temp = temp_path(media_dir, "recording");
+    audio::write_audio(audio_data, &temp, ExportFormat::Wav).map_err(|e| e.to_string())?;
+    let stored = store_temp(&temp, ExportFormat::Wav.extension(), media_dir)?;
+
+    info!(
+        output = %stored.display(),
+        seconds = audio_data.samples.len() as f64
+            / audio_data.channels.max(1) as f64
+            / audio_data.sample_rate as f64,
+        "Recording stored"
+    );
+    Ok(stored)
+}
+
+/// Point an edited sound back at its original file and trim
+pub fn revert_edits(sound: &mut Sound) -> Result<(), String> {
+    let original = sound
+        .original
+        .take()
+        .ok_or_else(|| format!("Sound has no edits to revert: {}", sound.id.as_str()))?;
+
+    sound.file_path = original.file_path;
+    sound.trim_start_ms = original.trim_start_ms;
+    sound.trim_end_ms = original.trim_end_ms;
+
+    info!(sound_id = %sound.id.as_str(), "Edits reverted");
+    Ok(())
+}
+
+/// Delete stored files no sound in the library refers to
+///
+/// Only content-addressed files are considered, so temporary files and anything
+/// else placed in the folder are left alone. Returns the removed paths.
+pub fn collect_garbage(
+    library: &SoundLibrary,
+    media_dir: &Path,
+    _lock: &CollectionLock,
+) -> Result<Vec<PathBuf>, String> {
+    let referenced = referenced_files(library);
+    let mut removed = Vec::new();
+    let mut freed_bytes = 0;
+
+    for (path, size) in stored_files(media_dir)? {
+        if referenced.contains(&path) {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                freed_bytes += size;
+                removed.push(path);
+            }
+            Err(e) => warn!(path = %path.display(), error = %e, "Failed to remove unused media"),
+        }
+    }
+
+    if !removed.is_empty() {
+        info!(
+            files = removed.len(),
+            freed_bytes = freed_bytes,
+            "Unused media removed"
+        );
+    }
+    Ok(removed)
+}
+
+/// Disk usage of the media folder
+pub fn storage_usage(library: &SoundLibrary, media_dir: &Path) -> Result<MediaUsage, String> {
+    let referenced = referenced_files(library);
+    let mut usage = MediaUsage::default();
+
+    for (path, size) in stored_files(media_dir)? {
+        usage.file_count += 1;
+        usage.total_bytes += size;
+        if !referenced.contains(&path) {
+            usage.unreferenced_count += 1;
+            usage.unreferenced_bytes += size;
+        }
+    }
+    Ok(usage)
+}
+
+/// Files the library points at, including the originals of edited sounds
+fn referenced_files(library: &SoundLibrary) -> HashSet<PathBuf> {
+    library
+        .sounds
+        .iter()
+        .flat_map(|sound| {
+            std::iter::once(&sound.file_path)
+                .chain(sound.original.as_ref().map(|original| &original.file_path))
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Content-addressed files in the media folder with their sizes
+fn stored_files(media_dir: &Path) -> Result<Vec<(PathBuf, u64)>, String> {
+    let entries = match fs::read_dir(media_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read media directory: {}", e)),
+    };
+
+    Ok(entries
+        .filter_map(Result::ok)
+        .filter(|entry| is_content_addressed(&entry.path()))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| (entry.path(), metadata.len()))
+        })
+        .collect())
+}
+
+/// Unique temporary file in the media folder (never content-addressed)
+fn temp_path(media_dir: &Path, kind: &str) -> PathBuf {
+    media_dir.join(format!(
+        "{}-{}-{}.tmp",
+        kind,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Move a finished temporary file to its content-addressed name
+fn store_temp(temp: &Path, extension: &str, media_dir: &Path) -> Result<PathBuf, String> {
+    let hash = hash_file(temp).map_err(|e| format!("Failed to read processed copy: {}", e))?;
+    let target = media_dir.join(format!("{}.{}", hash, extension));
+    if target.exists() {
+        let _ = fs::remove_file(temp);
+    } else {
+        fs::rename(temp, &target).map_err(|e| format!("Failed to store processed copy: {}", e))?;
+    }
+    Ok(target)
+}
+
+/// `<hash>.<ext>`, keeping the source's extension as a decoder hint
+fn stored_name(hash: &str, source: &Path) -> String {
+    match source.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}.{}", hash, extension.to_ascii_lowercase()),
+        None => hash.to_string(),
+    }
+}
+
+fn is_content_addressed(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.len() == HASH_LEN && stem.bytes().all(|b| b.is_ascii_hexdigit()))
+        && path.extension() != Some(OsStr::new("tmp"))
+}
+
+/// Hex-encoded SHA-256 of a file's contents
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decode_audio_file;
+    use crate::sounds::{self, CategoryId};
+
+    fn tone(seconds: usize) -> AudioData {
+        AudioData {
+            samples: (0..48000 * seconds)
+                .map(|i| 0.25 * (i as f32 * 0.05).sin())
+                .collect(),
+            sample_rate: 48000,
+            channels: 1,
+        }
+    }
+
+    fn add(library: &mut SoundLibrary, file_path: &Path) -> Sound {
+        sounds::add_sound(
+            library,
+            "Clip".to_string(),
+            file_path.to_string_lossy().into_owned(),
+            CategoryId::from_string("default".to_string()),
+            None,
+            None,
+        )
+    }
+
+    fn trimmed_sound(library: &mut SoundLibrary) -> Sound {
+        let mut sound = add(library, Path::new("/music/clip.mp3"));
+        sound.trim_start_ms = Some(500);
+        sound.trim_end_ms = Some(1500);
+        sound
+    }
+
+    #[test]
+    fn test_import_copies_and_deduplicates() {
+        let media = tempfile::tempdir().unwrap();
+        let downloads = tempfile::tempdir().unwrap();
+        let first = downloads.path().join("Airhorn.MP3");
+        let second = downloads.path().join("airhorn (1).mp3");
+        let other = downloads.path().join("other.wav");
+        fs::write(&first, b"same bytes").unwrap();
+        fs::write(&second, b"same bytes").unwrap();
+        fs::write(&other, b"other bytes").unwrap();
+
+        let stored = import_file(&first, media.path()).unwrap();
+        assert!(is_managed(&stored, media.path()));
+        assert!(stored.to_string_lossy().ends_with(".mp3"));
+        assert_eq!(import_file(&second, media.path()).unwrap(), stored);
+        assert_ne!(import_file(&other, media.path()).unwrap(), stored);
+        // Importing a stored file is a no-op
+        assert_eq!(import_file(&stored, media.path()).unwrap(), stored);
+
+        // The copy survives the original being deleted
+        fs::remove_file(&first).unwrap();
+        assert_eq!(fs::read(&stored).unwrap(), b"same bytes");
+        assert_eq!(stored_files(media.path()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_imports_of_one_file() {
+        let media = tempfile::tempdir().unwrap();
+        let downloads = tempfile::tempdir().unwrap();
+        let source = downloads.path().join("big.wav");
+        fs::write(&source, vec![7u8; 1 << 20]).unwrap();
+
+        let stored: Vec<PathBuf> = std::thread::scope(|scope| {
+            let imports: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| import_file(&source, media.path()).unwrap()))
+                .collect();
+            imports.into_iter().map(|i| i.join().unwrap()).collect()
+        });
+        assert!(stored.iter().all(|path| *path == stored[0]));
+        assert_eq!(fs::read(&stored[0]).unwrap().len(), 1 << 20);
+        // No temp file is left behind
+        assert_eq!(fs::read_dir(media.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_import_missing_file_fails() {
+        let media = tempfile::tempdir().unwrap();
+        assert!(import_file(Path::new("/nonexistent/sound.mp3"), media.path()).is_err());
+    }
+
+    #[test]
+    fn test_apply_writes_copy_and_keeps_original() {
+        let media = tempfile::tempdir().unwrap();
+        let mut sound = trimmed_sound(&mut SoundLibrary::default());
+
+        apply_edits(&mut sound, &tone(2), Some(-1.0), None, media.path()).unwrap();
+        assert!(is_managed(Path::new(&sound.file_path), media.path()));
+        assert!(is_content_addressed(Path::new(&sound.file_path)));
+        assert_eq!((sound.trim_start_ms, sound.trim_end_ms), (None, None));
+        assert_eq!(
+            sound.original,
+            Some(OriginalSource {
+                file_path: "/music/clip.mp3".to_string(),
+                trim_start_ms: Some(500),
+                trim_end_ms: Some(1500),
+            })
+        );
+
+        let written = decode_audio_file(&sound.file_path).unwrap();
+        assert_eq!(written.samples.len(), 48000);
+        let peak = written.samples.iter().fold(0.0f32, |p, s| p.max(s.abs()));
+        assert!((peak - audio::db_to_linear(-1.0)).abs() < 1e-3);
+        // No temporary file is left behind
+        assert_eq!(fs::read_dir(media.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_reapply_keeps_first_original() {
+        let media = tempfile::tempdir().unwrap();
+        let mut sound = trimmed_sound(&mut SoundLibrary::default());
+        apply_edits(&mut sound, &tone(2), None, None, media.path()).unwrap();
+        let first_copy = sound.file_path.clone();
+
+        let edited = decode_audio_file(&first_copy).unwrap();
+        apply_edits(&mut sound, &edited, None, Some(44100), media.path()).unwrap();
+        assert_ne!(sound.file_path, first_copy);
+        assert_eq!(
+            sound.original.as_ref().unwrap().file_path,
+            "/music/clip.mp3"
+        );
+        assert_eq!(
+            decode_audio_file(&sound.file_path).unwrap().sample_rate,
+            44100
+        );
+    }
+
+    #[test]
+    fn test_revert_restores_original() {
+        let media = tempfile::tempdir().unwrap();
+        let mut sound = trimmed_sound(&mut SoundLibrary::default());
+        assert!(revert_edits(&mut sound).is_err());
+
+        apply_edits(&mut sound, &tone(2), None, None, media.path()).unwrap();
+        revert_edits(&mut sound).unwrap();
+        assert_eq!(sound.file_path, "/music/clip.mp3");
+        assert_eq!(
+            (sound.trim_start_ms, sound.trim_end_ms),
+            (Some(500), Some(1500))
+        );
+        assert!(sound.original.is_none());
+    }
+
+    #[test]
+    fn test_store_recording_writes_wav() {
+        let media = tempfile::tempdir().unwrap();
+
+        let stored = store_recording(&tone(1), media.path()).unwrap();
+        assert!(is_content_addressed(&stored));
+        assert_eq!(stored.extension(), Some(OsStr::new("wav")));
+        assert_eq!(
+            decode_audio_file(&stored.to_string_lossy())
+                .unwrap()
+                .samples
+                .len(),
+            48000
+        );
+
+        // The same audio is stored once, and nothing temporary is left behind
+        assert_eq!(store_recording(&tone(1), media.path()).unwrap(), stored);
+        assert_eq!(fs::read_dir(media.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_garbage_collection_keeps_referenced_files() {
+        let media = tempfile::tempdir().unwrap();
+        let downloads = tempfile::tempdir().unwrap();
+        let source = downloads.path().join("clip.wav");
+        audio::write_audio(&tone(2), &source, ExportFormat::Wav).unwrap();
+
+        // An imported sound, edited: both the import and the processed copy are in use
+        let mut library = SoundLibrary::default();
+        let imported = import_file(&source, media.path()).unwrap();
+        let mut sound = add(&mut library, &imported);
+        sound.trim_end_ms = Some(1000);
+        apply_edits(&mut sound, &tone(2), None, None, media.path()).unwrap();
+        library.sounds[0] = sound.clone();
+
+        // Unreferenced: an orphaned copy; never touched: other files in the folder
+        let orphan = media.path().join(format!("{}.wav", "ab".repeat(32)));
+        fs::write(&orphan, b"orphan").unwrap();
+        fs::write(media.path().join("notes.txt"), b"keep").unwrap();
+        fs::write(
+            media.path().join(format!("{}.tmp", "cd".repeat(32))),
+            b"in flight",
+        )
+        .unwrap();
+
+        let usage = storage_usage(&library, media.path()).unwrap();
+        assert_eq!((usage.file_count, usage.unreferenced_count), (3, 1));
+        assert_eq!(usage.unreferenced_bytes, 6);
+
+        let lock = lock_for_collection();
+        assert_eq!(
+            collect_garbage(&library, media.path(), &lock).unwrap(),
+            vec![orphan]
+        );
+        assert!(imported.exists());
+        assert!(Path::new(&sound.file_path).exists());
+        assert_eq!(fs::read_dir(media.path()).unwrap().count(), 4);
+
+        // Once the sound is gone, so are its files
+        library.sounds.clear();
+        assert_eq!(
+            collect_garbage(&library, media.path(), &lock)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            storage_usage(&library, media.path()).unwrap(),
+            MediaUsage::default()
+        );
+    }
+
+    #[test]
+    fn test_collection_waits_for_protected_files() {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+
+        let pending = protect_new_files();
+        let collected = Arc::new(AtomicBool::new(false));
+        let collector = {
+            let collected = collected.clone();
+            std::thread::spawn(move || {
+                let _lock = lock_for_collection();
+                collected.store(true, Ordering::SeqCst);
+            })
+        };
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(!collected.load(Ordering::SeqCst));
+        drop(pending);
+        collector.join().unwrap();
+        assert!(collected.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,115 @@
+//! MIDI wiring into the running app
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+use tracing::{error, info, warn};
+
+use super::bindings::{MidiAction, MidiRouter};
+use super::input::MidiConnection;
+use super::message::MidiMessage;
+use crate::commands;
+use crate::sounds::SoundId;
+use crate::AppState;
+
+/// MIDI input of the app (managed Tauri state)
+#[derive(Default)]
+pub struct MidiState {
+    connection: Mutex<Option<MidiConnection>>,
+    /// Error of the last connection attempt
+    last_error: Mutex<Option<String>>,
+    router: Mutex<MidiRouter>,
+}
+
+/// MIDI input state for the settings UI
+#[derive(Debug, Clone, Serialize)]
+pub struct MidiStatus {
+    /// Connected port name
+    pub connected: Option<String>,
+    pub error: Option<String>,
+}
+
+impl MidiState {
+    pub fn status(&self) -> MidiStatus {
+        MidiStatus {
+            connected: self
+                .connection
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|connection| connection.port_name().to_string()),
+            error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+
+    /// Bind the next pad or button pressed to this sound
+    pub fn start_learn(&self, sound_id: SoundId) -> Result<(), String> {
+        if self.connection.lock().unwrap().is_none() {
+            return Err("No MIDI input connected".to_string());
+        }
+        self.router.lock().unwrap().start_learn(sound_id);
+        Ok(())
+    }
+
+    /// Leave learn mode without binding
+    pub fn cancel_learn(&self) {
+        self.router.lock().unwrap().cancel_learn();
+    }
+}
+
+/// Connect to the configured input port (None disconnects)
+pub fn apply_settings(app: &tauri::AppHandle, port_name: Option<&str>) {
+    let midi = app.state::<MidiState>();
+    let mut connection = midi.connection.lock().unwrap();
+    // Close first: ports can't be opened twice on Windows
+    *connection = None;
+    *midi.last_error.lock().unwrap() = None;
+    let Some(port_name) = port_name else {
+        return;
+    };
+
+    let handle = app.clone();
+    match MidiConnection::open(port_name, move |message| handle_message(&handle, message)) {
+        Ok(opened) => *connection = Some(opened),
+        Err(e) => {
+            error!("Failed to connect MIDI input: {}", e);
+            *midi.last_error.lock().unwrap() = Some(e);
+        }
+    }
+}
+
+fn handle_message(app: &tauri::AppHandle, message: MidiMessage) {
+    let state = app.state::<AppState>();
+    let action = {
+        let hotkeys = state.read_hotkeys();
+        app.state::<MidiState>()
+            .router
+            .lock()
+            .unwrap()
+            .handle(message, &hotkeys.midi)
+    };
+
+    match action {
+        Some(MidiAction::Trigger { sound_id, gain }) => {
+            if let Err(e) = commands::play_library_sound(app, &sound_id, gain) {
+                warn!("MIDI trigger failed: {}", e);
+            }
+        }
+        Some(MidiAction::Learned(binding)) => {
+            let saved = state.update_hotkeys(|mappings| {
+                mappings.midi.bind(binding.clone());
+                Ok(())
+            });
+            match saved {
+                Ok(()) => {
+                    state.hotkeys_changed(app);
+                    info!("Learned MIDI binding: {:?}", binding);
+                    let _ = app.emit("midi-learned", &binding);
+                }
+                Err(e) => error!("Failed to save MIDI binding: {}", e),
+            }
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,296 @@
+//! MIDI trigger bindings and dispatch
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::message::MidiMessage;
+use crate::sounds::SoundId;
+
+/// Control changes at or above this value count as pressed
+const CONTROL_PRESSED: u8 = 64;
+
+/// Input that triggers a sound (channels are 0-15)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MidiTrigger {
+    /// Note-on (pads, keys)
+    Note { channel: u8, note: u8 },
+    /// Control change going from released to pressed (buttons, sustain pedal)
+    Control { channel: u8, controller: u8 },
+}
+
+/// MIDI trigger bound to a sound
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MidiBinding {
+    pub trigger: MidiTrigger,
+    pub sound_id: SoundId,
+}
+
+/// MIDI bindings (stored with the hotkey mappings)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MidiMappings {
+    pub bindings: Vec<MidiBinding>,
+}
+
+impl MidiMappings {
+    /// Bind a trigger, replacing what it was bound to before
+    pub fn bind(&mut self, binding: MidiBinding) {
+        self.bindings
+            .retain(|existing| existing.trigger != binding.trigger);
+        self.bindings.push(binding);
+    }
+
+    /// Remove a trigger's binding
+    pub fn remove(&mut self, trigger: &MidiTrigger) -> Result<(), String> {
+        let before = self.bindings.len();
+        self.bindings.retain(|binding| binding.trigger != *trigger);
+        if self.bindings.len() == before {
+            return Err(format!("MIDI trigger {:?} is not bound", trigger));
+        }
+        Ok(())
+    }
+
+    /// Sound bound to a trigger
+    pub fn sound_for(&self, trigger: &MidiTrigger) -> Option<&SoundId> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.trigger == *trigger)
+            .map(|binding| &binding.sound_id)
+    }
+
+    /// Whether any trigger plays this sound
+    pub fn is_bound(&self, sound_id: &SoundId) -> bool {
+        self.bindings
+            .iter()
+            .any(|binding| binding.sound_id == *sound_id)
+    }
+}
+
+/// What an incoming message should do
+#[derive(Debug, Clone, PartialEq)]
+pub enum MidiAction {
+    /// Play a sound; `gain` (0.0 - 1.0) comes from the velocity or CC value
+    Trigger { sound_id: SoundId, gain: f32 },
+    /// Learn mode bound the message's trigger
+    Learned(MidiBinding),
+}
+
+/// Turns MIDI messages into actions (independent of the port layer)
+#[derive(Debug, Default)]
+pub struct MidiRouter {
+    /// Sound waiting for its trigger
+    learning: Option<SoundId>,
+    /// Last value of each (channel, controller), for press detection
+    controls: HashMap<(u8, u8), u8>,
+}
+
+impl MidiRouter {
+    /// Bind the next note-on or pressed control to this sound
+    pub fn start_learn(&mut self, sound_id: SoundId) {
+        self.learning = Some(sound_id);
+    }
+
+    /// Leave learn mode (false if it wasn't active)
+    pub fn cancel_learn(&mut self) -> bool {
+        self.learning.take().is_some()
+    }
+
+    /// Route a message: learn it, trigger its bound sound or ignore it
+    pub fn handle(&mut self, message: MidiMessage, mappings: &MidiMappings) -> Option<MidiAction> {
+        let (trigger, value) = match message {
+            MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => (MidiTrigger::Note { channel, note }, velocity),
+            MidiMessage::ControlChange {
+                channel,
+                controller,
+                value,
+            } => {
+                // Only the press edge, so knobs and held buttons don't retrigger
+                let previous = self
+                    .controls
+                    .insert((channel, controller), value)
+                    .unwrap_or(0);
+                if value < CONTROL_PRESSED || previous >= CONTROL_PRESSED {
+                    return None;
+                }
+                (
+                    MidiTrigger::Control {
+                        channel,
+                        controller,
+                    },
+                    value,
+                )
+            }
+            MidiMessage::NoteOff { .. } => return None,
+        };
+
+        if let Some(sound_id) = self.learning.take() {
+            return Some(MidiAction::Learned(MidiBinding { trigger, sound_id }));
+        }
+        mappings
+            .sound_for(&trigger)
+            .map(|sound_id| MidiAction::Trigger {
+                sound_id: sound_id.clone(),
+                gain: value as f32 / 127.0,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::midi::message::MidiParser;
+
+    fn sound_id(id: &str) -> SoundId {
+        serde_json::from_str(&format!("\"{}\"", id)).unwrap()
+    }
+
+    /// Feed a synthetic byte stream through the parser and router
+    fn route(router: &mut MidiRouter, mappings: &MidiMappings, bytes: &[u8]) -> Vec<MidiAction> {
+        MidiParser::new()
+            .parse(bytes)
+            .into_iter()
+            .filter_map(|message| router.handle(message, mappings))
+            .collect()
+    }
+
+    #[test]
+    fn test_learn_binds_next_message() {
+        let mut router = MidiRouter::default();
+        let mut mappings = MidiMappings::default();
+        router.start_learn(sound_id("horn"));
+
+        // Note-offs don't count, the next note-on is learned
+        let actions = route(&mut router, &mappings, &[0x80, 40, 0, 0x92, 36, 127]);
+        let binding = MidiBinding {
+            trigger: MidiTrigger::Note {
+                channel: 2,
+                note: 36,
+            },
+            sound_id: sound_id("horn"),
+        };
+        assert_eq!(actions, [MidiAction::Learned(binding.clone())]);
+        mappings.bind(binding);
+
+        // Learn mode ended: the same pad now triggers
+        let actions = route(&mut router, &mappings, &[0x92, 36, 127]);
+        assert_eq!(
+            actions,
+            [MidiAction::Trigger {
+                sound_id: sound_id("horn"),
+                gain: 1.0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_velocity_maps_to_gain() {
+        let mut router = MidiRouter::default();
+        let mut mappings = MidiMappings::default();
+        mappings.bind(MidiBinding {
+            trigger: MidiTrigger::Note {
+                channel: 0,
+                note: 36,
+            },
+            sound_id: sound_id("kick"),
+        });
+
+        let gains: Vec<f32> = route(&mut router, &mappings, &[0x90, 36, 127, 36, 0, 36, 32])
+            .into_iter()
+            .map(|action| match action {
+                MidiAction::Trigger { gain, .. } => gain,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(gains.len(), 2);
+        assert_eq!(gains[0], 1.0);
+        assert!((gains[1] - 32.0 / 127.0).abs() < 1e-6);
+
+        // Unbound notes and other channels do nothing
+        assert!(route(&mut router, &mappings, &[0x90, 37, 100, 0x91, 36, 100]).is_empty());
+    }
+
+    #[test]
+    fn test_control_triggers_on_press_edge() {
+        let mut router = MidiRouter::default();
+        let mut mappings = MidiMappings::default();
+        router.start_learn(sound_id("clap"));
+
+        // Released values are skipped while learning
+        let actions = route(&mut router, &mappings, &[0xB0, 20, 0, 20, 127]);
+        let MidiAction::Learned(binding) = &actions[0] else {
+            panic!("expected a learned binding");
+        };
+        assert_eq!(
+            binding.trigger,
+            MidiTrigger::Control {
+                channel: 0,
+                controller: 20
+            }
+        );
+        mappings.bind(binding.clone());
+
+        // Held (127, 127) triggers once; release and press again retriggers
+        let actions = route(&mut router, &mappings, &[0xB0, 20, 0, 20, 127, 20, 127]);
+        assert_eq!(actions.len(), 1);
+        let actions = route(&mut router, &mappings, &[0xB0, 20, 10, 20, 100]);
+        assert_eq!(
+            actions,
+            [MidiAction::Trigger {
+                sound_id: sound_id("clap"),
+                gain: 100.0 / 127.0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cancel_learn() {
+        let mut router = MidiRouter::default();
+        router.start_learn(sound_id("horn"));
+        assert!(router.cancel_learn());
+        assert!(!router.cancel_learn());
+        assert!(route(&mut router, &MidiMappings::default(), &[0x90, 36, 127]).is_empty());
+    }
+
+    #[test]
+    fn test_bind_replaces_and_remove() {
+        let trigger = MidiTrigger::Note {
+            channel: 0,
+            note: 36,
+        };
+        let mut mappings = MidiMappings::default();
+        for id in ["a", "b"] {
+            mappings.bind(MidiBinding {
+                trigger,
+                sound_id: sound_id(id),
+            });
+        }
+        assert_eq!(mappings.bindings.len(), 1);
+        assert_eq!(mappings.sound_for(&trigger), Some(&sound_id("b")));
+        assert!(mappings.is_bound(&sound_id("b")));
+        assert!(!mappings.is_bound(&sound_id("a")));
+
+        assert!(mappings.remove(&trigger).is_ok());
+        assert!(mappings.remove(&trigger).is_err());
+    }
+
+    #[test]
+    fn test_serde_format() {
+        let mut mappings = MidiMappings::default();
+        mappings.bind(MidiBinding {
+            trigger: MidiTrigger::Control {
+                channel: 1,
+                controller: 64,
+            },
+            sound_id: sound_id("s1"),
+        });
+        let json = serde_json::to_value(&mappings).unwrap();
+        assert_eq!(json["bindings"][0]["trigger"]["type"], "control");
+        assert_eq!(json["bindings"][0]["trigger"]["controller"], 64);
+        assert_eq!(json["bindings"][0]["sound_id"], "s1");
+    }
+}
@@ -0,0 +1,64 @@
+//! MIDI input ports (midir)
+
+use midir::{MidiInput, MidiInputConnection};
+use tracing::info;
+
+use super::message::{MidiMessage, MidiParser};
+
+/// Client name shown by the system MIDI layer
+const CLIENT_NAME: &str = "Sonic Deck";
+
+/// Names of the available MIDI input ports
+pub fn list_inputs() -> Result<Vec<String>, String> {
+    let input = MidiInput::new(CLIENT_NAME).map_err(|e| format!("MIDI unavailable: {}", e))?;
+    Ok(input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect())
+}
+
+/// Open MIDI input port (closed when dropped)
+pub struct MidiConnection {
+    port_name: String,
+    _connection: MidiInputConnection<MidiParser>,
+}
+
+impl MidiConnection {
+    /// Connect to a port by name; `on_message` runs on the MIDI thread
+    pub fn open(
+        port_name: &str,
+        mut on_message: impl FnMut(MidiMessage) + Send + 'static,
+    ) -> Result<Self, String> {
+        let input = MidiInput::new(CLIENT_NAME).map_err(|e| format!("MIDI unavailable: {}", e))?;
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| input.port_name(port).is_ok_and(|name| name == port_name))
+            .ok_or_else(|| format!("MIDI input not found: {}", port_name))?;
+
+        let connection = input
+            .connect(
+                &port,
+                "sonic-deck-input",
+                move |_timestamp, bytes, parser: &mut MidiParser| {
+                    for message in parser.parse(bytes) {
+                        on_message(message);
+                    }
+                },
+                MidiParser::new(),
+            )
+            .map_err(|e| format!("Failed to open MIDI input '{}': {}", port_name, e))?;
+
+        info!(port = %port_name, "MIDI input connected");
+        Ok(Self {
+            port_name: port_name.to_string(),
+            _connection: connection,
+        })
+    }
+
+    /// Name of the connected port
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+}
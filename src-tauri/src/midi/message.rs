@@ -0,0 +1,212 @@
+//! MIDI byte stream parsing
+
+/// Channel message relevant for triggers (channels are 0-15)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiMessage {
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    NoteOff {
+        channel: u8,
+        note: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+}
+
+/// Incremental MIDI parser
+///
+/// Handles running status, real-time bytes interleaved with messages and
+/// SysEx blocks. Messages other than notes and control changes are skipped.
+#[derive(Debug, Default)]
+pub struct MidiParser {
+    /// Status of the message being read (kept for running status)
+    status: Option<u8>,
+    /// Data bytes read so far
+    data: [u8; 2],
+    len: usize,
+    in_sysex: bool,
+}
+
+impl MidiParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a chunk of bytes (messages may span chunks)
+    pub fn parse(&mut self, bytes: &[u8]) -> Vec<MidiMessage> {
+        bytes.iter().filter_map(|&byte| self.feed(byte)).collect()
+    }
+
+    /// Feed one byte, returning a message once it is complete
+    pub fn feed(&mut self, byte: u8) -> Option<MidiMessage> {
+        match byte {
+            // Real-time (clock, start, stop...) may appear anywhere
+            0xF8..=0xFF => None,
+            0xF0 => {
+                self.in_sysex = true;
+                self.status = None;
+                None
+            }
+            0xF7 => {
+                self.in_sysex = false;
+                None
+            }
+            0x80..=0xEF => {
+                self.in_sysex = false;
+                self.status = Some(byte);
+                self.len = 0;
+                None
+            }
+            // Other system common messages cancel running status
+            0xF1..=0xF6 => {
+                self.in_sysex = false;
+                self.status = None;
+                None
+            }
+            _ if self.in_sysex => None,
+            data => {
+                let status = self.status?;
+                self.data[self.len] = data;
+                self.len += 1;
+                if self.len < data_length(status) {
+                    return None;
+                }
+                // Running status: the next data bytes start a new message
+                self.len = 0;
+                decode(status, self.data)
+            }
+        }
+    }
+}
+
+/// Number of data bytes of a channel message
+fn data_length(status: u8) -> usize {
+    match status & 0xF0 {
+        0xC0 | 0xD0 => 1,
+        _ => 2,
+    }
+}
+
+fn decode(status: u8, data: [u8; 2]) -> Option<MidiMessage> {
+    let channel = status & 0x0F;
+    match status & 0xF0 {
+        0x80 => Some(MidiMessage::NoteOff {
+            channel,
+            note: data[0],
+        }),
+        // Note-on with velocity 0 is a note-off
+        0x90 if data[1] == 0 => Some(MidiMessage::NoteOff {
+            channel,
+            note: data[0],
+        }),
+        0x90 => Some(MidiMessage::NoteOn {
+            channel,
+            note: data[0],
+            velocity: data[1],
+        }),
+        0xB0 => Some(MidiMessage::ControlChange {
+            channel,
+            controller: data[0],
+            value: data[1],
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_notes_and_control_changes() {
+        let mut parser = MidiParser::new();
+        let messages = parser.parse(&[0x91, 36, 100, 0x81, 36, 0, 0xB0, 7, 127]);
+        assert_eq!(
+            messages,
+            [
+                MidiMessage::NoteOn {
+                    channel: 1,
+                    note: 36,
+                    velocity: 100
+                },
+                MidiMessage::NoteOff {
+                    channel: 1,
+                    note: 36
+                },
+                MidiMessage::ControlChange {
+                    channel: 0,
+                    controller: 7,
+                    value: 127
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_running_status_and_zero_velocity() {
+        let mut parser = MidiParser::new();
+        let messages = parser.parse(&[0x90, 36, 90, 38, 64, 36, 0]);
+        assert_eq!(
+            messages,
+            [
+                MidiMessage::NoteOn {
+                    channel: 0,
+                    note: 36,
+                    velocity: 90
+                },
+                MidiMessage::NoteOn {
+                    channel: 0,
+                    note: 38,
+                    velocity: 64
+                },
+                MidiMessage::NoteOff {
+                    channel: 0,
+                    note: 36
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_messages_split_across_chunks_with_realtime_bytes() {
+        let mut parser = MidiParser::new();
+        assert!(parser.parse(&[0xF8, 0x99]).is_empty());
+        assert!(parser.parse(&[42, 0xFE]).is_empty());
+        assert_eq!(
+            parser.parse(&[0xF8, 127]),
+            [MidiMessage::NoteOn {
+                channel: 9,
+                note: 42,
+                velocity: 127
+            }]
+        );
+    }
+
+    #[test]
+    fn test_skips_sysex_and_other_messages() {
+        let mut parser = MidiParser::new();
+        let messages = parser.parse(&[
+            0xF0, 0x7E, 0x00, 0x06, 0x01, 0xF7, // SysEx identity request
+            0xC0, 5, // Program change
+            0xE0, 0x00, 0x40, // Pitch bend
+            0xB2, 64, 127,
+        ]);
+        assert_eq!(
+            messages,
+            [MidiMessage::ControlChange {
+                channel: 2,
+                controller: 64,
+                value: 127
+            }]
+        );
+
+        // Data without a status (after a system common message) is ignored
+        assert!(parser.parse(&[0xF3, 1, 36, 100]).is_empty());
+    }
+}
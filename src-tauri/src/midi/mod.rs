@@ -0,0 +1,14 @@
+//! MIDI controller triggers
+//!
+//! Note-on and control-change messages from the selected input port play their
+//! bound sounds, with the velocity (or CC value) scaling the volume. Parsing and
+//! binding dispatch are independent of the port layer.
+
+mod app;
+mod bindings;
+mod input;
+mod message;
+
+pub use app::{apply_settings, MidiState, MidiStatus};
+pub use bindings::{MidiMappings, MidiTrigger};
+pub use input::list_inputs;
@@ -0,0 +1,105 @@
+//! OBS wiring into the running app
+
+use std::sync::Mutex;
+
+use tauri::{Listener, Manager};
+use tracing::warn;
+
+use super::client::{ObsClient, ObsCommand, ObsEvent, ObsStatus};
+use super::config::{ObsAction, ObsConfig};
+use crate::commands::{self, SoundPlayState, SoundStateChange};
+use crate::sounds::SoundId;
+use crate::AppState;
+
+/// OBS connection of the app (managed Tauri state)
+#[derive(Default)]
+pub struct ObsState {
+    client: Mutex<Option<ObsClient>>,
+}
+
+impl ObsState {
+    pub fn status(&self) -> ObsStatus {
+        self.client
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(ObsClient::status)
+            .unwrap_or_default()
+    }
+
+    fn send(&self, command: ObsCommand) {
+        if let Some(client) = self.client.lock().unwrap().as_ref() {
+            client.send(command);
+        }
+    }
+}
+
+/// Connect, reconnect or disconnect to match the settings
+pub fn apply_settings(app: &tauri::AppHandle, config: &ObsConfig) {
+    let obs = app.state::<ObsState>();
+    let mut client = obs.client.lock().unwrap();
+    *client = None;
+    if !config.enabled {
+        return;
+    }
+
+    let handle = app.clone();
+    *client = Some(ObsClient::start(config.clone(), move |event| {
+        handle_event(&handle, event)
+    }));
+}
+
+/// Run "on play" actions as sounds start and stop (call once at startup)
+pub fn forward_sound_events(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.listen_any("sound-state-changed", move |event| {
+        let Ok(change) = serde_json::from_str::<SoundStateChange>(event.payload()) else {
+            return;
+        };
+        let playing = match change.state {
+            SoundPlayState::Playing => true,
+            SoundPlayState::Idle => false,
+            SoundPlayState::Decoding => return,
+        };
+
+        let config = handle.state::<AppState>().read_settings().obs.clone();
+        if !config.enabled {
+            return;
+        }
+        let obs = handle.state::<ObsState>();
+        for action in config.actions_for(&change.sound_id) {
+            match action {
+                ObsAction::SwitchScene { scene } if playing => {
+                    obs.send(ObsCommand::SwitchScene(scene.clone()))
+                }
+                ObsAction::SwitchScene { .. } => {}
+                // Overlays show while the sound plays
+                ObsAction::ShowSource { scene, source } => obs.send(ObsCommand::SetSourceVisible {
+                    scene: scene.clone(),
+                    source: source.clone(),
+                    visible: playing,
+                }),
+            }
+        }
+    });
+}
+
+/// Play the sounds triggered by a scene going live
+fn handle_event(app: &tauri::AppHandle, event: ObsEvent) {
+    match event {
+        ObsEvent::SceneChanged(scene) => {
+            let sounds: Vec<SoundId> = app
+                .state::<AppState>()
+                .read_settings()
+                .obs
+                .sounds_for_scene(&scene)
+                .cloned()
+                .collect();
+            for sound_id in sounds {
+                if let Err(e) = commands::play_library_sound(app, &sound_id, 1.0) {
+                    warn!("OBS scene trigger for '{}' failed: {}", scene, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,512 @@
+//! obs-websocket client connection
+//!
+//! Runs on its own thread and reconnects while enabled, so OBS can be started
+//! after the app. Commands sent while disconnected are dropped.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::json;
+use tracing::{debug, info, warn};
+use tungstenite::{Message, WebSocket};
+
+use super::config::ObsConfig;
+use super::protocol::{self, ServerMessage};
+
+/// How long to wait for OBS while connecting and identifying
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Read timeout between checks for commands and shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Delay before reconnecting after the connection failed or closed
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Something to do in OBS
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObsCommand {
+    SwitchScene(String),
+    SetSourceVisible {
+        scene: String,
+        source: String,
+        visible: bool,
+    },
+}
+
+/// Something that happened in OBS
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObsEvent {
+    /// The program scene changed (not sent for the scene found on connect)
+    SceneChanged(String),
+}
+
+/// Connection state for the settings UI
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ObsStatus {
+    pub connected: bool,
+    /// Why the last connection attempt failed or closed
+    pub error: Option<String>,
+    /// Current program scene, once known
+    pub scene: Option<String>,
+}
+
+/// Connection to OBS (disconnects when dropped)
+pub struct ObsClient {
+    commands: Sender<ObsCommand>,
+    status: Arc<Mutex<ObsStatus>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ObsClient {
+    /// Connect in the background; `on_event` runs on the client thread
+    pub fn start(config: ObsConfig, on_event: impl Fn(ObsEvent) + Send + 'static) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let status = Arc::new(Mutex::new(ObsStatus::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let connection = Connection {
+            config,
+            commands: receiver,
+            status: status.clone(),
+            stop: stop.clone(),
+            on_event: Box::new(on_event),
+        };
+        let thread = thread::Builder::new()
+            .name("obs-websocket".to_string())
+            .spawn(move || connection.run())
+            .map_err(|e| warn!("Failed to start OBS client thread: {}", e))
+            .ok();
+
+        Self {
+            commands,
+            status,
+            stop,
+            thread,
+        }
+    }
+
+    /// Queue a command (dropped if OBS isn't connected)
+    pub fn send(&self, command: ObsCommand) {
+        if self.status.lock().unwrap().connected {
+            let _ = self.commands.send(command);
+        } else {
+            debug!("OBS not connected, dropping {:?}", command);
+        }
+    }
+
+    pub fn status(&self) -> ObsStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+impl Drop for ObsClient {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// State of the client thread
+struct Connection {
+    config: ObsConfig,
+    commands: Receiver<ObsCommand>,
+    status: Arc<Mutex<ObsStatus>>,
+    stop: Arc<AtomicBool>,
+    on_event: Box<dyn Fn(ObsEvent) + Send>,
+}
+
+/// Source visibility change waiting for its scene item ID
+struct PendingVisibility {
+    scene: String,
+    visible: bool,
+}
+
+impl Connection {
+    fn run(self) {
+        while !self.stopped() {
+            let result = self.session();
+            let error = match result {
+                Ok(()) => None,
+                Err(e) => {
+                    debug!("OBS connection ended: {}", e);
+                    Some(e)
+                }
+            };
+            *self.status.lock().unwrap() = ObsStatus {
+                connected: false,
+                error,
+                scene: None,
+            };
+
+            let retry_at = Instant::now() + RETRY_INTERVAL;
+            while !self.stopped() && Instant::now() < retry_at {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// One connection: handshake, then events and commands until it closes
+    fn session(&self) -> Result<(), String> {
+        let url = self.config.url();
+        let address = (self.config.host.as_str(), self.config.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Failed to resolve {}: {}", url, e))?
+            .next()
+            .ok_or_else(|| format!("Failed to resolve {}", url))?;
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+            .map_err(|e| format!("Failed to connect to OBS at {}: {}", url, e))?;
+        stream
+            .set_read_timeout(Some(CONNECT_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        let (mut socket, _) = tungstenite::client(url.as_str(), stream)
+            .map_err(|e| format!("OBS WebSocket handshake failed: {}", e))?;
+
+        let authentication = match self.read(&mut socket)? {
+            Some(ServerMessage::Hello { authentication }) => authentication,
+            other => return Err(format!("Expected Hello from OBS, got {:?}", other)),
+        };
+        send(
+            &mut socket,
+            protocol::identify(&self.config.password, authentication.as_ref()),
+        )?;
+        match self.read(&mut socket) {
+            Ok(Some(ServerMessage::Identified)) => {}
+            // OBS closes the connection when the password is wrong
+            Err(_) if authentication.is_some() => {
+                return Err("OBS authentication failed (check the password)".to_string())
+            }
+            Err(e) => return Err(e),
+            Ok(other) => return Err(format!("Expected Identified from OBS, got {:?}", other)),
+        }
+
+        socket
+            .get_mut()
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .map_err(|e| e.to_string())?;
+        // Commands queued before this connection are stale
+        while self.commands.try_recv().is_ok() {}
+        *self.status.lock().unwrap() = ObsStatus {
+            connected: true,
+            error: None,
+            scene: None,
+        };
+        info!(url = %url, "Connected to OBS");
+
+        let mut next_request = 0u64;
+        let mut pending: HashMap<String, PendingVisibility> = HashMap::new();
+        send(
+            &mut socket,
+            protocol::request("GetCurrentProgramScene", "scene", json!({})),
+        )?;
+
+        while !self.stopped() {
+            while let Ok(command) = self.commands.try_recv() {
+                next_request += 1;
+                let request_id = next_request.to_string();
+                let message = match command {
+                    ObsCommand::SwitchScene(scene) => protocol::request(
+                        "SetCurrentProgramScene",
+                        &request_id,
+                        json!({ "sceneName": scene }),
+                    ),
+                    ObsCommand::SetSourceVisible {
+                        scene,
+                        source,
+                        visible,
+                    } => {
+                        let message = protocol::request(
+                            "GetSceneItemId",
+                            &request_id,
+                            json!({ "sceneName": scene, "sourceName": source }),
+                        );
+                        pending.insert(request_id, PendingVisibility { scene, visible });
+                        message
+                    }
+                };
+                send(&mut socket, message)?;
+            }
+
+            let Some(message) = self.read(&mut socket)? else {
+                continue;
+            };
+            match message {
+                ServerMessage::Event { event_type, data }
+                    if event_type == "CurrentProgramSceneChanged" =>
+                {
+                    if let Some(scene) = data["sceneName"].as_str() {
+                        self.status.lock().unwrap().scene = Some(scene.to_string());
+                        (self.on_event)(ObsEvent::SceneChanged(scene.to_string()));
+                    }
+                }
+                ServerMessage::RequestResponse {
+                    request_id,
+                    success,
+                    comment,
+                    data,
+                } => {
+                    if !success {
+                        pending.remove(&request_id);
+                        warn!(
+                            "OBS request {} failed: {}",
+                            request_id,
+                            comment.unwrap_or_default()
+                        );
+                    } else if request_id == "scene" {
+                        // Only a baseline: (re)connecting must not fire the
+                        // scene's "on enter" sounds
+                        if let Some(scene) = data["currentProgramSceneName"].as_str() {
+                            self.status.lock().unwrap().scene = Some(scene.to_string());
+                        }
+                    } else if let Some(change) = pending.remove(&request_id) {
+                        send(
+                            &mut socket,
+                            protocol::request(
+                                "SetSceneItemEnabled",
+                                &format!("{}-enable", request_id),
+                                json!({
+                                    "sceneName": change.scene,
+                                    "sceneItemId": data["sceneItemId"],
+                                    "sceneItemEnabled": change.visible,
+                                }),
+                            ),
+                        )?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let _ = socket.close(None);
+        let _ = socket.flush();
+        Ok(())
+    }
+
+    /// Read the next protocol message (None when the read timed out)
+    fn read(&self, socket: &mut WebSocket<TcpStream>) -> Result<Option<ServerMessage>, String> {
+        match socket.read() {
+            Ok(Message::Text(text)) => protocol::parse(&text).map(Some),
+            Ok(Message::Close(frame)) => Err(match frame {
+                Some(frame) => format!("OBS closed the connection: {}", frame.reason),
+                None => "OBS closed the connection".to_string(),
+            }),
+            Ok(_) => Ok(None),
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(format!("OBS connection lost: {}", e)),
+        }
+    }
+}
+
+fn send(socket: &mut WebSocket<TcpStream>, message: String) -> Result<(), String> {
+    socket
+        .send(Message::text(message))
+        .map_err(|e| format!("Failed to send to OBS: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::net::TcpListener;
+
+    const PASSWORD: &str = "supersecretpassword";
+
+    /// Scripted obs-websocket server for one client
+    struct MockObs {
+        socket: WebSocket<TcpStream>,
+    }
+
+    impl MockObs {
+        fn accept(listener: &TcpListener) -> Self {
+            let (stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            Self {
+                socket: tungstenite::accept(stream).unwrap(),
+            }
+        }
+
+        fn send(&mut self, message: Value) {
+            self.socket
+                .send(Message::text(message.to_string()))
+                .unwrap();
+        }
+
+        fn recv(&mut self) -> Value {
+            loop {
+                if let Message::Text(text) = self.socket.read().unwrap() {
+                    return serde_json::from_str(&text).unwrap();
+                }
+            }
+        }
+
+        /// Hello with the protocol docs' challenge, then check Identify
+        fn handshake(&mut self) -> Value {
+            self.send(json!({"op": 0, "d": {
+                "obsWebSocketVersion": "5.1.0",
+                "rpcVersion": 1,
+                "authentication": {
+                    "challenge": "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY=",
+                    "salt": "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI="
+                }
+            }}));
+            self.recv()
+        }
+    }
+
+    fn config(port: u16, password: &str) -> ObsConfig {
+        ObsConfig {
+            enabled: true,
+            host: "127.0.0.1".to_string(),
+            port,
+            password: password.to_string(),
+            ..ObsConfig::default()
+        }
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_identifies_reports_scenes_and_runs_commands() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (events_tx, events) = mpsc::channel();
+        let client = ObsClient::start(config(port, PASSWORD), move |event| {
+            let _ = events_tx.send(event);
+        });
+
+        let mut obs = MockObs::accept(&listener);
+        let identify = obs.handshake();
+        assert_eq!(identify["op"], 1);
+        assert_eq!(
+            identify["d"]["authentication"],
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+        obs.send(json!({"op": 2, "d": {"negotiatedRpcVersion": 1}}));
+
+        // The current scene is requested on connect
+        let request = obs.recv();
+        assert_eq!(request["d"]["requestType"], "GetCurrentProgramScene");
+        obs.send(json!({"op": 7, "d": {
+            "requestType": "GetCurrentProgramScene",
+            "requestId": request["d"]["requestId"],
+            "requestStatus": {"result": true, "code": 100},
+            "responseData": {"currentProgramSceneName": "Game"}
+        }}));
+        wait_until(|| client.status().scene.as_deref() == Some("Game"));
+        assert!(client.status().connected);
+        // ...but connecting is not a scene change
+        assert!(events.try_recv().is_err());
+        let timeout = Duration::from_secs(5);
+
+        // Scene changes in OBS reach the app
+        obs.send(json!({"op": 5, "d": {
+            "eventType": "CurrentProgramSceneChanged",
+            "eventIntent": 4,
+            "eventData": {"sceneName": "Starting Soon"}
+        }}));
+        assert_eq!(
+            events.recv_timeout(timeout).unwrap(),
+            ObsEvent::SceneChanged("Starting Soon".to_string())
+        );
+
+        // Switching scenes is one request
+        client.send(ObsCommand::SwitchScene("BRB".to_string()));
+        let request = obs.recv();
+        assert_eq!(request["op"], 6);
+        assert_eq!(request["d"]["requestType"], "SetCurrentProgramScene");
+        assert_eq!(request["d"]["requestData"]["sceneName"], "BRB");
+
+        // Showing a source looks up its scene item first
+        client.send(ObsCommand::SetSourceVisible {
+            scene: "Game".to_string(),
+            source: "Horn GIF".to_string(),
+            visible: true,
+        });
+        let lookup = obs.recv();
+        assert_eq!(lookup["d"]["requestType"], "GetSceneItemId");
+        assert_eq!(lookup["d"]["requestData"]["sourceName"], "Horn GIF");
+        obs.send(json!({"op": 7, "d": {
+            "requestType": "GetSceneItemId",
+            "requestId": lookup["d"]["requestId"],
+            "requestStatus": {"result": true, "code": 100},
+            "responseData": {"sceneItemId": 12}
+        }}));
+        let enable = obs.recv();
+        assert_eq!(enable["d"]["requestType"], "SetSceneItemEnabled");
+        assert_eq!(enable["d"]["requestData"]["sceneName"], "Game");
+        assert_eq!(enable["d"]["requestData"]["sceneItemId"], 12);
+        assert_eq!(enable["d"]["requestData"]["sceneItemEnabled"], true);
+
+        // Dropping the client closes the connection
+        drop(client);
+        assert!(matches!(obs.socket.read(), Ok(Message::Close(_)) | Err(_)));
+    }
+
+    #[test]
+    fn test_wrong_password_is_reported() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = ObsClient::start(config(port, "wrong"), |_| {});
+
+        let mut obs = MockObs::accept(&listener);
+        let identify = obs.handshake();
+        assert_ne!(
+            identify["d"]["authentication"],
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+        // obs-websocket closes with 4009 (authentication failed)
+        obs.socket
+            .close(Some(tungstenite::protocol::CloseFrame {
+                code: 4009.into(),
+                reason: "Authentication failed.".into(),
+            }))
+            .unwrap();
+        let _ = obs.socket.flush();
+
+        wait_until(|| client.status().error.is_some());
+        let status = client.status();
+        assert!(!status.connected);
+        assert!(status.error.unwrap().contains("authentication failed"));
+    }
+
+    #[test]
+    fn test_unreachable_server_is_reported() {
+        // Nothing listens on a just-released port
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let client = ObsClient::start(config(port, ""), |_| {});
+        wait_until(|| client.status().error.is_some());
+        assert!(!client.status().connected);
+
+        // Dropped rather than queued for a later connection
+        client.send(ObsCommand::SwitchScene("BRB".to_string()));
+    }
+}
@@ -0,0 +1,153 @@
+//! OBS integration settings
+
+use serde::{Deserialize, Serialize};
+
+use crate::sounds::SoundId;
+
+/// Default port of obs-websocket (OBS 28+)
+pub const DEFAULT_OBS_PORT: u16 = 4455;
+
+/// What OBS does when a sound starts playing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ObsAction {
+    /// Switch the program scene
+    SwitchScene { scene: String },
+    /// Show a source (an overlay) while the sound plays
+    ShowSource { scene: String, source: String },
+}
+
+/// "On play" action of a sound
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObsSoundAction {
+    pub sound_id: SoundId,
+    pub action: ObsAction,
+}
+
+/// Sound played when a scene becomes the program scene
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObsSceneTrigger {
+    pub scene: String,
+    pub sound_id: SoundId,
+}
+
+/// OBS WebSocket configuration (stored in settings)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObsConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// obs-websocket server password (empty if authentication is off)
+    pub password: String,
+    pub sound_actions: Vec<ObsSoundAction>,
+    pub scene_triggers: Vec<ObsSceneTrigger>,
+}
+
+impl Default for ObsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: DEFAULT_OBS_PORT,
+            password: String::new(),
+            sound_actions: Vec::new(),
+            scene_triggers: Vec::new(),
+        }
+    }
+}
+
+impl ObsConfig {
+    /// WebSocket URL of the OBS server
+    pub fn url(&self) -> String {
+        format!("ws://{}:{}", self.host, self.port)
+    }
+
+    /// Whether both connect to the same server the same way (actions aside)
+    pub fn same_connection(&self, other: &ObsConfig) -> bool {
+        self.enabled == other.enabled
+            && self.host == other.host
+            && self.port == other.port
+            && self.password == other.password
+    }
+
+    /// Actions to run when this sound starts playing
+    pub fn actions_for<'a>(&'a self, sound_id: &'a SoundId) -> impl Iterator<Item = &'a ObsAction> {
+        self.sound_actions
+            .iter()
+            .filter(move |entry| entry.sound_id == *sound_id)
+            .map(|entry| &entry.action)
+    }
+
+    /// Sounds to play when this scene goes live
+    pub fn sounds_for_scene<'a>(&'a self, scene: &'a str) -> impl Iterator<Item = &'a SoundId> {
+        self.scene_triggers
+            .iter()
+            .filter(move |trigger| trigger.scene == scene)
+            .map(|trigger| &trigger.sound_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sound_id(id: &str) -> SoundId {
+        serde_json::from_str(&format!("\"{}\"", id)).unwrap()
+    }
+
+    #[test]
+    fn test_config_defaults_and_serde() {
+        let config: ObsConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, ObsConfig::default());
+        assert_eq!(config.url(), "ws://localhost:4455");
+
+        let config: ObsConfig = serde_json::from_str(
+            r#"{
+                "enabled": true,
+                "sound_actions": [
+                    {"sound_id": "horn", "action": {"type": "show_source", "scene": "Game", "source": "Horn GIF"}},
+                    {"sound_id": "horn", "action": {"type": "switch_scene", "scene": "BRB"}}
+                ],
+                "scene_triggers": [{"scene": "Starting Soon", "sound_id": "jingle"}]
+            }"#,
+        )
+        .unwrap();
+        assert!(config.enabled);
+
+        let horn = sound_id("horn");
+        let actions: Vec<_> = config.actions_for(&horn).collect();
+        assert_eq!(
+            actions,
+            [
+                &ObsAction::ShowSource {
+                    scene: "Game".to_string(),
+                    source: "Horn GIF".to_string()
+                },
+                &ObsAction::SwitchScene {
+                    scene: "BRB".to_string()
+                },
+            ]
+        );
+        assert_eq!(config.actions_for(&sound_id("other")).count(), 0);
+
+        let sounds: Vec<_> = config.sounds_for_scene("Starting Soon").collect();
+        assert_eq!(sounds, [&sound_id("jingle")]);
+        assert_eq!(config.sounds_for_scene("Game").count(), 0);
+    }
+
+    #[test]
+    fn test_same_connection_ignores_actions() {
+        let config = ObsConfig::default();
+        let mut with_trigger = config.clone();
+        with_trigger.scene_triggers.push(ObsSceneTrigger {
+            scene: "Intro".to_string(),
+            sound_id: sound_id("jingle"),
+        });
+        assert!(config.same_connection(&with_trigger));
+
+        let mut other_port = config.clone();
+        other_port.port = 4456;
+        assert!(!config.same_connection(&other_port));
+    }
+}
@@ -0,0 +1,13 @@
+//! OBS Studio integration (obs-websocket v5)
+//!
+//! Sounds can switch the program scene or show an overlay source while they
+//! play, and scenes going live can play sounds (a "starting soon" jingle).
+
+mod app;
+mod client;
+mod config;
+mod protocol;
+
+pub use app::{apply_settings, forward_sound_events, ObsState};
+pub use client::ObsStatus;
+pub use config::ObsConfig;
@@ -0,0 +1,254 @@
+//! obs-websocket v5 messages
+//!
+//! Every message is `{"op": <opcode>, "d": {...}}`. The client answers the
+//! server's Hello with Identify (authenticating if the server asks), then
+//! receives events and sends requests.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// Protocol revision we speak
+pub const RPC_VERSION: u32 = 1;
+
+const OP_HELLO: u8 = 0;
+const OP_IDENTIFY: u8 = 1;
+const OP_IDENTIFIED: u8 = 2;
+const OP_EVENT: u8 = 5;
+const OP_REQUEST: u8 = 6;
+const OP_REQUEST_RESPONSE: u8 = 7;
+
+/// Event subscription: scene events (program scene changes)
+const EVENT_SUBSCRIPTION_SCENES: u32 = 1 << 2;
+
+/// Authentication challenge sent in Hello
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AuthChallenge {
+    pub challenge: String,
+    pub salt: String,
+}
+
+/// Message from the OBS server
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    Hello {
+        authentication: Option<AuthChallenge>,
+    },
+    Identified,
+    Event {
+        event_type: String,
+        data: Value,
+    },
+    RequestResponse {
+        request_id: String,
+        success: bool,
+        comment: Option<String>,
+        data: Value,
+    },
+    /// Opcodes we don't use (batches, reidentify)
+    Other,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    op: u8,
+    #[serde(default)]
+    d: Value,
+}
+
+#[derive(Deserialize)]
+struct HelloData {
+    authentication: Option<AuthChallenge>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventData {
+    event_type: String,
+    #[serde(default)]
+    event_data: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestResponseData {
+    request_id: String,
+    request_status: RequestStatus,
+    #[serde(default)]
+    response_data: Value,
+}
+
+#[derive(Deserialize)]
+struct RequestStatus {
+    result: bool,
+    comment: Option<String>,
+}
+
+/// Parse a text message from the server
+pub fn parse(text: &str) -> Result<ServerMessage, String> {
+    let envelope: Envelope =
+        serde_json::from_str(text).map_err(|e| format!("Invalid OBS message: {}", e))?;
+    let invalid = |e: serde_json::Error| format!("Invalid OBS message (op {}): {}", envelope.op, e);
+    Ok(match envelope.op {
+        OP_HELLO => {
+            let hello: HelloData = serde_json::from_value(envelope.d.clone()).map_err(invalid)?;
+            ServerMessage::Hello {
+                authentication: hello.authentication,
+            }
+        }
+        OP_IDENTIFIED => ServerMessage::Identified,
+        OP_EVENT => {
+            let event: EventData = serde_json::from_value(envelope.d.clone()).map_err(invalid)?;
+            ServerMessage::Event {
+                event_type: event.event_type,
+                data: event.event_data,
+            }
+        }
+        OP_REQUEST_RESPONSE => {
+            let response: RequestResponseData =
+                serde_json::from_value(envelope.d.clone()).map_err(invalid)?;
+            ServerMessage::RequestResponse {
+                request_id: response.request_id,
+                success: response.request_status.result,
+                comment: response.request_status.comment,
+                data: response.response_data,
+            }
+        }
+        _ => ServerMessage::Other,
+    })
+}
+
+/// Identify message answering Hello (subscribes to scene events)
+pub fn identify(password: &str, authentication: Option<&AuthChallenge>) -> String {
+    let mut data = json!({
+        "rpcVersion": RPC_VERSION,
+        "eventSubscriptions": EVENT_SUBSCRIPTION_SCENES,
+    });
+    if let Some(auth) = authentication {
+        data["authentication"] = json!(auth_response(password, auth));
+    }
+    json!({ "op": OP_IDENTIFY, "d": data }).to_string()
+}
+
+/// Request message (the response carries the same `request_id`)
+pub fn request(request_type: &str, request_id: &str, request_data: Value) -> String {
+    json!({
+        "op": OP_REQUEST,
+        "d": {
+            "requestType": request_type,
+            "requestId": request_id,
+            "requestData": request_data,
+        }
+    })
+    .to_string()
+}
+
+/// base64(sha256(base64(sha256(password + salt)) + challenge))
+fn auth_response(password: &str, auth: &AuthChallenge) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, auth.salt)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, auth.challenge)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_response_matches_protocol_example() {
+        let auth = AuthChallenge {
+            challenge: "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY=".to_string(),
+            salt: "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=".to_string(),
+        };
+        assert_eq!(
+            auth_response("supersecretpassword", &auth),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+
+        let message: Value =
+            serde_json::from_str(&identify("supersecretpassword", Some(&auth))).unwrap();
+        assert_eq!(message["op"], 1);
+        assert_eq!(message["d"]["rpcVersion"], 1);
+        assert_eq!(message["d"]["eventSubscriptions"], 4);
+        assert_eq!(
+            message["d"]["authentication"],
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+
+        let message: Value = serde_json::from_str(&identify("", None)).unwrap();
+        assert!(message["d"].get("authentication").is_none());
+    }
+
+    #[test]
+    fn test_parse_server_messages() {
+        let hello = parse(
+            r#"{"op":0,"d":{"obsWebSocketVersion":"5.1.0","rpcVersion":1,
+                "authentication":{"challenge":"c","salt":"s"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            hello,
+            ServerMessage::Hello {
+                authentication: Some(AuthChallenge {
+                    challenge: "c".to_string(),
+                    salt: "s".to_string()
+                })
+            }
+        );
+        assert_eq!(
+            parse(r#"{"op":0,"d":{"rpcVersion":1}}"#).unwrap(),
+            ServerMessage::Hello {
+                authentication: None
+            }
+        );
+        assert_eq!(
+            parse(r#"{"op":2,"d":{"negotiatedRpcVersion":1}}"#).unwrap(),
+            ServerMessage::Identified
+        );
+
+        let event = parse(
+            r#"{"op":5,"d":{"eventType":"CurrentProgramSceneChanged","eventIntent":4,
+                "eventData":{"sceneName":"Starting Soon"}}}"#,
+        )
+        .unwrap();
+        let ServerMessage::Event { event_type, data } = event else {
+            panic!("expected an event");
+        };
+        assert_eq!(event_type, "CurrentProgramSceneChanged");
+        assert_eq!(data["sceneName"], "Starting Soon");
+
+        let response = parse(
+            r#"{"op":7,"d":{"requestType":"GetSceneItemId","requestId":"2",
+                "requestStatus":{"result":false,"code":600,"comment":"No source"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response,
+            ServerMessage::RequestResponse {
+                request_id: "2".to_string(),
+                success: false,
+                comment: Some("No source".to_string()),
+                data: Value::Null,
+            }
+        );
+
+        assert_eq!(parse(r#"{"op":9,"d":{}}"#).unwrap(), ServerMessage::Other);
+        assert!(parse("not json").is_err());
+        assert!(parse(r#"{"op":5,"d":{}}"#).is_err());
+    }
+
+    #[test]
+    fn test_request_format() {
+        let message: Value = serde_json::from_str(&request(
+            "SetCurrentProgramScene",
+            "7",
+            json!({ "sceneName": "BRB" }),
+        ))
+        .unwrap();
+        assert_eq!(message["op"], 6);
+        assert_eq!(message["d"]["requestType"], "SetCurrentProgramScene");
+        assert_eq!(message["d"]["requestId"], "7");
+        assert_eq!(message["d"]["requestData"]["sceneName"], "BRB");
+    }
+}
@@ -0,0 +1,137 @@
+//! Capture buffer the capture thread fills with what the input stream delivered
+
+use std::collections::VecDeque;
+
+/// What happens once the buffer holds its maximum length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Drop the oldest audio (instant replay keeps the last N seconds)
+    Rolling,
+    /// Drop new audio (a recording stops growing at its limit)
+    Stop,
+}
+
+/// Interleaved samples captured so far, up to `max_frames` frames
+pub struct CaptureBuffer {
+    samples: VecDeque<f32>,
+    channels: usize,
+    max_samples: usize,
+    overflow: Overflow,
+    full: bool,
+}
+
+impl CaptureBuffer {
+    pub fn new(max_frames: usize, channels: usize, overflow: Overflow) -> Self {
+        let channels = channels.max(1);
+        Self {
+            samples: VecDeque::new(),
+            channels,
+            max_samples: max_frames * channels,
+            overflow,
+            full: false,
+        }
+    }
+
+    /// Append an interleaved block from the input stream
+    pub fn push(&mut self, data: &[f32]) {
+        match self.overflow {
+            Overflow::Rolling => {
+                // Only the newest `max_samples` of the block can survive
+                let data = &data[data.len().saturating_sub(self.max_samples)..];
+                self.samples.extend(data);
+                let excess = self.samples.len().saturating_sub(self.max_samples);
+                if excess > 0 {
+                    // Drop whole frames so channels stay aligned
+                    let excess = excess.div_ceil(self.channels) * self.channels;
+                    self.samples.drain(..excess.min(self.samples.len()));
+                    self.full = true;
+                }
+            }
+            Overflow::Stop => {
+                let room = self.max_samples - self.samples.len();
+                if data.len() >= room {
+                    self.full = true;
+                }
+                let take = data.len().min(room) / self.channels * self.channels;
+                self.samples.extend(&data[..take]);
+            }
+        }
+    }
+
+    /// Captured length in frames
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels
+    }
+
+    /// Whether the buffer reached its maximum length (audio has been dropped)
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Copy of the captured samples, oldest first
+    pub fn snapshot(&self) -> Vec<f32> {
+        self.samples.iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stereo frames numbered from `start`: [n, -n, n+1, -(n+1), ...]
+    fn frames(start: usize, count: usize) -> Vec<f32> {
+        (start..start + count)
+            .flat_map(|n| [n as f32, -(n as f32)])
+            .collect()
+    }
+
+    #[test]
+    fn test_rolling_keeps_the_newest_frames() {
+        let mut buffer = CaptureBuffer::new(4, 2, Overflow::Rolling);
+        buffer.push(&frames(0, 3));
+        assert_eq!(buffer.frames(), 3);
+        assert!(!buffer.is_full());
+
+        buffer.push(&frames(3, 3));
+        assert_eq!(buffer.frames(), 4);
+        assert!(buffer.is_full());
+        assert_eq!(buffer.snapshot(), frames(2, 4));
+
+        // A block longer than the whole window
+        buffer.push(&frames(6, 10));
+        assert_eq!(buffer.snapshot(), frames(12, 4));
+    }
+
+    #[test]
+    fn test_rolling_over_many_small_blocks() {
+        let mut buffer = CaptureBuffer::new(100, 2, Overflow::Rolling);
+        for start in (0..1000).step_by(7) {
+            buffer.push(&frames(start, 7));
+        }
+        // 1001 frames pushed (0..=1000); the window holds the last 100
+        assert_eq!(buffer.snapshot(), frames(901, 100));
+    }
+
+    #[test]
+    fn test_stop_drops_audio_past_the_limit() {
+        let mut buffer = CaptureBuffer::new(5, 2, Overflow::Stop);
+        buffer.push(&frames(0, 3));
+        assert!(!buffer.is_full());
+
+        buffer.push(&frames(3, 3));
+        assert!(buffer.is_full());
+        assert_eq!(buffer.snapshot(), frames(0, 5));
+
+        buffer.push(&frames(6, 3));
+        assert_eq!(buffer.snapshot(), frames(0, 5));
+    }
+
+    #[test]
+    fn test_snapshot_leaves_the_buffer_intact() {
+        let mut buffer = CaptureBuffer::new(10, 1, Overflow::Rolling);
+        buffer.push(&[0.1, 0.2, 0.3]);
+        assert_eq!(buffer.snapshot(), vec![0.1, 0.2, 0.3]);
+        buffer.push(&[0.4]);
+        assert_eq!(buffer.snapshot(), vec![0.1, 0.2, 0.3, 0.4]);
+    }
+}
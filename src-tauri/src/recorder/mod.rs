@@ -0,0 +1,13 @@
+//! Recording new sounds
+//!
+//! The recorder captures an input device, or what an output device plays
+//! (loopback), either as a recording that runs until it is saved or as an
+//! instant replay buffer holding the last N seconds. Saved audio is written as
+//! WAV into the media folder and added to the library like any other sound.
+//! The buffering is independent of the stream, so it is tested with synthetic
+//! input.
+
+mod buffer;
+mod session;
+
+pub use session::{list_sources, RecordMode, RecordSource, Recorder, RecorderStatus, SourceInfo};
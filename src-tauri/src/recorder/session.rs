@@ -0,0 +1,528 @@
+//! Recording sessions on a capture thread
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use super::buffer::{CaptureBuffer, Overflow};
+use crate::audio::{AudioBackend, AudioData, DeviceId, ErrorCallback};
+use crate::vbcable::{ring_buffer, RingConsumer};
+
+/// Longest recording, so a forgotten recorder doesn't fill memory
+pub const MAX_RECORDING_SECS: u32 = 600;
+/// Longest instant replay window
+pub const MAX_REPLAY_SECS: u32 = 300;
+/// Audio the ring between the input callback and the buffer holds, enough to
+/// ride out the capture thread copying a full replay window
+const RING_SECS: usize = 2;
+/// How often the capture thread moves audio from the ring into the buffer
+const DRAIN_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Where audio is recorded from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordSource {
+    /// An input device (microphone, line in, CABLE Output)
+    Input { device_id: DeviceId },
+    /// What an output device plays
+    Loopback { device_id: DeviceId },
+}
+
+/// How the recorder keeps audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordMode {
+    /// Record from start until saved (up to [`MAX_RECORDING_SECS`])
+    Record,
+    /// Keep the last `seconds` running; saving takes a copy and keeps going
+    Replay { seconds: u32 },
+}
+
+/// A source offered in the recorder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceInfo {
+    pub source: RecordSource,
+    pub name: String,
+}
+
+/// State of the running session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecorderStatus {
+    pub source: RecordSource,
+    pub mode: RecordMode,
+    /// Length of the audio that would be saved
+    pub recorded_secs: f32,
+    pub max_secs: u32,
+    /// A recording hit its limit, or the replay window is filled
+    pub full: bool,
+    /// The stream reported an error (e.g. the device was unplugged)
+    pub failed: bool,
+}
+
+/// Recording and replay sources of a backend
+///
+/// Every named input device, then every named output device as loopback.
+pub fn list_sources(backend: &dyn AudioBackend) -> Vec<SourceInfo> {
+    let inputs = backend.input_devices().unwrap_or_default();
+    let outputs = backend.output_devices().unwrap_or_default();
+
+    // Unnamed devices keep their index but aren't offered
+    let inputs = inputs
+        .into_iter()
+        .enumerate()
+        .filter(|(_, device)| !device.name.is_empty())
+        .map(|(index, device)| SourceInfo {
+            source: RecordSource::Input {
+                device_id: DeviceId::from_index(index),
+            },
+            name: device.name,
+        });
+    let outputs = outputs
+        .into_iter()
+        .enumerate()
+        .filter(|(_, device)| !device.name.is_empty())
+        .map(|(index, device)| SourceInfo {
+            source: RecordSource::Loopback {
+                device_id: DeviceId::from_index(index),
+            },
+            name: format!("{} (what it plays)", device.name),
+        });
+    inputs.chain(outputs).collect()
+}
+
+/// Captured audio behind the lock-free ring the input callback writes to
+///
+/// The callback never locks this; whoever holds the lock drains the ring first.
+struct Capture {
+    ring: RingConsumer,
+    scratch: Vec<f32>,
+    channels: usize,
+    buffer: CaptureBuffer,
+}
+
+impl Capture {
+    /// Move what the stream delivered so far into the buffer
+    fn drain(&mut self) -> &CaptureBuffer {
+        // Whole frames only, so the ring stays frame-aligned
+        let available = self.ring.available().min(self.scratch.len());
+        let count = available - available % self.channels;
+        if count > 0 {
+            self.ring.read(&mut self.scratch[..count]);
+            self.buffer.push(&self.scratch[..count]);
+        }
+        &self.buffer
+    }
+}
+
+/// A running capture
+struct Session {
+    source: RecordSource,
+    mode: RecordMode,
+    max_secs: u32,
+    sample_rate: u32,
+    channels: u16,
+    capture: Arc<Mutex<Capture>>,
+    stop_signal: Arc<AtomicBool>,
+    failed: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Session {
+    /// Stop the capture thread and wait for it to close the stream
+    fn stop(self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+    }
+}
+
+/// Records from one source at a time
+pub struct Recorder {
+    backend: Arc<dyn AudioBackend>,
+    session: Mutex<Option<Session>>,
+}
+
+impl Recorder {
+    pub fn new(backend: Arc<dyn AudioBackend>) -> Self {
+        Self {
+            backend,
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Start recording; fails if a session is running or the stream can't open
+    pub fn start(&self, source: RecordSource, mode: RecordMode) -> Result<(), String> {
+        let mut session = self.session.lock().unwrap();
+        if session.is_some() {
+            return Err("The recorder is already running. Stop it first.".to_string());
+        }
+
+        let (index, format) = match &source {
+            RecordSource::Input { device_id } => {
+                let index = device_id.index().map_err(|e| e.to_string())?;
+                (index, self.backend.input_format(index))
+            }
+            RecordSource::Loopback { device_id } => {
+                let index = device_id.index().map_err(|e| e.to_string())?;
+                (index, self.backend.output_format(index))
+            }
+        };
+        let format = format.map_err(|e| format!("Recording device unavailable: {}", e))?;
+
+        let (overflow, max_secs) = match mode {
+            RecordMode::Record => (Overflow::Stop, MAX_RECORDING_SECS),
+            RecordMode::Replay { seconds } => {
+                (Overflow::Rolling, seconds.clamp(1, MAX_REPLAY_SECS))
+            }
+        };
+        let channels = format.channels.max(1) as usize;
+        // A whole number of frames, so writes and drains never split one
+        let ring_samples = RING_SECS * format.sample_rate as usize * channels;
+        let (mut producer, consumer) = ring_buffer(ring_samples, 0, 1, 1);
+        let capture = Arc::new(Mutex::new(Capture {
+            ring: consumer,
+            scratch: vec![0.0; ring_samples],
+            channels,
+            buffer: CaptureBuffer::new(
+                max_secs as usize * format.sample_rate as usize,
+                channels,
+                overflow,
+            ),
+        }));
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        let failed = Arc::new(AtomicBool::new(false));
+
+        // Streams aren't Send, so one thread opens, keeps and drops the stream
+        let (opened_tx, opened_rx) = mpsc::channel();
+        let thread = {
+            let backend = self.backend.clone();
+            let source = source.clone();
+            let capture = capture.clone();
+            let stop_signal = stop_signal.clone();
+            let failed = failed.clone();
+            thread::spawn(move || {
+                // Runs on the audio thread: no locks, no allocation
+                let on_data = Box::new(move |data: &[f32]| {
+                    producer.write(data);
+                });
+                let on_error: ErrorCallback = Arc::new(move |err: String| {
+                    error!("Recording stream error: {}", err);
+                    failed.store(true, Ordering::Relaxed);
+                });
+                let stream = match source {
+                    RecordSource::Input { .. } => backend.open_input(index, on_data, on_error),
+                    RecordSource::Loopback { .. } => {
+                        backend.open_loopback(index, on_data, on_error)
+                    }
+                };
+                let stream = match stream {
+                    Ok(stream) => {
+                        let _ = opened_tx.send(Ok(()));
+                        stream
+                    }
+                    Err(e) => {
+                        let _ = opened_tx.send(Err(e));
+                        return;
+                    }
+                };
+
+                while !stop_signal.load(Ordering::Relaxed) {
+                    capture.lock().unwrap().drain();
+                    thread::sleep(DRAIN_INTERVAL);
+                }
+                drop(stream);
+            })
+        };
+
+        match opened_rx.recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                return Err(format!("Failed to open recording stream: {}", e));
+            }
+            Err(_) => {
+                let _ = thread.join();
+                return Err("Recording thread exited unexpectedly".to_string());
+            }
+        }
+
+        info!(
+            source = ?source,
+            mode = ?mode,
+            sample_rate = format.sample_rate,
+            channels = format.channels,
+            "Recorder started"
+        );
+        *session = Some(Session {
+            source,
+            mode,
+            max_secs,
+            sample_rate: format.sample_rate,
+            channels: format.channels,
+            capture,
+            stop_signal,
+            failed,
+            thread,
+        });
+        Ok(())
+    }
+
+    /// The running session, if any
+    pub fn status(&self) -> Option<RecorderStatus> {
+        let session = self.session.lock().unwrap();
+        let session = session.as_ref()?;
+        let (frames, full) = {
+            let mut capture = session.capture.lock().unwrap();
+            let buffer = capture.drain();
+            (buffer.frames(), buffer.is_full())
+        };
+        Some(RecorderStatus {
+            source: session.source.clone(),
+            mode: session.mode,
+            recorded_secs: frames as f32 / session.sample_rate as f32,
+            max_secs: session.max_secs,
+            full,
+            failed: session.failed.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Copy of the recorded audio (the replay window in instant replay)
+    ///
+    /// The session keeps running: a recording only ends once it is saved (see
+    /// [`Recorder::saved`]), so a failed save loses nothing.
+    pub fn audio(&self) -> Result<AudioData, String> {
+        let guard = self.session.lock().unwrap();
+        let session = guard.as_ref().ok_or("The recorder is not running")?;
+        let mut capture = session.capture.lock().unwrap();
+        let buffer = capture.drain();
+        if buffer.frames() == 0 {
+            return Err("Nothing has been recorded yet".to_string());
+        }
+        Ok(AudioData {
+            samples: buffer.snapshot(),
+            sample_rate: session.sample_rate,
+            channels: session.channels,
+        })
+    }
+
+    /// The audio was saved: a recording ends, instant replay keeps running
+    pub fn saved(&self) {
+        let mut guard = self.session.lock().unwrap();
+        if guard
+            .as_ref()
+            .is_some_and(|session| session.mode == RecordMode::Record)
+        {
+            guard.take().expect("session checked above").stop();
+            info!("Recording stopped");
+        }
+    }
+
+    /// Stop without keeping anything
+    pub fn stop(&self) {
+        if let Some(session) = self.session.lock().unwrap().take() {
+            session.stop();
+            info!("Recorder stopped");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{FakeBackend, StreamLatency};
+
+    fn backend() -> FakeBackend {
+        let backend = FakeBackend::new();
+        backend.add_input("Microphone", 100, 1);
+        backend.add_output("Speakers", 100, 2);
+        backend
+    }
+
+    fn input(index: usize) -> RecordSource {
+        RecordSource::Input {
+            device_id: DeviceId::from_index(index),
+        }
+    }
+
+    #[test]
+    fn test_record_until_saved() {
+        let backend = backend();
+        let recorder = Recorder::new(Arc::new(backend.clone()));
+
+        recorder.start(input(0), RecordMode::Record).unwrap();
+        assert!(backend.input_open(0));
+        assert!(recorder.audio().is_err(), "nothing captured yet");
+
+        backend.push_input(0, &[0.1; 150]);
+        backend.push_input(0, &[0.2; 50]);
+        let status = recorder.status().unwrap();
+        assert_eq!(status.recorded_secs, 2.0);
+        assert_eq!(status.max_secs, MAX_RECORDING_SECS);
+        assert!(!status.full && !status.failed);
+
+        let audio = recorder.audio().unwrap();
+        assert_eq!((audio.sample_rate, audio.channels), (100, 1));
+        assert_eq!(audio.samples.len(), 200);
+        assert_eq!(audio.samples[199], 0.2);
+
+        // Until the save succeeds the recording keeps going
+        assert!(backend.input_open(0));
+        assert_eq!(recorder.audio().unwrap().samples, audio.samples);
+
+        // Saving ends the session and closes the stream
+        recorder.saved();
+        assert!(recorder.status().is_none());
+        assert!(!backend.input_open(0));
+    }
+
+    #[test]
+    fn test_replay_keeps_the_last_seconds_and_keeps_running() {
+        let backend = backend();
+        let recorder = Recorder::new(Arc::new(backend.clone()));
+        recorder
+            .start(input(0), RecordMode::Replay { seconds: 2 })
+            .unwrap();
+
+        // Five seconds of numbered samples: only the last two survive
+        let samples: Vec<f32> = (0..500).map(|i| i as f32).collect();
+        for block in samples.chunks(32) {
+            backend.push_input(0, block);
+            // Drains the ring, as the capture thread does every few ms
+            recorder.status();
+        }
+        let status = recorder.status().unwrap();
+        assert_eq!(status.recorded_secs, 2.0);
+        assert!(status.full);
+
+        let replay = recorder.audio().unwrap();
+        assert_eq!(replay.samples, samples[300..]);
+
+        // Still capturing after the save
+        recorder.saved();
+        assert!(backend.input_open(0));
+        backend.push_input(0, &[-1.0; 10]);
+        let replay = recorder.audio().unwrap();
+        assert_eq!(replay.samples.len(), 200);
+        assert_eq!(replay.samples[199], -1.0);
+
+        recorder.stop();
+        assert!(recorder.status().is_none());
+        assert!(!backend.input_open(0));
+    }
+
+    #[test]
+    fn test_loopback_records_what_the_output_plays() {
+        let backend = backend();
+        let recorder = Recorder::new(Arc::new(backend.clone()));
+        recorder
+            .start(
+                RecordSource::Loopback {
+                    device_id: DeviceId::from_index(0),
+                },
+                RecordMode::Record,
+            )
+            .unwrap();
+        assert!(backend.loopback_open(0));
+
+        let _output = backend
+            .open_output(
+                0,
+                StreamLatency::Default,
+                Box::new(|data: &mut [f32]| data.fill(0.5)),
+                Arc::new(|_| {}),
+            )
+            .unwrap();
+        backend.render_output(0, 50);
+
+        let audio = recorder.audio().unwrap();
+        assert_eq!((audio.sample_rate, audio.channels), (100, 2));
+        assert_eq!(audio.samples, vec![0.5; 100]);
+        recorder.saved();
+        assert!(!backend.loopback_open(0));
+    }
+
+    #[test]
+    fn test_one_session_at_a_time() {
+        let backend = backend();
+        let recorder = Recorder::new(Arc::new(backend.clone()));
+
+        assert!(recorder.start(input(5), RecordMode::Record).is_err());
+        assert!(recorder.status().is_none());
+
+        recorder.start(input(0), RecordMode::Record).unwrap();
+        assert!(recorder
+            .start(input(0), RecordMode::Replay { seconds: 10 })
+            .is_err());
+        recorder.stop();
+        recorder
+            .start(input(0), RecordMode::Replay { seconds: 10_000 })
+            .unwrap();
+        assert_eq!(recorder.status().unwrap().max_secs, MAX_REPLAY_SECS);
+    }
+
+    #[test]
+    fn test_input_callback_never_waits_for_the_buffer() {
+        let backend = backend();
+        let recorder = Recorder::new(Arc::new(backend.clone()));
+        recorder.start(input(0), RecordMode::Record).unwrap();
+
+        // Capture lands in the ring while the buffer is locked...
+        let capture = recorder
+            .session
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .capture
+            .clone();
+        let held = capture.lock().unwrap();
+        backend.push_input(0, &[0.3; 40]);
+        drop(held);
+
+        // ...and reaches the buffer on the next drain
+        assert_eq!(recorder.audio().unwrap().samples, vec![0.3; 40]);
+    }
+
+    #[test]
+    fn test_stream_errors_are_reported() {
+        let backend = backend();
+        let recorder = Recorder::new(Arc::new(backend.clone()));
+        recorder.start(input(0), RecordMode::Record).unwrap();
+
+        backend.fail_input(0);
+        assert!(recorder.status().unwrap().failed);
+    }
+
+    #[test]
+    fn test_list_sources() {
+        let backend = backend();
+        let sources = list_sources(&backend);
+        assert_eq!(
+            sources,
+            vec![
+                SourceInfo {
+                    source: input(0),
+                    name: "Microphone".to_string(),
+                },
+                SourceInfo {
+                    source: RecordSource::Loopback {
+                        device_id: DeviceId::from_index(0),
+                    },
+                    name: "Speakers (what it plays)".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_serde_format() {
+        let json = serde_json::to_string(&input(1)).unwrap();
+        assert_eq!(json, r#"{"type":"input","device_id":"device_1"}"#);
+        let mode: RecordMode = serde_json::from_str(r#"{"type":"replay","seconds":30}"#).unwrap();
+        assert_eq!(mode, RecordMode::Replay { seconds: 30 });
+    }
+}
@@ -0,0 +1,145 @@
+//! Remote API wiring into the running app
+
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{Emitter, Listener, Manager};
+use tracing::error;
+
+use super::config::RemoteApiConfig;
+use super::events::{EventHub, FORWARDED_EVENTS};
+use super::server::{RemoteHandler, RemoteServer, TriggerResult};
+use super::surface::SurfaceHub;
+use crate::audio::AudioManager;
+use crate::commands;
+use crate::sounds::{SoundId, SoundLibrary};
+use crate::AppState;
+
+/// Remote API server of the app (managed Tauri state)
+#[derive(Default)]
+pub struct RemoteState {
+    server: Mutex<Option<RemoteServer>>,
+    /// Error of the last start attempt
+    last_error: Mutex<Option<String>>,
+    /// Outlives server restarts so event forwarding is registered once
+    events: Arc<EventHub>,
+    /// Control-surface buttons (kept up to date while the server is off)
+    surface: Arc<SurfaceHub>,
+}
+
+/// Remote API state for the settings UI
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    pub error: Option<String>,
+    /// Connected WebSocket clients
+    pub clients: usize,
+}
+
+impl RemoteState {
+    pub fn status(&self) -> RemoteStatus {
+        let server = self.server.lock().unwrap();
+        RemoteStatus {
+            running: server.is_some(),
+            address: server.as_ref().map(|server| server.address().to_string()),
+            error: self.last_error.lock().unwrap().clone(),
+            clients: self.events.subscriber_count() + self.surface.client_count(),
+        }
+    }
+}
+
+/// Serves remote requests from the app's state
+struct AppRemote {
+    app: tauri::AppHandle,
+}
+
+impl RemoteHandler for AppRemote {
+    fn library(&self) -> SoundLibrary {
+        self.app.state::<AppState>().read_sounds().clone()
+    }
+
+    fn play(&self, sound_id: &SoundId) -> Result<TriggerResult, String> {
+        let (sound, result) = commands::play_library_sound(&self.app, sound_id, 1.0)?;
+        Ok(TriggerResult {
+            sound_id: sound.id,
+            name: sound.name,
+            playback_id: result.playback_id,
+            action: result.action,
+        })
+    }
+
+    fn stop_all(&self) -> Result<(), String> {
+        self.app.state::<AudioManager>().stop_all();
+        Ok(())
+    }
+
+    fn stop_sound(&self, sound_id: &SoundId) -> Result<bool, String> {
+        let manager = self.app.state::<AudioManager>();
+        Ok(manager
+            .get_sound_state(sound_id.as_str())
+            .is_some_and(|state| manager.signal_stop(state.playback_id())))
+    }
+
+    fn volume(&self) -> f32 {
+        self.app.state::<AppState>().read_settings().default_volume
+    }
+
+    fn set_volume(&self, volume: f32) -> Result<(), String> {
+        self.app
+            .state::<AppState>()
+            .update_settings(|settings| settings.default_volume = volume)?;
+        // The UI reloads its copy so its next save doesn't revert this
+        let _ = self.app.emit("settings-changed", ());
+        Ok(())
+    }
+}
+
+/// Start, restart or stop the server to match the settings
+pub fn apply_settings(app: &tauri::AppHandle, config: &RemoteApiConfig) {
+    let remote = app.state::<RemoteState>();
+    let mut server = remote.server.lock().unwrap();
+    // Stop first so a restart can bind the same port
+    *server = None;
+    *remote.last_error.lock().unwrap() = None;
+    if !config.enabled {
+        return;
+    }
+
+    let handler = Arc::new(AppRemote { app: app.clone() });
+    match RemoteServer::start(
+        config,
+        handler,
+        remote.events.clone(),
+        remote.surface.clone(),
+    ) {
+        Ok(started) => *server = Some(started),
+        Err(e) => {
+            error!("Failed to start remote API: {}", e);
+            *remote.last_error.lock().unwrap() = Some(e);
+        }
+    }
+}
+
+/// Forward playback events to remote clients (call once at startup)
+pub fn forward_events(app: &tauri::AppHandle) {
+    let remote = app.state::<RemoteState>();
+    for &name in FORWARDED_EVENTS {
+        let events = remote.events.clone();
+        app.listen_any(name, move |event| events.publish(name, event.payload()));
+    }
+
+    // Control surfaces follow playback and library edits
+    let surface = remote.surface.clone();
+    surface.set_library(app.state::<AppState>().read_sounds().clone());
+    {
+        let surface = surface.clone();
+        app.listen_any("sound-state-changed", move |event| {
+            surface.apply_state_event(event.payload())
+        });
+    }
+    let handle = app.clone();
+    app.listen_any("sounds-changed", move |_| {
+        surface.set_library(handle.state::<AppState>().read_sounds().clone())
+    });
+}
@@ -0,0 +1,83 @@
+//! Remote-control API settings
+
+use serde::{Deserialize, Serialize};
+
+/// Default port of the remote-control API
+pub const DEFAULT_REMOTE_PORT: u16 = 7878;
+
+/// Which interfaces the remote-control API listens on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteBind {
+    /// Only this machine (127.0.0.1)
+    #[default]
+    Localhost,
+    /// Every interface, so phones and other PCs on the network can connect
+    Lan,
+}
+
+/// Remote-control API configuration (stored in settings)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteApiConfig {
+    pub enabled: bool,
+    pub bind: RemoteBind,
+    pub port: u16,
+    /// Secret clients must send; generated when the API is first enabled
+    pub token: String,
+}
+
+impl Default for RemoteApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: RemoteBind::default(),
+            port: DEFAULT_REMOTE_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+impl RemoteApiConfig {
+    /// Socket address to listen on
+    pub fn address(&self) -> String {
+        let host = match self.bind {
+            RemoteBind::Localhost => "127.0.0.1",
+            RemoteBind::Lan => "0.0.0.0",
+        };
+        format!("{}:{}", host, self.port)
+    }
+}
+
+/// Random 128-bit token as hex
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to generate token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_and_serde() {
+        let config: RemoteApiConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, RemoteApiConfig::default());
+        assert!(!config.enabled);
+        assert_eq!(config.address(), "127.0.0.1:7878");
+
+        let lan: RemoteApiConfig =
+            serde_json::from_str(r#"{"enabled":true,"bind":"lan","port":9000}"#).unwrap();
+        assert_eq!(lan.address(), "0.0.0.0:9000");
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let a = generate_token().unwrap();
+        let b = generate_token().unwrap();
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,75 @@
+//! Fan-out of app events to WebSocket clients
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+/// Events forwarded to remote clients
+pub const FORWARDED_EVENTS: &[&str] = &[
+    "playback-progress",
+    "playback-complete",
+    "sound-state-changed",
+];
+
+/// Broadcasts events to every subscribed client
+#[derive(Default)]
+pub struct EventHub {
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+impl EventHub {
+    /// Register a client; messages arrive as JSON text
+    pub fn subscribe(&self) -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Send `{"event": name, "payload": ...}` to all clients (payload is JSON text)
+    pub fn publish(&self, event: &str, payload: &str) {
+        if self.subscriber_count() == 0 {
+            return;
+        }
+        let payload = serde_json::from_str::<Value>(payload).unwrap_or(Value::Null);
+        self.broadcast(&json!({ "event": event, "payload": payload }).to_string());
+    }
+
+    /// Send a message as is to all clients
+    pub fn broadcast(&self, message: &str) {
+        // Disconnected clients drop their receiver
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.send(message.to_string()).is_ok());
+    }
+
+    /// Drop all clients (their feeds end)
+    pub fn disconnect_all(&self) {
+        self.subscribers.lock().unwrap().clear();
+    }
+
+    /// Number of connected clients
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_reaches_subscribers_and_drops_closed() {
+        let hub = EventHub::default();
+        let a = hub.subscribe();
+        let b = hub.subscribe();
+        drop(b);
+
+        hub.publish("playback-complete", "\"playback_1\"");
+        let message: Value = serde_json::from_str(&a.recv().unwrap()).unwrap();
+        assert_eq!(message["event"], "playback-complete");
+        assert_eq!(message["payload"], "playback_1");
+        assert_eq!(hub.subscriber_count(), 1);
+    }
+}
@@ -0,0 +1,15 @@
+//! Remote-control API
+//!
+//! Opt-in local HTTP server (localhost or LAN, token protected) so sounds can be
+//! triggered from a phone or a second PC, with a WebSocket feed of playback events.
+//! Control surfaces (Stream Deck, Bitfocus Companion) get every category and
+//! sound as a button that lights up while it plays.
+
+mod app;
+mod config;
+mod events;
+mod server;
+mod surface;
+
+pub use app::{apply_settings, forward_events, RemoteState, RemoteStatus};
+pub use config::{generate_token, RemoteApiConfig};
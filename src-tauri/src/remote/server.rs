@@ -0,0 +1,811 @@
+//! HTTP/WebSocket server of the remote-control API
+//!
+//! Every request needs the token, as `Authorization: Bearer <token>` or a
+//! `?token=` query parameter (browsers can't set headers on WebSockets).
+//!
+//! - `GET  /api/sounds`: categories and sounds (without file paths)
+//! - `POST /api/sounds/{id}/play`: trigger a sound by ID
+//! - `POST /api/play?name=...`: trigger a sound by name (case-insensitive)
+//! - `POST /api/stop`: stop all playback
+//! - `GET|PUT /api/volume`: default volume as `{"volume": 0.0-1.0}`
+//! - `GET  /api/events`: WebSocket feed of playback events
+//! - `GET  /api/surface`: control-surface buttons (see [`super::surface`])
+//! - `POST /api/surface/{button}/press`: press a button
+//! - `GET  /api/surface/feed`: WebSocket feed of the layout and button updates
+
+use std::io::{Cursor, Read};
+use std::net::SocketAddr;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+use tracing::{debug, info, warn};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use super::config::RemoteApiConfig;
+use super::events::EventHub;
+use super::surface::{PressAction, SurfaceHub};
+use crate::sounds::{CategoryId, Sound, SoundId, SoundLibrary};
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// What the server needs from the app
+pub trait RemoteHandler: Send + Sync {
+    /// Current sound library
+    fn library(&self) -> SoundLibrary;
+    /// Play a sound on the configured devices
+    fn play(&self, sound_id: &SoundId) -> Result<TriggerResult, String>;
+    /// Stop all playback
+    fn stop_all(&self) -> Result<(), String>;
+    /// Stop a sound if it's playing (true if it was)
+    fn stop_sound(&self, sound_id: &SoundId) -> Result<bool, String>;
+    /// Default volume (0.0 - 1.0)
+    fn volume(&self) -> f32;
+    /// Set and persist the default volume
+    fn set_volume(&self, volume: f32) -> Result<(), String>;
+}
+
+/// Outcome of a remote trigger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerResult {
+    pub sound_id: SoundId,
+    pub name: String,
+    pub playback_id: Option<String>,
+    /// "started", "restarted" or "ignored" (as for play_dual_output)
+    pub action: String,
+}
+
+/// Sound as listed to remote clients
+#[derive(Debug, Serialize)]
+struct RemoteSound<'a> {
+    id: &'a SoundId,
+    name: &'a str,
+    category_id: &'a CategoryId,
+    icon: Option<&'a str>,
+    is_favorite: bool,
+}
+
+/// Category as listed to remote clients
+#[derive(Debug, Serialize)]
+struct RemoteCategory<'a> {
+    id: &'a CategoryId,
+    name: &'a str,
+    icon: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VolumeBody {
+    volume: f32,
+}
+
+/// Running remote-control server (stops when dropped)
+pub struct RemoteServer {
+    server: Arc<Server>,
+    address: SocketAddr,
+    events: Arc<EventHub>,
+    surface: Arc<SurfaceHub>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RemoteServer {
+    /// Listen on the configured address and serve requests on background threads
+    pub fn start(
+        config: &RemoteApiConfig,
+        handler: Arc<dyn RemoteHandler>,
+        events: Arc<EventHub>,
+        surface: Arc<SurfaceHub>,
+    ) -> Result<Self, String> {
+        if config.token.is_empty() {
+            return Err("Remote API token is not set".to_string());
+        }
+
+        let server = Server::http(config.address())
+            .map_err(|e| format!("Failed to listen on {}: {}", config.address(), e))?;
+        let address = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| "Remote API is not bound to an IP address".to_string())?;
+        let server = Arc::new(server);
+
+        let context = Arc::new(Context {
+            token: config.token.clone(),
+            handler,
+            events: events.clone(),
+            surface: surface.clone(),
+        });
+        let accept_server = server.clone();
+        let thread = thread::Builder::new()
+            .name("remote-api".to_string())
+            .spawn(move || {
+                for request in accept_server.incoming_requests() {
+                    let context = context.clone();
+                    thread::spawn(move || context.handle(request));
+                }
+            })
+            .map_err(|e| format!("Failed to start remote API thread: {}", e))?;
+
+        info!(address = %address, "Remote API listening");
+        Ok(Self {
+            server,
+            address,
+            events,
+            surface,
+            thread: Some(thread),
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        // Ends the WebSocket feeds
+        self.events.disconnect_all();
+        self.surface.disconnect_all();
+        info!(address = %self.address, "Remote API stopped");
+    }
+}
+
+/// State shared by request threads
+struct Context {
+    token: String,
+    handler: Arc<dyn RemoteHandler>,
+    events: Arc<EventHub>,
+    surface: Arc<SurfaceHub>,
+}
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+impl Context {
+    fn handle(&self, mut request: Request) {
+        let (path, query) = split_url(request.url());
+        let method = request.method().clone();
+        debug!(method = %method, path = %path, "Remote API request");
+
+        // CORS preflight (browser remotes send the token in a header)
+        if method == Method::Options {
+            let _ = request.respond(with_cors(Response::from_data(Vec::new())));
+            return;
+        }
+
+        let token = bearer_token(&request).or_else(|| query_param(&query, "token"));
+        if !token.is_some_and(|token| tokens_match(&token, &self.token)) {
+            let _ = request.respond(error(401, "Invalid or missing token"));
+            return;
+        }
+
+        if method == Method::Get && path == "/api/events" {
+            stream_websocket(request, None, self.events.subscribe());
+            return;
+        }
+        if method == Method::Get && path == "/api/surface/feed" {
+            let (layout, updates) = self.surface.subscribe();
+            stream_websocket(request, Some(layout), updates);
+            return;
+        }
+
+        let body = match read_body(&mut request) {
+            Ok(body) => body,
+            Err(response) => {
+                let _ = request.respond(response);
+                return;
+            }
+        };
+        let response = self.route(&method, &path, &query, &body);
+        let _ = request.respond(response);
+    }
+
+    fn route(&self, method: &Method, path: &str, query: &str, body: &str) -> HttpResponse {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            (Method::Get, ["api", "sounds"]) => self.list_sounds(),
+            (Method::Post, ["api", "sounds", id, "play"]) => {
+                let id = percent_decode(id);
+                self.play_matching(&id, |sound| sound.id.as_str() == id)
+            }
+            (Method::Post, ["api", "play"]) => match query_param(query, "name") {
+                Some(name) => {
+                    self.play_matching(&name, |sound| sound.name.eq_ignore_ascii_case(name.trim()))
+                }
+                None => error(400, "Missing 'name' parameter"),
+            },
+            (Method::Post, ["api", "stop"]) => match self.handler.stop_all() {
+                Ok(()) => json_response(200, &json!({ "stopped": true })),
+                Err(e) => error(500, &e),
+            },
+            (Method::Get, ["api", "volume"]) => {
+                json_response(200, &json!({ "volume": self.handler.volume() }))
+            }
+            (Method::Put | Method::Post, ["api", "volume"]) => self.set_volume(body),
+            (Method::Get, ["api", "surface"]) => {
+                json_response(200, &json!({ "buttons": self.surface.buttons() }))
+            }
+            (Method::Post, ["api", "surface", button, "press"]) => {
+                self.press(&percent_decode(button))
+            }
+            _ => error(404, "Not found"),
+        }
+    }
+
+    fn list_sounds(&self) -> HttpResponse {
+        let library = self.handler.library();
+        let categories: Vec<RemoteCategory> = library
+            .categories
+            .iter()
+            .map(|category| RemoteCategory {
+                id: &category.id,
+                name: &category.name,
+                icon: category.icon.as_deref(),
+            })
+            .collect();
+        let sounds: Vec<RemoteSound> = library
+            .sounds
+            .iter()
+            .map(|sound| RemoteSound {
+                id: &sound.id,
+                name: &sound.name,
+                category_id: &sound.category_id,
+                icon: sound.icon.as_deref(),
+                is_favorite: sound.is_favorite,
+            })
+            .collect();
+        json_response(200, &json!({ "categories": categories, "sounds": sounds }))
+    }
+
+    /// Play the first library sound matching `predicate`
+    fn play_matching(&self, label: &str, predicate: impl Fn(&Sound) -> bool) -> HttpResponse {
+        let sound_id = self
+            .handler
+            .library()
+            .sounds
+            .into_iter()
+            .find(|sound| predicate(sound))
+            .map(|sound| sound.id);
+        let Some(sound_id) = sound_id else {
+            return error(404, &format!("Sound not found: {}", label));
+        };
+        match self.handler.play(&sound_id) {
+            Ok(result) => json_response(200, &result),
+            Err(e) => error(500, &e),
+        }
+    }
+
+    fn set_volume(&self, body: &str) -> HttpResponse {
+        let volume = match serde_json::from_str::<VolumeBody>(body) {
+            Ok(body) if body.volume.is_finite() => body.volume.clamp(0.0, 1.0),
+            _ => return error(400, "Expected {\"volume\": 0.0-1.0}"),
+        };
+        match self.handler.set_volume(volume) {
+            Ok(()) => json_response(200, &json!({ "volume": volume })),
+            Err(e) => error(500, &e),
+        }
+    }
+
+    fn press(&self, button: &str) -> HttpResponse {
+        match self.surface.press(button) {
+            Some(PressAction::Play(sound_id)) => match self.handler.play(&sound_id) {
+                Ok(result) => json_response(200, &result),
+                Err(e) => error(500, &e),
+            },
+            Some(PressAction::Stop(sound_ids)) => {
+                let mut stopped = 0;
+                for sound_id in &sound_ids {
+                    match self.handler.stop_sound(sound_id) {
+                        Ok(true) => stopped += 1,
+                        Ok(false) => {}
+                        Err(e) => return error(500, &e),
+                    }
+                }
+                json_response(200, &json!({ "stopped": stopped }))
+            }
+            None => error(404, &format!("Button not found: {}", button)),
+        }
+    }
+}
+
+/// Switch the connection to a WebSocket and stream messages until it closes
+fn stream_websocket(request: Request, first: Option<String>, messages: Receiver<String>) {
+    let Some(key) = header_value(&request, "Sec-WebSocket-Key") else {
+        let _ = request.respond(error(400, "Expected a WebSocket upgrade"));
+        return;
+    };
+
+    let response = Response::empty(101)
+        .with_header(header("Upgrade", "websocket"))
+        .with_header(header("Connection", "Upgrade"))
+        .with_header(header(
+            "Sec-WebSocket-Accept",
+            &derive_accept_key(key.as_bytes()),
+        ));
+    let stream = request.upgrade("websocket", response);
+    debug!("Remote API WebSocket client connected");
+
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    for message in first.into_iter().chain(messages) {
+        if let Err(e) = socket.send(Message::text(message)) {
+            debug!(error = %e, "Remote API WebSocket client disconnected");
+            return;
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+}
+
+/// Read the request body (bounded), or the error response to send
+fn read_body(request: &mut Request) -> Result<String, HttpResponse> {
+    if request
+        .body_length()
+        .is_some_and(|len| len as u64 > MAX_BODY_BYTES)
+    {
+        return Err(error(413, "Request body too large"));
+    }
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| {
+            warn!(error = %e, "Failed to read remote API request body");
+            error(400, "Invalid request body")
+        })?;
+    Ok(body)
+}
+
+fn split_url(url: &str) -> (String, String) {
+    match url.split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (url.to_string(), String::new()),
+    }
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+}
+
+/// Decode `%XX` escapes and `+` (as sent by forms and URL builders)
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                if let (Some(high), Some(low)) = (hex_value(bytes[i + 1]), hex_value(bytes[i + 2]))
+                {
+                    decoded.push(high << 4 | low);
+                    i += 3;
+                    continue;
+                }
+                decoded.push(b'%');
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+fn header_value(request: &Request, name: &'static str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str().to_string())
+}
+
+fn bearer_token(request: &Request) -> Option<String> {
+    header_value(request, "Authorization")?
+        .strip_prefix("Bearer ")
+        .map(|token| token.trim().to_string())
+}
+
+/// Compare in constant time so the token can't be guessed byte by byte
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+fn with_cors(response: HttpResponse) -> HttpResponse {
+    response
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .with_header(header(
+            "Access-Control-Allow-Headers",
+            "Authorization, Content-Type",
+        ))
+        .with_header(header(
+            "Access-Control-Allow-Methods",
+            "GET, POST, PUT, OPTIONS",
+        ))
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> HttpResponse {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    with_cors(
+        Response::from_data(body)
+            .with_status_code(StatusCode(status))
+            .with_header(header("Content-Type", "application/json")),
+    )
+}
+
+fn error(status: u16, message: &str) -> HttpResponse {
+    json_response(status, &json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::SoundPlayState;
+    use crate::remote::config::RemoteBind;
+    use crate::sounds;
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    const TOKEN: &str = "secret-token";
+
+    struct FakeHandler {
+        library: SoundLibrary,
+        played: Mutex<Vec<SoundId>>,
+        stops: Mutex<usize>,
+        stopped_sounds: Mutex<Vec<SoundId>>,
+        volume: Mutex<f32>,
+    }
+
+    impl RemoteHandler for FakeHandler {
+        fn library(&self) -> SoundLibrary {
+            self.library.clone()
+        }
+
+        fn play(&self, sound_id: &SoundId) -> Result<TriggerResult, String> {
+            self.played.lock().unwrap().push(sound_id.clone());
+            let sound = self.library.sounds.iter().find(|s| s.id == *sound_id);
+            Ok(TriggerResult {
+                sound_id: sound_id.clone(),
+                name: sound.map(|s| s.name.clone()).unwrap_or_default(),
+                playback_id: Some("playback_1".to_string()),
+                action: "started".to_string(),
+            })
+        }
+
+        fn stop_all(&self) -> Result<(), String> {
+            *self.stops.lock().unwrap() += 1;
+            Ok(())
+        }
+
+        fn stop_sound(&self, sound_id: &SoundId) -> Result<bool, String> {
+            self.stopped_sounds.lock().unwrap().push(sound_id.clone());
+            Ok(true)
+        }
+
+        fn volume(&self) -> f32 {
+            *self.volume.lock().unwrap()
+        }
+
+        fn set_volume(&self, volume: f32) -> Result<(), String> {
+            *self.volume.lock().unwrap() = volume;
+            Ok(())
+        }
+    }
+
+    fn start() -> (
+        RemoteServer,
+        Arc<FakeHandler>,
+        Arc<EventHub>,
+        Arc<SurfaceHub>,
+    ) {
+        let mut library = SoundLibrary {
+            categories: Vec::new(),
+            sounds: Vec::new(),
+        };
+        let category = sounds::add_category(&mut library, "Memes".to_string(), None);
+        for name in ["Air Horn", "Rimshot"] {
+            sounds::add_sound(
+                &mut library,
+                name.to_string(),
+                format!("C:/sounds/{}.mp3", name),
+                category.id.clone(),
+                None,
+                None,
+            );
+        }
+        let handler = Arc::new(FakeHandler {
+            library,
+            played: Mutex::default(),
+            stops: Mutex::default(),
+            stopped_sounds: Mutex::default(),
+            volume: Mutex::new(0.5),
+        });
+        let events = Arc::new(EventHub::default());
+        let surface = Arc::new(SurfaceHub::default());
+        surface.set_library(handler.library.clone());
+        let config = RemoteApiConfig {
+            enabled: true,
+            bind: RemoteBind::Localhost,
+            port: 0,
+            token: TOKEN.to_string(),
+        };
+        let server =
+            RemoteServer::start(&config, handler.clone(), events.clone(), surface.clone()).unwrap();
+        (server, handler, events, surface)
+    }
+
+    /// Minimal HTTP/1.1 client: returns the status and the JSON body
+    fn send(
+        server: &RemoteServer,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+        let mut request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            path,
+            body.len()
+        );
+        if let Some(token) = token {
+            request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1;
+        (status, serde_json::from_str(body).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_requires_token() {
+        let (server, handler, _, _) = start();
+        assert_eq!(send(&server, "GET", "/api/sounds", None, "").0, 401);
+        assert_eq!(
+            send(&server, "GET", "/api/sounds", Some("wrong"), "").0,
+            401
+        );
+        assert_eq!(
+            send(&server, "POST", "/api/stop", Some("secret"), "").0,
+            401
+        );
+        assert_eq!(*handler.stops.lock().unwrap(), 0);
+
+        let path = format!("/api/sounds?token={}", TOKEN);
+        assert_eq!(send(&server, "GET", &path, None, "").0, 200);
+    }
+
+    #[test]
+    fn test_lists_library_without_file_paths() {
+        let (server, _, _, _) = start();
+        let (status, body) = send(&server, "GET", "/api/sounds", Some(TOKEN), "");
+        assert_eq!(status, 200);
+        assert_eq!(body["categories"][0]["name"], "Memes");
+        assert_eq!(body["sounds"].as_array().unwrap().len(), 2);
+        assert_eq!(body["sounds"][0]["name"], "Air Horn");
+        assert!(body["sounds"][0].get("file_path").is_none());
+    }
+
+    #[test]
+    fn test_play_by_id_and_name() {
+        let (server, handler, _, _) = start();
+        let rimshot = handler.library.sounds[1].id.clone();
+
+        let path = format!("/api/sounds/{}/play", rimshot.as_str());
+        let (status, body) = send(&server, "POST", &path, Some(TOKEN), "");
+        assert_eq!(status, 200);
+        assert_eq!(body["name"], "Rimshot");
+        assert_eq!(body["action"], "started");
+
+        let (status, body) = send(
+            &server,
+            "POST",
+            "/api/play?name=air%20horn",
+            Some(TOKEN),
+            "",
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["name"], "Air Horn");
+        assert_eq!(handler.played.lock().unwrap().len(), 2);
+
+        assert_eq!(
+            send(&server, "POST", "/api/play?name=Nope", Some(TOKEN), "").0,
+            404
+        );
+        assert_eq!(
+            send(&server, "POST", "/api/sounds/x/play", Some(TOKEN), "").0,
+            404
+        );
+        assert_eq!(send(&server, "POST", "/api/play", Some(TOKEN), "").0, 400);
+        assert_eq!(handler.played.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_stop_and_volume() {
+        let (server, handler, _, _) = start();
+        assert_eq!(send(&server, "POST", "/api/stop", Some(TOKEN), "").0, 200);
+        assert_eq!(*handler.stops.lock().unwrap(), 1);
+
+        let (status, body) = send(
+            &server,
+            "PUT",
+            "/api/volume",
+            Some(TOKEN),
+            r#"{"volume":1.5}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["volume"], 1.0);
+        let (_, body) = send(&server, "GET", "/api/volume", Some(TOKEN), "");
+        assert_eq!(body["volume"], 1.0);
+        assert_eq!(
+            send(&server, "PUT", "/api/volume", Some(TOKEN), "{}").0,
+            400
+        );
+        assert_eq!(
+            send(&server, "DELETE", "/api/volume", Some(TOKEN), "").0,
+            404
+        );
+    }
+
+    #[test]
+    fn test_event_feed_over_websocket() {
+        let (server, _, events, _) = start();
+        let url = format!("ws://{}/api/events?token={}", server.address(), TOKEN);
+        let (mut socket, _) = tungstenite::connect(url).unwrap();
+
+        // The subscription is registered right after the handshake response
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while events.subscriber_count() == 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        events.publish(
+            "playback-progress",
+            r#"{"playback_id":"playback_1","progress_pct":50}"#,
+        );
+
+        let message = socket.read().unwrap();
+        let message: serde_json::Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(message["event"], "playback-progress");
+        assert_eq!(message["payload"]["progress_pct"], 50);
+
+        // Stopping the server ends the feed
+        drop(server);
+        assert_eq!(events.subscriber_count(), 0);
+    }
+
+    /// Scripted surface: reads the layout, presses buttons over HTTP and
+    /// sees them light up while the sound plays
+    #[test]
+    fn test_control_surface_flow() {
+        let (server, handler, _, surface) = start();
+        let url = format!("ws://{}/api/surface/feed?token={}", server.address(), TOKEN);
+        let (mut socket, _) = tungstenite::connect(url).unwrap();
+        let mut next = || -> serde_json::Value {
+            let message = socket.read().unwrap();
+            serde_json::from_str(message.to_text().unwrap()).unwrap()
+        };
+
+        let layout = next();
+        assert_eq!(layout["type"], "layout");
+        let buttons = layout["buttons"].as_array().unwrap();
+        assert_eq!(buttons.len(), 3);
+        let category = buttons[0]["id"].as_str().unwrap().to_string();
+        let rimshot = buttons[2]["id"].as_str().unwrap().to_string();
+        assert_eq!(buttons[2]["title"], "Rimshot");
+
+        let (status, body) = send(&server, "GET", "/api/surface", Some(TOKEN), "");
+        assert_eq!(status, 200);
+        assert_eq!(body["buttons"], layout["buttons"]);
+
+        // Pressing a sound plays it
+        let path = format!("/api/surface/{}/press", rimshot);
+        let (status, body) = send(&server, "POST", &path, Some(TOKEN), "");
+        assert_eq!(status, 200);
+        assert_eq!(body["name"], "Rimshot");
+        let rimshot_id = handler.library.sounds[1].id.clone();
+        assert_eq!(
+            *handler.played.lock().unwrap(),
+            std::slice::from_ref(&rimshot_id)
+        );
+
+        // The app reports the state; sound and category light up
+        surface.set_sound_state(rimshot_id.as_str(), SoundPlayState::Playing);
+        let lit: Vec<_> = [next(), next()]
+            .iter()
+            .map(|update| {
+                assert_eq!(update["type"], "update");
+                assert_eq!(update["button"]["state"], "playing");
+                update["button"]["id"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(lit, [category.clone(), rimshot.clone()]);
+
+        // Pressing the category stops its playing sounds
+        let path = format!("/api/surface/{}/press", category);
+        let (status, body) = send(&server, "POST", &path, Some(TOKEN), "");
+        assert_eq!(status, 200);
+        assert_eq!(body["stopped"], 1);
+        assert_eq!(
+            *handler.stopped_sounds.lock().unwrap(),
+            std::slice::from_ref(&rimshot_id)
+        );
+
+        surface.set_sound_state(rimshot_id.as_str(), SoundPlayState::Idle);
+        assert_eq!(next()["button"]["state"], "idle");
+        assert_eq!(next()["button"]["state"], "idle");
+
+        assert_eq!(
+            send(
+                &server,
+                "POST",
+                "/api/surface/sound:x/press",
+                Some(TOKEN),
+                ""
+            )
+            .0,
+            404
+        );
+
+        drop(server);
+        assert_eq!(surface.client_count(), 0);
+    }
+
+    #[test]
+    fn test_start_requires_token_and_free_port() {
+        let (server, handler, events, surface) = start();
+        let taken = RemoteApiConfig {
+            port: server.address().port(),
+            token: TOKEN.to_string(),
+            ..Default::default()
+        };
+        assert!(
+            RemoteServer::start(&taken, handler.clone(), events.clone(), surface.clone()).is_err()
+        );
+
+        let no_token = RemoteApiConfig {
+            port: 0,
+            ..Default::default()
+        };
+        assert!(RemoteServer::start(&no_token, handler, events, surface).is_err());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("air%20horn+2"), "air horn 2");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+}
@@ -0,0 +1,377 @@
+//! Control-surface adapter (Stream Deck, Bitfocus Companion)
+//!
+//! Exposes every category and sound as a button with a title, the emoji icon
+//! and a playing state, and pushes changes to connected surfaces:
+//!
+//! - `{"type": "layout", "buttons": [...]}` on connect, and when buttons are
+//!   added, removed or reordered
+//! - `{"type": "update", "button": {...}}` when a button's title, icon or
+//!   state changes
+//!
+//! A category button shows the most active state of its sounds, so it lights
+//! up while any of them plays.
+
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::json;
+
+use super::events::EventHub;
+use crate::commands::{SoundPlayState, SoundStateChange};
+use crate::sounds::{SoundId, SoundLibrary};
+
+const CATEGORY_PREFIX: &str = "category:";
+const SOUND_PREFIX: &str = "sound:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonKind {
+    Category,
+    Sound,
+}
+
+/// Button of a control surface
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SurfaceButton {
+    /// `category:<id>` or `sound:<id>`
+    pub id: String,
+    pub kind: ButtonKind,
+    pub title: String,
+    pub icon: Option<String>,
+    /// Button of the sound's category (sounds only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub state: SoundPlayState,
+}
+
+/// What pressing a button does
+#[derive(Debug, PartialEq)]
+pub enum PressAction {
+    /// Sound button: play it (restart/ignore policy applies)
+    Play(SoundId),
+    /// Category button: stop its active sounds
+    Stop(Vec<SoundId>),
+}
+
+/// Library and playback states as last rendered
+struct Surface {
+    library: SoundLibrary,
+    /// sound_id -> state (idle sounds are absent)
+    states: HashMap<String, SoundPlayState>,
+    buttons: Vec<SurfaceButton>,
+}
+
+impl Surface {
+    fn render(&self) -> Vec<SurfaceButton> {
+        let mut categories: Vec<_> = self.library.categories.iter().collect();
+        categories.sort_by_key(|category| category.sort_order);
+
+        let mut buttons = Vec::with_capacity(categories.len() + self.library.sounds.len());
+        for category in &categories {
+            let state = self
+                .library
+                .sounds
+                .iter()
+                .filter(|sound| sound.category_id == category.id)
+                .map(|sound| self.sound_state(sound.id.as_str()))
+                .max()
+                .unwrap_or_default();
+            buttons.push(SurfaceButton {
+                id: format!("{}{}", CATEGORY_PREFIX, category.id.as_str()),
+                kind: ButtonKind::Category,
+                title: category.name.clone(),
+                icon: category.icon.clone(),
+                category: None,
+                state,
+            });
+        }
+        for category in &categories {
+            let category_button = format!("{}{}", CATEGORY_PREFIX, category.id.as_str());
+            for sound in self
+                .library
+                .sounds
+                .iter()
+                .filter(|sound| sound.category_id == category.id)
+            {
+                buttons.push(SurfaceButton {
+                    id: format!("{}{}", SOUND_PREFIX, sound.id.as_str()),
+                    kind: ButtonKind::Sound,
+                    title: sound.name.clone(),
+                    icon: sound.icon.clone(),
+                    category: Some(category_button.clone()),
+                    state: self.sound_state(sound.id.as_str()),
+                });
+            }
+        }
+        buttons
+    }
+
+    fn sound_state(&self, sound_id: &str) -> SoundPlayState {
+        self.states.get(sound_id).copied().unwrap_or_default()
+    }
+
+    /// Re-render and return the messages describing what changed
+    fn refresh(&mut self) -> Vec<String> {
+        let buttons = self.render();
+        let same_layout = buttons.len() == self.buttons.len()
+            && buttons
+                .iter()
+                .zip(&self.buttons)
+                .all(|(new, old)| new.id == old.id);
+        let messages = if same_layout {
+            buttons
+                .iter()
+                .zip(&self.buttons)
+                .filter(|(new, old)| new != old)
+                .map(|(new, _)| update_message(new))
+                .collect()
+        } else {
+            vec![layout_message(&buttons)]
+        };
+        self.buttons = buttons;
+        messages
+    }
+}
+
+impl Default for Surface {
+    fn default() -> Self {
+        Self {
+            library: SoundLibrary {
+                categories: Vec::new(),
+                sounds: Vec::new(),
+            },
+            states: HashMap::new(),
+            buttons: Vec::new(),
+        }
+    }
+}
+
+fn layout_message(buttons: &[SurfaceButton]) -> String {
+    json!({ "type": "layout", "buttons": buttons }).to_string()
+}
+
+fn update_message(button: &SurfaceButton) -> String {
+    json!({ "type": "update", "button": button }).to_string()
+}
+
+/// Button state shared by all connected surfaces
+#[derive(Default)]
+pub struct SurfaceHub {
+    surface: Mutex<Surface>,
+    clients: EventHub,
+}
+
+impl SurfaceHub {
+    /// Register a surface: returns the current layout and the feed of changes
+    pub fn subscribe(&self) -> (String, Receiver<String>) {
+        // Under the lock so no change falls between the layout and the feed
+        let surface = self.surface.lock().unwrap();
+        (layout_message(&surface.buttons), self.clients.subscribe())
+    }
+
+    /// Current buttons
+    pub fn buttons(&self) -> Vec<SurfaceButton> {
+        self.surface.lock().unwrap().buttons.clone()
+    }
+
+    /// Re-render after the library changed (names, icons, added sounds...)
+    pub fn set_library(&self, library: SoundLibrary) {
+        let mut surface = self.surface.lock().unwrap();
+        surface.library = library;
+        self.push(surface.refresh());
+    }
+
+    /// Track a sound's playback state
+    pub fn set_sound_state(&self, sound_id: &str, state: SoundPlayState) {
+        let mut surface = self.surface.lock().unwrap();
+        if state == SoundPlayState::Idle {
+            surface.states.remove(sound_id);
+        } else {
+            surface.states.insert(sound_id.to_string(), state);
+        }
+        self.push(surface.refresh());
+    }
+
+    /// Apply a `sound-state-changed` event payload
+    pub fn apply_state_event(&self, payload: &str) {
+        match serde_json::from_str::<SoundStateChange>(payload) {
+            Ok(change) => self.set_sound_state(change.sound_id.as_str(), change.state),
+            Err(e) => tracing::warn!("Invalid sound state event: {}", e),
+        }
+    }
+
+    /// Resolve a button press (None for unknown buttons)
+    pub fn press(&self, button_id: &str) -> Option<PressAction> {
+        let surface = self.surface.lock().unwrap();
+        let sounds = &surface.library.sounds;
+        if let Some(id) = button_id.strip_prefix(SOUND_PREFIX) {
+            return sounds
+                .iter()
+                .find(|sound| sound.id.as_str() == id)
+                .map(|sound| PressAction::Play(sound.id.clone()));
+        }
+        let id = button_id.strip_prefix(CATEGORY_PREFIX)?;
+        surface
+            .library
+            .categories
+            .iter()
+            .any(|category| category.id.as_str() == id)
+            .then(|| {
+                PressAction::Stop(
+                    sounds
+                        .iter()
+                        .filter(|sound| sound.category_id.as_str() == id)
+                        .filter(|sound| surface.states.contains_key(sound.id.as_str()))
+                        .map(|sound| sound.id.clone())
+                        .collect(),
+                )
+            })
+    }
+
+    /// Drop all surfaces (their feeds end)
+    pub fn disconnect_all(&self) {
+        self.clients.disconnect_all();
+    }
+
+    /// Number of connected surfaces
+    pub fn client_count(&self) -> usize {
+        self.clients.subscriber_count()
+    }
+
+    fn push(&self, messages: Vec<String>) {
+        for message in messages {
+            self.clients.broadcast(&message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sounds;
+    use serde_json::Value;
+
+    fn library() -> SoundLibrary {
+        let mut library = SoundLibrary {
+            categories: Vec::new(),
+            sounds: Vec::new(),
+        };
+        let memes = sounds::add_category(&mut library, "Memes".to_string(), Some("😂".into()));
+        let music = sounds::add_category(&mut library, "Music".to_string(), None);
+        for (name, category) in [("Air Horn", &memes), ("Intro", &music), ("Bruh", &memes)] {
+            sounds::add_sound(
+                &mut library,
+                name.to_string(),
+                format!("C:/sounds/{}.mp3", name),
+                category.id.clone(),
+                Some("🔊".to_string()),
+                None,
+            );
+        }
+        library
+    }
+
+    fn parse(message: &str) -> Value {
+        serde_json::from_str(message).unwrap()
+    }
+
+    #[test]
+    fn test_layout_lists_categories_then_their_sounds() {
+        let hub = SurfaceHub::default();
+        hub.set_library(library());
+        let (layout, _feed) = hub.subscribe();
+        let layout = parse(&layout);
+        assert_eq!(layout["type"], "layout");
+
+        let titles: Vec<&str> = layout["buttons"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|button| button["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, ["Memes", "Music", "Air Horn", "Bruh", "Intro"]);
+        assert_eq!(layout["buttons"][0]["icon"], "😂");
+        assert_eq!(layout["buttons"][2]["icon"], "🔊");
+        assert_eq!(layout["buttons"][2]["category"], layout["buttons"][0]["id"]);
+        assert_eq!(layout["buttons"][2]["state"], "idle");
+    }
+
+    #[test]
+    fn test_state_changes_update_sound_and_category() {
+        let library = library();
+        let air_horn = library.sounds[0].id.as_str().to_string();
+        let hub = SurfaceHub::default();
+        hub.set_library(library);
+        let (_, feed) = hub.subscribe();
+
+        hub.apply_state_event(&format!(
+            r#"{{"sound_id":"{}","state":"playing"}}"#,
+            air_horn
+        ));
+        let updates: Vec<Value> = feed.try_iter().map(|m| parse(&m)).collect();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0]["button"]["title"], "Memes");
+        assert_eq!(updates[0]["button"]["state"], "playing");
+        assert_eq!(updates[1]["button"]["title"], "Air Horn");
+        assert_eq!(updates[1]["button"]["state"], "playing");
+
+        // Unchanged state sends nothing
+        hub.set_sound_state(&air_horn, SoundPlayState::Playing);
+        assert!(feed.try_recv().is_err());
+
+        hub.set_sound_state(&air_horn, SoundPlayState::Idle);
+        let updates: Vec<Value> = feed.try_iter().map(|m| parse(&m)).collect();
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().all(|u| u["button"]["state"] == "idle"));
+    }
+
+    #[test]
+    fn test_library_edits_push_updates_or_layout() {
+        let mut library = library();
+        let hub = SurfaceHub::default();
+        hub.set_library(library.clone());
+        let (_, feed) = hub.subscribe();
+
+        // Renaming keeps the layout
+        library.sounds[1].name = "Outro".to_string();
+        library.sounds[1].icon = Some("🎸".to_string());
+        hub.set_library(library.clone());
+        let update = parse(&feed.try_recv().unwrap());
+        assert_eq!(update["type"], "update");
+        assert_eq!(update["button"]["title"], "Outro");
+        assert_eq!(update["button"]["icon"], "🎸");
+        assert!(feed.try_recv().is_err());
+
+        // Removing a sound sends a new layout
+        library.sounds.remove(0);
+        hub.set_library(library);
+        let layout = parse(&feed.try_recv().unwrap());
+        assert_eq!(layout["type"], "layout");
+        assert_eq!(layout["buttons"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_press_resolves_buttons() {
+        let library = library();
+        let air_horn = library.sounds[0].id.clone();
+        let memes = format!("category:{}", library.categories[0].id.as_str());
+        let hub = SurfaceHub::default();
+        hub.set_library(library);
+
+        let sound_button = format!("sound:{}", air_horn.as_str());
+        assert_eq!(
+            hub.press(&sound_button),
+            Some(PressAction::Play(air_horn.clone()))
+        );
+        assert_eq!(hub.press(&memes), Some(PressAction::Stop(Vec::new())));
+
+        hub.set_sound_state(air_horn.as_str(), SoundPlayState::Decoding);
+        assert_eq!(hub.press(&memes), Some(PressAction::Stop(vec![air_horn])));
+
+        assert_eq!(hub.press("sound:missing"), None);
+        assert_eq!(hub.press("category:missing"), None);
+        assert_eq!(hub.press("nonsense"), None);
+    }
+}
@@ -3,9 +3,14 @@
 //! Stores settings as JSON in the platform-specific app data directory.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use tauri::Manager;
-
+use std::path::Path;
+
+use crate::audio::DuckingConfig;
+use crate::obs::ObsConfig;
+use crate::remote::RemoteApiConfig;
+use crate::tts::TtsConfig;
+use crate::twitch::TwitchConfig;
+use crate::vbcable::{MicGateMode, MicProcessingConfig};
 use crate::DeviceId;
 
 /// Application settings for device routing and preferences
@@ -37,12 +42,65 @@ pub struct AppSettings {
     /// Whether microphone routing is enabled
     #[serde(default)]
     pub microphone_routing_enabled: bool,
+    /// Microphone gain in the routed CABLE Input mix (dB)
+    #[serde(default)]
+    pub microphone_routing_mic_gain_db: f32,
+    /// Soundboard gain in the routed CABLE Input mix (dB)
+    #[serde(default)]
+    pub microphone_routing_board_gain_db: f32,
+    /// Processing chain (gain, high-pass, gate, compressor) on the routed mic
+    #[serde(default)]
+    pub microphone_processing: MicProcessingConfig,
+    /// Push-to-talk/push-to-mute mode of the routed mic
+    #[serde(default)]
+    pub microphone_gate_mode: MicGateMode,
+    /// Global shortcut driving the push-to-talk/push-to-mute gate
+    #[serde(default)]
+    pub microphone_gate_hotkey: Option<String>,
+    /// Mic/soundboard ducking (sidechain) configuration
+    #[serde(default)]
+    pub ducking: DuckingConfig,
+    /// Output bus limiter ceiling in dBFS (-24.0 - 0.0), default -1.0
+    #[serde(default = "default_limiter_ceiling_db")]
+    pub limiter_ceiling_db: f32,
+    /// Copy added sounds into the app's media folder instead of referencing them
+    #[serde(default)]
+    pub copy_sounds_to_library: bool,
+    /// Size cap of the persistent decode cache in MB (0 disables it), default 1024
+    #[serde(default = "default_decode_cache_max_mb")]
+    pub decode_cache_max_mb: u32,
+    /// Store decoded audio resampled to the monitor device rate
+    #[serde(default)]
+    pub decode_cache_resample: bool,
+    /// Local HTTP/WebSocket remote-control API
+    #[serde(default)]
+    pub remote_api: RemoteApiConfig,
+    /// MIDI input port used for triggers (None = MIDI off)
+    #[serde(default)]
+    pub midi_input_port: Option<String>,
+    /// OBS WebSocket connection, sound actions and scene triggers
+    #[serde(default)]
+    pub obs: ObsConfig,
+    /// Twitch chat connection, triggers, cooldowns and queue
+    #[serde(default)]
+    pub twitch: TwitchConfig,
+    /// Text-to-speech engine
+    #[serde(default)]
+    pub tts: TtsConfig,
 }
 
 fn default_volume_multiplier() -> f32 {
     1.0 // Default: disabled (no boost), sounds play at normal Windows volume
 }
 
+fn default_limiter_ceiling_db() -> f32 {
+    crate::audio::DEFAULT_LIMITER_CEILING_DB
+}
+
+fn default_decode_cache_max_mb() -> u32 {
+    crate::audio::DEFAULT_DISK_CACHE_MB
+}
+
 fn default_minimize_to_tray() -> bool {
     true // Default: close minimizes to tray
 }
@@ -60,34 +118,33 @@ impl Default for AppSettings {
             autostart_enabled: false,
             microphone_routing_device_id: None,
             microphone_routing_enabled: false,
+            microphone_routing_mic_gain_db: 0.0,
+            microphone_routing_board_gain_db: 0.0,
+            microphone_processing: MicProcessingConfig::default(),
+            microphone_gate_mode: MicGateMode::default(),
+            microphone_gate_hotkey: None,
+            ducking: DuckingConfig::default(),
+            limiter_ceiling_db: default_limiter_ceiling_db(),
+            copy_sounds_to_library: false,
+            decode_cache_max_mb: default_decode_cache_max_mb(),
+            decode_cache_resample: false,
+            remote_api: RemoteApiConfig::default(),
+            midi_input_port: None,
+            obs: ObsConfig::default(),
+            twitch: TwitchConfig::default(),
+            tts: TtsConfig::default(),
         }
     }
 }
 
-/// Get the path to the settings file
-pub fn get_settings_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_local_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    // Ensure directory exists
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-
-    Ok(app_data_dir.join("settings.json"))
-}
-
-/// Load application settings from disk
-pub fn load(app_handle: &tauri::AppHandle) -> Result<AppSettings, String> {
-    let settings_path = get_settings_path(app_handle)?;
-
+/// Load a settings file (defaults if it doesn't exist)
+pub fn load_from_path(settings_path: &Path) -> Result<AppSettings, String> {
     if !settings_path.exists() {
         // Return default settings if file doesn't exist
         return Ok(AppSettings::default());
     }
 
-    let content = std::fs::read_to_string(&settings_path)
+    let content = std::fs::read_to_string(settings_path)
         .map_err(|e| format!("Failed to read settings file: {}", e))?;
 
     let settings: AppSettings =
@@ -96,14 +153,12 @@ pub fn load(app_handle: &tauri::AppHandle) -> Result<AppSettings, String> {
     Ok(settings)
 }
 
-/// Save application settings to disk (atomic write)
-pub fn save(settings: &AppSettings, app_handle: &tauri::AppHandle) -> Result<(), String> {
-    let settings_path = get_settings_path(app_handle)?;
-
+/// Save a settings file (atomic write)
+pub fn save_to_path(settings: &AppSettings, settings_path: &Path) -> Result<(), String> {
     let json = serde_json::to_string_pretty(settings)
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-    crate::persistence::atomic_write(&settings_path, &json)
+    crate::persistence::atomic_write(settings_path, &json)
 }
 
 // ============================================================================
@@ -183,6 +238,42 @@ mod tests {
             autostart_enabled: true,
             microphone_routing_device_id: Some("device_2".to_string()),
             microphone_routing_enabled: true,
+            microphone_routing_mic_gain_db: 3.0,
+            microphone_routing_board_gain_db: -6.0,
+            microphone_processing: MicProcessingConfig {
+                enabled: true,
+                gate_threshold_db: -50.0,
+                ..MicProcessingConfig::default()
+            },
+            microphone_gate_mode: MicGateMode::PushToTalk,
+            microphone_gate_hotkey: Some("Ctrl+Space".to_string()),
+            ducking: DuckingConfig {
+                mic_ducking_enabled: true,
+                ..DuckingConfig::default()
+            },
+            limiter_ceiling_db: -3.0,
+            copy_sounds_to_library: true,
+            decode_cache_max_mb: 256,
+            decode_cache_resample: true,
+            remote_api: RemoteApiConfig {
+                enabled: true,
+                token: "abc".to_string(),
+                ..RemoteApiConfig::default()
+            },
+            midi_input_port: Some("nanoPAD2".to_string()),
+            obs: ObsConfig {
+                enabled: true,
+                password: "hunter2".to_string(),
+                ..ObsConfig::default()
+            },
+            twitch: TwitchConfig {
+                channel: "streamer".to_string(),
+                max_queue: 3,
+                ..TwitchConfig::default()
+            },
+            tts: TtsConfig {
+                program: "/opt/espeak/espeak-ng".to_string(),
+            },
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -210,6 +301,28 @@ mod tests {
             Some("device_2".to_string())
         );
         assert!(deserialized.microphone_routing_enabled);
+        assert_eq!(deserialized.microphone_routing_mic_gain_db, 3.0);
+        assert_eq!(deserialized.microphone_routing_board_gain_db, -6.0);
+        assert!(deserialized.microphone_processing.enabled);
+        assert_eq!(deserialized.microphone_processing.gate_threshold_db, -50.0);
+        assert_eq!(deserialized.microphone_gate_mode, MicGateMode::PushToTalk);
+        assert_eq!(
+            deserialized.microphone_gate_hotkey,
+            Some("Ctrl+Space".to_string())
+        );
+        assert!(deserialized.ducking.mic_ducking_enabled);
+        assert_eq!(deserialized.limiter_ceiling_db, -3.0);
+        assert!(deserialized.copy_sounds_to_library);
+        assert_eq!(deserialized.decode_cache_max_mb, 256);
+        assert!(deserialized.decode_cache_resample);
+        assert!(deserialized.remote_api.enabled);
+        assert_eq!(deserialized.remote_api.token, "abc");
+        assert_eq!(deserialized.midi_input_port, Some("nanoPAD2".to_string()));
+        assert!(deserialized.obs.enabled);
+        assert_eq!(deserialized.obs.password, "hunter2");
+        assert_eq!(deserialized.twitch.channel, "streamer");
+        assert_eq!(deserialized.twitch.max_queue, 3);
+        assert_eq!(deserialized.tts.program, "/opt/espeak/espeak-ng");
     }
 
     #[test]
@@ -231,6 +344,24 @@ mod tests {
         assert!(!settings.autostart_enabled);
         assert_eq!(settings.microphone_routing_device_id, None);
         assert!(!settings.microphone_routing_enabled);
+        assert_eq!(settings.microphone_routing_mic_gain_db, 0.0);
+        assert_eq!(settings.microphone_routing_board_gain_db, 0.0);
+        assert_eq!(
+            settings.microphone_processing,
+            MicProcessingConfig::default()
+        );
+        assert_eq!(settings.microphone_gate_mode, MicGateMode::AlwaysOn);
+        assert_eq!(settings.microphone_gate_hotkey, None);
+        assert_eq!(settings.ducking, DuckingConfig::default());
+        assert_eq!(settings.limiter_ceiling_db, -1.0);
+        assert!(!settings.copy_sounds_to_library);
+        assert_eq!(settings.decode_cache_max_mb, 1024);
+        assert!(!settings.decode_cache_resample);
+        assert_eq!(settings.remote_api, RemoteApiConfig::default());
+        assert_eq!(settings.midi_input_port, None);
+        assert_eq!(settings.obs, ObsConfig::default());
+        assert_eq!(settings.twitch, TwitchConfig::default());
+        assert_eq!(settings.tts, TtsConfig::default());
     }
 
     #[test]
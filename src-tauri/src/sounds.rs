@@ -3,8 +3,10 @@
 //! Stores sounds and categories as JSON in the platform-specific app data directory.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use tauri::Manager;
+use std::path::Path;
+
+use crate::audio::EffectConfig;
+use crate::tts::TtsSpeech;
 
 /// Unique identifier for a sound
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -17,6 +19,11 @@ impl SoundId {
         Self(uuid_v4())
     }
 
+    /// Create from a raw string (e.g. a sound ID held as `&str`)
+    pub fn from_string(s: String) -> Self {
+        Self(s)
+    }
+
     /// Get the raw string ID
     pub fn as_str(&self) -> &str {
         &self.0
@@ -104,6 +111,24 @@ pub struct Sound {
     /// Optional trim end time in milliseconds
     #[serde(default)]
     pub trim_end_ms: Option<u64>,
+    /// Non-destructive effect chain applied at render time (in order)
+    #[serde(default)]
+    pub effects: Vec<EffectConfig>,
+    /// Where the sound came from before edits were applied (None if unedited)
+    #[serde(default)]
+    pub original: Option<OriginalSource>,
+    /// What a text-to-speech sound says; `file_path` is its rendered audio
+    #[serde(default)]
+    pub tts: Option<TtsSpeech>,
+}
+
+/// File and trim of a sound before destructive edits, kept so they can be reverted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OriginalSource {
+    /// Path to the user's original audio file
+    pub file_path: String,
+    pub trim_start_ms: Option<u64>,
+    pub trim_end_ms: Option<u64>,
 }
 
 /// A category to organize sounds
@@ -143,30 +168,14 @@ impl Default for SoundLibrary {
     }
 }
 
-/// Get the path to the sounds file
-pub fn get_sounds_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_local_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    // Ensure directory exists
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-
-    Ok(app_data_dir.join("sounds.json"))
-}
-
-/// Load sound library from disk
-pub fn load(app_handle: &tauri::AppHandle) -> Result<SoundLibrary, String> {
-    let sounds_path = get_sounds_path(app_handle)?;
-
+/// Load a sound library file (the default library if it doesn't exist)
+pub fn load_from_path(sounds_path: &Path) -> Result<SoundLibrary, String> {
     if !sounds_path.exists() {
         // Return default library if file doesn't exist
         return Ok(SoundLibrary::default());
     }
 
-    let content = std::fs::read_to_string(&sounds_path)
+    let content = std::fs::read_to_string(sounds_path)
         .map_err(|e| format!("Failed to read sounds file: {}", e))?;
 
     let library: SoundLibrary =
@@ -175,14 +184,12 @@ pub fn load(app_handle: &tauri::AppHandle) -> Result<SoundLibrary, String> {
     Ok(library)
 }
 
-/// Save sound library to disk (atomic write)
-pub fn save(library: &SoundLibrary, app_handle: &tauri::AppHandle) -> Result<(), String> {
-    let sounds_path = get_sounds_path(app_handle)?;
-
+/// Save a sound library file (atomic write)
+pub fn save_to_path(library: &SoundLibrary, sounds_path: &Path) -> Result<(), String> {
     let json = serde_json::to_string_pretty(library)
         .map_err(|e| format!("Failed to serialize sounds: {}", e))?;
 
-    crate::persistence::atomic_write(&sounds_path, &json)
+    crate::persistence::atomic_write(sounds_path, &json)
 }
 
 // ============================================================================
@@ -208,6 +215,9 @@ pub fn add_sound(
         is_favorite: false,
         trim_start_ms: None,
         trim_end_ms: None,
+        effects: Vec::new(),
+        original: None,
+        tts: None,
     };
     library.sounds.push(sound.clone());
     sound
@@ -261,6 +271,45 @@ pub fn update_sound(
     Ok(sound.clone())
 }
 
+/// Replace the effect chain of a sound
+pub fn set_effects(
+    library: &mut SoundLibrary,
+    sound_id: &SoundId,
+    effects: Vec<EffectConfig>,
+) -> Result<Sound, String> {
+    let sound = library
+        .sounds
+        .iter_mut()
+        .find(|s| &s.id == sound_id)
+        .ok_or_else(|| format!("Sound not found: {}", sound_id.as_str()))?;
+
+    sound.effects = effects;
+    Ok(sound.clone())
+}
+
+/// Make a sound speak `speech`, rendered to `file_path`
+///
+/// A new render replaces any trim or applied edits of the previous audio.
+pub fn set_speech(
+    library: &mut SoundLibrary,
+    sound_id: &SoundId,
+    speech: TtsSpeech,
+    file_path: String,
+) -> Result<Sound, String> {
+    let sound = library
+        .sounds
+        .iter_mut()
+        .find(|s| &s.id == sound_id)
+        .ok_or_else(|| format!("Sound not found: {}", sound_id.as_str()))?;
+
+    sound.tts = Some(speech);
+    sound.file_path = file_path;
+    sound.trim_start_ms = None;
+    sound.trim_end_ms = None;
+    sound.original = None;
+    Ok(sound.clone())
+}
+
 /// Delete a sound from the library
 pub fn delete_sound(library: &mut SoundLibrary, sound_id: &SoundId) -> Result<(), String> {
     let initial_len = library.sounds.len();
@@ -729,6 +778,98 @@ mod tests {
         assert_eq!(updated.volume, Some(1.0));
     }
 
+    // -------------------------------------------------------------------------
+    // set_effects Tests
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_set_effects_replaces_chain() {
+        let mut library = SoundLibrary::default();
+        let sound = add_sound(
+            &mut library,
+            "Airhorn".to_string(),
+            "/airhorn.mp3".to_string(),
+            CategoryId::from_string("default".to_string()),
+            None,
+            None,
+        );
+        assert!(sound.effects.is_empty());
+
+        let chain = vec![
+            EffectConfig::PitchShift { semitones: 7.0 },
+            EffectConfig::Gain { gain_db: -3.0 },
+        ];
+        let updated = set_effects(&mut library, &sound.id, chain.clone()).unwrap();
+
+        assert_eq!(updated.effects, chain);
+        assert_eq!(library.sounds[0].effects, chain);
+    }
+
+    #[test]
+    fn test_set_effects_not_found() {
+        let mut library = SoundLibrary::default();
+        let result = set_effects(&mut library, &SoundId::new(), vec![]);
+        assert!(result.unwrap_err().contains("Sound not found"));
+    }
+
+    #[test]
+    fn test_sound_without_effects_field_deserializes() {
+        // sounds.json written before effect chains existed
+        let json = r#"{
+            "id": "abc",
+            "name": "Old",
+            "file_path": "/old.mp3",
+            "category_id": "default",
+            "icon": null,
+            "volume": null
+        }"#;
+
+        let sound: Sound = serde_json::from_str(json).unwrap();
+        assert!(sound.effects.is_empty());
+        assert_eq!(sound.tts, None);
+    }
+
+    #[test]
+    fn test_set_speech_replaces_audio() {
+        let mut library = SoundLibrary::default();
+        let sound = add_sound(
+            &mut library,
+            "Greeting".to_string(),
+            "/media/old.wav".to_string(),
+            CategoryId::from_string("default".to_string()),
+            None,
+            None,
+        );
+        library.sounds[0].trim_start_ms = Some(100);
+        library.sounds[0].original = Some(OriginalSource {
+            file_path: "/hello.wav".to_string(),
+            trim_start_ms: None,
+            trim_end_ms: None,
+        });
+
+        let speech = TtsSpeech {
+            text: "Hello chat".to_string(),
+            voice: "en-us".to_string(),
+            rate: 1.2,
+        };
+        let updated = set_speech(
+            &mut library,
+            &sound.id,
+            speech.clone(),
+            "/media/new.wav".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(updated.tts, Some(speech));
+        assert_eq!(updated.file_path, "/media/new.wav");
+        assert_eq!(updated.trim_start_ms, None);
+        assert_eq!(updated.original, None);
+
+        let json = serde_json::to_string(&library).unwrap();
+        let reloaded: SoundLibrary = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.sounds[0].tts.as_ref().unwrap().text, "Hello chat");
+    }
+
     // -------------------------------------------------------------------------
     // delete_sound Tests
     // -------------------------------------------------------------------------
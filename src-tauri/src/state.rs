@@ -1,12 +1,18 @@
 //! In-memory application state for zero-latency hotkey handling
 //!
 //! All state changes are written to both in-memory state and disk for persistence.
+//! The state is loaded from a [`StorageRoot`], so it also works without a Tauri app.
 
-use std::sync::{Arc, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 
+use tauri::{Emitter, Manager};
+
+use crate::audio::AudioManager;
 use crate::hotkeys::HotkeyMappings;
 use crate::settings::AppSettings;
 use crate::sounds::SoundLibrary;
+use crate::storage::StorageRoot;
 
 /// Thread-safe in-memory application state
 pub struct AppState {
@@ -16,16 +22,25 @@ pub struct AppState {
     pub sounds: Arc<RwLock<SoundLibrary>>,
     /// Application settings (devices, volumes, preferences)
     pub settings: Arc<RwLock<AppSettings>>,
+    /// Where the state is persisted
+    storage: StorageRoot,
+    /// Serializes settings writers so updates from different threads don't
+    /// overwrite each other
+    settings_writer: Mutex<()>,
+    /// Serializes sound library writers, like `settings_writer`
+    sounds_writer: Mutex<()>,
+    /// Serializes hotkey writers, like `settings_writer`
+    hotkeys_writer: Mutex<()>,
 }
 
 impl AppState {
     /// Initialize state by loading all data from disk
-    pub fn load(app_handle: &tauri::AppHandle) -> Result<Self, String> {
-        tracing::info!("Loading application state from disk");
+    pub fn load(storage: StorageRoot) -> Result<Self, String> {
+        tracing::info!("Loading application state from {}", storage.dir().display());
 
-        let hotkeys = crate::hotkeys::load(app_handle)?;
-        let sounds = crate::sounds::load(app_handle)?;
-        let settings = crate::settings::load(app_handle)?;
+        let hotkeys = storage.load_hotkeys()?;
+        let sounds = storage.load_sounds()?;
+        let settings = storage.load_settings()?;
 
         tracing::info!(
             "State loaded: {} hotkeys, {} sounds, {} categories",
@@ -38,9 +53,28 @@ impl AppState {
             hotkeys: Arc::new(RwLock::new(hotkeys)),
             sounds: Arc::new(RwLock::new(sounds)),
             settings: Arc::new(RwLock::new(settings)),
+            storage,
+            settings_writer: Mutex::new(()),
+            sounds_writer: Mutex::new(()),
+            hotkeys_writer: Mutex::new(()),
         })
     }
 
+    /// Initialize state from the files in a data folder
+    pub fn load_from_dir(data_dir: PathBuf) -> Result<Self, String> {
+        Self::load(StorageRoot::new(data_dir)?)
+    }
+
+    /// Where the state is persisted
+    pub fn storage(&self) -> &StorageRoot {
+        &self.storage
+    }
+
+    /// Folder the state is persisted in
+    pub fn data_dir(&self) -> &Path {
+        self.storage.dir()
+    }
+
     /// Get a read-locked reference to hotkey mappings
     pub fn read_hotkeys(&self) -> std::sync::RwLockReadGuard<'_, HotkeyMappings> {
         self.hotkeys
@@ -83,14 +117,30 @@ impl AppState {
             .expect("RwLock poisoned: settings write failed")
     }
 
-    /// Update hotkeys in memory and persist to disk
-    pub fn update_and_save_hotkeys(
+    /// Replace hotkeys in memory and persist to disk
+    pub fn save_hotkeys(&self, mappings: HotkeyMappings) -> Result<(), String> {
+        let _writer = self.hotkeys_writer.lock().unwrap();
+        self.store_hotkeys(mappings)
+    }
+
+    /// Change hotkeys in place and persist them
+    ///
+    /// Like [`AppState::update_sounds`], other writers wait while `update` runs.
+    /// Nothing is saved if it fails.
+    pub fn update_hotkeys<R>(
         &self,
-        app_handle: &tauri::AppHandle,
-        mappings: HotkeyMappings,
-    ) -> Result<(), String> {
+        update: impl FnOnce(&mut HotkeyMappings) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let _writer = self.hotkeys_writer.lock().unwrap();
+        let mut mappings = self.read_hotkeys().clone();
+        let result = update(&mut mappings)?;
+        self.store_hotkeys(mappings)?;
+        Ok(result)
+    }
+
+    fn store_hotkeys(&self, mappings: HotkeyMappings) -> Result<(), String> {
         // Write to disk first (fail fast if disk error)
-        crate::hotkeys::save(&mappings, app_handle)?;
+        self.storage.save_hotkeys(&mappings)?;
 
         // Update in-memory state
         *self.write_hotkeys() = mappings;
@@ -99,35 +149,339 @@ impl AppState {
         Ok(())
     }
 
-    /// Update sound library in memory and persist to disk
-    pub fn update_and_save_sounds(
+    /// Replace the sound library in memory and persist to disk
+    pub fn save_sounds(&self, library: SoundLibrary) -> Result<(), String> {
+        let _writer = self.sounds_writer.lock().unwrap();
+        self.store_sounds(library)
+    }
+
+    /// Change the sound library in place and persist it
+    ///
+    /// Like [`AppState::update_settings`], other writers wait while `update` runs.
+    /// Nothing is saved if it fails.
+    pub fn update_sounds<R>(
         &self,
-        app_handle: &tauri::AppHandle,
-        library: SoundLibrary,
-    ) -> Result<(), String> {
-        // Write to disk first (fail fast if disk error)
-        crate::sounds::save(&library, app_handle)?;
+        update: impl FnOnce(&mut SoundLibrary) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let _writer = self.sounds_writer.lock().unwrap();
+        let mut library = self.read_sounds().clone();
+        let result = update(&mut library)?;
+        self.store_sounds(library)?;
+        Ok(result)
+    }
 
-        // Update in-memory state
+    fn store_sounds(&self, library: SoundLibrary) -> Result<(), String> {
+        self.storage.save_sounds(&library)?;
         *self.write_sounds() = library;
 
         tracing::debug!("Sound library updated in memory and persisted to disk");
         Ok(())
     }
 
-    /// Update settings in memory and persist to disk
-    pub fn update_and_save_settings(
+    /// Replace settings in memory and persist to disk
+    pub fn save_settings(&self, settings: AppSettings) -> Result<(), String> {
+        let _writer = self.settings_writer.lock().unwrap();
+        self.store_settings(settings)
+    }
+
+    /// Change some settings in place and persist them
+    ///
+    /// `update` runs on the current settings while other writers wait, so changes
+    /// made elsewhere in the meantime are not lost. Readers are only blocked while
+    /// the result is swapped in.
+    pub fn update_settings<R>(
         &self,
-        app_handle: &tauri::AppHandle,
-        settings: AppSettings,
-    ) -> Result<(), String> {
-        // Write to disk first (fail fast if disk error)
-        crate::settings::save(&settings, app_handle)?;
+        update: impl FnOnce(&mut AppSettings) -> R,
+    ) -> Result<R, String> {
+        let _writer = self.settings_writer.lock().unwrap();
+        let mut settings = self.read_settings().clone();
+        let result = update(&mut settings);
+        self.store_settings(settings)?;
+        Ok(result)
+    }
 
-        // Update in-memory state
+    fn store_settings(&self, settings: AppSettings) -> Result<(), String> {
+        self.storage.save_settings(&settings)?;
         *self.write_settings() = settings;
 
         tracing::debug!("Settings updated in memory and persisted to disk");
         Ok(())
     }
+
+    /// Update hotkeys in memory and persist to disk
+    pub fn update_and_save_hotkeys(
+        &self,
+        app_handle: &tauri::AppHandle,
+        mappings: HotkeyMappings,
+    ) -> Result<(), String> {
+        self.save_hotkeys(mappings)?;
+        self.hotkeys_changed(app_handle);
+        Ok(())
+    }
+
+    /// Refresh what depends on the hotkeys after they were saved
+    pub fn hotkeys_changed(&self, app_handle: &tauri::AppHandle) {
+        self.refresh_cache_pins(app_handle);
+    }
+
+    /// Update sound library in memory and persist to disk
+    pub fn update_and_save_sounds(
+        &self,
+        app_handle: &tauri::AppHandle,
+        library: SoundLibrary,
+    ) -> Result<(), String> {
+        self.save_sounds(library)?;
+        self.sounds_changed(app_handle);
+        Ok(())
+    }
+
+    /// Refresh what depends on the library after it was saved
+    pub fn sounds_changed(&self, app_handle: &tauri::AppHandle) {
+        self.refresh_cache_pins(app_handle);
+        // Control surfaces re-render their buttons
+        let _ = app_handle.emit("sounds-changed", ());
+    }
+
+    /// File paths of sounds bound to a hotkey or MIDI trigger
+    pub fn hotkey_bound_files(&self) -> Vec<String> {
+        let hotkeys = self.read_hotkeys();
+        let library = self.read_sounds();
+        library
+            .sounds
+            .iter()
+            .filter(|sound| {
+                hotkeys.mappings.values().any(|id| *id == sound.id)
+                    || hotkeys.midi.is_bound(&sound.id)
+            })
+            .map(|sound| sound.file_path.clone())
+            .collect()
+    }
+
+    /// Files to warm at startup: hotkey-bound sounds first, then favorites
+    pub fn startup_preload_files(&self) -> Vec<String> {
+        let mut files = self.hotkey_bound_files();
+        let library = self.read_sounds();
+        for sound in library.sounds.iter().filter(|sound| sound.is_favorite) {
+            if !files.contains(&sound.file_path) {
+                files.push(sound.file_path.clone());
+            }
+        }
+        files
+    }
+
+    /// Keep hotkey-bound sounds pinned in the audio cache
+    fn refresh_cache_pins(&self, app_handle: &tauri::AppHandle) {
+        // Not managed yet while the app is being set up
+        if let Some(manager) = app_handle.try_state::<AudioManager>() {
+            manager.set_pinned_files(self.hotkey_bound_files());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sounds::{self, CategoryId, Sound, SoundId};
+    use tempfile::TempDir;
+
+    fn load(temp: &TempDir) -> AppState {
+        AppState::load_from_dir(temp.path().join("data")).unwrap()
+    }
+
+    fn add_sound(library: &mut SoundLibrary, name: &str) -> Sound {
+        sounds::add_sound(
+            library,
+            name.to_string(),
+            format!("{}.mp3", name),
+            CategoryId::from_string("default".to_string()),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_load_from_empty_folder_uses_defaults() {
+        let temp = TempDir::new().unwrap();
+        let state = load(&temp);
+
+        assert!(state.data_dir().is_dir());
+        assert_eq!(state.data_dir(), temp.path().join("data"));
+        assert!(state.read_hotkeys().mappings.is_empty());
+        assert!(state.read_sounds().sounds.is_empty());
+        assert_eq!(state.read_sounds().categories.len(), 1);
+        assert_eq!(state.read_settings().default_volume, 0.5);
+        // Nothing is written until something changes
+        assert!(!state.storage().settings_path().exists());
+    }
+
+    #[test]
+    fn test_load_mutate_save_reload() {
+        let temp = TempDir::new().unwrap();
+        let state = load(&temp);
+
+        let mut library = state.read_sounds().clone();
+        let category = sounds::add_category(&mut library, "Memes".to_string(), None);
+        let airhorn = add_sound(&mut library, "Airhorn");
+        let rimshot = add_sound(&mut library, "Rimshot");
+        state.save_sounds(library).unwrap();
+
+        let mut mappings = state.read_hotkeys().clone();
+        crate::hotkeys::add_mapping(&mut mappings, "Ctrl+1".to_string(), airhorn.id.clone())
+            .unwrap();
+        state.save_hotkeys(mappings).unwrap();
+
+        let mut settings = state.read_settings().clone();
+        settings.default_volume = 0.8;
+        settings.start_minimized = true;
+        state.save_settings(settings).unwrap();
+
+        let reloaded = load(&temp);
+        let library = reloaded.read_sounds().clone();
+        assert_eq!(library.sounds.len(), 2);
+        assert_eq!(library.sounds[0].id, airhorn.id);
+        assert_eq!(library.sounds[1].name, "Rimshot");
+        assert!(library.categories.iter().any(|c| c.id == category.id));
+        assert_eq!(
+            reloaded.read_hotkeys().mappings.get("Ctrl+1"),
+            Some(&airhorn.id)
+        );
+        assert_eq!(reloaded.read_settings().default_volume, 0.8);
+        assert!(reloaded.read_settings().start_minimized);
+        assert_eq!(reloaded.hotkey_bound_files(), vec!["Airhorn.mp3"]);
+
+        // A second cycle on the reloaded state
+        let mut library = reloaded.read_sounds().clone();
+        sounds::delete_sound(&mut library, &airhorn.id).unwrap();
+        reloaded.save_sounds(library).unwrap();
+        let mut mappings = reloaded.read_hotkeys().clone();
+        crate::hotkeys::remove_mapping(&mut mappings, "Ctrl+1").unwrap();
+        reloaded.save_hotkeys(mappings).unwrap();
+
+        let reloaded = load(&temp);
+        assert_eq!(reloaded.read_sounds().sounds.len(), 1);
+        assert_eq!(reloaded.read_sounds().sounds[0].id, rimshot.id);
+        assert!(reloaded.read_hotkeys().mappings.is_empty());
+        assert!(reloaded.hotkey_bound_files().is_empty());
+        assert_eq!(reloaded.read_settings().default_volume, 0.8);
+    }
+
+    #[test]
+    fn test_failed_save_keeps_memory_unchanged() {
+        let temp = TempDir::new().unwrap();
+        let state = load(&temp);
+
+        // A file where the data folder was makes every write fail
+        std::fs::remove_dir_all(state.storage().dir()).unwrap();
+        std::fs::write(state.storage().dir(), b"").unwrap();
+
+        let mut library = state.read_sounds().clone();
+        add_sound(&mut library, "Airhorn");
+        assert!(state.save_sounds(library).is_err());
+        assert!(state.read_sounds().sounds.is_empty());
+
+        let mut settings = state.read_settings().clone();
+        settings.default_volume = 0.1;
+        assert!(state.save_settings(settings).is_err());
+        assert_eq!(state.read_settings().default_volume, 0.5);
+    }
+
+    #[test]
+    fn test_concurrent_hotkey_updates_are_not_lost() {
+        let temp = TempDir::new().unwrap();
+        let state = Arc::new(load(&temp));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    state
+                        .update_hotkeys(|mappings| {
+                            crate::hotkeys::add_mapping(
+                                mappings,
+                                format!("Ctrl+{}", i),
+                                SoundId::from_string(format!("sound-{}", i)),
+                            )
+                        })
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(load(&temp).read_hotkeys().mappings.len(), 8);
+    }
+
+    #[test]
+    fn test_concurrent_sound_updates_are_not_lost() {
+        let temp = TempDir::new().unwrap();
+        let state = Arc::new(load(&temp));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    state
+                        .update_sounds(|library| {
+                            add_sound(library, &format!("Sound {}", i));
+                            Ok(())
+                        })
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(load(&temp).read_sounds().sounds.len(), 8);
+
+        // A failed update saves nothing
+        let result = state.update_sounds(|library| {
+            library.sounds.clear();
+            Err::<(), _>("nope".to_string())
+        });
+        assert!(result.is_err());
+        assert_eq!(state.read_sounds().sounds.len(), 8);
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let temp = TempDir::new().unwrap();
+        let state = Arc::new(load(&temp));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    state
+                        .update_settings(|settings| {
+                            if i % 2 == 0 {
+                                settings.default_volume += 0.01;
+                            } else {
+                                settings.limiter_ceiling_db -= 1.0;
+                            }
+                        })
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let settings = load(&temp).read_settings().clone();
+        assert!((settings.default_volume - 0.54).abs() < 1e-4);
+        assert_eq!(settings.limiter_ceiling_db, -5.0);
+        assert_eq!(state.read_settings().limiter_ceiling_db, -5.0);
+    }
+
+    #[test]
+    fn test_corrupt_file_fails_to_load() {
+        let temp = TempDir::new().unwrap();
+        let storage = StorageRoot::new(temp.path()).unwrap();
+        std::fs::write(storage.sounds_path(), "{ not json").unwrap();
+
+        let error = AppState::load(storage).err().unwrap();
+        assert!(error.starts_with("Failed to parse sounds"));
+    }
 }
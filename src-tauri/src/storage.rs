@@ -0,0 +1,211 @@
+//! Layout of the app data folder
+//!
+//! `StorageRoot` knows where settings, the sound library, hotkeys, managed
+//! media and the decode cache live inside one data folder, and loads and saves
+//! them with the modules' `load_from_path`/`save_to_path`. Only
+//! [`StorageRoot::from_app`] asks Tauri for the folder; everything else works on
+//! a plain directory, so the state layer can run against a temp folder in tests
+//! or outside the app.
+
+use std::path::{Path, PathBuf};
+
+use tauri::Manager;
+
+use crate::hotkeys::{self, HotkeyMappings};
+use crate::media;
+use crate::settings::{self, AppSettings};
+use crate::sounds::{self, SoundLibrary};
+
+/// Identifier of the app (its data folder name)
+const APP_IDENTIFIER: &str = "com.sonicdeck.app";
+
+const SETTINGS_FILE: &str = "settings.json";
+const SOUNDS_FILE: &str = "sounds.json";
+const HOTKEYS_FILE: &str = "hotkeys.json";
+/// Folder of the persistent decode cache
+const DECODE_CACHE_DIR_NAME: &str = "decode-cache";
+
+/// An app data folder
+#[derive(Debug, Clone)]
+pub struct StorageRoot {
+    dir: PathBuf,
+}
+
+impl StorageRoot {
+    /// Use `dir` as the data folder, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, String> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+        Ok(Self { dir })
+    }
+
+    /// The running app's local data folder
+    pub fn from_app(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let dir = app_handle
+            .path()
+            .app_local_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+        Self::new(dir)
+    }
+
+    /// The same folder as [`Self::from_app`], found without a running app
+    pub fn user_default() -> Result<Self, String> {
+        let dir = dirs::data_local_dir().ok_or("Failed to get app data directory")?;
+        Self::new(dir.join(APP_IDENTIFIER))
+    }
+
+    /// The data folder
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn settings_path(&self) -> PathBuf {
+        self.dir.join(SETTINGS_FILE)
+    }
+
+    pub fn sounds_path(&self) -> PathBuf {
+        self.dir.join(SOUNDS_FILE)
+    }
+
+    pub fn hotkeys_path(&self) -> PathBuf {
+        self.dir.join(HOTKEYS_FILE)
+    }
+
+    pub fn decode_cache_dir(&self) -> PathBuf {
+        self.dir.join(DECODE_CACHE_DIR_NAME)
+    }
+
+    /// The managed media folder, creating it if needed
+    pub fn media_dir(&self) -> Result<PathBuf, String> {
+        media::media_dir_in(&self.dir)
+    }
+
+    /// Load settings (defaults if never saved)
+    pub fn load_settings(&self) -> Result<AppSettings, String> {
+        settings::load_from_path(&self.settings_path())
+    }
+
+    pub fn save_settings(&self, settings: &AppSettings) -> Result<(), String> {
+        settings::save_to_path(settings, &self.settings_path())
+    }
+
+    /// Load the sound library (the default categories if never saved)
+    pub fn load_sounds(&self) -> Result<SoundLibrary, String> {
+        sounds::load_from_path(&self.sounds_path())
+    }
+
+    pub fn save_sounds(&self, library: &SoundLibrary) -> Result<(), String> {
+        sounds::save_to_path(library, &self.sounds_path())
+    }
+
+    /// Load hotkey mappings (empty if never saved)
+    pub fn load_hotkeys(&self) -> Result<HotkeyMappings, String> {
+        hotkeys::load_from_path(&self.hotkeys_path())
+    }
+
+    pub fn save_hotkeys(&self, mappings: &HotkeyMappings) -> Result<(), String> {
+        hotkeys::save_to_path(mappings, &self.hotkeys_path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sounds::CategoryId;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_new_creates_the_folder() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("a").join("b");
+        let storage = StorageRoot::new(&dir).unwrap();
+
+        assert!(dir.is_dir());
+        assert_eq!(storage.dir(), dir);
+        assert_eq!(storage.settings_path(), dir.join("settings.json"));
+        assert_eq!(storage.sounds_path(), dir.join("sounds.json"));
+        assert_eq!(storage.hotkeys_path(), dir.join("hotkeys.json"));
+        assert!(storage.decode_cache_dir().starts_with(&dir));
+        assert!(storage.media_dir().unwrap().is_dir());
+    }
+
+    #[test]
+    fn test_missing_files_load_defaults() {
+        let temp = TempDir::new().unwrap();
+        let storage = StorageRoot::new(temp.path()).unwrap();
+
+        assert_eq!(storage.load_settings().unwrap().default_volume, 0.5);
+        assert!(storage.load_sounds().unwrap().sounds.is_empty());
+        assert!(storage.load_hotkeys().unwrap().mappings.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_reload_each_file() {
+        let temp = TempDir::new().unwrap();
+        let storage = StorageRoot::new(temp.path()).unwrap();
+
+        let mut settings = storage.load_settings().unwrap();
+        settings.minimize_to_tray = false;
+        settings.limiter_ceiling_db = -3.0;
+        storage.save_settings(&settings).unwrap();
+
+        let mut library = storage.load_sounds().unwrap();
+        let sound = sounds::add_sound(
+            &mut library,
+            "Airhorn".to_string(),
+            "airhorn.mp3".to_string(),
+            CategoryId::from_string("default".to_string()),
+            Some("📯".to_string()),
+            Some(0.7),
+        );
+        storage.save_sounds(&library).unwrap();
+
+        let mut mappings = storage.load_hotkeys().unwrap();
+        hotkeys::add_mapping(&mut mappings, "Ctrl+Shift+A".to_string(), sound.id.clone()).unwrap();
+        storage.save_hotkeys(&mappings).unwrap();
+
+        // A fresh root on the same folder sees everything
+        let reopened = StorageRoot::new(temp.path()).unwrap();
+        let settings = reopened.load_settings().unwrap();
+        assert!(!settings.minimize_to_tray);
+        assert_eq!(settings.limiter_ceiling_db, -3.0);
+        let library = reopened.load_sounds().unwrap();
+        assert_eq!(library.sounds[0].id, sound.id);
+        assert_eq!(library.sounds[0].icon.as_deref(), Some("📯"));
+        assert_eq!(library.sounds[0].volume, Some(0.7));
+        assert_eq!(
+            reopened
+                .load_hotkeys()
+                .unwrap()
+                .mappings
+                .get("Ctrl+Shift+A"),
+            Some(&sound.id)
+        );
+
+        // Atomic writes leave no temp files behind
+        let leftovers: Vec<_> = std::fs::read_dir(temp.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_files_are_errors() {
+        let temp = TempDir::new().unwrap();
+        let storage = StorageRoot::new(temp.path()).unwrap();
+        std::fs::write(storage.settings_path(), "not json").unwrap();
+        std::fs::write(storage.hotkeys_path(), "[]").unwrap();
+
+        assert!(storage
+            .load_settings()
+            .unwrap_err()
+            .starts_with("Failed to parse settings"));
+        assert!(storage
+            .load_hotkeys()
+            .unwrap_err()
+            .starts_with("Failed to parse hotkeys"));
+    }
+}
@@ -42,19 +42,17 @@ pub fn init<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), Box<dyn std::er
 /// Handle tray menu item clicks
 fn handle_tray_menu_event<R: Runtime>(app: &tauri::AppHandle<R>, event_id: &str) {
     match event_id {
-        "show_hide" => {
-            if let Some(window) = app.get_webview_window("main") {
-                if window.is_visible().unwrap_or(false) {
-                    let _ = window.hide();
-                    tracing::debug!("Window hidden from tray menu");
-                } else {
-                    let _ = window.show();
-                    let _ = window.unminimize();
-                    let _ = window.set_focus();
-                    tracing::debug!("Window shown from tray menu");
-                }
+        "show_hide" => match crate::window::main_window(app) {
+            Some(window) if window.is_visible().unwrap_or(false) => {
+                let _ = window.hide();
+                tracing::debug!("Window hidden from tray menu");
             }
-        }
+            // Also opens the window in daemon mode, where it may not exist yet
+            _ => {
+                crate::window::show_main_window(app);
+                tracing::debug!("Window shown from tray menu");
+            }
+        },
         "stop_all" => {
             // Call the stop_all_audio command
             let manager = app.state::<crate::AudioManager>();
@@ -93,32 +91,16 @@ fn handle_tray_icon_event<R: Runtime>(tray: &tauri::tray::TrayIcon<R>, event: Tr
             ..
         } => {
             // Left click shows window (always try to show/focus)
-            let app = tray.app_handle();
-            if let Some(window) = app.get_webview_window("main") {
-                // Always try to show and focus the window - simpler and more reliable
-                if let Err(e) = window.show() {
-                    tracing::error!("Failed to show window from tray click: {}", e);
-                } else {
-                    let _ = window.unminimize();
-                    let _ = window.set_focus();
-                    tracing::info!("Window restored from tray left-click");
-                }
-            } else {
-                tracing::error!("Could not find main window");
-            }
+            crate::window::show_main_window(tray.app_handle());
+            tracing::info!("Window restored from tray left-click");
         }
         TrayIconEvent::DoubleClick {
             button: MouseButton::Left,
             ..
         } => {
             // Handle double-click same as single click for now
-            let app = tray.app_handle();
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.unminimize();
-                let _ = window.set_focus();
-                tracing::info!("Window restored from tray double-click");
-            }
+            crate::window::show_main_window(tray.app_handle());
+            tracing::info!("Window restored from tray double-click");
         }
         _ => {
             // Silently ignore other events (mouse moves, etc.) - no logging to reduce spam
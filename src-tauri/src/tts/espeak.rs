@@ -0,0 +1,217 @@
+//! espeak-ng command-line engine
+//!
+//! Runs the espeak-ng binary once per render. The text goes through stdin so it
+//! is never parsed as options, and espeak-ng writes the WAV itself (`-w`).
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+use super::{TtsEngine, TtsSpeech, TtsVoice};
+use crate::audio::AudioError;
+
+/// Program run when the settings don't name one
+pub const DEFAULT_PROGRAM: &str = "espeak-ng";
+
+/// espeak-ng's default speed in words per minute
+const NORMAL_WPM: f32 = 175.0;
+
+/// Keep espeak-ng from flashing a console window on Windows
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Speaks through an espeak-ng executable
+pub struct EspeakEngine {
+    program: String,
+}
+
+impl EspeakEngine {
+    pub fn new(program: &str) -> Self {
+        let program = program.trim();
+        Self {
+            program: if program.is_empty() {
+                DEFAULT_PROGRAM.to_string()
+            } else {
+                program.to_string()
+            },
+        }
+    }
+
+    fn command(&self) -> Command {
+        #[allow(unused_mut)]
+        let mut command = Command::new(&self.program);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+        command
+    }
+
+    /// Run to completion, turning a failed start or exit into an error
+    fn run(&self, mut command: Command, stdin: Option<&str>) -> Result<Output, AudioError> {
+        command
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| AudioError::Synthesis(format!("Failed to run {}: {}", self.program, e)))?;
+        if let (Some(text), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(text.as_bytes())
+                .map_err(|e| AudioError::Synthesis(format!("Failed to send text: {}", e)))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| AudioError::Synthesis(format!("{} failed: {}", self.program, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AudioError::Synthesis(format!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                stderr.trim()
+            )));
+        }
+        Ok(output)
+    }
+}
+
+impl TtsEngine for EspeakEngine {
+    fn id(&self) -> String {
+        format!("espeak-ng:{}", self.program)
+    }
+
+    fn synthesize(&self, speech: &TtsSpeech, output: &Path) -> Result<(), AudioError> {
+        let mut command = self.command();
+        command.args(synthesize_args(speech)).arg("-w").arg(output);
+        self.run(command, Some(&speech.text))?;
+
+        if !output.is_file() {
+            return Err(AudioError::Synthesis(format!(
+                "{} wrote no audio",
+                self.program
+            )));
+        }
+        Ok(())
+    }
+
+    fn voices(&self) -> Result<Vec<TtsVoice>, AudioError> {
+        let mut command = self.command();
+        command.arg("--voices");
+        let output = self.run(command, None)?;
+        Ok(parse_voices(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Voice and speed options for a render (text comes from stdin)
+fn synthesize_args(speech: &TtsSpeech) -> Vec<String> {
+    let mut args = Vec::new();
+    if !speech.voice.is_empty() {
+        args.push("-v".to_string());
+        args.push(speech.voice.clone());
+    }
+    args.push("-s".to_string());
+    args.push(((NORMAL_WPM * speech.rate).round() as u32).to_string());
+    args.push("--stdin".to_string());
+    args
+}
+
+/// Parse the table printed by `espeak-ng --voices`
+///
+/// ```text
+/// Pty Language       Age/Gender VoiceName          File                 Other Languages
+///  5  en-us           --/M      English_(America)  gmw/en-US            (en 3)
+/// ```
+fn parse_voices(table: &str) -> Vec<TtsVoice> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let language = columns.nth(1)?;
+            let name = columns.nth(1)?;
+            Some(TtsVoice {
+                id: language.to_string(),
+                name: name.replace('_', " "),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthesize_args() {
+        let speech = TtsSpeech {
+            text: "-v looks like an option".to_string(),
+            voice: "en-us".to_string(),
+            rate: 1.5,
+        };
+        assert_eq!(
+            synthesize_args(&speech),
+            vec!["-v", "en-us", "-s", "263", "--stdin"]
+        );
+
+        // Default voice: no -v at all, and the text never becomes an argument
+        let speech = TtsSpeech {
+            voice: String::new(),
+            rate: 1.0,
+            ..speech
+        };
+        assert_eq!(synthesize_args(&speech), vec!["-s", "175", "--stdin"]);
+    }
+
+    #[test]
+    fn test_parse_voices() {
+        let table = "\
+Pty Language       Age/Gender VoiceName          File                 Other Languages
+ 5  af              --/M      Afrikaans          gmw/af
+ 5  en-us           --/M      English_(America)  gmw/en-US            (en 3)
+
+";
+        assert_eq!(
+            parse_voices(table),
+            vec![
+                TtsVoice {
+                    id: "af".to_string(),
+                    name: "Afrikaans".to_string(),
+                },
+                TtsVoice {
+                    id: "en-us".to_string(),
+                    name: "English (America)".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_program_falls_back_to_default() {
+        assert_eq!(EspeakEngine::new("  ").program, DEFAULT_PROGRAM);
+    }
+
+    #[test]
+    fn test_missing_program_is_a_synthesis_error() {
+        let engine = EspeakEngine::new("sonicdeck-missing-espeak");
+        let temp = tempfile::TempDir::new().unwrap();
+        let speech = TtsSpeech {
+            text: "Hi".to_string(),
+            voice: String::new(),
+            rate: 1.0,
+        };
+
+        let err = engine
+            .synthesize(&speech, &temp.path().join("out.wav"))
+            .unwrap_err();
+        assert!(matches!(err, AudioError::Synthesis(_)));
+        assert!(engine.voices().is_err());
+    }
+}
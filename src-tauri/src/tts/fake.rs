@@ -0,0 +1,63 @@
+//! [`TtsEngine`] for tests
+//!
+//! Writes a short tone instead of speech, one tenth of a second per word so
+//! different texts give different audio, and counts the renders so tests can
+//! check when synthesis runs.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use super::{TtsEngine, TtsSpeech, TtsVoice};
+use crate::audio::{self, AudioData, AudioError, ExportFormat};
+
+pub const SAMPLE_RATE: u32 = 16_000;
+
+#[derive(Default)]
+pub struct FakeEngine {
+    renders: AtomicUsize,
+    failing: AtomicBool,
+}
+
+impl FakeEngine {
+    /// How many times `synthesize` was called
+    pub fn renders(&self) -> usize {
+        self.renders.load(Ordering::SeqCst)
+    }
+
+    /// Make every following render fail
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
+}
+
+impl TtsEngine for FakeEngine {
+    fn id(&self) -> String {
+        "fake".to_string()
+    }
+
+    fn synthesize(&self, speech: &TtsSpeech, output: &Path) -> Result<(), AudioError> {
+        self.renders.fetch_add(1, Ordering::SeqCst);
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(AudioError::Synthesis("fake engine failure".to_string()));
+        }
+
+        let words = speech.text.split_whitespace().count().max(1);
+        let frames = words * SAMPLE_RATE as usize / 10;
+        let samples = (0..frames)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / SAMPLE_RATE as f32).sin() * 0.5)
+            .collect();
+        let audio_data = AudioData {
+            samples,
+            sample_rate: SAMPLE_RATE,
+            channels: 1,
+        };
+        audio::write_audio(&audio_data, output, ExportFormat::Wav)
+    }
+
+    fn voices(&self) -> Result<Vec<TtsVoice>, AudioError> {
+        Ok(vec![TtsVoice {
+            id: "fake".to_string(),
+            name: "Fake".to_string(),
+        }])
+    }
+}
@@ -0,0 +1,159 @@
+//! Text-to-speech sounds
+//!
+//! A TTS sound stores what to say ([`TtsSpeech`]) next to a regular `file_path`
+//! pointing at its rendered WAV in the managed media folder. Playback decodes and
+//! caches that file like any other sound, so speech goes through the same
+//! dual-output path, effects and `AudioCache`; synthesis only runs when the text,
+//! voice or rate changes, or when the rendered file has gone missing.
+//!
+//! Engines implement [`TtsEngine`]. The app uses a local espeak-ng binary
+//! ([`EspeakEngine`]); tests use a fake (see the `fake` module) that writes a tone
+//! and counts how often it was asked to speak.
+
+mod espeak;
+#[cfg(test)]
+pub mod fake;
+mod render;
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioError;
+
+pub use espeak::EspeakEngine;
+pub use render::{ensure_rendered, render};
+
+/// Slowest and fastest speaking rate (1.0 is the engine's normal speed)
+pub const MIN_RATE: f32 = 0.5;
+pub const MAX_RATE: f32 = 3.0;
+
+/// What a TTS sound says (stored on the sound)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtsSpeech {
+    pub text: String,
+    /// Engine voice ID; empty uses the engine's default voice
+    #[serde(default)]
+    pub voice: String,
+    /// Speaking rate relative to normal speed
+    #[serde(default = "default_rate")]
+    pub rate: f32,
+}
+
+fn default_rate() -> f32 {
+    1.0
+}
+
+impl TtsSpeech {
+    /// Trim the text and voice and clamp the rate; empty text is an error
+    pub fn normalized(self) -> Result<Self, String> {
+        let text = self.text.trim().to_string();
+        if text.is_empty() {
+            return Err("Text to speak is empty".to_string());
+        }
+        let rate = if self.rate.is_finite() {
+            self.rate.clamp(MIN_RATE, MAX_RATE)
+        } else {
+            default_rate()
+        };
+        Ok(Self {
+            text,
+            voice: self.voice.trim().to_string(),
+            rate,
+        })
+    }
+}
+
+/// A voice offered by an engine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TtsVoice {
+    /// Passed back to the engine as [`TtsSpeech::voice`]
+    pub id: String,
+    pub name: String,
+}
+
+/// A speech synthesiser
+pub trait TtsEngine: Send + Sync {
+    /// Identifies the engine in render file names, so switching engines
+    /// doesn't reuse another engine's renders
+    fn id(&self) -> String;
+
+    /// Speak `speech` into a WAV file at `output`
+    fn synthesize(&self, speech: &TtsSpeech, output: &Path) -> Result<(), AudioError>;
+
+    /// Voices the engine can speak with
+    fn voices(&self) -> Result<Vec<TtsVoice>, AudioError>;
+}
+
+/// Text-to-speech configuration (stored in settings)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TtsConfig {
+    /// espeak-ng executable: a name on PATH or a full path
+    pub program: String,
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            program: espeak::DEFAULT_PROGRAM.to_string(),
+        }
+    }
+}
+
+/// The engine described by the settings
+pub fn engine(config: &TtsConfig) -> Box<dyn TtsEngine> {
+    Box::new(EspeakEngine::new(&config.program))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalized_trims_and_clamps() {
+        let speech = TtsSpeech {
+            text: "  Hello chat \n".to_string(),
+            voice: " en-us ".to_string(),
+            rate: 10.0,
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(speech.text, "Hello chat");
+        assert_eq!(speech.voice, "en-us");
+        assert_eq!(speech.rate, MAX_RATE);
+
+        let speech = TtsSpeech {
+            text: "Hi".to_string(),
+            voice: String::new(),
+            rate: f32::NAN,
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(speech.rate, 1.0);
+    }
+
+    #[test]
+    fn test_normalized_rejects_empty_text() {
+        let speech = TtsSpeech {
+            text: "   ".to_string(),
+            voice: String::new(),
+            rate: 1.0,
+        };
+        assert!(speech.normalized().is_err());
+    }
+
+    #[test]
+    fn test_speech_deserializes_with_defaults() {
+        let speech: TtsSpeech = serde_json::from_str(r#"{"text":"Hi"}"#).unwrap();
+        assert_eq!(speech.voice, "");
+        assert_eq!(speech.rate, 1.0);
+    }
+
+    #[test]
+    fn test_default_config_uses_espeak() {
+        let config = TtsConfig::default();
+        assert_eq!(config.program, "espeak-ng");
+        assert_eq!(engine(&config).id(), "espeak-ng:espeak-ng");
+    }
+}
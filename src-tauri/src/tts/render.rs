@@ -0,0 +1,207 @@
+//! Rendering speech into the media folder
+//!
+//! Renders are named after the SHA-256 of the engine, voice, rate and text, so
+//! the same speech is synthesised once and shared, and the media folder's
+//! garbage collection removes renders no sound refers to any more.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+
+use super::{TtsEngine, TtsSpeech};
+use crate::audio::AudioError;
+
+/// Render `speech` into `media_dir`, reusing an existing render
+pub fn render(
+    engine: &dyn TtsEngine,
+    speech: &TtsSpeech,
+    media_dir: &Path,
+) -> Result<PathBuf, AudioError> {
+    let target = media_dir.join(format!("{}.wav", render_key(engine, speech)));
+    if target.is_file() {
+        debug!(path = %target.display(), "Speech already rendered");
+        return Ok(target);
+    }
+    synthesize_to(engine, speech, &target)?;
+    Ok(target)
+}
+
+/// Render `speech` to `path` again if the file has gone missing
+pub fn ensure_rendered(
+    engine: &dyn TtsEngine,
+    speech: &TtsSpeech,
+    path: &Path,
+) -> Result<(), AudioError> {
+    if path.is_file() {
+        return Ok(());
+    }
+    info!(path = %path.display(), "Speech render missing, synthesising again");
+    synthesize_to(engine, speech, path)
+}
+
+/// Synthesise under a temporary name so a half-written file is never played
+fn synthesize_to(
+    engine: &dyn TtsEngine,
+    speech: &TtsSpeech,
+    target: &Path,
+) -> Result<(), AudioError> {
+    let temp = target.with_extension("tmp");
+    if let Err(e) = engine.synthesize(speech, &temp) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    fs::rename(&temp, target)
+        .map_err(|e| AudioError::Synthesis(format!("Failed to store render: {}", e)))?;
+
+    info!(
+        engine = %engine.id(),
+        chars = speech.text.chars().count(),
+        path = %target.display(),
+        "Speech rendered"
+    );
+    Ok(())
+}
+
+/// Hex-encoded SHA-256 of everything that changes the rendered audio
+fn render_key(engine: &dyn TtsEngine, speech: &TtsSpeech) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        engine.id().as_bytes(),
+        speech.voice.as_bytes(),
+        &speech.rate.to_le_bytes(),
+        speech.text.as_bytes(),
+    ] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioCache;
+    use crate::media;
+    use crate::sounds::{self, CategoryId, SoundLibrary};
+    use crate::tts::fake::{FakeEngine, SAMPLE_RATE};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    fn speech(text: &str) -> TtsSpeech {
+        TtsSpeech {
+            text: text.to_string(),
+            voice: String::new(),
+            rate: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_same_speech_is_rendered_once() {
+        let temp = TempDir::new().unwrap();
+        let engine = FakeEngine::default();
+
+        let first = render(&engine, &speech("Hello chat"), temp.path()).unwrap();
+        let second = render(&engine, &speech("Hello chat"), temp.path()).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(engine.renders(), 1);
+        assert!(media::is_managed(&first, temp.path()));
+
+        // Anything that changes the audio is a new render
+        let slower = TtsSpeech {
+            rate: 0.8,
+            ..speech("Hello chat")
+        };
+        let other_voice = TtsSpeech {
+            voice: "en-gb".to_string(),
+            ..speech("Hello chat")
+        };
+        let paths = [
+            render(&engine, &slower, temp.path()).unwrap(),
+            render(&engine, &other_voice, temp.path()).unwrap(),
+            render(&engine, &speech("Goodbye chat"), temp.path()).unwrap(),
+        ];
+        assert!(paths.iter().all(|path| path != &first));
+        assert_eq!(engine.renders(), 4);
+    }
+
+    #[test]
+    fn test_missing_render_is_synthesised_again() {
+        let temp = TempDir::new().unwrap();
+        let engine = FakeEngine::default();
+        let path = render(&engine, &speech("Hello"), temp.path()).unwrap();
+
+        ensure_rendered(&engine, &speech("Hello"), &path).unwrap();
+        assert_eq!(engine.renders(), 1);
+
+        fs::remove_file(&path).unwrap();
+        ensure_rendered(&engine, &speech("Hello"), &path).unwrap();
+        assert_eq!(engine.renders(), 2);
+        assert!(path.is_file());
+    }
+
+    #[test]
+    fn test_failed_render_leaves_no_file() {
+        let temp = TempDir::new().unwrap();
+        let engine = FakeEngine::default();
+        engine.set_failing(true);
+
+        let err = render(&engine, &speech("Hello"), temp.path()).unwrap_err();
+        assert!(matches!(err, AudioError::Synthesis(_)));
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_render_is_decoded_and_cached_like_a_file() {
+        let temp = TempDir::new().unwrap();
+        let engine = FakeEngine::default();
+        let path = render(&engine, &speech("one two three"), temp.path()).unwrap();
+        let file_path = path.to_string_lossy().into_owned();
+
+        let cache = Mutex::new(AudioCache::new(16));
+        let first = AudioCache::get_or_decode(&cache, &file_path).unwrap();
+        let second = AudioCache::get_or_decode(&cache, &file_path).unwrap();
+
+        assert!(std::sync::Arc::ptr_eq(&first, &second));
+        assert_eq!(first.sample_rate, SAMPLE_RATE);
+        assert_eq!(first.samples.len(), 3 * SAMPLE_RATE as usize / 10);
+        let stats = AudioCache::stats(&cache);
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_changed_text_orphans_the_old_render() {
+        let temp = TempDir::new().unwrap();
+        let media_dir = media::media_dir_in(temp.path()).unwrap();
+        let engine = FakeEngine::default();
+
+        let mut library = SoundLibrary::default();
+        let old = render(&engine, &speech("Hello"), &media_dir).unwrap();
+        let sound = sounds::add_sound(
+            &mut library,
+            "Greeting".to_string(),
+            old.to_string_lossy().into_owned(),
+            CategoryId::from_string("default".to_string()),
+            None,
+            None,
+        );
+        let new = render(&engine, &speech("Hello there"), &media_dir).unwrap();
+        sounds::set_speech(
+            &mut library,
+            &sound.id,
+            speech("Hello there"),
+            new.to_string_lossy().into_owned(),
+        )
+        .unwrap();
+
+        let lock = media::lock_for_collection();
+        let removed = media::collect_garbage(&library, &media_dir, &lock).unwrap();
+        assert_eq!(removed, vec![old]);
+        assert!(new.is_file());
+    }
+}
@@ -0,0 +1,163 @@
+//! Twitch chat wiring into the running app
+
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::Serialize;
+use tauri::{Listener, Manager};
+use tracing::{info, warn};
+
+use super::client::TwitchClient;
+use super::config::TwitchConfig;
+use super::irc::ChatMessage;
+use super::router::{ChatAction, ChatRouter, SoundQueue};
+use crate::audio::AudioManager;
+use crate::commands::{self, SoundPlayState, SoundStateChange};
+use crate::sounds::SoundId;
+use crate::AppState;
+
+/// Twitch chat connection and trigger state (managed Tauri state)
+#[derive(Default)]
+pub struct TwitchState {
+    client: Mutex<Option<TwitchClient>>,
+    router: Mutex<ChatRouter>,
+    queue: Mutex<SoundQueue>,
+    /// Last redeemed reward, so its ID can be copied into a trigger
+    last_reward_id: Mutex<Option<String>>,
+}
+
+/// Chat integration state for the settings UI
+#[derive(Debug, Clone, Serialize)]
+pub struct TwitchStatus {
+    pub connected: bool,
+    pub error: Option<String>,
+    /// Triggers paused by the kill switch
+    pub paused: bool,
+    /// Sounds waiting in the chat queue
+    pub queued: usize,
+    pub last_reward_id: Option<String>,
+}
+
+impl TwitchState {
+    pub fn status(&self) -> TwitchStatus {
+        let client = self
+            .client
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(TwitchClient::status);
+        TwitchStatus {
+            connected: client.as_ref().is_some_and(|status| status.connected),
+            error: client.and_then(|status| status.error),
+            paused: self.router.lock().unwrap().is_paused(),
+            queued: self.queue.lock().unwrap().waiting(),
+            last_reward_id: self.last_reward_id.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Connect, reconnect or disconnect to match the settings
+pub fn apply_settings(app: &tauri::AppHandle, config: &TwitchConfig) {
+    let twitch = app.state::<TwitchState>();
+    let mut client = twitch.client.lock().unwrap();
+    *client = None;
+    if !config.enabled {
+        return;
+    }
+
+    let handle = app.clone();
+    *client = Some(TwitchClient::start(config.clone(), move |message| {
+        handle_message(&handle, message)
+    }));
+}
+
+/// Play the next queued chat sound when one finishes (call once at startup)
+pub fn forward_sound_events(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.listen_any("sound-state-changed", move |event| {
+        let Ok(change) = serde_json::from_str::<SoundStateChange>(event.payload()) else {
+            return;
+        };
+        if change.state != SoundPlayState::Idle {
+            return;
+        }
+        let next = handle
+            .state::<TwitchState>()
+            .queue
+            .lock()
+            .unwrap()
+            .finished(&change.sound_id);
+        play_queued(&handle, next);
+    });
+}
+
+/// Pause (clearing the queue and stopping the chat sound) or resume triggers
+pub fn set_paused(app: &tauri::AppHandle, paused: bool) {
+    let twitch = app.state::<TwitchState>();
+    twitch.router.lock().unwrap().set_paused(paused);
+    if paused {
+        stop_queue(app);
+    }
+}
+
+fn handle_message(app: &tauri::AppHandle, message: ChatMessage) {
+    let twitch = app.state::<TwitchState>();
+    if let Some(reward_id) = &message.reward_id {
+        *twitch.last_reward_id.lock().unwrap() = Some(reward_id.clone());
+    }
+
+    let config = app.state::<AppState>().read_settings().twitch.clone();
+    let action = twitch
+        .router
+        .lock()
+        .unwrap()
+        .handle(&message, &config, Instant::now());
+    match action {
+        Some(ChatAction::Play(sound_id)) => {
+            let pushed = twitch
+                .queue
+                .lock()
+                .unwrap()
+                .push(sound_id, config.max_queue);
+            match pushed {
+                Ok(now) => play_queued(app, now),
+                Err(e) => warn!("Chat trigger from {} dropped: {}", message.user, e),
+            }
+        }
+        Some(ChatAction::Kill) => {
+            info!("Chat sounds stopped by {}", message.user);
+            stop_queue(app);
+        }
+        Some(ChatAction::Resume) => info!("Chat sounds resumed by {}", message.user),
+        None => {}
+    }
+}
+
+/// Play a sound from the queue, skipping sounds that fail to play
+fn play_queued(app: &tauri::AppHandle, mut next: Option<SoundId>) {
+    while let Some(sound_id) = next {
+        match commands::play_library_sound(app, &sound_id, 1.0) {
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Chat sound '{}' failed to play: {}", sound_id.as_str(), e);
+                next = app
+                    .state::<TwitchState>()
+                    .queue
+                    .lock()
+                    .unwrap()
+                    .finished(&sound_id);
+            }
+        }
+    }
+}
+
+/// Clear the queue and stop the chat sound playing
+fn stop_queue(app: &tauri::AppHandle) {
+    let playing = app.state::<TwitchState>().queue.lock().unwrap().clear();
+    if let Some(sound_id) = playing {
+        let manager = app.state::<AudioManager>();
+        if let Some(state) = manager.get_sound_state(sound_id.as_str()) {
+            manager.signal_stop(state.playback_id());
+        }
+    }
+}
@@ -0,0 +1,462 @@
+//! Twitch chat (IRC) client connection
+//!
+//! Runs on its own thread and reconnects while enabled. Only reads chat; the
+//! bot never posts. The login token is only ever sent over TLS: plain TCP is
+//! limited to a local stand-in server.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use tracing::{debug, info, warn};
+
+use super::config::TwitchConfig;
+use super::irc::{ChatMessage, IrcMessage};
+
+/// How long to wait for the server while connecting and logging in
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Read timeout between checks for shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Delay before reconnecting after the connection failed or closed
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Connection state
+#[derive(Debug, Clone, Default)]
+pub struct ClientStatus {
+    pub connected: bool,
+    /// Why the last connection attempt failed or closed
+    pub error: Option<String>,
+}
+
+/// Connection to Twitch chat (disconnects when dropped)
+pub struct TwitchClient {
+    status: Arc<Mutex<ClientStatus>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TwitchClient {
+    /// Connect in the background; `on_message` runs on the client thread
+    pub fn start(config: TwitchConfig, on_message: impl Fn(ChatMessage) + Send + 'static) -> Self {
+        let status = Arc::new(Mutex::new(ClientStatus::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let connection = Connection {
+            config,
+            status: status.clone(),
+            stop: stop.clone(),
+            on_message: Box::new(on_message),
+        };
+        let thread = thread::Builder::new()
+            .name("twitch-chat".to_string())
+            .spawn(move || connection.run())
+            .map_err(|e| warn!("Failed to start Twitch chat thread: {}", e))
+            .ok();
+
+        Self {
+            status,
+            stop,
+            thread,
+        }
+    }
+
+    pub fn status(&self) -> ClientStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+impl Drop for TwitchClient {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// State of the client thread
+struct Connection {
+    config: TwitchConfig,
+    status: Arc<Mutex<ClientStatus>>,
+    stop: Arc<AtomicBool>,
+    on_message: Box<dyn Fn(ChatMessage) + Send>,
+}
+
+impl Connection {
+    fn run(self) {
+        while !self.stopped() {
+            let error = self.session().err();
+            if let Some(e) = &error {
+                debug!("Twitch chat connection ended: {}", e);
+            }
+            *self.status.lock().unwrap() = ClientStatus {
+                connected: false,
+                error,
+            };
+
+            let retry_at = Instant::now() + RETRY_INTERVAL;
+            while !self.stopped() && Instant::now() < retry_at {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// One connection: log in and join, then read chat until it closes
+    fn session(&self) -> Result<(), String> {
+        let server = format!("{}:{}", self.config.host, self.config.port);
+        let address = (self.config.host.as_str(), self.config.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Failed to resolve {}: {}", server, e))?
+            .next()
+            .ok_or_else(|| format!("Failed to resolve {}", server))?;
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+            .map_err(|e| format!("Failed to connect to {}: {}", server, e))?;
+        let stream = if self.config.uses_tls() {
+            ChatStream::tls(&self.config.host, stream)
+                .map_err(|e| format!("TLS handshake with {} failed: {}", server, e))?
+        } else {
+            ChatStream::Plain(stream)
+        };
+        stream
+            .socket()
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .map_err(|e| e.to_string())?;
+        let mut lines = LineReader::new(stream);
+
+        let channel = self
+            .config
+            .channel
+            .trim()
+            .trim_start_matches('#')
+            .to_ascii_lowercase();
+        if channel.is_empty() {
+            return Err("No Twitch channel set".to_string());
+        }
+        let token = self.config.oauth_token.trim();
+        if !token.is_empty() {
+            let token = token.strip_prefix("oauth:").unwrap_or(token);
+            lines.send(&format!("PASS oauth:{}", token))?;
+        }
+        let nick = match self.config.username.trim() {
+            // Twitch lets "justinfan" users read chat without logging in
+            "" => format!("justinfan{}", 10000 + std::process::id() % 90000),
+            name => name.to_ascii_lowercase(),
+        };
+        lines.send(&format!("NICK {}", nick))?;
+        lines.send("CAP REQ :twitch.tv/tags twitch.tv/commands")?;
+        lines.send(&format!("JOIN #{}", channel))?;
+
+        let deadline = Instant::now() + CONNECT_TIMEOUT;
+        let mut joined = false;
+        while !self.stopped() {
+            let Some(line) = lines.next_line()? else {
+                if !joined && Instant::now() > deadline {
+                    return Err("Timed out logging in to Twitch chat".to_string());
+                }
+                continue;
+            };
+            let Some(message) = IrcMessage::parse(&line) else {
+                continue;
+            };
+            match message.command.as_str() {
+                "PING" => {
+                    let token = message.params.first().map_or("", String::as_str);
+                    lines.send(&format!("PONG :{}", token))?;
+                }
+                // Welcome: logged in
+                "001" => {
+                    joined = true;
+                    *self.status.lock().unwrap() = ClientStatus {
+                        connected: true,
+                        error: None,
+                    };
+                    info!(channel = %channel, "Connected to Twitch chat");
+                }
+                // Login failures come as a NOTICE before the server hangs up
+                "NOTICE" if !joined => {
+                    let text = message.params.last().cloned().unwrap_or_default();
+                    return Err(format!("Twitch chat login failed: {}", text));
+                }
+                "RECONNECT" => return Err("Twitch asked to reconnect".to_string()),
+                "PRIVMSG" => {
+                    if let Some(chat) = ChatMessage::from_irc(&message) {
+                        (self.on_message)(chat);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Connection to the chat server
+enum ChatStream {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+}
+
+impl ChatStream {
+    /// Wrap `stream` in TLS, verifying `host` against the bundled web roots
+    fn tls(host: &str, mut stream: TcpStream) -> Result<Self, String> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(|e| e.to_string())?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        let name = ServerName::try_from(host.trim().to_string()).map_err(|e| e.to_string())?;
+        let mut connection =
+            ClientConnection::new(Arc::new(config), name).map_err(|e| e.to_string())?;
+
+        // Finish the handshake up front, before reads start timing out
+        stream
+            .set_read_timeout(Some(CONNECT_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        while connection.is_handshaking() {
+            connection
+                .complete_io(&mut stream)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(Self::Tls(Box::new(StreamOwned::new(connection, stream))))
+    }
+
+    fn socket(&self) -> &TcpStream {
+        match self {
+            Self::Plain(stream) => stream,
+            Self::Tls(stream) => stream.get_ref(),
+        }
+    }
+}
+
+impl Read for ChatStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(stream) => stream.read(buf),
+            Self::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for ChatStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(stream) => stream.write(buf),
+            Self::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(stream) => stream.flush(),
+            Self::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Splits the stream into lines, tolerating read timeouts mid-line
+struct LineReader {
+    stream: ChatStream,
+    buffer: Vec<u8>,
+}
+
+impl LineReader {
+    fn new(stream: ChatStream) -> Self {
+        Self {
+            stream,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), String> {
+        self.stream
+            .write_all(format!("{}\r\n", line).as_bytes())
+            .map_err(|e| format!("Failed to send to Twitch chat: {}", e))
+    }
+
+    /// Next complete line (None when the read timed out first)
+    fn next_line(&mut self) -> Result<Option<String>, String> {
+        loop {
+            if let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                return Ok(Some(String::from_utf8_lossy(&line).trim_end().to_string()));
+            }
+            let mut chunk = [0u8; 4096];
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err("Twitch chat closed the connection".to_string()),
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return Ok(None)
+                }
+                Err(e) => return Err(format!("Twitch chat connection lost: {}", e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Stand-in IRC server for one client
+    struct MockIrc {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl MockIrc {
+        fn accept(listener: &TcpListener) -> Self {
+            let (stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            Self {
+                writer: stream.try_clone().unwrap(),
+                reader: BufReader::new(stream),
+            }
+        }
+
+        fn recv(&mut self) -> String {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            line.trim_end().to_string()
+        }
+
+        fn send(&mut self, line: &str) {
+            self.writer
+                .write_all(format!("{}\r\n", line).as_bytes())
+                .unwrap();
+        }
+    }
+
+    fn config(port: u16, username: &str, token: &str) -> TwitchConfig {
+        TwitchConfig {
+            enabled: true,
+            host: "127.0.0.1".to_string(),
+            port,
+            channel: "#Streamer".to_string(),
+            username: username.to_string(),
+            oauth_token: token.to_string(),
+            ..TwitchConfig::default()
+        }
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_logs_in_answers_pings_and_reports_chat() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, messages) = mpsc::channel();
+        let client = TwitchClient::start(config(port, "SoundBot", "abc123"), move |message| {
+            let _ = sender.send(message);
+        });
+
+        let mut server = MockIrc::accept(&listener);
+        assert_eq!(server.recv(), "PASS oauth:abc123");
+        assert_eq!(server.recv(), "NICK soundbot");
+        assert_eq!(server.recv(), "CAP REQ :twitch.tv/tags twitch.tv/commands");
+        assert_eq!(server.recv(), "JOIN #streamer");
+        assert!(!client.status().connected);
+
+        server.send(":tmi.twitch.tv 001 soundbot :Welcome, GLHF!");
+        wait_until(|| client.status().connected);
+
+        server.send("PING :tmi.twitch.tv");
+        assert_eq!(server.recv(), "PONG :tmi.twitch.tv");
+
+        // Split across writes, like a slow network
+        server
+            .writer
+            .write_all(b"@badges=;mod=0 :viewer!viewer@viewer.tmi.twitch.tv PRIV")
+            .unwrap();
+        thread::sleep(Duration::from_millis(150));
+        server.send("MSG #streamer :!airhorn");
+        let message = messages.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(message.user, "viewer");
+        assert_eq!(message.text, "!airhorn");
+
+        drop(client);
+    }
+
+    #[test]
+    fn test_failed_login_is_reported() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = TwitchClient::start(config(port, "soundbot", "oauth:wrong"), |_| {});
+
+        let mut server = MockIrc::accept(&listener);
+        assert_eq!(server.recv(), "PASS oauth:wrong");
+        for _ in 0..3 {
+            server.recv();
+        }
+        server.send(":tmi.twitch.tv NOTICE * :Login authentication failed");
+        drop(server);
+
+        wait_until(|| client.status().error.is_some());
+        let status = client.status();
+        assert!(!status.connected);
+        assert!(status
+            .error
+            .unwrap()
+            .contains("Login authentication failed"));
+    }
+
+    #[test]
+    fn test_anonymous_login() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let _client = TwitchClient::start(config(port, "", ""), |_| {});
+
+        let mut server = MockIrc::accept(&listener);
+        assert!(server.recv().starts_with("NICK justinfan"));
+    }
+
+    #[test]
+    fn test_remote_servers_get_a_tls_handshake_first() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let handshake = thread::spawn(move || {
+            let stream = TcpStream::connect(address).unwrap();
+            ChatStream::tls("irc.chat.twitch.tv", stream).map(|_| ())
+        });
+
+        // A TLS record (ClientHello) rather than plaintext IRC
+        let (mut server, _) = listener.accept().unwrap();
+        let mut header = [0u8; 3];
+        server.read_exact(&mut header).unwrap();
+        assert_eq!(header, [0x16, 0x03, 0x01]);
+
+        // A server that doesn't speak TLS fails the connection
+        server
+            .write_all(b":tmi.twitch.tv 001 bot :Welcome\r\n")
+            .unwrap();
+        assert!(handshake.join().unwrap().is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Twitch chat integration settings
+
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sounds::SoundId;
+
+/// Twitch chat over IRC with TLS
+pub const DEFAULT_TWITCH_HOST: &str = "irc.chat.twitch.tv";
+pub const DEFAULT_TWITCH_PORT: u16 = 6697;
+
+/// Chat command playing a sound (`!airhorn`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatCommandTrigger {
+    pub command: String,
+    pub sound_id: SoundId,
+}
+
+/// Channel-point reward playing a sound
+///
+/// Twitch IRC only identifies rewards by ID (and only rewards that ask the
+/// viewer for text), so the title is a label for the settings UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedemptionTrigger {
+    pub title: String,
+    pub reward_id: String,
+    pub sound_id: SoundId,
+}
+
+/// Twitch chat configuration (stored in settings)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TwitchConfig {
+    pub enabled: bool,
+    /// IRC server (a local stand-in server works for testing, over plain TCP)
+    pub host: String,
+    pub port: u16,
+    /// Channel to join (without '#')
+    pub channel: String,
+    /// Bot account login; empty joins anonymously (read-only)
+    pub username: String,
+    /// OAuth token of the bot account ("oauth:" prefix optional)
+    pub oauth_token: String,
+    pub commands: Vec<ChatCommandTrigger>,
+    pub redemptions: Vec<RedemptionTrigger>,
+    /// Per-viewer cooldown between chat commands
+    pub user_cooldown_secs: u32,
+    /// Cooldown between any two chat commands
+    pub global_cooldown_secs: u32,
+    /// Sounds waiting behind the playing one before requests are dropped
+    pub max_queue: usize,
+    /// Moderator command clearing the queue and pausing triggers
+    pub kill_command: String,
+    /// Moderator command resuming triggers
+    pub resume_command: String,
+}
+
+impl Default for TwitchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: DEFAULT_TWITCH_HOST.to_string(),
+            port: DEFAULT_TWITCH_PORT,
+            channel: String::new(),
+            username: String::new(),
+            oauth_token: String::new(),
+            commands: Vec::new(),
+            redemptions: Vec::new(),
+            user_cooldown_secs: 30,
+            global_cooldown_secs: 5,
+            max_queue: 5,
+            kill_command: "!stopsounds".to_string(),
+            resume_command: "!resumesounds".to_string(),
+        }
+    }
+}
+
+impl TwitchConfig {
+    /// Whether both join the same channel the same way (triggers aside)
+    pub fn same_connection(&self, other: &TwitchConfig) -> bool {
+        self.enabled == other.enabled
+            && self.host == other.host
+            && self.port == other.port
+            && self.channel == other.channel
+            && self.username == other.username
+            && self.oauth_token == other.oauth_token
+    }
+
+    /// Whether the connection uses TLS: always, except to a loopback host
+    pub fn uses_tls(&self) -> bool {
+        let host = self.host.trim();
+        let loopback = host.eq_ignore_ascii_case("localhost")
+            || host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback());
+        !loopback
+    }
+
+    /// Sound of a chat command (case-insensitive)
+    pub fn sound_for_command(&self, command: &str) -> Option<&SoundId> {
+        self.commands
+            .iter()
+            .find(|trigger| trigger.command.eq_ignore_ascii_case(command))
+            .map(|trigger| &trigger.sound_id)
+    }
+
+    /// Sound of a channel-point reward
+    pub fn sound_for_reward(&self, reward_id: &str) -> Option<&SoundId> {
+        self.redemptions
+            .iter()
+            .find(|trigger| trigger.reward_id == reward_id)
+            .map(|trigger| &trigger.sound_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_and_lookups() {
+        let config: TwitchConfig = serde_json::from_str(
+            r#"{
+                "channel": "streamer",
+                "commands": [{"command": "!airhorn", "sound_id": "horn"}],
+                "redemptions": [{"title": "Scream", "reward_id": "abc-123", "sound_id": "scream"}]
+            }"#,
+        )
+        .unwrap();
+        assert!(!config.enabled);
+        assert_eq!(config.host, DEFAULT_TWITCH_HOST);
+        assert_eq!(config.max_queue, 5);
+
+        assert_eq!(
+            config.sound_for_command("!AirHorn").map(|id| id.as_str()),
+            Some("horn")
+        );
+        assert!(config.sound_for_command("!other").is_none());
+        assert_eq!(
+            config.sound_for_reward("abc-123").map(|id| id.as_str()),
+            Some("scream")
+        );
+        assert!(config.sound_for_reward("Scream").is_none());
+
+        let mut other = config.clone();
+        other.user_cooldown_secs = 0;
+        assert!(config.same_connection(&other));
+        other.channel = "someone".to_string();
+        assert!(!config.same_connection(&other));
+    }
+
+    #[test]
+    fn test_only_loopback_hosts_skip_tls() {
+        let mut config = TwitchConfig::default();
+        assert!(config.uses_tls());
+        for host in ["localhost", "127.0.0.1", "::1", "[::1]"] {
+            config.host = host.to_string();
+            assert!(!config.uses_tls(), "{}", host);
+        }
+        for host in ["192.168.1.10", "example.com", "localhost.example.com"] {
+            config.host = host.to_string();
+            assert!(config.uses_tls(), "{}", host);
+        }
+    }
+}
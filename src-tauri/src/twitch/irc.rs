@@ -0,0 +1,170 @@
+//! IRC message parsing (with the IRCv3 tags Twitch sends)
+
+use std::collections::HashMap;
+
+/// One IRC line: `[@tags] [:prefix] COMMAND params... [:trailing]`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IrcMessage {
+    pub tags: HashMap<String, String>,
+    pub prefix: Option<String>,
+    pub command: String,
+    pub params: Vec<String>,
+}
+
+impl IrcMessage {
+    /// Parse a line (without the trailing CRLF); None if it has no command
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut rest = line.trim_end_matches(['\r', '\n']);
+        let mut message = IrcMessage::default();
+
+        if let Some(tagged) = rest.strip_prefix('@') {
+            let (tags, after) = tagged.split_once(' ')?;
+            message.tags = tags
+                .split(';')
+                .map(|tag| match tag.split_once('=') {
+                    Some((key, value)) => (key.to_string(), unescape_tag(value)),
+                    None => (tag.to_string(), String::new()),
+                })
+                .collect();
+            rest = after.trim_start();
+        }
+        if let Some(prefixed) = rest.strip_prefix(':') {
+            let (prefix, after) = prefixed.split_once(' ')?;
+            message.prefix = Some(prefix.to_string());
+            rest = after.trim_start();
+        }
+
+        let (middle, trailing) = match rest.split_once(" :") {
+            Some((middle, trailing)) => (middle, Some(trailing)),
+            None => (rest, None),
+        };
+        let mut words = middle.split(' ').filter(|word| !word.is_empty());
+        message.command = words.next()?.to_string();
+        message.params = words.map(str::to_string).collect();
+        message.params.extend(trailing.map(str::to_string));
+        Some(message)
+    }
+
+    /// Nickname of the sender (`nick!user@host`)
+    pub fn nick(&self) -> Option<&str> {
+        let prefix = self.prefix.as_deref()?;
+        Some(prefix.split_once('!').map_or(prefix, |(nick, _)| nick))
+    }
+
+    fn tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .get(key)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Tag values escape `;`, spaces and backslashes
+fn unescape_tag(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(':') => unescaped.push(';'),
+            Some('s') => unescaped.push(' '),
+            Some('r') => unescaped.push('\r'),
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Chat message in the joined channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    /// Login name of the sender
+    pub user: String,
+    /// Moderator or the broadcaster
+    pub moderator: bool,
+    pub text: String,
+    /// Channel-point reward redeemed with this message
+    pub reward_id: Option<String>,
+}
+
+impl ChatMessage {
+    /// Chat message carried by a PRIVMSG
+    pub fn from_irc(message: &IrcMessage) -> Option<Self> {
+        if message.command != "PRIVMSG" {
+            return None;
+        }
+        let broadcaster = message
+            .tag("badges")
+            .is_some_and(|badges| badges.split(',').any(|b| b.starts_with("broadcaster/")));
+        Some(Self {
+            user: message.nick()?.to_ascii_lowercase(),
+            moderator: broadcaster || message.tag("mod") == Some("1"),
+            text: message.params.get(1)?.clone(),
+            reward_id: message.tag("custom-reward-id").map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let ping = IrcMessage::parse("PING :tmi.twitch.tv\r\n").unwrap();
+        assert_eq!(ping.command, "PING");
+        assert_eq!(ping.params, ["tmi.twitch.tv"]);
+        assert!(ping.prefix.is_none());
+
+        let welcome = IrcMessage::parse(":tmi.twitch.tv 001 bot :Welcome, GLHF!").unwrap();
+        assert_eq!(welcome.prefix.as_deref(), Some("tmi.twitch.tv"));
+        assert_eq!(welcome.command, "001");
+        assert_eq!(welcome.params, ["bot", "Welcome, GLHF!"]);
+
+        let tagged = IrcMessage::parse(
+            r"@badges=;display-name=Some\sOne;emotes= :someone!someone@someone.tmi.twitch.tv PRIVMSG #chan :hi :)",
+        )
+        .unwrap();
+        assert_eq!(tagged.tags["display-name"], "Some One");
+        assert_eq!(tagged.tags["emotes"], "");
+        assert_eq!(tagged.nick(), Some("someone"));
+        assert_eq!(tagged.params, ["#chan", "hi :)"]);
+
+        assert!(IrcMessage::parse("").is_none());
+        assert!(IrcMessage::parse(":prefix-only").is_none());
+    }
+
+    #[test]
+    fn test_chat_messages() {
+        let line = "@badges=moderator/1;mod=1;user-id=1 :ModGuy!modguy@modguy.tmi.twitch.tv PRIVMSG #chan :!stopsounds";
+        let message = ChatMessage::from_irc(&IrcMessage::parse(line).unwrap()).unwrap();
+        assert_eq!(
+            message,
+            ChatMessage {
+                user: "modguy".to_string(),
+                moderator: true,
+                text: "!stopsounds".to_string(),
+                reward_id: None,
+            }
+        );
+
+        let line = "@badges=broadcaster/1;mod=0;custom-reward-id=abc-123 :streamer!streamer@streamer.tmi.twitch.tv PRIVMSG #streamer :AAAAH";
+        let message = ChatMessage::from_irc(&IrcMessage::parse(line).unwrap()).unwrap();
+        assert!(message.moderator);
+        assert_eq!(message.reward_id.as_deref(), Some("abc-123"));
+
+        // Anonymous connections without tags: nobody is a moderator
+        let line = ":viewer!viewer@viewer.tmi.twitch.tv PRIVMSG #chan :!airhorn";
+        let message = ChatMessage::from_irc(&IrcMessage::parse(line).unwrap()).unwrap();
+        assert!(!message.moderator);
+        assert!(message.reward_id.is_none());
+
+        let join = IrcMessage::parse(":bot!bot@bot.tmi.twitch.tv JOIN #chan").unwrap();
+        assert!(ChatMessage::from_irc(&join).is_none());
+    }
+}
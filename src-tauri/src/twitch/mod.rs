@@ -0,0 +1,14 @@
+//! Twitch chat integration
+//!
+//! Chat commands (`!airhorn`) and channel-point redemptions play sounds
+//! through a queue, with per-viewer and global cooldowns and a moderator
+//! kill switch.
+
+mod app;
+mod client;
+mod config;
+mod irc;
+mod router;
+
+pub use app::{apply_settings, forward_sound_events, set_paused, TwitchState, TwitchStatus};
+pub use config::TwitchConfig;
@@ -0,0 +1,284 @@
+//! Chat trigger rules: cooldowns, the play queue and the kill switch
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use super::config::TwitchConfig;
+use super::irc::ChatMessage;
+use crate::sounds::SoundId;
+
+/// What a chat message asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatAction {
+    /// Queue a sound
+    Play(SoundId),
+    /// A moderator cleared the queue and paused triggers
+    Kill,
+    /// A moderator resumed triggers
+    Resume,
+}
+
+/// Turns chat messages into actions (independent of the connection)
+#[derive(Debug, Default)]
+pub struct ChatRouter {
+    paused: bool,
+    last_command: Option<Instant>,
+    last_command_by_user: HashMap<String, Instant>,
+}
+
+impl ChatRouter {
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause or resume triggers (from the app rather than chat)
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Route a message at `now`
+    ///
+    /// Redemptions are paid for with channel points, so only chat commands
+    /// have cooldowns.
+    pub fn handle(
+        &mut self,
+        message: &ChatMessage,
+        config: &TwitchConfig,
+        now: Instant,
+    ) -> Option<ChatAction> {
+        let command = message.text.split_whitespace().next().unwrap_or("");
+        if message.moderator && command.eq_ignore_ascii_case(&config.kill_command) {
+            self.paused = true;
+            return Some(ChatAction::Kill);
+        }
+        if message.moderator && command.eq_ignore_ascii_case(&config.resume_command) {
+            self.paused = false;
+            return Some(ChatAction::Resume);
+        }
+        if self.paused {
+            return None;
+        }
+
+        if let Some(reward_id) = &message.reward_id {
+            return config
+                .sound_for_reward(reward_id)
+                .map(|sound_id| ChatAction::Play(sound_id.clone()));
+        }
+
+        let sound_id = config.sound_for_command(command)?;
+        let cooling = |last: Option<&Instant>, secs: u32| {
+            last.is_some_and(|last| now.duration_since(*last) < Duration::from_secs(secs as u64))
+        };
+        if cooling(self.last_command.as_ref(), config.global_cooldown_secs) {
+            debug!("{} ignored: global cooldown", command);
+            return None;
+        }
+        if cooling(
+            self.last_command_by_user.get(&message.user),
+            config.user_cooldown_secs,
+        ) {
+            debug!("{} from {} ignored: user cooldown", command, message.user);
+            return None;
+        }
+        self.last_command = Some(now);
+        self.last_command_by_user.insert(message.user.clone(), now);
+        Some(ChatAction::Play(sound_id.clone()))
+    }
+}
+
+/// Chat sounds play one at a time, in order
+#[derive(Debug, Default)]
+pub struct SoundQueue {
+    playing: Option<SoundId>,
+    waiting: VecDeque<SoundId>,
+}
+
+impl SoundQueue {
+    /// Add a sound, returning it if nothing is playing (so it plays now)
+    ///
+    /// Fails when `max_waiting` sounds are already waiting.
+    pub fn push(
+        &mut self,
+        sound_id: SoundId,
+        max_waiting: usize,
+    ) -> Result<Option<SoundId>, String> {
+        if self.playing.is_none() {
+            self.playing = Some(sound_id.clone());
+            return Ok(Some(sound_id));
+        }
+        if self.waiting.len() >= max_waiting {
+            return Err(format!(
+                "Chat sound queue is full ({} waiting)",
+                max_waiting
+            ));
+        }
+        self.waiting.push_back(sound_id);
+        Ok(None)
+    }
+
+    /// A sound stopped; if it was the playing one, returns the next to play
+    pub fn finished(&mut self, sound_id: &SoundId) -> Option<SoundId> {
+        if self.playing.as_ref() != Some(sound_id) {
+            return None;
+        }
+        self.playing = self.waiting.pop_front();
+        self.playing.clone()
+    }
+
+    /// Drop everything, returning the playing sound (to stop it)
+    pub fn clear(&mut self) -> Option<SoundId> {
+        self.waiting.clear();
+        self.playing.take()
+    }
+
+    /// Sounds waiting behind the playing one
+    pub fn waiting(&self) -> usize {
+        self.waiting.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sound_id(id: &str) -> SoundId {
+        serde_json::from_str(&format!("\"{}\"", id)).unwrap()
+    }
+
+    fn chat(user: &str, text: &str) -> ChatMessage {
+        ChatMessage {
+            user: user.to_string(),
+            moderator: false,
+            text: text.to_string(),
+            reward_id: None,
+        }
+    }
+
+    fn config() -> TwitchConfig {
+        serde_json::from_value(serde_json::json!({
+            "commands": [{"command": "!airhorn", "sound_id": "horn"}],
+            "redemptions": [{"title": "Scream", "reward_id": "r1", "sound_id": "scream"}],
+            "user_cooldown_secs": 30,
+            "global_cooldown_secs": 5,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cooldowns() {
+        let config = config();
+        let mut router = ChatRouter::default();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let horn = Some(ChatAction::Play(sound_id("horn")));
+
+        assert_eq!(
+            router.handle(&chat("alice", "!airhorn"), &config, at(0)),
+            horn
+        );
+        // Global cooldown applies to everyone
+        assert_eq!(
+            router.handle(&chat("bob", "!AIRHORN"), &config, at(2)),
+            None
+        );
+        assert_eq!(
+            router.handle(&chat("bob", "!airhorn now"), &config, at(6)),
+            horn
+        );
+        // Per-user cooldown outlasts the global one
+        assert_eq!(
+            router.handle(&chat("alice", "!airhorn"), &config, at(12)),
+            None
+        );
+        assert_eq!(
+            router.handle(&chat("alice", "!airhorn"), &config, at(30)),
+            horn
+        );
+
+        // Other chat is ignored and doesn't start cooldowns
+        assert_eq!(
+            router.handle(&chat("carol", "hello !airhorn"), &config, at(40)),
+            None
+        );
+        assert_eq!(
+            router.handle(&chat("carol", "!airhorn"), &config, at(40)),
+            horn
+        );
+    }
+
+    #[test]
+    fn test_redemptions_skip_cooldowns() {
+        let config = config();
+        let mut router = ChatRouter::default();
+        let now = Instant::now();
+        let mut redemption = chat("alice", "AAAH");
+        redemption.reward_id = Some("r1".to_string());
+
+        for _ in 0..2 {
+            assert_eq!(
+                router.handle(&redemption, &config, now),
+                Some(ChatAction::Play(sound_id("scream")))
+            );
+        }
+        redemption.reward_id = Some("unmapped".to_string());
+        assert_eq!(router.handle(&redemption, &config, now), None);
+    }
+
+    #[test]
+    fn test_kill_switch_is_moderator_only() {
+        let config = config();
+        let mut router = ChatRouter::default();
+        let now = Instant::now();
+
+        assert_eq!(
+            router.handle(&chat("troll", "!stopsounds"), &config, now),
+            None
+        );
+        assert!(!router.is_paused());
+
+        let mut moderator = chat("mod", "!StopSounds");
+        moderator.moderator = true;
+        assert_eq!(
+            router.handle(&moderator, &config, now),
+            Some(ChatAction::Kill)
+        );
+        assert_eq!(
+            router.handle(&chat("alice", "!airhorn"), &config, now),
+            None
+        );
+
+        moderator.text = "!resumesounds".to_string();
+        assert_eq!(
+            router.handle(&moderator, &config, now),
+            Some(ChatAction::Resume)
+        );
+        assert_eq!(
+            router.handle(&chat("alice", "!airhorn"), &config, now),
+            Some(ChatAction::Play(sound_id("horn")))
+        );
+    }
+
+    #[test]
+    fn test_queue_plays_in_order_with_cap() {
+        let mut queue = SoundQueue::default();
+        assert_eq!(queue.push(sound_id("a"), 2), Ok(Some(sound_id("a"))));
+        assert_eq!(queue.push(sound_id("b"), 2), Ok(None));
+        assert_eq!(queue.push(sound_id("c"), 2), Ok(None));
+        assert!(queue.push(sound_id("d"), 2).is_err());
+        assert_eq!(queue.waiting(), 2);
+
+        // Sounds other than the playing one finishing don't advance it
+        assert_eq!(queue.finished(&sound_id("b")), None);
+        assert_eq!(queue.finished(&sound_id("a")), Some(sound_id("b")));
+        assert_eq!(queue.finished(&sound_id("b")), Some(sound_id("c")));
+        assert_eq!(queue.finished(&sound_id("c")), None);
+
+        assert_eq!(queue.push(sound_id("e"), 0), Ok(Some(sound_id("e"))));
+        assert!(queue.push(sound_id("f"), 0).is_err());
+        assert_eq!(queue.clear(), Some(sound_id("e")));
+        assert_eq!(queue.waiting(), 0);
+        assert_eq!(queue.clear(), None);
+    }
+}
@@ -0,0 +1,340 @@
+//! Format, channel and sample-rate conversion for microphone routing
+//!
+//! The microphone and CABLE Input run on independent clocks and may differ in
+//! channel count and nominal sample rate (sample formats are handled by the audio
+//! backend). Everything here works on plain interleaved f32 buffers so it can be
+//! tested without audio devices:
+//!
+//! - [`convert_channels`] maps any channel layout onto any other
+//! - [`Resampler`] is a streaming linear-interpolation resampler whose ratio can be
+//!   nudged per callback
+//! - [`DriftController`] derives that nudge from the ring buffer fill level so the
+//!   buffer neither drains nor overflows when the two clocks drift apart
+
+/// Map interleaved `input` with `in_channels` onto `output` with `out_channels`
+///
+/// - Equal layouts are copied.
+/// - Mono input is duplicated to every output channel.
+/// - Extra input channels are folded in: each output channel is the mean of the
+///   input channels that map to it (`in % out_channels`), so 2 -> 1 averages and
+///   4 -> 2 mixes channels 1+3 and 2+4.
+/// - Extra output channels (multi-channel input) are left silent.
+///
+/// Converts as many whole frames as both buffers hold; the rest of `output` is
+/// filled with silence.
+pub fn convert_channels(
+    input: &[f32],
+    in_channels: usize,
+    output: &mut [f32],
+    out_channels: usize,
+) {
+    let in_channels = in_channels.max(1);
+    let out_channels = out_channels.max(1);
+    let frames = (input.len() / in_channels).min(output.len() / out_channels);
+    let (converted, rest) = output.split_at_mut(frames * out_channels);
+    rest.fill(0.0);
+
+    let in_frames = input.chunks_exact(in_channels);
+    let out_frames = converted.chunks_exact_mut(out_channels);
+
+    if in_channels == out_channels {
+        converted.copy_from_slice(&input[..frames * in_channels]);
+    } else if in_channels == 1 {
+        for (inp, out) in in_frames.zip(out_frames) {
+            out.fill(inp[0]);
+        }
+    } else if in_channels > out_channels {
+        for (inp, out) in in_frames.zip(out_frames) {
+            for (c, sample) in out.iter_mut().enumerate() {
+                let (sum, count) = inp
+                    .iter()
+                    .skip(c)
+                    .step_by(out_channels)
+                    .fold((0.0f32, 0usize), |(s, n), x| (s + x, n + 1));
+                *sample = sum / count as f32;
+            }
+        }
+    } else {
+        for (inp, out) in in_frames.zip(out_frames) {
+            out[..in_channels].copy_from_slice(inp);
+            out[in_channels..].fill(0.0);
+        }
+    }
+}
+
+/// Streaming linear-interpolation resampler for interleaved audio
+///
+/// Callers first ask how many input frames the next output block needs
+/// ([`input_frames_needed`](Self::input_frames_needed)), push exactly that many,
+/// then render. The `adjust` factor scales the nominal step so a
+/// [`DriftController`] can speed up or slow down consumption.
+pub struct Resampler {
+    channels: usize,
+    /// Input frames advanced per output frame at nominal rates
+    step: f64,
+    /// Read position in `fifo`, in frames
+    pos: f64,
+    /// Pending input, always holding at least the frame under `pos`
+    fifo: Vec<f32>,
+}
+
+impl Resampler {
+    /// Create a resampler from `input_rate` to `output_rate`
+    ///
+    /// `max_frames` sizes the internal buffer so typical callbacks never allocate.
+    pub fn new(input_rate: u32, output_rate: u32, channels: usize, max_frames: usize) -> Self {
+        let channels = channels.max(1);
+        let mut fifo = Vec::with_capacity((max_frames + 2) * channels);
+        // One frame of silence to interpolate from at startup
+        fifo.resize(channels, 0.0);
+        Self {
+            channels,
+            step: input_rate.max(1) as f64 / output_rate.max(1) as f64,
+            pos: 0.0,
+            fifo,
+        }
+    }
+
+    fn fifo_frames(&self) -> usize {
+        self.fifo.len() / self.channels
+    }
+
+    /// Input frames to push before rendering `out_frames` with the given adjustment
+    pub fn input_frames_needed(&self, out_frames: usize, adjust: f64) -> usize {
+        if out_frames == 0 {
+            return 0;
+        }
+        let step = self.step * adjust;
+        let last = (self.pos + (out_frames - 1) as f64 * step).floor() as usize;
+        // Interpolation reads one frame past `last`
+        (last + 2).saturating_sub(self.fifo_frames())
+    }
+
+    /// Append interleaved input frames
+    pub fn push(&mut self, input: &[f32]) {
+        self.fifo.extend_from_slice(input);
+    }
+
+    /// Fill `output` with resampled audio, consuming input from the buffer
+    ///
+    /// Missing input (fewer frames pushed than needed) is treated as silence.
+    pub fn render(&mut self, output: &mut [f32], adjust: f64) {
+        let channels = self.channels;
+        let step = self.step * adjust;
+        let frames = self.fifo_frames();
+
+        for (k, out) in output.chunks_exact_mut(channels).enumerate() {
+            let p = self.pos + k as f64 * step;
+            let index = p.floor() as usize;
+            let frac = (p - index as f64) as f32;
+            for (c, sample) in out.iter_mut().enumerate() {
+                let a = if index < frames {
+                    self.fifo[index * channels + c]
+                } else {
+                    0.0
+                };
+                let b = if index + 1 < frames {
+                    self.fifo[(index + 1) * channels + c]
+                } else {
+                    a
+                };
+                *sample = a + (b - a) * frac;
+            }
+        }
+
+        // Advance and drop fully consumed frames, keeping the one under `pos`
+        self.pos += (output.len() / channels) as f64 * step;
+        let consumed = (self.pos.floor() as usize).min(frames.saturating_sub(1));
+        self.pos -= consumed as f64;
+        self.fifo.drain(..consumed * channels);
+        if self.fifo.is_empty() {
+            self.fifo.resize(channels, 0.0);
+            self.pos = 0.0;
+        }
+    }
+}
+
+/// Fraction of the fill error applied to the resampling ratio
+const DRIFT_GAIN: f64 = 0.05;
+/// Largest ratio correction (0.5%, well below audible pitch change)
+const DRIFT_MAX_ADJUST: f64 = 0.005;
+/// Smoothing of the measured fill level per update
+const DRIFT_SMOOTHING: f64 = 0.05;
+
+/// Keeps the ring buffer near its target fill by nudging the resampling ratio
+///
+/// When the microphone clock runs fast the buffer fills up and the controller
+/// consumes slightly faster; when it runs slow, slightly slower.
+pub struct DriftController {
+    target: f64,
+    smoothed: f64,
+}
+
+impl DriftController {
+    /// Create a controller aiming for `target_fill` buffered samples
+    pub fn new(target_fill: usize) -> Self {
+        let target = target_fill.max(1) as f64;
+        Self {
+            target,
+            smoothed: target,
+        }
+    }
+
+    /// Change the fill level to aim for, keeping the smoothed measurement
+    pub fn set_target(&mut self, target_fill: usize) {
+        self.target = target_fill.max(1) as f64;
+    }
+
+    /// Feed the current fill level and get the ratio adjustment for this block
+    pub fn update(&mut self, fill: usize) -> f64 {
+        self.smoothed += DRIFT_SMOOTHING * (fill as f64 - self.smoothed);
+        let error = (self.smoothed - self.target) / self.target;
+        1.0 + (error * DRIFT_GAIN).clamp(-DRIFT_MAX_ADJUST, DRIFT_MAX_ADJUST)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::test_util::{sine, zero_crossing_freq};
+
+    /// Resample `input` in blocks of `block` output frames
+    fn resample(input: &[f32], from: u32, to: u32, block: usize) -> Vec<f32> {
+        let mut resampler = Resampler::new(from, to, 1, block * 2);
+        let mut read = 0;
+        let mut output = Vec::new();
+        let mut out = vec![0.0; block];
+        while read < input.len() {
+            let needed = resampler.input_frames_needed(block, 1.0);
+            let end = (read + needed).min(input.len());
+            resampler.push(&input[read..end]);
+            read = end;
+            resampler.render(&mut out, 1.0);
+            output.extend_from_slice(&out);
+        }
+        output
+    }
+
+    #[test]
+    fn test_channels_mono_to_stereo() {
+        let mut output = [0.0; 6];
+        convert_channels(&[0.1, 0.2, 0.3], 1, &mut output, 2);
+        assert_eq!(output, [0.1, 0.1, 0.2, 0.2, 0.3, 0.3]);
+    }
+
+    #[test]
+    fn test_channels_stereo_to_mono_averages() {
+        let mut output = [0.0; 2];
+        convert_channels(&[0.2, 0.4, -1.0, 1.0], 2, &mut output, 1);
+        assert!((output[0] - 0.3).abs() < 1e-6);
+        assert_eq!(output[1], 0.0);
+    }
+
+    #[test]
+    fn test_channels_quad_to_stereo_folds() {
+        let mut output = [0.0; 2];
+        convert_channels(&[0.1, 0.2, 0.3, 0.4], 4, &mut output, 2);
+        assert!((output[0] - 0.2).abs() < 1e-6);
+        assert!((output[1] - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_channels_stereo_to_surround_leaves_extra_silent() {
+        let mut output = [9.0; 6];
+        convert_channels(&[0.5, -0.5], 2, &mut output, 6);
+        assert_eq!(output, [0.5, -0.5, 0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_channels_short_input_pads_silence() {
+        let mut output = [9.0; 8];
+        convert_channels(&[0.5, 0.5], 2, &mut output, 2);
+        assert_eq!(output, [0.5, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_resampler_equal_rates_is_delayed_copy() {
+        let input: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let output = resample(&input, 48000, 48000, 256);
+        // One frame of startup silence, then the input unchanged
+        assert_eq!(output[0], 0.0);
+        assert_eq!(&output[1..1000], &input[..999]);
+    }
+
+    #[test]
+    fn test_resampler_preserves_pitch_and_length() {
+        let input = sine(1000.0, 44100, 44100, 1, 1.0);
+        let output = resample(&input, 44100, 48000, 480);
+
+        // One second in, about one second out
+        assert!((output.len() as i64 - 48000).abs() <= 480);
+        let freq = zero_crossing_freq(&output[..48000], 48000);
+        assert!((freq - 1000.0).abs() < 5.0, "frequency was {}", freq);
+    }
+
+    #[test]
+    fn test_resampler_block_size_independent() {
+        let input = sine(440.0, 48000, 9600, 1, 1.0);
+        let a = resample(&input, 48000, 44100, 128);
+        let b = resample(&input, 48000, 44100, 441);
+        let n = a.len().min(b.len()) - 512;
+        for (x, y) in a[..n].iter().zip(&b[..n]) {
+            assert!((x - y).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_resampler_multichannel_keeps_channels_apart() {
+        let input: Vec<f32> = (0..2000).flat_map(|_| [0.5f32, -0.5]).collect();
+        let mut resampler = Resampler::new(48000, 44100, 2, 512);
+        let needed = resampler.input_frames_needed(441, 1.0);
+        resampler.push(&input[..needed * 2]);
+        let mut output = vec![0.0; 441 * 2];
+        resampler.render(&mut output, 1.0);
+
+        for frame in output[4..].chunks(2) {
+            assert!((frame[0] - 0.5).abs() < 1e-6);
+            assert!((frame[1] + 0.5).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_drift_controller_direction() {
+        let mut controller = DriftController::new(4800);
+        assert_eq!(controller.update(4800), 1.0);
+
+        let mut fast = DriftController::new(4800);
+        let mut slow = DriftController::new(4800);
+        for _ in 0..200 {
+            assert!(fast.update(9000) >= 1.0);
+            assert!(slow.update(100) <= 1.0);
+        }
+        assert!((fast.update(9000) - 1.0 - DRIFT_MAX_ADJUST).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drift_compensation_keeps_buffer_bounded() {
+        // Mic clock 0.2% fast against a 48 kHz output, 10 ms blocks on both
+        // sides, for one simulated minute
+        let target = 4800;
+        let mut fill = target as f64;
+        let mut controller = DriftController::new(target);
+        let mut resampler = Resampler::new(48000, 48000, 1, 1024);
+        let block = 480;
+        let block_in = vec![0.0f32; 1024];
+        let mut out = vec![0.0f32; block];
+
+        for _ in 0..6000 {
+            fill += block as f64 * 1.002;
+            let adjust = controller.update(fill as usize);
+            let needed = resampler.input_frames_needed(block, adjust);
+            fill -= needed as f64;
+            resampler.push(&block_in[..needed]);
+            resampler.render(&mut out, adjust);
+
+            assert!(fill > 0.0 && fill < (target * 2) as f64, "fill {}", fill);
+        }
+        // Settled within 20% of the target
+        assert!((fill - target as f64).abs() < target as f64 * 0.2);
+    }
+}
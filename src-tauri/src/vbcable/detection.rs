@@ -1,11 +1,12 @@
-//! VB-Cable detection via cpal device enumeration
+//! VB-Cable detection via audio backend device enumeration
 
-use cpal::traits::{DeviceTrait, HostTrait};
 use serde::Serialize;
 use std::thread;
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use crate::audio::{AudioBackend, AudioError, BackendDevice};
+
 /// Information about detected VB-Cable devices
 #[derive(Debug, Clone, Serialize)]
 pub struct VbCableInfo {
@@ -31,36 +32,17 @@ pub enum VbCableStatus {
 ///
 /// Searches for both the output device (CABLE Input) and input device (CABLE Output).
 /// Returns None if VB-Cable output device is not found.
-pub fn detect_vb_cable() -> Option<VbCableInfo> {
-    let host = cpal::default_host();
-
-    let mut output_device = None;
-    let mut input_device = None;
-
+pub fn detect_vb_cable(backend: &dyn AudioBackend) -> Option<VbCableInfo> {
     // Find output device (CABLE Input - where apps send audio)
-    if let Ok(devices) = host.output_devices() {
-        for device in devices {
-            if let Ok(name) = device.name() {
-                if name.to_lowercase().contains("cable input") {
-                    debug!("VB-Cable output device found: {}", name);
-                    output_device = Some(name);
-                    break;
-                }
-            }
-        }
+    let output_device = find_device(backend.output_devices(), "cable input");
+    if let Some(name) = &output_device {
+        debug!("VB-Cable output device found: {}", name);
     }
 
     // Find input device (CABLE Output - where apps receive audio)
-    if let Ok(devices) = host.input_devices() {
-        for device in devices {
-            if let Ok(name) = device.name() {
-                if name.to_lowercase().contains("cable output") {
-                    debug!("VB-Cable input device found: {}", name);
-                    input_device = Some(name);
-                    break;
-                }
-            }
-        }
+    let input_device = find_device(backend.input_devices(), "cable output");
+    if let Some(name) = &input_device {
+        debug!("VB-Cable input device found: {}", name);
     }
 
     // VB-Cable output device is required, input device is optional
@@ -70,6 +52,15 @@ pub fn detect_vb_cable() -> Option<VbCableInfo> {
     })
 }
 
+/// Name of the first device whose lowercased name contains `pattern`
+fn find_device(devices: Result<Vec<BackendDevice>, AudioError>, pattern: &str) -> Option<String> {
+    devices
+        .ok()?
+        .into_iter()
+        .map(|device| device.name)
+        .find(|name| name.to_lowercase().contains(pattern))
+}
+
 const MAX_RETRIES: u32 = 5;
 const RETRY_DELAY_MS: u64 = 1000;
 
@@ -77,9 +68,9 @@ const RETRY_DELAY_MS: u64 = 1000;
 ///
 /// Use after installation when Windows needs time to register the device.
 /// Returns early as soon as device is detected, or None after all retries.
-pub fn wait_for_vb_cable() -> Option<VbCableInfo> {
+pub fn wait_for_vb_cable(backend: &dyn AudioBackend) -> Option<VbCableInfo> {
     for attempt in 1..=MAX_RETRIES {
-        if let Some(info) = detect_vb_cable() {
+        if let Some(info) = detect_vb_cable(backend) {
             info!("VB-Cable detected on attempt {}/{}", attempt, MAX_RETRIES);
             return Some(info);
         }
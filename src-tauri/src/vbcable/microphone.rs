@@ -5,17 +5,66 @@
 //!
 //! Audio flow: Microphone -> [This Module] -> CABLE Input -> CABLE Output -> Discord
 //!
+//! ## Mixing
+//! The routing output is the only stream on CABLE Input. Its soundboard bus is
+//! attached to the [`BusRegistry`], so sounds played to CABLE Input are summed with
+//! the mic here (see [`RoutingMixer`]) instead of opening a competing stream.
+//!
+//! ## Ducking
+//! The routed mic can be ducked while soundboard voices play, and its level is
+//! published to the shared sidechain so output buses can duck while the user speaks.
+//!
+//! ## Processing
+//! An optional chain (gain, high-pass, noise gate, compressor, see [`MicChain`])
+//! runs on the captured mic before it enters the ring buffer. Settings changes are
+//! built into a new chain on the routing thread and handed to the input callback
+//! without restarting the streams.
+//!
+//! ## Push-to-talk
+//! In push-to-talk or push-to-mute mode the processed mic is gated by a global
+//! shortcut (see the `talk` module) before level detection, so a muted mic never
+//! triggers ducking. Soundboard audio on CABLE Input is not affected.
+//!
+//! ## Conversion
+//! Each stream runs in its device's native format, channel count and sample rate.
+//! The output callback resamples the mic to the CABLE rate and remaps channels
+//! (see the `convert` module), nudging the ratio to keep the ring buffer half full
+//! as the two device clocks drift apart.
+//!
 //! ## Latency
-//! Uses a 100ms ring buffer for balance between latency and stability.
-//! Earlier versions used 1s which caused noticeable delay (see #83).
-
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+//! Mic audio crosses between the two audio threads through a lock-free ring
+//! (see the `ring` module). It starts at a 50ms latency target and adapts between
+//! 20ms and 150ms: underruns raise the target, long stable periods lower it.
+//! Earlier versions used a fixed 1s buffer which caused noticeable delay (see #83).
+//! Underrun/overrun counters and the current latency are available through
+//! [`get_routing_stats`].
+//!
+//! ## Real-time safety
+//! Neither callback locks or allocates: scratch buffers are sized for one block
+//! when the streams open and larger callbacks are processed block by block, the
+//! soundboard bus is owned by the output callback, and replaced mic chains are
+//! dropped on the routing thread.
+//!
+//! ## Recovery
+//! Stream errors (e.g. an unplugged microphone) mark the session as failed instead
+//! of only being logged. The app's device monitor checks [`routing_failed`] and
+//! restarts routing once the microphone is available again.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use tracing::{debug, error, info, warn};
-
-use crate::audio::DeviceId;
+use tracing::{debug, error, info};
+
+use super::convert::{convert_channels, DriftController, Resampler};
+use super::mixer::{RoutingGains, RoutingMixer};
+use super::processing::{MicChain, MicProcessingConfig};
+use super::ring::{ring_buffer, RingMonitor, RoutingStats};
+use super::talk::{GateRamp, MicGateMode, TalkGate};
+use crate::audio::{
+    AudioBackend, BusMixer, BusRegistry, DeviceId, Ducker, EnvelopeFollower, ErrorCallback,
+    StreamLatency, VOICE_DETECT_ATTACK_MS, VOICE_DETECT_RELEASE_MS,
+};
 
 // ============================================================================
 // Global Routing State
@@ -24,14 +73,32 @@ use crate::audio::DeviceId;
 /// Global state for active microphone routing - only stores thread-safe data
 static ROUTING_STATE: Mutex<Option<RoutingHandle>> = Mutex::new(None);
 
+/// Mic and soundboard gains for the routed stream (persisted in settings)
+static ROUTING_GAINS: RoutingGains = RoutingGains::new();
+
+/// Push-to-talk/push-to-mute state (mode persisted in settings)
+static TALK_GATE: TalkGate = TalkGate::new();
+
+/// Mic processing settings (persisted in settings)
+static MIC_PROCESSING: Mutex<Option<MicProcessingConfig>> = Mutex::new(None);
+
+/// Bumped on every [`set_mic_processing`] so the routing thread knows to rebuild the chain
+static MIC_PROCESSING_VERSION: AtomicU64 = AtomicU64::new(0);
+
 /// Thread-safe handle for controlling an active routing session
 struct RoutingHandle {
     /// Device ID of the microphone being routed
     microphone_id: String,
     /// Signal to stop the routing thread
     stop_signal: Arc<AtomicBool>,
+    /// Set when a stream reports an error or the thread exits early
+    failed: Arc<AtomicBool>,
     /// Handle to the routing thread (for cleanup)
     _thread_handle: JoinHandle<()>,
+    /// Ring buffer statistics, with the mic format to convert them to milliseconds
+    ring_monitor: RingMonitor,
+    input_rate: u32,
+    input_channels: usize,
 }
 
 // ============================================================================
@@ -42,18 +109,15 @@ struct RoutingHandle {
 ///
 /// Returns (DeviceId, display_name) pairs.
 /// Excludes VB-Cable devices (CABLE Output is a recording device).
-pub fn list_capture_devices() -> Vec<(String, String)> {
-    let host = cpal::default_host();
+pub fn list_capture_devices(backend: &dyn AudioBackend) -> Vec<(String, String)> {
     let mut devices = Vec::new();
 
-    if let Ok(input_devices) = host.input_devices() {
-        for (index, device) in input_devices.enumerate() {
-            if let Ok(name) = device.name() {
-                // Skip VB-Cable devices (CABLE Output appears as input device)
-                if !name.to_lowercase().contains("cable") {
-                    let id = DeviceId::from_index(index).to_string();
-                    devices.push((id, name));
-                }
+    if let Ok(input_devices) = backend.input_devices() {
+        for (index, device) in input_devices.into_iter().enumerate() {
+            // Skip unnamed devices and VB-Cable (CABLE Output appears as input device)
+            if !device.name.is_empty() && !device.name.to_lowercase().contains("cable") {
+                let id = DeviceId::from_index(index).to_string();
+                devices.push((id, device.name));
             }
         }
     }
@@ -65,91 +129,99 @@ pub fn list_capture_devices() -> Vec<(String, String)> {
     devices
 }
 
-/// Find a capture device by DeviceId
-fn find_capture_device(device_id: &str) -> Option<cpal::Device> {
-    let host = cpal::default_host();
-
+/// Find a capture device by DeviceId, returning its index and name
+fn find_capture_device(backend: &dyn AudioBackend, device_id: &str) -> Option<(usize, String)> {
     // Parse the index from the device_id (e.g., "device_0" -> 0)
     let index = device_id
         .strip_prefix("device_")
         .and_then(|s| s.parse::<usize>().ok())?;
 
-    host.input_devices().ok()?.nth(index)
+    let device = backend.input_devices().ok()?.into_iter().nth(index)?;
+    Some((index, device.name))
 }
 
 /// Find CABLE Input device (output device for routing audio to VB-Cable)
-fn find_cable_input_device() -> Option<cpal::Device> {
-    let host = cpal::default_host();
-
-    if let Ok(devices) = host.output_devices() {
-        for device in devices {
-            if let Ok(name) = device.name() {
-                if name.to_lowercase().contains("cable input") {
-                    debug!("Found CABLE Input device: {}", name);
-                    return Some(device);
-                }
-            }
-        }
-    }
-
-    None
+///
+/// Returns the device name together with its output DeviceId so the routing bus can
+/// be registered under the same ID that playback uses.
+fn find_cable_input_device(backend: &dyn AudioBackend) -> Option<(DeviceId, String)> {
+    let devices = backend.output_devices().ok()?;
+    let (index, device) = devices
+        .into_iter()
+        .enumerate()
+        .find(|(_, device)| device.name.to_lowercase().contains("cable input"))?;
+    debug!("Found CABLE Input device: {}", device.name);
+    Some((DeviceId::from_index(index), device.name))
 }
 
 // ============================================================================
 // Microphone Routing
 // ============================================================================
 
-/// Ring buffer for transferring audio between input and output streams
-struct RingBuffer {
-    buffer: Vec<f32>,
-    write_pos: usize,
-    read_pos: usize,
-    capacity: usize,
-    overflow_logged: bool,
+/// Frames processed at a time; larger callbacks are split so scratch buffers never grow
+const BLOCK_FRAMES: usize = 1024;
+
+/// How often the routing thread checks for a stop and for new processing settings
+const ROUTING_POLL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Ring buffer capacity
+const RING_CAPACITY_MS: usize = 200;
+/// Initial latency target, prefilled with silence so the output never starts starved
+const RING_PREFILL_MS: usize = 50;
+/// Bounds of the adaptive latency target
+const RING_MIN_TARGET_MS: usize = 20;
+const RING_MAX_TARGET_MS: usize = 150;
+
+/// Most mic frames the resampler can need for one output block
+///
+/// Covers the drift adjustment and the frames interpolation reads past the block.
+fn max_input_frames(input_rate: u32, output_rate: u32) -> usize {
+    let step = input_rate.max(1) as f64 / output_rate.max(1) as f64;
+    (BLOCK_FRAMES as f64 * step * 1.01).ceil() as usize + 2
 }
 
-impl RingBuffer {
-    /// Create a new ring buffer with prefilled silence
-    ///
-    /// Prefills half the buffer with silence so the output stream
-    /// never "starves" waiting for input data. This prevents
-    /// audio glitches at stream startup (industry standard practice).
-    fn new(capacity: usize) -> Self {
-        Self {
-            buffer: vec![0.0; capacity],
-            write_pos: capacity / 2, // Start ahead to prevent underruns
-            read_pos: 0,
-            capacity,
-            overflow_logged: false,
-        }
-    }
+/// Error callback for a routing stream: log and mark the session as failed
+fn stream_error_handler(failed: &Arc<AtomicBool>, what: &'static str) -> ErrorCallback {
+    let failed = failed.clone();
+    Arc::new(move |err: String| {
+        error!("{} stream error: {}", what, err);
+        failed.store(true, Ordering::Relaxed);
+    })
+}
 
-    fn write(&mut self, samples: &[f32]) {
-        for &sample in samples {
-            self.buffer[self.write_pos] = sample;
-            self.write_pos = (self.write_pos + 1) % self.capacity;
+/// Set the mic and soundboard gains (in dB) of the routed stream
+///
+/// Applies immediately to an active routing session and to future ones.
+pub fn set_routing_gains(mic_gain_db: f32, board_gain_db: f32) {
+    ROUTING_GAINS.set_db(mic_gain_db, board_gain_db);
+}
 
-            // Detect buffer overflow (write catching up to read)
-            if self.write_pos == self.read_pos && !self.overflow_logged {
-                warn!("Ring buffer overflow detected - some audio data may be lost");
-                self.overflow_logged = true;
-            }
-        }
+/// Set the processing chain applied to the routed mic
+///
+/// Applies immediately to an active routing session and to future ones.
+pub fn set_mic_processing(config: MicProcessingConfig) {
+    if let Ok(mut current) = MIC_PROCESSING.lock() {
+        *current = Some(config);
+        MIC_PROCESSING_VERSION.fetch_add(1, Ordering::Release);
     }
+}
 
-    fn read(&mut self, output: &mut [f32]) {
-        for sample in output.iter_mut() {
-            *sample = self.buffer[self.read_pos];
-            self.read_pos = (self.read_pos + 1) % self.capacity;
-        }
-    }
+/// Build the mic chain from the current settings
+fn current_mic_chain(sample_rate: u32, channels: usize) -> MicChain {
+    let config = MIC_PROCESSING
+        .lock()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default();
+    MicChain::new(config, sample_rate, channels)
 }
 
 /// Enable microphone routing to CABLE Input
 ///
 /// Captures audio from the specified microphone and routes it to CABLE Input.
-/// This allows the user's voice to be mixed with soundboard audio in VB-Cable.
-pub fn enable_routing(microphone_id: &str) -> Result<(), String> {
+/// Soundboard voices playing on CABLE Input are mixed into the same stream via a
+/// bus attached to `buses`, whose sidechain also drives ducking.
+pub fn enable_routing(microphone_id: &str, buses: Arc<BusRegistry>) -> Result<(), String> {
     // Check if routing is already active
     {
         let state = ROUTING_STATE
@@ -169,193 +241,244 @@ pub fn enable_routing(microphone_id: &str) -> Result<(), String> {
         }
     }
 
+    let backend = buses.backend();
+
     // Find microphone device
-    let mic_device = find_capture_device(microphone_id)
+    let (mic_index, mic_name) = find_capture_device(&*backend, microphone_id)
         .ok_or_else(|| format!("Microphone device not found: {}", microphone_id))?;
-    let mic_name = mic_device.name().unwrap_or_else(|_| "Unknown".to_string());
     info!("Found microphone: {}", mic_name);
 
     // Find CABLE Input device
-    let cable_device =
-        find_cable_input_device().ok_or("CABLE Input device not found. Is VB-Cable installed?")?;
-    let cable_name = cable_device
-        .name()
-        .unwrap_or_else(|_| "Unknown".to_string());
+    let (cable_id, cable_name) = find_cable_input_device(&*backend)
+        .ok_or("CABLE Input device not found. Is VB-Cable installed?")?;
+    let cable_index = cable_id.index().map_err(|e| e.to_string())?;
     info!("Found CABLE Input: {}", cable_name);
 
-    // Get supported configs
-    let input_config = mic_device
-        .default_input_config()
+    // Both streams run in their device's native format; the backend converts samples
+    let input_format = backend
+        .input_format(mic_index)
         .map_err(|e| format!("No input config for microphone: {}", e))?;
-    let output_config = cable_device
-        .default_output_config()
+    let output_format = backend
+        .output_format(cable_index)
         .map_err(|e| format!("No output config for CABLE Input: {}", e))?;
 
     info!(
-        "Input config: {} Hz, {} channels, {:?}",
-        input_config.sample_rate().0,
-        input_config.channels(),
-        input_config.sample_format()
+        "Input config: {} Hz, {} channels",
+        input_format.sample_rate, input_format.channels
     );
     info!(
-        "Output config: {} Hz, {} channels, {:?}",
-        output_config.sample_rate().0,
-        output_config.channels(),
-        output_config.sample_format()
+        "Output config: {} Hz, {} channels",
+        output_format.sample_rate, output_format.channels
     );
 
     // Create stop signal
     let stop_signal = Arc::new(AtomicBool::new(false));
     let stop_signal_clone = stop_signal.clone();
+    let failed = Arc::new(AtomicBool::new(false));
+    let failed_thread = failed.clone();
 
     // Store configuration for the thread
     let mic_id = microphone_id.to_string();
-    let input_channels = input_config.channels();
-    let output_channels = output_config.channels();
-    let sample_rate = input_config.sample_rate();
-
-    // Calculate buffer size for ~100ms latency (balance between latency and stability)
-    // Formula: sample_rate * channels / 10 (100ms = 1/10 second)
-    // Note: 50ms was too aggressive and caused audio glitches
-    let buffer_size = (sample_rate.0 as usize * input_channels as usize / 10).max(4096);
+    let input_channels = input_format.channels as usize;
+    let output_channels = output_format.channels as usize;
+    let input_rate = input_format.sample_rate;
+    let output_rate = output_format.sample_rate;
+    if input_rate != output_rate {
+        info!(
+            "Resampling microphone from {} Hz to {} Hz",
+            input_rate, output_rate
+        );
+    }
+
+    // Ring sizes are in interleaved mic samples
+    let samples_per_ms = (input_rate as usize * input_channels / 1000).max(1);
+    let (mut ring_producer, mut ring_consumer) = ring_buffer(
+        RING_CAPACITY_MS * samples_per_ms,
+        RING_PREFILL_MS * samples_per_ms,
+        RING_MIN_TARGET_MS * samples_per_ms,
+        RING_MAX_TARGET_MS * samples_per_ms,
+    );
+    let ring_monitor = ring_consumer.monitor();
     debug!(
-        "Ring buffer size: {} samples (~100ms at {} Hz, {} ch)",
-        buffer_size, sample_rate.0, input_channels
+        "Ring buffer: {} samples ({}ms at {} Hz, {} ch)",
+        RING_CAPACITY_MS * samples_per_ms,
+        RING_CAPACITY_MS,
+        input_rate,
+        input_channels
     );
 
     // Spawn routing thread
     let thread_handle = thread::spawn(move || {
-        let ring_buffer = Arc::new(Mutex::new(RingBuffer::new(buffer_size)));
-        let ring_buffer_input = ring_buffer.clone();
-        let ring_buffer_output = ring_buffer;
-
         let stop_signal_input = stop_signal_clone.clone();
         let stop_signal_output = stop_signal_clone.clone();
 
+        // Mic level detection (input side) and mic ducking (output side)
+        let sidechain_input = buses.sidechain();
+        let sidechain_output = buses.sidechain();
+        let mut voice_detector = EnvelopeFollower::new(
+            VOICE_DETECT_ATTACK_MS,
+            VOICE_DETECT_RELEASE_MS,
+            input_rate,
+            input_channels,
+        );
+        let ducking = sidechain_output.config();
+
+        // Mic processing; this thread builds new chains and frees the replaced ones
+        let mut mic_chain_version = MIC_PROCESSING_VERSION.load(Ordering::Acquire);
+        let mut mic_chain = current_mic_chain(input_rate, input_channels);
+        let (chain_tx, chain_rx) = mpsc::sync_channel::<MicChain>(1);
+        let (retired_tx, retired_rx) = mpsc::sync_channel::<MicChain>(1);
+        let mut processed = vec![0.0f32; BLOCK_FRAMES * input_channels];
+        let mut talk_ramp = GateRamp::new(TALK_GATE.is_open(), input_rate, input_channels);
+
+        let mut mic_ducker = Ducker::new(
+            ducking.mic_duck_depth_db,
+            ducking.attack_ms,
+            ducking.release_ms,
+            output_rate,
+            output_channels,
+        );
+
         // Build input stream (capture from microphone)
-        let input_stream = match mic_device.build_input_stream(
-            &input_config.into(),
-            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+        let input_stream = match backend.open_input(
+            mic_index,
+            Box::new(move |data: &[f32]| {
                 if stop_signal_input.load(Ordering::Relaxed) {
                     return;
                 }
 
-                if let Ok(mut buffer) = ring_buffer_input.lock() {
-                    buffer.write(data);
+                if let Ok(chain) = chain_rx.try_recv() {
+                    let replaced = std::mem::replace(&mut mic_chain, chain);
+                    // Freed by the routing thread (the channel always has room)
+                    let _ = retired_tx.try_send(replaced);
                 }
-            },
-            move |err| {
-                error!("Input stream error: {}", err);
-            },
-            None,
+
+                for input in data.chunks(processed.len()) {
+                    let block = &mut processed[..input.len()];
+                    block.copy_from_slice(input);
+                    mic_chain.process(block);
+                    talk_ramp.process(block, TALK_GATE.is_open());
+
+                    sidechain_input.set_mic_level(voice_detector.process(block));
+
+                    ring_producer.write(block);
+                }
+            }),
+            stream_error_handler(&failed_thread, "Input"),
         ) {
             Ok(stream) => stream,
             Err(e) => {
-                error!("Failed to build input stream: {}", e);
+                error!("Failed to open input stream: {}", e);
+                failed_thread.store(true, Ordering::Relaxed);
                 return;
             }
         };
 
-        // Build output stream config matching input sample rate
-        let output_stream_config = cpal::StreamConfig {
-            channels: output_channels,
-            sample_rate,
-            buffer_size: cpal::BufferSize::Default,
-        };
+        // Mic audio is pulled at the mic rate, resampled to the CABLE rate (with
+        // drift compensation against the ring fill level) and then remapped to the
+        // CABLE channel layout. All buffers are sized for one block here.
+        let mut drift = DriftController::new(ring_consumer.target());
+        let max_input_frames = max_input_frames(input_rate, output_rate);
+        let mut resampler =
+            Resampler::new(input_rate, output_rate, input_channels, max_input_frames);
+        let mut ring_buf = vec![0.0f32; max_input_frames * input_channels];
+        let mut resampled = vec![0.0f32; BLOCK_FRAMES * input_channels];
+
+        // Soundboard bus for CABLE Input, mixed with the mic in the output callback
+        let mut board_mixer = BusMixer::new(
+            output_rate,
+            output_channels,
+            buses.ceiling_db(),
+            buses.sidechain(),
+        );
+        let board_handle = board_mixer.handle();
+        let mut routing_mixer = RoutingMixer::new(output_rate, output_channels, buses.ceiling_db());
+        let mut mic_buf = vec![0.0f32; BLOCK_FRAMES * output_channels];
+        let mut board_buf = vec![0.0f32; BLOCK_FRAMES * output_channels];
 
         // Build output stream (play to CABLE Input)
-        let input_ch = input_channels;
-        let output_ch = output_channels;
-
-        // Pre-allocate conversion buffer to avoid heap allocation in audio callback
-        // Max buffer size for typical audio callbacks (256-4096 samples per channel)
-        const MAX_CALLBACK_SAMPLES: usize = 8192;
-        let conversion_buffer = Arc::new(Mutex::new(vec![0.0f32; MAX_CALLBACK_SAMPLES]));
-        let conversion_buffer_clone = conversion_buffer.clone();
-
-        let output_stream = match cable_device.build_output_stream(
-            &output_stream_config,
-            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+        let output_stream = match backend.open_output(
+            cable_index,
+            StreamLatency::Default,
+            Box::new(move |data: &mut [f32]| {
                 if stop_signal_output.load(Ordering::Relaxed) {
                     // Fill with silence when stopped
-                    for sample in data.iter_mut() {
-                        *sample = 0.0;
-                    }
+                    data.fill(0.0);
                     return;
                 }
 
-                if let Ok(mut buffer) = ring_buffer_output.lock() {
-                    // Handle channel conversion if needed
-                    if input_ch == output_ch {
-                        buffer.read(data);
-                    } else if input_ch == 1 && output_ch == 2 {
-                        // Mono to stereo: duplicate each sample
-                        let mono_samples = data.len() / 2;
-                        if let Ok(mut conv_buf) = conversion_buffer_clone.try_lock() {
-                            let conv_slice =
-                                &mut conv_buf[..mono_samples.min(MAX_CALLBACK_SAMPLES)];
-                            buffer.read(conv_slice);
-                            for (i, &sample) in conv_slice.iter().enumerate() {
-                                if i * 2 + 1 < data.len() {
-                                    data[i * 2] = sample;
-                                    data[i * 2 + 1] = sample;
-                                }
-                            }
-                        }
-                    } else if input_ch == 2 && output_ch == 1 {
-                        // Stereo to mono: average channels
-                        let stereo_samples = (data.len() * 2).min(MAX_CALLBACK_SAMPLES);
-                        if let Ok(mut conv_buf) = conversion_buffer_clone.try_lock() {
-                            let conv_slice = &mut conv_buf[..stereo_samples];
-                            buffer.read(conv_slice);
-                            for i in 0..data.len() {
-                                if i * 2 + 1 < stereo_samples {
-                                    data[i] = (conv_slice[i * 2] + conv_slice[i * 2 + 1]) * 0.5;
-                                }
-                            }
-                        }
-                    } else {
-                        // Fallback: just read what we can
-                        buffer.read(data);
-                    }
-                } else {
-                    // Fill with silence if lock fails
-                    for sample in data.iter_mut() {
-                        *sample = 0.0;
-                    }
+                for output in data.chunks_mut(BLOCK_FRAMES * output_channels) {
+                    // Pull exactly the mic frames the resampler needs for this block
+                    let out_frames = output.len() / output_channels;
+                    drift.set_target(ring_consumer.target());
+                    let adjust = drift.update(ring_consumer.available());
+                    let needed = resampler
+                        .input_frames_needed(out_frames, adjust)
+                        .min(max_input_frames);
+                    let ring_block = &mut ring_buf[..needed * input_channels];
+                    ring_consumer.read(ring_block);
+                    resampler.push(ring_block);
+                    let resampled = &mut resampled[..out_frames * input_channels];
+                    resampler.render(resampled, adjust);
+
+                    // Mic samples in the output channel layout
+                    let mic = &mut mic_buf[..output.len()];
+                    convert_channels(resampled, input_channels, mic, output_channels);
+
+                    // Duck the mic while soundboard audio is playing
+                    let ducking = sidechain_output.config();
+                    mic_ducker.set_params(
+                        ducking.mic_duck_depth_db,
+                        ducking.attack_ms,
+                        ducking.release_ms,
+                        output_rate,
+                    );
+                    let keyed = ducking.mic_ducking_enabled && sidechain_output.board_active();
+                    mic_ducker.process(mic, keyed);
+
+                    // Render soundboard voices and sum both into the CABLE stream
+                    let board = &mut board_buf[..output.len()];
+                    board_mixer.render(board);
+                    let (mic_gain, board_gain) = ROUTING_GAINS.linear();
+                    routing_mixer.mix(mic, board, mic_gain, board_gain, output);
                 }
-            },
-            move |err| {
-                error!("Output stream error: {}", err);
-            },
-            None,
+            }),
+            stream_error_handler(&failed_thread, "Output"),
         ) {
             Ok(stream) => stream,
             Err(e) => {
-                error!("Failed to build output stream: {}", e);
+                error!("Failed to open output stream: {}", e);
+                failed_thread.store(true, Ordering::Relaxed);
                 return;
             }
         };
 
-        // Start both streams
-        if let Err(e) = input_stream.play() {
-            error!("Failed to start input stream: {}", e);
-            return;
-        }
-        if let Err(e) = output_stream.play() {
-            error!("Failed to start output stream: {}", e);
-            return;
-        }
+        // Soundboard playback to CABLE Input now goes through our stream
+        buses.attach(cable_id.clone(), board_handle.clone());
 
         info!("Microphone routing started: {} -> CABLE Input", mic_id);
 
-        // Keep thread alive while routing is active
+        // Keep thread alive while routing is active, building chains for new settings
         while !stop_signal_clone.load(Ordering::Relaxed) {
-            thread::sleep(std::time::Duration::from_millis(100));
+            thread::sleep(ROUTING_POLL);
+
+            // Only one chain is in flight at a time, so the retired slot is free again
+            while retired_rx.try_recv().is_ok() {}
+            let version = MIC_PROCESSING_VERSION.load(Ordering::Acquire);
+            if version != mic_chain_version
+                && chain_tx
+                    .try_send(current_mic_chain(input_rate, input_channels))
+                    .is_ok()
+            {
+                mic_chain_version = version;
+            }
         }
 
+        buses.detach(&cable_id, &board_handle);
+
         info!("Microphone routing thread stopping");
-        // Streams are dropped here, which stops them
+        // Dropping the streams stops them
+        drop((input_stream, output_stream));
     });
 
     info!("Microphone routing enabled: {} -> {}", mic_name, cable_name);
@@ -367,7 +490,11 @@ pub fn enable_routing(microphone_id: &str) -> Result<(), String> {
     *state = Some(RoutingHandle {
         microphone_id: microphone_id.to_string(),
         stop_signal,
+        failed,
         _thread_handle: thread_handle,
+        ring_monitor,
+        input_rate,
+        input_channels,
     });
 
     Ok(())
@@ -396,14 +523,61 @@ pub fn disable_routing() -> Result<(), String> {
     Ok(())
 }
 
+/// Whether the active routing session has broken (stream error or failed start)
+///
+/// Returns false if routing is not active.
+pub fn routing_failed() -> bool {
+    ROUTING_STATE.lock().ok().is_some_and(|state| {
+        state
+            .as_ref()
+            .is_some_and(|s| s.failed.load(Ordering::Relaxed))
+    })
+}
+
+/// Set the push-to-talk/push-to-mute mode of the routed mic
+///
+/// The gate key is considered released after a mode change.
+pub fn set_gate_mode(mode: MicGateMode) {
+    TALK_GATE.set_mode(mode);
+}
+
+/// Report the gate shortcut being pressed or released
+pub fn set_gate_key_held(held: bool) {
+    TALK_GATE.set_key_held(held);
+}
+
+/// Status of an active routing session
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingStatus {
+    /// Device ID of the routed microphone
+    pub microphone_id: String,
+    pub gate_mode: MicGateMode,
+    /// Whether the mic currently passes the push-to-talk gate
+    pub mic_open: bool,
+}
+
 /// Get current routing status
 ///
-/// Returns the microphone device ID if routing is active, None otherwise.
-pub fn get_routing_status() -> Option<String> {
-    ROUTING_STATE
-        .lock()
-        .ok()
-        .and_then(|state| state.as_ref().map(|s| s.microphone_id.clone()))
+/// Returns the routed microphone and gate state if routing is active, None otherwise.
+pub fn get_routing_status() -> Option<RoutingStatus> {
+    ROUTING_STATE.lock().ok().and_then(|state| {
+        state.as_ref().map(|s| RoutingStatus {
+            microphone_id: s.microphone_id.clone(),
+            gate_mode: TALK_GATE.mode(),
+            mic_open: TALK_GATE.is_open(),
+        })
+    })
+}
+
+/// Get buffer statistics of the active routing session
+///
+/// Returns None if routing is not active.
+pub fn get_routing_stats() -> Option<RoutingStats> {
+    ROUTING_STATE.lock().ok().and_then(|state| {
+        state
+            .as_ref()
+            .map(|s| s.ring_monitor.stats(s.input_rate, s.input_channels))
+    })
 }
 
 // ============================================================================
@@ -413,72 +587,7 @@ pub fn get_routing_status() -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_ring_buffer_prefill() {
-        let buffer = RingBuffer::new(10);
-
-        // Buffer should be prefilled with write_pos ahead of read_pos
-        assert_eq!(buffer.write_pos, 5); // capacity / 2
-        assert_eq!(buffer.read_pos, 0);
-        assert!(!buffer.overflow_logged);
-
-        // Buffer contains silence (0.0)
-        assert!(buffer.buffer.iter().all(|&x| x == 0.0));
-    }
-
-    #[test]
-    fn test_ring_buffer_write_read() {
-        let mut buffer = RingBuffer::new(10);
-
-        // Skip prefilled silence first (5 samples)
-        let mut prefill = [0.0; 5];
-        buffer.read(&mut prefill);
-        assert!(prefill.iter().all(|&x| x == 0.0));
-
-        // Now write and read should be in sync
-        buffer.write(&[1.0, 2.0, 3.0]);
-
-        let mut output = [0.0; 3];
-        buffer.read(&mut output);
-
-        assert_eq!(output, [1.0, 2.0, 3.0]);
-    }
-
-    #[test]
-    fn test_ring_buffer_wrap() {
-        let mut buffer = RingBuffer::new(8);
-
-        // Skip prefilled silence (4 samples)
-        let mut prefill = [0.0; 4];
-        buffer.read(&mut prefill);
-
-        // Write and read multiple times to test wrap-around
-        buffer.write(&[1.0, 2.0]);
-        let mut out1 = [0.0; 2];
-        buffer.read(&mut out1);
-        assert_eq!(out1, [1.0, 2.0]);
-
-        buffer.write(&[3.0, 4.0, 5.0]);
-        let mut out2 = [0.0; 3];
-        buffer.read(&mut out2);
-        assert_eq!(out2, [3.0, 4.0, 5.0]);
-    }
-
-    #[test]
-    fn test_ring_buffer_overflow_detection() {
-        let mut buffer = RingBuffer::new(8);
-        assert!(!buffer.overflow_logged);
-
-        // Write enough samples to cause overflow (more than capacity)
-        // Buffer starts with write_pos = 4, read_pos = 0
-        // Writing 5 samples: write_pos goes 4->5->6->7->0->1
-        // When write_pos becomes 0 and read_pos is still 0, overflow is detected
-        buffer.write(&[1.0, 2.0, 3.0, 4.0, 5.0]);
-
-        // Overflow should be detected (write caught up to read)
-        assert!(buffer.overflow_logged);
-    }
+    use crate::audio::FakeBackend;
 
     #[test]
     fn test_get_routing_status_none() {
@@ -491,15 +600,85 @@ mod tests {
 
     #[test]
     fn test_list_capture_devices_excludes_cable() {
-        // Verifies VB-Cable devices are filtered out from the list
-        // (actual device count depends on system hardware)
-        let devices = list_capture_devices();
-        for (_, name) in &devices {
-            assert!(
-                !name.to_lowercase().contains("cable"),
-                "VB-Cable device should be excluded: {}",
-                name
-            );
+        let backend = FakeBackend::new();
+        backend.add_input("USB Microphone", 48000, 1);
+        backend.add_input("CABLE Output (VB-Audio Virtual Cable)", 48000, 2);
+        backend.add_input("Headset Microphone", 44100, 1);
+
+        // IDs keep the enumeration index of the skipped device in mind
+        assert_eq!(
+            list_capture_devices(&backend),
+            vec![
+                ("device_0".to_string(), "USB Microphone".to_string()),
+                ("device_2".to_string(), "Headset Microphone".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_input_fits_preallocated_buffer() {
+        for (input_rate, output_rate) in [(48000, 48000), (44100, 48000), (192000, 8000)] {
+            let resampler = Resampler::new(input_rate, output_rate, 2, 0);
+            // Fastest drift adjustment
+            let needed = resampler.input_frames_needed(BLOCK_FRAMES, 1.005);
+            assert!(needed <= max_input_frames(input_rate, output_rate));
         }
     }
+
+    #[test]
+    fn test_routing_mixes_mic_and_board_into_cable() {
+        let backend = FakeBackend::new();
+        backend.add_output("Speakers", 48000, 2);
+        let cable = backend.add_output("CABLE Input (VB-Audio Virtual Cable)", 48000, 2);
+        let mic = backend.add_input("USB Microphone", 48000, 1);
+        let buses = Arc::new(BusRegistry::new(Arc::new(backend.clone()), -1.0));
+
+        enable_routing(&DeviceId::from_index(mic).to_string(), buses.clone()).unwrap();
+        assert!(backend.wait_for_input(mic, true));
+        assert!(backend.wait_for_output(cable, true));
+
+        // 50ms of prefilled silence plays first, then the mono mic on both channels
+        backend.push_input(mic, &[0.25; 4800]);
+        let output = backend.render_output(cable, 4800);
+        assert_eq!(output[0], 0.0);
+        assert!(output[9000..].iter().all(|&s| (s - 0.25).abs() < 1e-3));
+
+        // Sounds played to CABLE Input go through the routing stream's bus
+        let clip = Arc::new(crate::audio::AudioData {
+            samples: vec![0.5; 48000],
+            sample_rate: 48000,
+            channels: 1,
+        });
+        buses
+            .play(
+                &[&cable_id(&backend)],
+                "playback_1",
+                clip,
+                Arc::new(Mutex::new(1.0)),
+                None,
+                None,
+            )
+            .unwrap();
+        backend.push_input(mic, &[0.25; 4800]);
+        let output = backend.render_output(cable, 4800);
+        let board = 0.5 * crate::audio::db_to_linear(-14.0);
+        let last = &output[output.len() - 2..];
+        assert!(last[0] > 0.25 + board * 0.5, "board missing: {:?}", last);
+        assert!((last[1] - 0.25).abs() < 1e-3);
+        assert_eq!(backend.rendered(cable).len(), 2 * 9600);
+
+        // A stream error marks the session as failed for the device monitor
+        assert!(!routing_failed());
+        backend.fail_input(mic);
+        assert!(routing_failed());
+
+        disable_routing().unwrap();
+        assert!(backend.wait_for_output(cable, false));
+        assert!(!backend.input_open(mic));
+        assert!(buses.meters().is_empty());
+    }
+
+    fn cable_id(backend: &FakeBackend) -> DeviceId {
+        find_cable_input_device(backend).unwrap().0
+    }
 }
@@ -0,0 +1,150 @@
+//! Mixing core for the routed CABLE Input stream
+//!
+//! The routing output stream carries both the microphone and the soundboard. This
+//! module sums the two with independent gains and limits the result. It works on
+//! plain interleaved buffers so it can be tested without audio devices.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::audio::{db_to_linear, LookaheadLimiter};
+
+/// Gain range accepted for the mic and board channels, in dB
+const GAIN_RANGE_DB: (f32, f32) = (-60.0, 12.0);
+
+/// Live mic/board gains shared with the audio callback (linear, stored as f32 bits)
+pub struct RoutingGains {
+    mic: AtomicU32,
+    board: AtomicU32,
+}
+
+impl RoutingGains {
+    /// Unity gain on both channels
+    pub const fn new() -> Self {
+        // 0x3f80_0000 is 1.0f32
+        Self {
+            mic: AtomicU32::new(0x3f80_0000),
+            board: AtomicU32::new(0x3f80_0000),
+        }
+    }
+
+    /// Set both gains in dB (clamped to -60..+12 dB)
+    pub fn set_db(&self, mic_db: f32, board_db: f32) {
+        let (min, max) = GAIN_RANGE_DB;
+        self.mic.store(
+            db_to_linear(mic_db.clamp(min, max)).to_bits(),
+            Ordering::Relaxed,
+        );
+        self.board.store(
+            db_to_linear(board_db.clamp(min, max)).to_bits(),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Current (mic, board) gains as linear factors
+    pub fn linear(&self) -> (f32, f32) {
+        (
+            f32::from_bits(self.mic.load(Ordering::Relaxed)),
+            f32::from_bits(self.board.load(Ordering::Relaxed)),
+        )
+    }
+}
+
+impl Default for RoutingGains {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sums microphone and soundboard audio into one limited output
+pub struct RoutingMixer {
+    limiter: LookaheadLimiter,
+}
+
+impl RoutingMixer {
+    pub fn new(sample_rate: u32, channels: usize, ceiling_db: f32) -> Self {
+        Self {
+            limiter: LookaheadLimiter::new(ceiling_db, sample_rate, channels),
+        }
+    }
+
+    /// Mix `mic` and `board` into `output` with the given linear gains.
+    ///
+    /// All buffers are interleaved in the output channel layout. Inputs shorter
+    /// than the output are treated as silence past their end.
+    pub fn mix(
+        &mut self,
+        mic: &[f32],
+        board: &[f32],
+        mic_gain: f32,
+        board_gain: f32,
+        output: &mut [f32],
+    ) {
+        for (i, out) in output.iter_mut().enumerate() {
+            let m = mic.get(i).copied().unwrap_or(0.0);
+            let b = board.get(i).copied().unwrap_or(0.0);
+            *out = m * mic_gain + b * board_gain;
+        }
+        self.limiter.process_interleaved(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Look-ahead delay of the output limiter, skipped when comparing output to input
+    fn latency_frames() -> usize {
+        LookaheadLimiter::new(0.0, 48000, 1).latency_frames()
+    }
+
+    #[test]
+    fn test_routing_gains_default_unity() {
+        let gains = RoutingGains::new();
+        assert_eq!(gains.linear(), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_routing_gains_set_db() {
+        let gains = RoutingGains::new();
+        gains.set_db(-6.0, 100.0);
+        let (mic, board) = gains.linear();
+        assert!((mic - 0.501).abs() < 0.001);
+        // Clamped to +12 dB
+        assert!((board - db_to_linear(12.0)).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_mix_sums_with_gains() {
+        let mut mixer = RoutingMixer::new(48000, 1, 0.0);
+        let mic = vec![0.2f32; 480];
+        let board = vec![0.1f32; 480];
+        let mut output = vec![0.0f32; 480];
+        mixer.mix(&mic, &board, 1.0, 0.5, &mut output);
+
+        assert!(output[latency_frames()..]
+            .iter()
+            .all(|s| (s - 0.25).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_mix_short_inputs_are_silence() {
+        let mut mixer = RoutingMixer::new(48000, 1, 0.0);
+        let mut output = vec![1.0f32; 480];
+        mixer.mix(&[], &[0.3; 200], 1.0, 1.0, &mut output);
+
+        let delayed = &output[latency_frames()..];
+        assert!((delayed[0] - 0.3).abs() < 1e-6);
+        assert_eq!(delayed[250], 0.0);
+    }
+
+    #[test]
+    fn test_mix_is_limited() {
+        let mut mixer = RoutingMixer::new(48000, 2, -1.0);
+        let mic = vec![0.9f32; 4800];
+        let board = vec![0.9f32; 4800];
+        let mut output = vec![0.0f32; 4800];
+        mixer.mix(&mic, &board, 1.0, 1.0, &mut output);
+
+        assert!(output.iter().all(|s| s.abs() <= db_to_linear(-1.0) + 1e-6));
+    }
+}
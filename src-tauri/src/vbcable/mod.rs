@@ -4,10 +4,15 @@
 //! microphone routing, and automatic communications device switching for Discord integration.
 
 mod communications;
+mod convert;
 mod default_device;
 mod detection;
 mod installer;
 mod microphone;
+mod mixer;
+mod processing;
+mod ring;
+mod talk;
 
 pub use communications::{
     activate as activate_comm_mode, deactivate as deactivate_comm_mode,
@@ -16,4 +21,12 @@ pub use communications::{
 pub use default_device::{DefaultDeviceManager, RestoreResult, SavedDefaults};
 pub use detection::{detect_vb_cable, wait_for_vb_cable, VbCableStatus};
 pub use installer::{cleanup_temp_files, install_vbcable, uninstall_vbcable};
-pub use microphone::{disable_routing, enable_routing, get_routing_status, list_capture_devices};
+pub use microphone::{
+    disable_routing, enable_routing, get_routing_stats, get_routing_status, list_capture_devices,
+    routing_failed, set_gate_key_held, set_gate_mode, set_mic_processing, set_routing_gains,
+    RoutingStatus,
+};
+pub use processing::MicProcessingConfig;
+pub use ring::RoutingStats;
+pub(crate) use ring::{ring_buffer, RingConsumer};
+pub use talk::MicGateMode;
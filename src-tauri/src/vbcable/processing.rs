@@ -0,0 +1,207 @@
+//! Processing chain for the routed microphone
+//!
+//! Runs in the mic input callback before samples enter the ring buffer:
+//! gain -> high-pass -> noise gate -> compressor. Every stage can be switched off,
+//! and the whole chain is bypassed when processing is disabled.
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{db_to_linear, Biquad, BiquadCoeffs, Compressor, NoiseGate};
+
+/// Butterworth Q for the high-pass filter
+const HIGH_PASS_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+/// Compressor attack time for speech
+const COMPRESSOR_ATTACK_MS: f32 = 5.0;
+/// Compressor release time for speech
+const COMPRESSOR_RELEASE_MS: f32 = 120.0;
+
+/// Persisted microphone processing settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MicProcessingConfig {
+    /// Master switch for the whole chain
+    pub enabled: bool,
+    /// Input gain in dB
+    pub gain_db: f32,
+    /// Remove low-frequency rumble (desk bumps, fans)
+    pub high_pass_enabled: bool,
+    pub high_pass_hz: f32,
+    /// Silence the mic while the level is below the threshold
+    pub gate_enabled: bool,
+    /// Level at which the gate opens, in dBFS
+    pub gate_threshold_db: f32,
+    /// How far below the open threshold the gate closes again, in dB
+    pub gate_hysteresis_db: f32,
+    /// Time the signal must stay below the close threshold before closing
+    pub gate_hold_ms: f32,
+    /// Fade-out time once the gate closes
+    pub gate_release_ms: f32,
+    /// Even out loud and quiet speech
+    pub compressor_enabled: bool,
+    pub compressor_threshold_db: f32,
+    pub compressor_ratio: f32,
+    pub compressor_makeup_db: f32,
+}
+
+impl Default for MicProcessingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gain_db: 0.0,
+            high_pass_enabled: true,
+            high_pass_hz: 80.0,
+            gate_enabled: true,
+            gate_threshold_db: -45.0,
+            gate_hysteresis_db: 6.0,
+            gate_hold_ms: 150.0,
+            gate_release_ms: 100.0,
+            compressor_enabled: true,
+            compressor_threshold_db: -18.0,
+            compressor_ratio: 3.0,
+            compressor_makeup_db: 3.0,
+        }
+    }
+}
+
+/// Stateful processing chain built from a [`MicProcessingConfig`]
+pub struct MicChain {
+    config: MicProcessingConfig,
+    gain: f32,
+    high_pass: Biquad,
+    gate: NoiseGate,
+    compressor: Compressor,
+}
+
+impl MicChain {
+    pub fn new(config: MicProcessingConfig, sample_rate: u32, channels: usize) -> Self {
+        Self {
+            gain: db_to_linear(config.gain_db.clamp(-24.0, 24.0)),
+            high_pass: Biquad::new(
+                BiquadCoeffs::high_pass(sample_rate, config.high_pass_hz, HIGH_PASS_Q),
+                channels,
+            ),
+            gate: NoiseGate::new(
+                config.gate_threshold_db,
+                config.gate_hysteresis_db,
+                config.gate_hold_ms,
+                config.gate_release_ms,
+                sample_rate,
+                channels,
+            ),
+            compressor: Compressor::new(
+                config.compressor_threshold_db,
+                config.compressor_ratio,
+                COMPRESSOR_ATTACK_MS,
+                COMPRESSOR_RELEASE_MS,
+                config.compressor_makeup_db,
+                sample_rate,
+                channels,
+            ),
+            config,
+        }
+    }
+
+    /// Process an interleaved mic buffer in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if !self.config.enabled {
+            return;
+        }
+
+        if self.gain != 1.0 {
+            for sample in samples.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+        if self.config.high_pass_enabled {
+            self.high_pass.process_interleaved(samples);
+        }
+        if self.config.gate_enabled {
+            self.gate.process_interleaved(samples);
+        }
+        if self.config.compressor_enabled {
+            self.compressor.process_interleaved(samples);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::test_util::{noise, rms, sine};
+
+    fn enabled() -> MicProcessingConfig {
+        MicProcessingConfig {
+            enabled: true,
+            ..MicProcessingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_disabled_chain_is_passthrough() {
+        let input = noise(4800, 1, 0.1, 12345);
+        let mut output = input.clone();
+        MicChain::new(MicProcessingConfig::default(), 48000, 1).process(&mut output);
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_background_noise_is_gated() {
+        // Keyboard/fan noise around -60 dBFS
+        let mut samples = noise(48000, 1, db_to_linear(-55.0), 12345);
+        MicChain::new(enabled(), 48000, 1).process(&mut samples);
+        assert!(rms(&samples[4800..]) < db_to_linear(-90.0));
+    }
+
+    #[test]
+    fn test_speech_level_tone_passes() {
+        // Voice-band tone with a low noise floor
+        let mut samples: Vec<f32> = sine(300.0, 48000, 48000, 1, 0.3)
+            .iter()
+            .zip(noise(48000, 1, 0.002, 12345))
+            .map(|(t, n)| t + n)
+            .collect();
+        MicChain::new(enabled(), 48000, 1).process(&mut samples);
+
+        // Compressed but clearly audible
+        let level = rms(&samples[9600..]);
+        assert!(level > 0.1, "level was {}", level);
+    }
+
+    #[test]
+    fn test_high_pass_removes_rumble_before_gate() {
+        // Loud 20 Hz rumble alone should not open the gate
+        let config = MicProcessingConfig {
+            enabled: true,
+            high_pass_hz: 120.0,
+            compressor_enabled: false,
+            ..MicProcessingConfig::default()
+        };
+        let mut samples = sine(20.0, 48000, 48000, 1, 0.05);
+        MicChain::new(config, 48000, 1).process(&mut samples);
+        assert!(rms(&samples[9600..]) < 1e-4);
+    }
+
+    #[test]
+    fn test_gain_only() {
+        let config = MicProcessingConfig {
+            enabled: true,
+            gain_db: 6.0,
+            high_pass_enabled: false,
+            gate_enabled: false,
+            compressor_enabled: false,
+            ..MicProcessingConfig::default()
+        };
+        let mut samples = vec![0.1f32; 100];
+        MicChain::new(config, 48000, 1).process(&mut samples);
+        assert!((samples[50] - 0.1 * db_to_linear(6.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_config_partial_json_uses_defaults() {
+        let config: MicProcessingConfig =
+            serde_json::from_str(r#"{"enabled": true, "gate_threshold_db": -50.0}"#).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.gate_threshold_db, -50.0);
+        assert_eq!(config.high_pass_hz, 80.0);
+    }
+}
@@ -0,0 +1,407 @@
+//! Lock-free single-producer/single-consumer ring buffer for microphone routing
+//!
+//! The mic input callback writes and the CABLE output callback reads, each on its
+//! own audio thread (the recorder also uses it to get captured audio off the
+//! input callback). Neither side ever blocks: samples are stored as f32 bits in
+//! atomics and the read/write positions are monotonically increasing counters, so
+//! the fill level is always `written - read` and can never exceed the capacity.
+//!
+//! The consumer also owns the latency target. It starts at the prefill level, grows
+//! after an underrun and shrinks slowly while playback is stable, so routing settles
+//! on the lowest latency the two devices can sustain.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+/// Growth factor of the latency target after an underrun
+const TARGET_GROWTH: f64 = 1.5;
+/// Shrink factor of the latency target after a stable period
+const TARGET_SHRINK: f64 = 0.9;
+/// Stable reads (in buffer lengths) before the target shrinks
+const STABLE_BUFFERS: usize = 100;
+
+/// State shared between the producer and consumer
+struct Shared {
+    samples: Box<[AtomicU32]>,
+    /// Total samples ever written
+    written: AtomicUsize,
+    /// Total samples ever read
+    read: AtomicUsize,
+    target: AtomicUsize,
+    underruns: AtomicU64,
+    overruns: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Shared {
+    /// Fill level as seen from any thread
+    ///
+    /// `read` is loaded first so a concurrent read can't make it pass `written`;
+    /// a concurrent write can only overshoot, which is clamped.
+    fn available(&self) -> usize {
+        let read = self.read.load(Ordering::Acquire);
+        let written = self.written.load(Ordering::Acquire);
+        written.wrapping_sub(read).min(self.samples.len())
+    }
+}
+
+/// Create a ring of `capacity` samples prefilled with `prefill` samples of silence
+///
+/// The prefill is also the initial latency target; `min_target` and `max_target`
+/// bound how far the consumer may adapt it.
+pub fn ring_buffer(
+    capacity: usize,
+    prefill: usize,
+    min_target: usize,
+    max_target: usize,
+) -> (RingProducer, RingConsumer) {
+    let capacity = capacity.max(1);
+    let max_target = max_target.clamp(1, capacity);
+    let min_target = min_target.clamp(1, max_target);
+    let prefill = prefill.min(capacity);
+
+    let shared = Arc::new(Shared {
+        samples: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+        written: AtomicUsize::new(prefill),
+        read: AtomicUsize::new(0),
+        target: AtomicUsize::new(prefill.clamp(min_target, max_target)),
+        underruns: AtomicU64::new(0),
+        overruns: AtomicU64::new(0),
+        dropped: AtomicU64::new(0),
+    });
+
+    (
+        RingProducer {
+            shared: shared.clone(),
+            _not_sync: PhantomData,
+        },
+        RingConsumer {
+            shared,
+            min_target,
+            max_target,
+            priming: false,
+            stable_samples: 0,
+        },
+    )
+}
+
+/// Write side of the ring, owned by the input callback
+///
+/// Neither `Clone` nor `Sync`, and writing takes `&mut self`, so there is only
+/// ever one writer.
+pub struct RingProducer {
+    shared: Arc<Shared>,
+    /// Can be sent to the audio thread but not shared with another one
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl RingProducer {
+    /// Write as many samples as fit; the rest are dropped and counted as an overrun
+    pub fn write(&mut self, samples: &[f32]) -> usize {
+        let shared = &*self.shared;
+        let capacity = shared.samples.len();
+        let written = shared.written.load(Ordering::Relaxed);
+        let read = shared.read.load(Ordering::Acquire);
+        let free = capacity - written.wrapping_sub(read);
+        let count = samples.len().min(free);
+
+        for (i, &sample) in samples[..count].iter().enumerate() {
+            shared.samples[written.wrapping_add(i) % capacity]
+                .store(sample.to_bits(), Ordering::Relaxed);
+        }
+        shared
+            .written
+            .store(written.wrapping_add(count), Ordering::Release);
+
+        if count < samples.len() {
+            shared.overruns.fetch_add(1, Ordering::Relaxed);
+            shared
+                .dropped
+                .fetch_add((samples.len() - count) as u64, Ordering::Relaxed);
+        }
+        count
+    }
+}
+
+/// Read side of the ring, owned by the output callback
+pub struct RingConsumer {
+    shared: Arc<Shared>,
+    min_target: usize,
+    max_target: usize,
+    /// Holding back output after an underrun until the target fill is reached
+    priming: bool,
+    stable_samples: usize,
+}
+
+impl RingConsumer {
+    /// Samples written but not yet read
+    pub fn available(&self) -> usize {
+        self.shared.available()
+    }
+
+    /// Current latency target in samples
+    pub fn target(&self) -> usize {
+        self.shared.target.load(Ordering::Relaxed)
+    }
+
+    /// Handle for reading statistics from other threads
+    pub fn monitor(&self) -> RingMonitor {
+        RingMonitor {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Fill `output` from the ring, padding with silence on underrun
+    ///
+    /// Returns the number of samples taken from the ring. After an underrun the
+    /// consumer outputs silence until the fill level is back at the (raised) target,
+    /// instead of playing every sample as soon as it arrives.
+    pub fn read(&mut self, output: &mut [f32]) -> usize {
+        let shared = &*self.shared;
+        let capacity = shared.samples.len();
+        let read = shared.read.load(Ordering::Relaxed);
+        let available = shared.written.load(Ordering::Acquire).wrapping_sub(read);
+
+        if self.priming {
+            if available < self.target() {
+                output.fill(0.0);
+                return 0;
+            }
+            self.priming = false;
+        }
+
+        let count = output.len().min(available);
+        for (i, out) in output[..count].iter_mut().enumerate() {
+            *out = f32::from_bits(
+                shared.samples[read.wrapping_add(i) % capacity].load(Ordering::Relaxed),
+            );
+        }
+        shared
+            .read
+            .store(read.wrapping_add(count), Ordering::Release);
+
+        if count < output.len() {
+            output[count..].fill(0.0);
+            self.on_underrun();
+        } else {
+            self.on_stable(count);
+        }
+        count
+    }
+
+    fn on_underrun(&mut self) {
+        let shared = &*self.shared;
+        shared.underruns.fetch_add(1, Ordering::Relaxed);
+        let target = ((self.target() as f64 * TARGET_GROWTH) as usize).min(self.max_target);
+        shared.target.store(target, Ordering::Relaxed);
+        self.priming = true;
+        self.stable_samples = 0;
+    }
+
+    fn on_stable(&mut self, count: usize) {
+        self.stable_samples += count;
+        if self.stable_samples >= self.shared.samples.len() * STABLE_BUFFERS {
+            self.stable_samples = 0;
+            let target = ((self.target() as f64 * TARGET_SHRINK) as usize).max(self.min_target);
+            self.shared.target.store(target, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Read-only view of ring statistics
+#[derive(Clone)]
+pub struct RingMonitor {
+    shared: Arc<Shared>,
+}
+
+impl RingMonitor {
+    /// Snapshot the counters, converting sample counts to milliseconds
+    pub fn stats(&self, sample_rate: u32, channels: usize) -> RoutingStats {
+        let shared = &*self.shared;
+        let samples_per_ms = (sample_rate as f32 * channels.max(1) as f32 / 1000.0).max(1.0);
+        RoutingStats {
+            underruns: shared.underruns.load(Ordering::Relaxed),
+            overruns: shared.overruns.load(Ordering::Relaxed),
+            dropped_samples: shared.dropped.load(Ordering::Relaxed),
+            buffered_ms: shared.available() as f32 / samples_per_ms,
+            target_latency_ms: shared.target.load(Ordering::Relaxed) as f32 / samples_per_ms,
+        }
+    }
+}
+
+/// Microphone routing buffer statistics
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoutingStats {
+    /// Output callbacks that ran out of mic audio
+    pub underruns: u64,
+    /// Input callbacks that found the buffer full
+    pub overruns: u64,
+    /// Mic samples discarded by overruns
+    pub dropped_samples: u64,
+    /// Mic audio currently buffered
+    pub buffered_ms: f32,
+    /// Latency the buffer is currently aiming for
+    pub target_latency_ms: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_prefill_is_silence() {
+        let (_producer, mut consumer) = ring_buffer(10, 5, 1, 10);
+        assert_eq!(consumer.available(), 5);
+        assert_eq!(consumer.target(), 5);
+
+        let mut out = [1.0; 5];
+        assert_eq!(consumer.read(&mut out), 5);
+        assert!(out.iter().all(|&x| x == 0.0));
+    }
+
+    #[test]
+    fn test_write_read_wraps() {
+        let (mut producer, mut consumer) = ring_buffer(8, 4, 1, 8);
+        consumer.read(&mut [0.0; 4]);
+
+        for chunk in [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]] {
+            assert_eq!(producer.write(&chunk), 3);
+            let mut out = [0.0; 3];
+            assert_eq!(consumer.read(&mut out), 3);
+            assert_eq!(out, chunk);
+        }
+        assert_eq!(consumer.available(), 0);
+    }
+
+    #[test]
+    fn test_overrun_drops_excess_and_counts() {
+        let (mut producer, consumer) = ring_buffer(8, 4, 1, 8);
+        // Only four slots are free
+        assert_eq!(producer.write(&[1.0; 6]), 4);
+        assert_eq!(consumer.available(), 8);
+
+        let stats = consumer.monitor().stats(1000, 1);
+        assert_eq!(stats.overruns, 1);
+        assert_eq!(stats.dropped_samples, 2);
+    }
+
+    #[test]
+    fn test_underrun_pads_silence_and_raises_target() {
+        let (mut producer, mut consumer) = ring_buffer(64, 8, 4, 32);
+        consumer.read(&mut [0.0; 8]);
+        producer.write(&[0.5; 3]);
+
+        let mut out = [1.0; 6];
+        assert_eq!(consumer.read(&mut out), 3);
+        assert_eq!(out, [0.5, 0.5, 0.5, 0.0, 0.0, 0.0]);
+        assert_eq!(consumer.monitor().stats(1000, 1).underruns, 1);
+        assert_eq!(consumer.target(), 12);
+
+        // Priming: silence until the raised target is buffered again
+        producer.write(&[0.25; 10]);
+        assert_eq!(consumer.read(&mut out), 0);
+        assert_eq!(out, [0.0; 6]);
+        producer.write(&[0.25; 2]);
+        assert_eq!(consumer.read(&mut out), 6);
+        assert_eq!(out, [0.25; 6]);
+        // Priming silence is not counted as another underrun
+        assert_eq!(consumer.monitor().stats(1000, 1).underruns, 1);
+    }
+
+    #[test]
+    fn test_target_bounded_and_shrinks_when_stable() {
+        let (mut producer, mut consumer) = ring_buffer(64, 8, 4, 20);
+        // Three underruns in a row: 8 -> 12 -> 18 -> 20 (capped)
+        consumer.read(&mut [0.0; 16]);
+        assert_eq!(consumer.target(), 12);
+        producer.write(&[0.0; 12]);
+        consumer.read(&mut [0.0; 16]);
+        assert_eq!(consumer.target(), 18);
+        producer.write(&[0.0; 18]);
+        consumer.read(&mut [0.0; 32]);
+        assert_eq!(consumer.target(), 20);
+
+        // 100 buffer lengths of uninterrupted reads shrink the target
+        producer.write(&[0.0; 20]);
+        let mut out = [0.0; 4];
+        for _ in 0..(64 * STABLE_BUFFERS / 4) {
+            producer.write(&[0.0; 4]);
+            consumer.read(&mut out);
+        }
+        assert_eq!(consumer.target(), 18);
+    }
+
+    #[test]
+    fn test_stats_in_milliseconds() {
+        let (_producer, consumer) = ring_buffer(9600, 4800, 960, 9600);
+        let stats = consumer.monitor().stats(48000, 2);
+        assert_eq!(stats.buffered_ms, 50.0);
+        assert_eq!(stats.target_latency_ms, 50.0);
+    }
+
+    #[test]
+    fn test_concurrent_transfer_preserves_order() {
+        const TOTAL: usize = 200_000;
+        let (mut producer, mut consumer) = ring_buffer(1024, 0, 1, 1024);
+
+        let writer = thread::spawn(move || {
+            let mut next = 1usize;
+            while next <= TOTAL {
+                let end = (next + 37).min(TOTAL + 1);
+                let chunk: Vec<f32> = (next..end).map(|v| v as f32).collect();
+                let written = producer.write(&chunk);
+                next += written;
+                if written < chunk.len() {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        // Underrun padding shows up as zeros; everything else must arrive in order
+        let mut expected = 1usize;
+        let mut out = vec![0.0f32; 53];
+        while expected <= TOTAL {
+            let count = consumer.read(&mut out);
+            for &value in &out[..count] {
+                assert_eq!(value as usize, expected);
+                expected += 1;
+            }
+            if count == 0 {
+                thread::yield_now();
+            }
+        }
+        writer.join().unwrap();
+
+        // The writer retried instead of dropping, so nothing was lost
+        assert_eq!(consumer.available(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_overruns_never_exceed_capacity() {
+        let (mut producer, mut consumer) = ring_buffer(256, 128, 64, 256);
+        let monitor = consumer.monitor();
+
+        let writer = thread::spawn(move || {
+            (0..20_000)
+                .map(|_| producer.write(&[1.0; 64]) as u64)
+                .sum::<u64>()
+        });
+        let mut out = [0.0f32; 16];
+        for _ in 0..20_000 {
+            consumer.read(&mut out);
+            assert!(consumer.available() <= 256);
+        }
+        let accepted = writer.join().unwrap();
+
+        // Writing 4x faster than reading must overrun, and every drop is counted
+        let stats = monitor.stats(48000, 1);
+        assert!(stats.overruns > 0);
+        assert_eq!(accepted + stats.dropped_samples, 20_000 * 64);
+        assert!(consumer.available() <= 256);
+    }
+}
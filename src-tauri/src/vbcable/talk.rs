@@ -0,0 +1,206 @@
+//! Push-to-talk and push-to-mute gating for the routed microphone
+//!
+//! A global shortcut drives [`TalkGate`]; the mic input callback reads it and
+//! applies a short [`GateRamp`] so opening and closing never clicks. Only the mic
+//! path is gated: soundboard audio on CABLE Input keeps playing either way.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Time for the mic to fully open or close
+const RAMP_MS: f32 = 10.0;
+
+/// How the routed microphone is gated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MicGateMode {
+    /// Mic is always forwarded
+    #[default]
+    AlwaysOn,
+    /// Mic is forwarded only while the key is held
+    PushToTalk,
+    /// Mic is muted while the key is held
+    PushToMute,
+}
+
+impl MicGateMode {
+    /// Whether the mic passes for the given key state
+    pub fn is_open(self, key_held: bool) -> bool {
+        match self {
+            Self::AlwaysOn => true,
+            Self::PushToTalk => key_held,
+            Self::PushToMute => !key_held,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::AlwaysOn => 0,
+            Self::PushToTalk => 1,
+            Self::PushToMute => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::PushToTalk,
+            2 => Self::PushToMute,
+            _ => Self::AlwaysOn,
+        }
+    }
+}
+
+/// Gate mode and key state shared between the shortcut handler and the audio callback
+pub struct TalkGate {
+    mode: AtomicU8,
+    key_held: AtomicBool,
+}
+
+impl TalkGate {
+    /// Always-on gate with the key released
+    pub const fn new() -> Self {
+        Self {
+            mode: AtomicU8::new(0),
+            key_held: AtomicBool::new(false),
+        }
+    }
+
+    pub fn mode(&self) -> MicGateMode {
+        MicGateMode::from_u8(self.mode.load(Ordering::Relaxed))
+    }
+
+    /// Change the mode; the key is considered released afterwards
+    pub fn set_mode(&self, mode: MicGateMode) {
+        self.mode.store(mode.to_u8(), Ordering::Relaxed);
+        self.key_held.store(false, Ordering::Relaxed);
+    }
+
+    pub fn set_key_held(&self, held: bool) {
+        self.key_held.store(held, Ordering::Relaxed);
+    }
+
+    /// Whether the mic currently passes
+    pub fn is_open(&self) -> bool {
+        self.mode().is_open(self.key_held.load(Ordering::Relaxed))
+    }
+}
+
+impl Default for TalkGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Linear gain ramp between muted and open
+pub struct GateRamp {
+    gain: f32,
+    step: f32,
+    channels: usize,
+}
+
+impl GateRamp {
+    /// Create a ramp starting in the given state
+    pub fn new(open: bool, sample_rate: u32, channels: usize) -> Self {
+        Self {
+            gain: if open { 1.0 } else { 0.0 },
+            step: 1.0 / (RAMP_MS / 1000.0 * sample_rate as f32).max(1.0),
+            channels: channels.max(1),
+        }
+    }
+
+    /// Apply the gate to an interleaved buffer in place
+    pub fn process(&mut self, samples: &mut [f32], open: bool) {
+        let target = if open { 1.0 } else { 0.0 };
+        if self.gain == target {
+            if !open {
+                samples.fill(0.0);
+            }
+            return;
+        }
+
+        for frame in samples.chunks_mut(self.channels) {
+            self.gain = if open {
+                (self.gain + self.step).min(1.0)
+            } else {
+                (self.gain - self.step).max(0.0)
+            };
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gate_modes() {
+        assert!(MicGateMode::AlwaysOn.is_open(false));
+        assert!(MicGateMode::AlwaysOn.is_open(true));
+        assert!(!MicGateMode::PushToTalk.is_open(false));
+        assert!(MicGateMode::PushToTalk.is_open(true));
+        assert!(MicGateMode::PushToMute.is_open(false));
+        assert!(!MicGateMode::PushToMute.is_open(true));
+    }
+
+    #[test]
+    fn test_talk_gate_key_state() {
+        let gate = TalkGate::new();
+        assert!(gate.is_open());
+
+        gate.set_mode(MicGateMode::PushToTalk);
+        assert_eq!(gate.mode(), MicGateMode::PushToTalk);
+        assert!(!gate.is_open());
+        gate.set_key_held(true);
+        assert!(gate.is_open());
+
+        // Switching modes releases the key
+        gate.set_mode(MicGateMode::PushToMute);
+        assert!(gate.is_open());
+        gate.set_key_held(true);
+        assert!(!gate.is_open());
+    }
+
+    #[test]
+    fn test_ramp_opens_without_click() {
+        // 10 ms ramp at 48 kHz = 480 frames
+        let mut ramp = GateRamp::new(false, 48000, 2);
+        let mut samples = vec![1.0f32; 1000 * 2];
+        ramp.process(&mut samples, true);
+
+        assert!(samples[0] < 0.01);
+        assert!((samples[479 * 2] - 1.0).abs() < 1e-4);
+        assert_eq!(samples[999 * 2 + 1], 1.0);
+        // Both channels follow the same gain
+        assert!(samples.chunks(2).all(|f| f[0] == f[1]));
+        assert!(samples.windows(2).all(|w| w[1] >= w[0]));
+    }
+
+    #[test]
+    fn test_ramp_closes_to_silence() {
+        let mut ramp = GateRamp::new(true, 48000, 1);
+        let mut samples = vec![0.5f32; 1000];
+        ramp.process(&mut samples, false);
+
+        assert!(samples[0] > 0.49);
+        assert_eq!(samples[600], 0.0);
+
+        // Fully closed: later blocks are silent
+        let mut next = vec![0.5f32; 100];
+        ramp.process(&mut next, false);
+        assert!(next.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_mode_serde_names() {
+        assert_eq!(
+            serde_json::to_string(&MicGateMode::PushToTalk).unwrap(),
+            "\"push_to_talk\""
+        );
+        let mode: MicGateMode = serde_json::from_str("\"push_to_mute\"").unwrap();
+        assert_eq!(mode, MicGateMode::PushToMute);
+    }
+}
@@ -0,0 +1,59 @@
+//! Main window management
+//!
+//! The window is not created from tauri.conf.json but at startup, so daemon
+//! mode can run without it and open it later from the tray.
+
+use tauri::{Manager, Runtime, WebviewWindow, WebviewWindowBuilder};
+
+/// Label of the main window in tauri.conf.json
+const MAIN_WINDOW: &str = "main";
+
+/// Create the main window from its tauri.conf.json entry
+pub fn create_main_window<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    visible: bool,
+) -> Result<WebviewWindow<R>, String> {
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == MAIN_WINDOW)
+        .ok_or("Main window is missing from tauri.conf.json")?
+        .clone();
+
+    WebviewWindowBuilder::from_config(app, &config)
+        .map_err(|e| format!("Failed to configure main window: {}", e))?
+        .visible(visible)
+        .build()
+        .map_err(|e| format!("Failed to create main window: {}", e))
+}
+
+/// The main window, if it is open
+pub fn main_window<R: Runtime>(app: &tauri::AppHandle<R>) -> Option<WebviewWindow<R>> {
+    app.get_webview_window(MAIN_WINDOW)
+}
+
+/// Show and focus the main window, creating it if it isn't open
+pub fn show_main_window<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let window = match main_window(app) {
+        Some(window) => window,
+        None => match create_main_window(app, true) {
+            Ok(window) => {
+                tracing::info!("Main window created");
+                window
+            }
+            Err(e) => {
+                tracing::error!("{}", e);
+                return;
+            }
+        },
+    };
+
+    if let Err(e) = window.show() {
+        tracing::error!("Failed to show main window: {}", e);
+        return;
+    }
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+}
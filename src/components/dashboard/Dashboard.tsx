@@ -6,6 +6,7 @@ import { ANIMATION_DURATIONS } from "../../constants";
 import CategoryTabs from "../categories/CategoryTabs";
 import DashboardHeader from "./DashboardHeader";
 import DashboardSoundGrid from "./DashboardSoundGrid";
+import RecorderModal from "../modals/RecorderModal";
 import SoundModal from "../modals/SoundModal";
 import Toast from "../common/Toast";
 import TrimEditor from "../modals/TrimEditor";
@@ -49,6 +50,9 @@ export default function Dashboard({
   const [droppedFilePath, setDroppedFilePath] = useState<string | null>(null);
   const [fileQueue, setFileQueue] = useState<string[]>([]);
 
+  // Recorder modal state
+  const [isRecorderOpen, setIsRecorderOpen] = useState(false);
+
   // Trim editor state
   const [trimEditorSound, setTrimEditorSound] = useState<Sound | null>(null);
 
@@ -280,6 +284,7 @@ export default function Dashboard({
         activeWaveform={activeWaveform}
         isWaveformExiting={isWaveformExiting}
         playingSoundIds={playingSoundIds}
+        onOpenRecorder={() => setIsRecorderOpen(true)}
       />
 
       {/* Device Warning */}
@@ -418,6 +423,18 @@ export default function Dashboard({
         defaultFilePath={droppedFilePath || undefined}
       />
 
+      {/* Recorder Modal */}
+      <RecorderModal
+        isOpen={isRecorderOpen}
+        onClose={() => setIsRecorderOpen(false)}
+        onSave={async (sound) => {
+          await refreshSounds();
+          showToast(`Recorded: ${sound.name}`);
+        }}
+        categories={soundLibrary.categories}
+        defaultCategoryId={selectedCategoryId}
+      />
+
       {/* Toast Notification */}
       {toastMessage && (
         <Toast message={toastMessage} onClose={() => setToastMessage(null)} />
@@ -8,6 +8,7 @@ interface DashboardHeaderProps {
   activeWaveform: ActiveWaveform | null;
   isWaveformExiting: boolean;
   playingSoundIds: Set<string>;
+  onOpenRecorder: () => void;
 }
 
 export default function DashboardHeader({
@@ -17,6 +18,7 @@ export default function DashboardHeader({
   activeWaveform,
   isWaveformExiting,
   playingSoundIds,
+  onOpenRecorder,
 }: DashboardHeaderProps) {
   return (
     <div className="bg-discord-darker px-6 py-4 border-b border-discord-dark">
@@ -77,6 +79,16 @@ export default function DashboardHeader({
             </span>
           </div>
 
+          {/* Record Button */}
+          <button
+            onClick={onOpenRecorder}
+            title="Record a new sound"
+            className="px-4 py-2 bg-discord-dark hover:bg-discord-darkest rounded-lg
+                       text-discord-text font-medium transition-colors"
+          >
+            🎙️ Record
+          </button>
+
           {/* Stop Button */}
           <button
             onClick={onStopAll}
@@ -1,7 +1,19 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Sound, HotkeyMapping } from "../../types";
-import { formatHotkeyForDisplay } from "../../utils/hotkeyDisplay";
+import { listen } from "@tauri-apps/api/event";
+import { Sound, HotkeyMapping, MidiBinding, MidiTrigger } from "../../types";
+import {
+  formatHotkeyForDisplay,
+  keysFromKeyboardEvent,
+} from "../../utils/hotkeyDisplay";
+
+/** Display a MIDI trigger with 1-based channels (as controllers label them) */
+function formatMidiTrigger(trigger: MidiTrigger): string {
+  const channel = `Ch ${trigger.channel + 1}`;
+  return trigger.type === "note"
+    ? `Note ${trigger.note} (${channel})`
+    : `CC ${trigger.controller} (${channel})`;
+}
 
 interface HotkeyManagerProps {
   sound: Sound;
@@ -20,21 +32,17 @@ export default function HotkeyManager({
   const [capturedKeys, setCapturedKeys] = useState<string[]>([]);
   const [error, setError] = useState<string>("");
   const [status, setStatus] = useState<string>("");
+  const [isLearningMidi, setIsLearningMidi] = useState(false);
 
   // Get existing hotkey for this sound
   const existingHotkey = Object.entries(hotkeyMappings.mappings).find(
     ([_, soundId]) => soundId === sound.id
   )?.[0];
 
-  // Normalize modifier key names
-  const normalizeKey = (key: string): string => {
-    const keyMap: Record<string, string> = {
-      Control: "Ctrl",
-      Meta: "Super",
-      " ": "Space",
-    };
-    return keyMap[key] || key;
-  };
+  // MIDI triggers bound to this sound
+  const midiBindings = (hotkeyMappings.midi?.bindings ?? []).filter(
+    (binding) => binding.sound_id === sound.id
+  );
 
   // Handle key down during capture
   // Using native DOM KeyboardEvent for addEventListener compatibility
@@ -44,54 +52,7 @@ export default function HotkeyManager({
     e.preventDefault();
     e.stopPropagation();
 
-    const keys: string[] = [];
-
-    // Add modifiers
-    if (e.ctrlKey) keys.push("Ctrl");
-    if (e.shiftKey) keys.push("Shift");
-    if (e.altKey) keys.push("Alt");
-    if (e.metaKey) keys.push("Super");
-
-    // Add actual key if it's not a modifier
-    if (!["Control", "Shift", "Alt", "Meta"].includes(e.key)) {
-      let keyName = e.key;
-
-      // Handle NumPad keys correctly using e.code instead of e.key
-      if (e.code.startsWith("Numpad")) {
-        if (e.code === "Numpad0") keyName = "NumPad0";
-        else if (e.code === "Numpad1") keyName = "NumPad1";
-        else if (e.code === "Numpad2") keyName = "NumPad2";
-        else if (e.code === "Numpad3") keyName = "NumPad3";
-        else if (e.code === "Numpad4") keyName = "NumPad4";
-        else if (e.code === "Numpad5") keyName = "NumPad5";
-        else if (e.code === "Numpad6") keyName = "NumPad6";
-        else if (e.code === "Numpad7") keyName = "NumPad7";
-        else if (e.code === "Numpad8") keyName = "NumPad8";
-        else if (e.code === "Numpad9") keyName = "NumPad9";
-        else if (e.code === "NumpadDecimal") keyName = "NumPadDecimal";
-        else if (e.code === "NumpadEnter") keyName = "NumPadEnter";
-        else if (e.code === "NumpadAdd") keyName = "NumPadAdd";
-        else if (e.code === "NumpadSubtract") keyName = "NumPadSubtract";
-        else if (e.code === "NumpadMultiply") keyName = "NumPadMultiply";
-        else if (e.code === "NumpadDivide") keyName = "NumPadDivide";
-      } else {
-        keyName = normalizeKey(e.key);
-      }
-
-      keys.push(keyName);
-      console.log(
-        "Captured key:",
-        keyName,
-        "Code:",
-        e.code,
-        "Key:",
-        e.key,
-        "Modifiers:",
-        keys.slice(0, -1)
-      );
-    }
-
-    setCapturedKeys(keys);
+    setCapturedKeys(keysFromKeyboardEvent(e));
   };
 
   // Setup keyboard listener
@@ -219,6 +180,50 @@ export default function HotkeyManager({
     }
   };
 
+  // Bind the next MIDI note or button to this sound
+  const startMidiLearn = async () => {
+    try {
+      await invoke("start_midi_learn", { soundId: sound.id });
+      setIsLearningMidi(true);
+      setError("");
+      setStatus("Press a pad or button on your MIDI controller...");
+    } catch (err) {
+      setError(`Failed to start MIDI learn: ${err}`);
+    }
+  };
+
+  const cancelMidiLearn = async () => {
+    setIsLearningMidi(false);
+    setStatus("");
+    await invoke("cancel_midi_learn").catch(() => {});
+  };
+
+  const removeMidiBinding = async (trigger: MidiTrigger) => {
+    try {
+      await invoke("remove_midi_binding", { trigger });
+      setStatus(`✓ MIDI ${formatMidiTrigger(trigger)} removed`);
+      setError("");
+      onHotkeyAssigned();
+    } catch (err) {
+      setError(`Failed to remove MIDI binding: ${err}`);
+    }
+  };
+
+  // Learn mode ends when the backend binds a message
+  useEffect(() => {
+    if (!isLearningMidi) return;
+    const unlisten = listen<MidiBinding>("midi-learned", (event) => {
+      setIsLearningMidi(false);
+      setStatus(`✓ MIDI ${formatMidiTrigger(event.payload.trigger)} assigned`);
+      onHotkeyAssigned();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+      // Closing the dialog mid-learn must not bind the next pad press
+      invoke("cancel_midi_learn").catch(() => {});
+    };
+  }, [isLearningMidi]);
+
   // Auto-save hotkey after user stops pressing keys (500ms debounce)
   useEffect(() => {
     if (!isCapturing || capturedKeys.length === 0) return;
@@ -337,6 +342,33 @@ export default function HotkeyManager({
           </div>
         )}
 
+        {/* MIDI bindings */}
+        <div className="mt-4 pt-4 border-t border-discord-darker space-y-2">
+          <p className="text-sm font-medium text-discord-text">MIDI</p>
+          {midiBindings.map((binding) => (
+            <div
+              key={formatMidiTrigger(binding.trigger)}
+              className="flex items-center justify-between bg-discord-darker rounded px-3 py-2"
+            >
+              <span className="font-mono text-sm text-discord-success">
+                {formatMidiTrigger(binding.trigger)}
+              </span>
+              <button
+                onClick={() => removeMidiBinding(binding.trigger)}
+                className="px-3 py-1 bg-discord-danger hover:bg-discord-danger-hover rounded text-white text-xs transition-colors"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+          <button
+            onClick={isLearningMidi ? cancelMidiLearn : startMidiLearn}
+            className="w-full py-2 bg-discord-darker hover:bg-discord-darkest rounded text-discord-text font-medium transition-colors"
+          >
+            {isLearningMidi ? "Cancel MIDI Learn" : "Learn MIDI"}
+          </button>
+        </div>
+
         {/* Status messages */}
         {status && (
           <div className="mt-3 p-2 bg-discord-success/10 border border-discord-success rounded text-sm text-discord-success">
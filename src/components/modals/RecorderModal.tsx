@@ -0,0 +1,352 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import {
+  Category,
+  RecordMode,
+  RecorderStatus,
+  RecordingSourceInfo,
+  RecordSource,
+  Sound,
+} from "../../types";
+
+interface RecorderModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  onSave: (sound: Sound) => Promise<void>;
+  categories: Category[];
+  defaultCategoryId?: string;
+}
+
+const fieldClass = `w-full bg-discord-darker border border-discord-dark rounded
+  px-3 py-2 text-discord-text focus:outline-none
+  focus:ring-2 focus:ring-discord-primary`;
+
+const sourceKey = (source: RecordSource) =>
+  `${source.type}:${source.device_id}`;
+
+const formatSecs = (secs: number) =>
+  `${Math.floor(secs / 60)}:${Math.floor(secs % 60)
+    .toString()
+    .padStart(2, "0")}`;
+
+export default function RecorderModal({
+  isOpen,
+  onClose,
+  onSave,
+  categories,
+  defaultCategoryId,
+}: RecorderModalProps) {
+  const [sources, setSources] = useState<RecordingSourceInfo[]>([]);
+  const [sourceId, setSourceId] = useState("");
+  const [modeType, setModeType] = useState<RecordMode["type"]>("record");
+  const [replaySeconds, setReplaySeconds] = useState(30);
+  const [status, setStatus] = useState<RecorderStatus | null>(null);
+  const [name, setName] = useState("");
+  const [categoryId, setCategoryId] = useState("");
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const refreshStatus = () =>
+    invoke<RecorderStatus | null>("get_recorder_status")
+      .then(setStatus)
+      .catch((err) => console.error("Failed to get recorder status:", err));
+
+  // The recorder keeps running while the modal is closed (instant replay),
+  // so pick up its state whenever the modal opens
+  useEffect(() => {
+    if (!isOpen) return;
+    setCategoryId(defaultCategoryId || categories[0]?.id || "");
+    setError(null);
+    invoke<RecordingSourceInfo[]>("list_recording_sources")
+      .then((list) => {
+        setSources(list);
+        setSourceId(
+          (current) => current || (list[0] ? sourceKey(list[0].source) : "")
+        );
+      })
+      .catch((err) => setError(String(err)));
+    refreshStatus();
+    const interval = setInterval(refreshStatus, 500);
+    return () => clearInterval(interval);
+  }, [isOpen, defaultCategoryId, categories]);
+
+  const handleStart = async () => {
+    const source = sources.find(
+      (s) => sourceKey(s.source) === sourceId
+    )?.source;
+    if (!source) {
+      setError("Please select a source");
+      return;
+    }
+    const mode: RecordMode =
+      modeType === "record"
+        ? { type: "record" }
+        : { type: "replay", seconds: replaySeconds };
+    try {
+      setError(null);
+      await invoke("start_recording", { source, mode });
+      await refreshStatus();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleDiscard = async () => {
+    try {
+      await invoke("stop_recording");
+      await refreshStatus();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleSave = async () => {
+    if (!name.trim()) {
+      setError("Please enter a name for the sound");
+      return;
+    }
+    if (!categoryId) {
+      setError("Please select a category");
+      return;
+    }
+    try {
+      setIsBusy(true);
+      setError(null);
+      const sound = await invoke<Sound>("save_recording", {
+        name: name.trim(),
+        categoryId,
+      });
+      setName("");
+      await refreshStatus();
+      await onSave(sound);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  if (!isOpen) return null;
+
+  const sourceName = (source: RecordSource) =>
+    sources.find((s) => sourceKey(s.source) === sourceKey(source))?.name ??
+    source.device_id;
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/60" onClick={onClose} />
+
+      {/* Modal */}
+      <div
+        className="relative bg-discord-dark rounded-lg shadow-xl w-full max-w-md
+                    border border-discord-darker p-6 mx-4"
+      >
+        {/* Header */}
+        <div className="flex items-center justify-between mb-6">
+          <h2 className="text-xl font-bold text-discord-text">Record Sound</h2>
+          <button
+            onClick={onClose}
+            className="text-discord-text-muted hover:text-discord-text
+                     text-2xl leading-none transition-colors"
+          >
+            &times;
+          </button>
+        </div>
+
+        {/* Error */}
+        {error && (
+          <div
+            className="mb-4 p-3 bg-discord-danger/20 border border-discord-danger
+                        rounded-lg text-discord-danger text-sm"
+          >
+            {error}
+          </div>
+        )}
+
+        <div className="space-y-4">
+          {status ? (
+            /* Running session */
+            <div className="p-3 bg-discord-darker rounded-lg space-y-1">
+              <p className="text-sm text-discord-text">
+                <span className="text-discord-danger">●</span>{" "}
+                {status.mode.type === "record"
+                  ? "Recording"
+                  : `Instant replay (last ${status.max_secs}s)`}{" "}
+                from {sourceName(status.source)}
+              </p>
+              <p className="text-2xl font-mono text-discord-text">
+                {formatSecs(status.recorded_secs)}
+              </p>
+              {status.failed && (
+                <p className="text-xs text-discord-danger">
+                  The device stopped delivering audio. Save what was captured
+                  or discard it.
+                </p>
+              )}
+              {status.full && status.mode.type === "record" && (
+                <p className="text-xs text-discord-warning">
+                  Maximum length reached, recording has stopped growing.
+                </p>
+              )}
+            </div>
+          ) : (
+            <>
+              {/* Source */}
+              <div>
+                <label className="block text-sm font-medium text-discord-text-muted mb-2">
+                  Source
+                </label>
+                <select
+                  value={sourceId}
+                  onChange={(e) => setSourceId(e.target.value)}
+                  className={fieldClass}
+                >
+                  {sources.map((s) => (
+                    <option
+                      key={sourceKey(s.source)}
+                      value={sourceKey(s.source)}
+                    >
+                      {s.name}
+                    </option>
+                  ))}
+                </select>
+                <p className="text-xs text-discord-text-muted mt-1">
+                  Output devices record what they play (Windows only)
+                </p>
+              </div>
+
+              {/* Mode */}
+              <div className="flex gap-2">
+                {[
+                  { type: "record" as const, label: "⏺️ Record" },
+                  { type: "replay" as const, label: "⏪ Instant replay" },
+                ].map((option) => (
+                  <button
+                    key={option.type}
+                    type="button"
+                    onClick={() => setModeType(option.type)}
+                    className={`flex-1 px-3 py-2 rounded text-sm transition-colors ${
+                      modeType === option.type
+                        ? "bg-discord-primary text-white"
+                        : "bg-discord-darker text-discord-text-muted hover:text-discord-text"
+                    }`}
+                  >
+                    {option.label}
+                  </button>
+                ))}
+              </div>
+              {modeType === "replay" && (
+                <label className="flex items-center gap-2 text-sm text-discord-text">
+                  Keep the last
+                  <input
+                    type="number"
+                    min="1"
+                    max="300"
+                    value={replaySeconds}
+                    onChange={(e) =>
+                      setReplaySeconds(
+                        Math.min(
+                          300,
+                          Math.max(1, parseInt(e.target.value) || 1)
+                        )
+                      )
+                    }
+                    className="bg-discord-darker text-discord-text rounded px-2 py-1 text-sm w-20"
+                  />
+                  seconds
+                </label>
+              )}
+            </>
+          )}
+
+          {status && (
+            <>
+              {/* Name */}
+              <div>
+                <label className="block text-sm font-medium text-discord-text-muted mb-2">
+                  Name *
+                </label>
+                <input
+                  type="text"
+                  value={name}
+                  onChange={(e) => setName(e.target.value)}
+                  placeholder="e.g., Epic fail"
+                  className={fieldClass}
+                />
+              </div>
+
+              {/* Category */}
+              <div>
+                <label className="block text-sm font-medium text-discord-text-muted mb-2">
+                  Category *
+                </label>
+                <select
+                  value={categoryId}
+                  onChange={(e) => setCategoryId(e.target.value)}
+                  className={fieldClass}
+                >
+                  {categories.map((cat) => (
+                    <option key={cat.id} value={cat.id}>
+                      {cat.icon && `${cat.icon} `}
+                      {cat.name}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            </>
+          )}
+        </div>
+
+        {/* Actions */}
+        <div className="flex gap-3 mt-6">
+          {status ? (
+            <>
+              <button
+                onClick={handleDiscard}
+                disabled={isBusy}
+                className="flex-1 px-4 py-2 bg-discord-dark hover:bg-discord-darker
+                         rounded-lg text-discord-text font-medium transition-colors
+                         disabled:opacity-50"
+              >
+                {status.mode.type === "record" ? "Discard" : "Stop replay"}
+              </button>
+              <button
+                onClick={handleSave}
+                disabled={isBusy}
+                className="flex-1 px-4 py-2 bg-discord-success hover:bg-green-600
+                         disabled:bg-gray-600 disabled:cursor-not-allowed
+                         rounded-lg text-white font-medium transition-colors"
+              >
+                {isBusy
+                  ? "Saving..."
+                  : status.mode.type === "record"
+                    ? "Stop & Save"
+                    : "Save Replay"}
+              </button>
+            </>
+          ) : (
+            <>
+              <button
+                onClick={onClose}
+                className="flex-1 px-4 py-2 bg-discord-dark hover:bg-discord-darker
+                         rounded-lg text-discord-text font-medium transition-colors"
+              >
+                Close
+              </button>
+              <button
+                onClick={handleStart}
+                disabled={!sourceId}
+                className="flex-1 px-4 py-2 bg-discord-danger hover:bg-red-600
+                         disabled:bg-gray-600 disabled:cursor-not-allowed
+                         rounded-lg text-white font-medium transition-colors"
+              >
+                Start
+              </button>
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
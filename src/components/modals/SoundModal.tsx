@@ -1,7 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
-import { Sound, Category } from "../../types";
+import { Sound, Category, TtsSpeech, TtsVoice } from "../../types";
 import EmojiPicker from "../common/EmojiPicker";
 
 interface SoundModalProps {
@@ -29,6 +29,12 @@ export default function SoundModal({
   const [icon, setIcon] = useState<string | null>(null);
   const [volume, setVolume] = useState<number | null>(null);
   const [useCustomVolume, setUseCustomVolume] = useState(false);
+  const [isTts, setIsTts] = useState(false);
+  const [ttsText, setTtsText] = useState("");
+  const [ttsVoice, setTtsVoice] = useState("");
+  const [ttsRate, setTtsRate] = useState(1.0);
+  const [voices, setVoices] = useState<TtsVoice[]>([]);
+  const [voicesError, setVoicesError] = useState<string | null>(null);
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
@@ -45,6 +51,10 @@ export default function SoundModal({
         setIcon(sound.icon);
         setVolume(sound.volume);
         setUseCustomVolume(sound.volume !== null);
+        setIsTts(!!sound.tts);
+        setTtsText(sound.tts?.text ?? "");
+        setTtsVoice(sound.tts?.voice ?? "");
+        setTtsRate(sound.tts?.rate ?? 1.0);
       } else {
         // Add mode - use defaults
         const path = defaultFilePath || "";
@@ -53,6 +63,10 @@ export default function SoundModal({
         setIcon(null);
         setVolume(null);
         setUseCustomVolume(false);
+        setIsTts(false);
+        setTtsText("");
+        setTtsVoice("");
+        setTtsRate(1.0);
 
         // Auto-generate name from defaultFilePath if provided
         if (path) {
@@ -67,6 +81,20 @@ export default function SoundModal({
     }
   }, [isOpen, sound, defaultCategoryId, defaultFilePath, categories]);
 
+  // Load the engine's voices once a text-to-speech sound is being edited
+  useEffect(() => {
+    if (!isOpen || !isTts) return;
+    invoke<TtsVoice[]>("list_tts_voices")
+      .then((list) => {
+        setVoices(list);
+        setVoicesError(null);
+      })
+      .catch((err) => {
+        setVoices([]);
+        setVoicesError(String(err));
+      });
+  }, [isOpen, isTts]);
+
   // Smart filename formatter: kebab-case/snake_case to Title Case + Umlauts
   const formatFileName = (fileName: string): string => {
     // Remove extension
@@ -156,7 +184,11 @@ export default function SoundModal({
       setError("Please enter a name for the sound");
       return;
     }
-    if (!filePath.trim()) {
+    if (isTts && !ttsText.trim()) {
+      setError("Please enter the text to speak");
+      return;
+    }
+    if (!isTts && !filePath.trim()) {
       setError("Please enter the file path");
       return;
     }
@@ -165,6 +197,12 @@ export default function SoundModal({
       return;
     }
 
+    const speech: TtsSpeech = {
+      text: ttsText.trim(),
+      voice: ttsVoice,
+      rate: ttsRate,
+    };
+
     try {
       setIsSubmitting(true);
       setError(null);
@@ -181,6 +219,24 @@ export default function SoundModal({
           icon: icon,
           volume: volumeValue, // Always included in edit mode
         });
+        // Only re-render the speech if it changed
+        const previous = sound.tts;
+        if (
+          previous &&
+          (previous.text !== speech.text ||
+            previous.voice !== speech.voice ||
+            previous.rate !== speech.rate)
+        ) {
+          await invoke("update_tts_sound", { soundId: sound.id, speech });
+        }
+      } else if (isTts) {
+        await invoke("add_tts_sound", {
+          name: name.trim(),
+          speech,
+          categoryId: categoryId,
+          icon: icon,
+          volume: useCustomVolume ? volume : null,
+        });
       } else {
         // Add new sound
         await invoke("add_sound", {
@@ -265,38 +321,128 @@ export default function SoundModal({
             />
           </div>
 
-          {/* File Path */}
-          <div>
-            <label className="block text-sm font-medium text-discord-text-muted mb-2">
-              File Path * (drag & drop supported)
-            </label>
-            <div className="relative">
-              <input
-                type="text"
-                value={filePath}
-                onChange={(e) => handleFilePathChange(e.target.value)}
-                onDragOver={handleDragOver}
-                onDrop={handleDrop}
-                placeholder="e.g., C:/Sounds/airhorn.mp3 (or drag file here)"
-                className="w-full bg-discord-darker border border-discord-dark rounded
-                         pl-3 pr-12 py-2 text-discord-text focus:outline-none
-                         focus:ring-2 focus:ring-discord-primary"
-              />
-              <button
-                type="button"
-                onClick={handleBrowseFile}
-                className="absolute right-1 top-1/2 -translate-y-1/2
-                         w-10 h-[calc(100%-8px)] bg-discord-dark hover:bg-discord-darker
-                         rounded text-xl transition-colors flex items-center justify-center"
-                title="Browse for audio file"
-              >
-                📁
-              </button>
+          {/* Source: audio file or text to speech (fixed once added) */}
+          {!isEditMode && (
+            <div className="flex gap-2">
+              {[
+                { tts: false, label: "🎵 Audio file" },
+                { tts: true, label: "🗣️ Text to speech" },
+              ].map((option) => (
+                <button
+                  key={option.label}
+                  type="button"
+                  onClick={() => setIsTts(option.tts)}
+                  className={`flex-1 px-3 py-2 rounded text-sm transition-colors ${
+                    isTts === option.tts
+                      ? "bg-discord-primary text-white"
+                      : "bg-discord-darker text-discord-text-muted hover:text-discord-text"
+                  }`}
+                >
+                  {option.label}
+                </button>
+              ))}
             </div>
-            <p className="text-xs text-discord-text-muted mt-1">
-              Supports MP3, WAV, OGG, M4A - Click 📁 or drag file here
-            </p>
-          </div>
+          )}
+
+          {isTts ? (
+            <>
+              {/* Text */}
+              <div>
+                <label className="block text-sm font-medium text-discord-text-muted mb-2">
+                  Text *
+                </label>
+                <textarea
+                  value={ttsText}
+                  onChange={(e) => setTtsText(e.target.value)}
+                  placeholder="e.g., Thanks for the follow!"
+                  rows={3}
+                  className="w-full bg-discord-darker border border-discord-dark rounded
+                           px-3 py-2 text-discord-text focus:outline-none
+                           focus:ring-2 focus:ring-discord-primary resize-none"
+                />
+                <p className="text-xs text-discord-text-muted mt-1">
+                  Shift+Enter for a new line
+                </p>
+              </div>
+
+              {/* Voice & rate */}
+              <div className="flex gap-3">
+                <div className="flex-1">
+                  <label className="block text-sm font-medium text-discord-text-muted mb-2">
+                    Voice
+                  </label>
+                  <select
+                    value={ttsVoice}
+                    onChange={(e) => setTtsVoice(e.target.value)}
+                    className="w-full bg-discord-darker border border-discord-dark rounded
+                             px-3 py-2 text-discord-text focus:outline-none
+                             focus:ring-2 focus:ring-discord-primary"
+                  >
+                    <option value="">Default</option>
+                    {ttsVoice && !voices.some((v) => v.id === ttsVoice) && (
+                      <option value={ttsVoice}>{ttsVoice}</option>
+                    )}
+                    {voices.map((voice) => (
+                      <option key={voice.id} value={voice.id}>
+                        {voice.name} ({voice.id})
+                      </option>
+                    ))}
+                  </select>
+                </div>
+                <div className="w-32">
+                  <label className="block text-sm font-medium text-discord-text-muted mb-2">
+                    Rate {ttsRate.toFixed(1)}x
+                  </label>
+                  <input
+                    type="range"
+                    min="0.5"
+                    max="3"
+                    step="0.1"
+                    value={ttsRate}
+                    onChange={(e) => setTtsRate(parseFloat(e.target.value))}
+                    className="w-full mt-3"
+                  />
+                </div>
+              </div>
+              {voicesError && (
+                <p className="text-xs text-discord-danger">
+                  {voicesError} (check Settings → Text to Speech)
+                </p>
+              )}
+            </>
+          ) : (
+            <div>
+              <label className="block text-sm font-medium text-discord-text-muted mb-2">
+                File Path * (drag & drop supported)
+              </label>
+              <div className="relative">
+                <input
+                  type="text"
+                  value={filePath}
+                  onChange={(e) => handleFilePathChange(e.target.value)}
+                  onDragOver={handleDragOver}
+                  onDrop={handleDrop}
+                  placeholder="e.g., C:/Sounds/airhorn.mp3 (or drag file here)"
+                  className="w-full bg-discord-darker border border-discord-dark rounded
+                           pl-3 pr-12 py-2 text-discord-text focus:outline-none
+                           focus:ring-2 focus:ring-discord-primary"
+                />
+                <button
+                  type="button"
+                  onClick={handleBrowseFile}
+                  className="absolute right-1 top-1/2 -translate-y-1/2
+                           w-10 h-[calc(100%-8px)] bg-discord-dark hover:bg-discord-darker
+                           rounded text-xl transition-colors flex items-center justify-center"
+                  title="Browse for audio file"
+                >
+                  📁
+                </button>
+              </div>
+              <p className="text-xs text-discord-text-muted mt-1">
+                Supports MP3, WAV, OGG, M4A - Click 📁 or drag file here
+              </p>
+            </div>
+          )}
 
           {/* Category */}
           <div>
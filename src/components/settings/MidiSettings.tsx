@@ -0,0 +1,93 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AppSettings, MidiStatus } from "../../types";
+
+interface MidiSettingsProps {
+  settings: AppSettings;
+  onUpdateSetting: (key: keyof AppSettings, value: string | null) => void;
+}
+
+export default function MidiSettings({
+  settings,
+  onUpdateSetting,
+}: MidiSettingsProps) {
+  const [inputs, setInputs] = useState<string[]>([]);
+  const [status, setStatus] = useState<MidiStatus | null>(null);
+  const [isRefreshing, setIsRefreshing] = useState(false);
+
+  const refresh = async () => {
+    setIsRefreshing(true);
+    try {
+      setInputs(await invoke<string[]>("list_midi_inputs"));
+      setStatus(await invoke<MidiStatus>("get_midi_status"));
+    } catch (error) {
+      console.error("Failed to list MIDI inputs:", error);
+    } finally {
+      setIsRefreshing(false);
+    }
+  };
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  const selected = settings.midi_input_port;
+
+  return (
+    <div className="bg-discord-dark rounded-lg p-6 space-y-4">
+      <div className="flex items-center justify-between mb-4">
+        <h2 className="text-xl font-semibold text-discord-text">MIDI Input</h2>
+        <button
+          onClick={refresh}
+          disabled={isRefreshing}
+          className="px-3 py-1.5 text-sm bg-discord-primary hover:bg-discord-primary-hover 
+                   disabled:bg-gray-600 disabled:cursor-not-allowed rounded text-white 
+                   font-medium transition-colors"
+        >
+          {isRefreshing ? "Refreshing..." : "Refresh"}
+        </button>
+      </div>
+
+      <div>
+        <label className="block text-sm font-medium text-discord-text mb-2">
+          Controller
+          <span className="text-discord-text-muted text-xs ml-2">
+            (Pads and buttons trigger sounds)
+          </span>
+        </label>
+        <select
+          value={selected || ""}
+          onChange={(e) =>
+            onUpdateSetting("midi_input_port", e.target.value || null)
+          }
+          className="w-full bg-discord-darker border border-discord-dark rounded px-3 py-2 
+                   text-discord-text focus:outline-none focus:ring-2 focus:ring-discord-primary"
+        >
+          <option value="">Off</option>
+          {selected && !inputs.includes(selected) && (
+            <option value={selected}>{selected} (not connected)</option>
+          )}
+          {inputs.map((name) => (
+            <option key={name} value={name}>
+              {name}
+            </option>
+          ))}
+        </select>
+        <p className="text-xs text-discord-text-muted mt-2">
+          Bind pads in a sound's hotkey dialog with "Learn MIDI". Velocity sets
+          the volume.
+        </p>
+      </div>
+
+      {status && (
+        <p className="text-xs text-discord-text-muted">
+          {status.connected
+            ? `Connected to ${status.connected}`
+            : status.error
+              ? `Error: ${status.error}`
+              : "Not connected"}
+        </p>
+      )}
+    </div>
+  );
+}
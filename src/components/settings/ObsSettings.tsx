@@ -0,0 +1,251 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AppSettings, ObsAction, ObsConfig, ObsStatus } from "../../types";
+import { useSoundLibrary } from "../../contexts/SoundLibraryContext";
+
+interface ObsSettingsProps {
+  settings: AppSettings;
+  onUpdateObs: (changes: Partial<ObsConfig>) => void;
+}
+
+const inputClass =
+  "bg-discord-darker text-discord-text rounded px-2 py-1 text-sm";
+
+export default function ObsSettings({ settings, onUpdateObs }: ObsSettingsProps) {
+  const obs = settings.obs;
+  const { soundLibrary } = useSoundLibrary();
+  const sounds = soundLibrary.sounds;
+  const [status, setStatus] = useState<ObsStatus | null>(null);
+
+  // New entries being added
+  const [triggerScene, setTriggerScene] = useState("");
+  const [triggerSound, setTriggerSound] = useState("");
+  const [actionSound, setActionSound] = useState("");
+  const [actionType, setActionType] = useState<ObsAction["type"]>("switch_scene");
+  const [actionScene, setActionScene] = useState("");
+  const [actionSource, setActionSource] = useState("");
+
+  // Connecting happens in the background, so poll while the section is open
+  useEffect(() => {
+    const refresh = () =>
+      invoke<ObsStatus>("get_obs_status")
+        .then(setStatus)
+        .catch((error) => console.error("Failed to get OBS status:", error));
+    refresh();
+    const interval = setInterval(refresh, 2000);
+    return () => clearInterval(interval);
+  }, []);
+
+  const soundName = (id: string) =>
+    sounds.find((s) => s.id === id)?.name ?? `${id} (missing)`;
+
+  const describeAction = (action: ObsAction) =>
+    action.type === "switch_scene"
+      ? `Switch to scene "${action.scene}"`
+      : `Show "${action.source}" in "${action.scene}" while playing`;
+
+  const addTrigger = () => {
+    if (!triggerScene.trim() || !triggerSound) return;
+    onUpdateObs({
+      scene_triggers: [
+        ...obs.scene_triggers,
+        { scene: triggerScene.trim(), sound_id: triggerSound },
+      ],
+    });
+    setTriggerScene("");
+  };
+
+  const addAction = () => {
+    if (!actionSound || !actionScene.trim()) return;
+    if (actionType === "show_source" && !actionSource.trim()) return;
+    const action: ObsAction =
+      actionType === "switch_scene"
+        ? { type: "switch_scene", scene: actionScene.trim() }
+        : {
+            type: "show_source",
+            scene: actionScene.trim(),
+            source: actionSource.trim(),
+          };
+    onUpdateObs({
+      sound_actions: [...obs.sound_actions, { sound_id: actionSound, action }],
+    });
+    setActionScene("");
+    setActionSource("");
+  };
+
+  const soundSelect = (value: string, onChange: (id: string) => void) => (
+    <select
+      value={value}
+      onChange={(e) => onChange(e.target.value)}
+      className={inputClass}
+    >
+      <option value="">Sound...</option>
+      {sounds.map((sound) => (
+        <option key={sound.id} value={sound.id}>
+          {sound.name}
+        </option>
+      ))}
+    </select>
+  );
+
+  return (
+    <div className="bg-discord-dark rounded-lg p-6 space-y-4">
+      <h2 className="text-xl font-semibold text-discord-text mb-4">OBS Studio</h2>
+
+      <label className="flex items-center gap-2 cursor-pointer">
+        <input
+          type="checkbox"
+          checked={obs.enabled}
+          onChange={(e) => onUpdateObs({ enabled: e.target.checked })}
+          className="rounded border-discord-dark bg-discord-darker
+                     text-discord-primary focus:ring-discord-primary cursor-pointer"
+        />
+        <span className="text-sm text-discord-text">
+          Connect to OBS WebSocket
+        </span>
+      </label>
+      <p className="text-xs text-discord-text-muted ml-6">
+        Enable the WebSocket server in OBS (Tools → WebSocket Server Settings).
+      </p>
+
+      <div className="flex flex-wrap items-center gap-4">
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          Host
+          <input
+            type="text"
+            value={obs.host}
+            onChange={(e) => onUpdateObs({ host: e.target.value })}
+            className={`${inputClass} w-40`}
+          />
+        </label>
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          Port
+          <input
+            type="number"
+            min="1"
+            max="65535"
+            value={obs.port}
+            onChange={(e) => onUpdateObs({ port: parseInt(e.target.value) || 0 })}
+            className={`${inputClass} w-24`}
+          />
+        </label>
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          Password
+          <input
+            type="password"
+            value={obs.password}
+            onChange={(e) => onUpdateObs({ password: e.target.value })}
+            className={`${inputClass} w-40`}
+          />
+        </label>
+      </div>
+
+      {status && obs.enabled && (
+        <p className="text-xs text-discord-text-muted">
+          {status.connected
+            ? status.scene
+              ? `Connected (scene: ${status.scene})`
+              : "Connected"
+            : status.error
+              ? `Error: ${status.error}`
+              : "Connecting..."}
+        </p>
+      )}
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-medium text-discord-text">
+          When a sound plays
+        </h3>
+        {obs.sound_actions.map((entry, index) => (
+          <div key={index} className="flex items-center gap-2 text-sm">
+            <span className="flex-1 text-discord-text">
+              {soundName(entry.sound_id)}: {describeAction(entry.action)}
+            </span>
+            <button
+              onClick={() =>
+                onUpdateObs({
+                  sound_actions: obs.sound_actions.filter((_, i) => i !== index),
+                })
+              }
+              className="text-discord-danger hover:underline text-xs"
+            >
+              Remove
+            </button>
+          </div>
+        ))}
+        <div className="flex flex-wrap items-center gap-2">
+          {soundSelect(actionSound, setActionSound)}
+          <select
+            value={actionType}
+            onChange={(e) => setActionType(e.target.value as ObsAction["type"])}
+            className={inputClass}
+          >
+            <option value="switch_scene">Switch scene</option>
+            <option value="show_source">Show source</option>
+          </select>
+          <input
+            type="text"
+            placeholder="Scene"
+            value={actionScene}
+            onChange={(e) => setActionScene(e.target.value)}
+            className={`${inputClass} w-32`}
+          />
+          {actionType === "show_source" && (
+            <input
+              type="text"
+              placeholder="Source"
+              value={actionSource}
+              onChange={(e) => setActionSource(e.target.value)}
+              className={`${inputClass} w-32`}
+            />
+          )}
+          <button
+            onClick={addAction}
+            className="px-3 py-1 bg-discord-darker hover:bg-discord-darkest text-discord-text text-sm rounded"
+          >
+            Add
+          </button>
+        </div>
+      </div>
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-medium text-discord-text">
+          When a scene goes live
+        </h3>
+        {obs.scene_triggers.map((trigger, index) => (
+          <div key={index} className="flex items-center gap-2 text-sm">
+            <span className="flex-1 text-discord-text">
+              "{trigger.scene}": play {soundName(trigger.sound_id)}
+            </span>
+            <button
+              onClick={() =>
+                onUpdateObs({
+                  scene_triggers: obs.scene_triggers.filter((_, i) => i !== index),
+                })
+              }
+              className="text-discord-danger hover:underline text-xs"
+            >
+              Remove
+            </button>
+          </div>
+        ))}
+        <div className="flex flex-wrap items-center gap-2">
+          <input
+            type="text"
+            placeholder="Scene"
+            value={triggerScene}
+            onChange={(e) => setTriggerScene(e.target.value)}
+            className={`${inputClass} w-32`}
+          />
+          {soundSelect(triggerSound, setTriggerSound)}
+          <button
+            onClick={addTrigger}
+            className="px-3 py-1 bg-discord-darker hover:bg-discord-darkest text-discord-text text-sm rounded"
+          >
+            Add
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
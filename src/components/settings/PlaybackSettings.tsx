@@ -1,4 +1,6 @@
-import { AppSettings } from "../../types";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AppSettings, BusMeter } from "../../types";
 
 interface PlaybackSettingsProps {
   settings: AppSettings;
@@ -12,6 +14,23 @@ export default function PlaybackSettings({
   settings,
   onUpdateSetting,
 }: PlaybackSettingsProps) {
+  const [meters, setMeters] = useState<BusMeter[]>([]);
+
+  // Poll limiter gain reduction while the settings page is open
+  useEffect(() => {
+    const interval = setInterval(() => {
+      invoke<BusMeter[]>("get_limiter_meters")
+        .then(setMeters)
+        .catch(() => setMeters([]));
+    }, 250);
+    return () => clearInterval(interval);
+  }, []);
+
+  const gainReduction = meters.reduce(
+    (max, meter) => Math.max(max, meter.gain_reduction_db),
+    0
+  );
+
   return (
     <div className="bg-discord-dark rounded-lg p-6 space-y-4">
       <h2 className="text-xl font-semibold text-discord-text mb-4">
@@ -122,6 +141,98 @@ export default function PlaybackSettings({
           </p>
         )}
       </div>
+
+      {/* Output Limiter */}
+      <div>
+        <label className="block text-sm font-medium text-discord-text mb-2">
+          Limiter Ceiling: {settings.limiter_ceiling_db.toFixed(1)} dB
+        </label>
+        <input
+          type="range"
+          min="-24"
+          max="0"
+          step="0.5"
+          value={settings.limiter_ceiling_db}
+          onChange={(e) =>
+            onUpdateSetting("limiter_ceiling_db", parseFloat(e.target.value))
+          }
+          className="w-full"
+          style={{ accentColor: "#5865f2" }}
+        />
+        <div className="mt-2 h-2 w-full bg-discord-darker rounded overflow-hidden">
+          <div
+            className="h-full bg-yellow-500"
+            style={{ width: `${Math.min(100, (gainReduction / 12) * 100)}%` }}
+          />
+        </div>
+        <p className="text-xs text-discord-text-muted mt-1">
+          Overlapping sounds are limited so the output never exceeds this level.
+          Gain reduction: {gainReduction.toFixed(1)} dB
+        </p>
+      </div>
+
+      {/* Managed Media Library */}
+      <div>
+        <label className="flex items-center gap-2 cursor-pointer">
+          <input
+            type="checkbox"
+            checked={settings.copy_sounds_to_library}
+            onChange={(e) =>
+              onUpdateSetting("copy_sounds_to_library", e.target.checked)
+            }
+            className="rounded border-discord-dark bg-discord-darker
+                     text-discord-primary focus:ring-discord-primary cursor-pointer"
+          />
+          <span className="text-sm text-discord-text">
+            Copy added sounds into the library
+          </span>
+        </label>
+        <p className="text-xs text-discord-text-muted ml-6">
+          Sounds keep working when the original file is moved or deleted.
+          Identical files are stored once.
+        </p>
+      </div>
+
+      {/* Persistent Decode Cache */}
+      <div>
+        <label className="block text-sm font-medium text-discord-text mb-2">
+          Decode Cache:{" "}
+          {settings.decode_cache_max_mb === 0
+            ? "Off"
+            : `${settings.decode_cache_max_mb} MB`}
+        </label>
+        <input
+          type="range"
+          min="0"
+          max="8192"
+          step="256"
+          value={settings.decode_cache_max_mb}
+          onChange={(e) =>
+            onUpdateSetting("decode_cache_max_mb", parseInt(e.target.value))
+          }
+          className="w-full"
+          style={{ accentColor: "#5865f2" }}
+        />
+        <label className="flex items-center gap-2 cursor-pointer mt-2">
+          <input
+            type="checkbox"
+            checked={settings.decode_cache_resample}
+            disabled={settings.decode_cache_max_mb === 0}
+            onChange={(e) =>
+              onUpdateSetting("decode_cache_resample", e.target.checked)
+            }
+            className="rounded border-discord-dark bg-discord-darker
+                     text-discord-primary focus:ring-discord-primary cursor-pointer"
+          />
+          <span className="text-sm text-discord-text">
+            Store at the monitor device sample rate
+          </span>
+        </label>
+        <p className="text-xs text-discord-text-muted ml-6">
+          Decoded sounds are kept on disk so they play instantly after a
+          restart.
+        </p>
+      </div>
     </div>
   );
 }
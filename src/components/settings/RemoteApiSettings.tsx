@@ -0,0 +1,111 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AppSettings, RemoteApiConfig, RemoteApiStatus } from "../../types";
+
+interface RemoteApiSettingsProps {
+  settings: AppSettings;
+  onUpdateRemoteApi: (changes: Partial<RemoteApiConfig>) => void;
+}
+
+export default function RemoteApiSettings({
+  settings,
+  onUpdateRemoteApi,
+}: RemoteApiSettingsProps) {
+  const remote = settings.remote_api;
+  const [status, setStatus] = useState<RemoteApiStatus | null>(null);
+
+  // Status follows the saved settings, not the unsaved form
+  useEffect(() => {
+    invoke<RemoteApiStatus>("get_remote_api_status")
+      .then(setStatus)
+      .catch((error) => console.error("Failed to get remote API status:", error));
+  }, [remote.token]);
+
+  const handleRegenerateToken = async () => {
+    try {
+      await invoke<string>("regenerate_remote_api_token");
+      setStatus(await invoke<RemoteApiStatus>("get_remote_api_status"));
+    } catch (error) {
+      console.error("Failed to regenerate token:", error);
+    }
+  };
+
+  return (
+    <div className="bg-discord-dark rounded-lg p-6 space-y-4">
+      <h2 className="text-xl font-semibold text-discord-text mb-4">
+        Remote Control
+      </h2>
+
+      <label className="flex items-center gap-2 cursor-pointer">
+        <input
+          type="checkbox"
+          checked={remote.enabled}
+          onChange={(e) => onUpdateRemoteApi({ enabled: e.target.checked })}
+          className="rounded border-discord-dark bg-discord-darker
+                     text-discord-primary focus:ring-discord-primary cursor-pointer"
+        />
+        <span className="text-sm text-discord-text">
+          Enable the remote-control API
+        </span>
+      </label>
+      <p className="text-xs text-discord-text-muted ml-6">
+        Trigger sounds from a phone, Stream Deck or another PC over HTTP. A
+        token is created when the API is first enabled.
+      </p>
+
+      <div className="flex items-center gap-4">
+        <label className="flex items-center gap-2 cursor-pointer">
+          <input
+            type="checkbox"
+            checked={remote.bind === "lan"}
+            onChange={(e) =>
+              onUpdateRemoteApi({ bind: e.target.checked ? "lan" : "localhost" })
+            }
+            className="rounded border-discord-dark bg-discord-darker
+                     text-discord-primary focus:ring-discord-primary cursor-pointer"
+          />
+          <span className="text-sm text-discord-text">
+            Allow other devices on the network
+          </span>
+        </label>
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          Port
+          <input
+            type="number"
+            min="1"
+            max="65535"
+            value={remote.port}
+            onChange={(e) =>
+              onUpdateRemoteApi({ port: parseInt(e.target.value) || 0 })
+            }
+            className="w-24 bg-discord-darker text-discord-text rounded px-2 py-1"
+          />
+        </label>
+      </div>
+
+      {remote.token && (
+        <div className="flex items-center gap-2">
+          <code className="flex-1 bg-discord-darker text-discord-text text-xs rounded px-3 py-2 select-all">
+            {remote.token}
+          </code>
+          <button
+            onClick={handleRegenerateToken}
+            className="px-3 py-2 bg-discord-darker hover:bg-discord-darkest text-discord-text text-sm rounded"
+          >
+            Regenerate
+          </button>
+        </div>
+      )}
+
+      {status && (
+        <p className="text-xs text-discord-text-muted">
+          {status.running
+            ? `Listening on ${status.address} (${status.clients} event clients)`
+            : status.error
+              ? `Error: ${status.error}`
+              : "Not running"}
+        </p>
+      )}
+    </div>
+  );
+}
@@ -1,11 +1,22 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { AppSettings } from "../../types";
+import {
+  AppSettings,
+  ObsConfig,
+  RemoteApiConfig,
+  TtsConfig,
+  TwitchConfig,
+} from "../../types";
 import { useAudio } from "../../contexts/AudioContext";
 import { useSettings as useSettingsContext } from "../../contexts/SettingsContext";
 import AudioDeviceSettings from "./AudioDeviceSettings";
+import MidiSettings from "./MidiSettings";
+import ObsSettings from "./ObsSettings";
 import PlaybackSettings from "./PlaybackSettings";
+import RemoteApiSettings from "./RemoteApiSettings";
 import SystemTraySettings from "./SystemTraySettings";
+import TtsSettings from "./TtsSettings";
+import TwitchSettings from "./TwitchSettings";
 import VbCableSettings from "./VbCableSettings";
 import SettingsAbout from "./SettingsAbout";
 
@@ -29,6 +40,71 @@ export default function Settings() {
     autostart_enabled: false,
     microphone_routing_device_id: null,
     microphone_routing_enabled: false,
+    microphone_routing_mic_gain_db: 0,
+    microphone_routing_board_gain_db: 0,
+    microphone_processing: {
+      enabled: false,
+      gain_db: 0,
+      high_pass_enabled: true,
+      high_pass_hz: 80,
+      gate_enabled: true,
+      gate_threshold_db: -45,
+      gate_hysteresis_db: 6,
+      gate_hold_ms: 150,
+      gate_release_ms: 100,
+      compressor_enabled: true,
+      compressor_threshold_db: -18,
+      compressor_ratio: 3,
+      compressor_makeup_db: 3,
+    },
+    microphone_gate_mode: "always_on",
+    microphone_gate_hotkey: null,
+    ducking: {
+      mic_ducking_enabled: false,
+      mic_duck_depth_db: -12,
+      board_ducking_enabled: false,
+      board_duck_depth_db: -9,
+      voice_threshold_db: -40,
+      attack_ms: 10,
+      release_ms: 300,
+    },
+    limiter_ceiling_db: -1.0,
+    copy_sounds_to_library: false,
+    decode_cache_max_mb: 1024,
+    decode_cache_resample: false,
+    remote_api: {
+      enabled: false,
+      bind: "localhost",
+      port: 7878,
+      token: "",
+    },
+    midi_input_port: null,
+    obs: {
+      enabled: false,
+      host: "localhost",
+      port: 4455,
+      password: "",
+      sound_actions: [],
+      scene_triggers: [],
+    },
+    twitch: {
+      enabled: false,
+      host: "irc.chat.twitch.tv",
+      port: 6697,
+      channel: "",
+      username: "",
+      oauth_token: "",
+      commands: [],
+      redemptions: [],
+      user_cooldown_secs: 30,
+      global_cooldown_secs: 5,
+      max_queue: 5,
+      kill_command: "!stopsounds",
+      resume_command: "!resumesounds",
+    },
+    tts: {
+      program: "espeak-ng",
+    },
   });
   const [isRefreshing, setIsRefreshing] = useState<boolean>(false);
   const [isSaving, setIsSaving] = useState<boolean>(false);
@@ -99,6 +175,28 @@ export default function Settings() {
     setSettings((prev) => ({ ...prev, [key]: value }));
   };
 
+  const updateRemoteApi = (changes: Partial<RemoteApiConfig>) => {
+    setSettings((prev) => ({
+      ...prev,
+      remote_api: { ...prev.remote_api, ...changes },
+    }));
+  };
+
+  const updateObs = (changes: Partial<ObsConfig>) => {
+    setSettings((prev) => ({ ...prev, obs: { ...prev.obs, ...changes } }));
+  };
+
+  const updateTwitch = (changes: Partial<TwitchConfig>) => {
+    setSettings((prev) => ({
+      ...prev,
+      twitch: { ...prev.twitch, ...changes },
+    }));
+  };
+
+  const updateTts = (changes: Partial<TtsConfig>) => {
+    setSettings((prev) => ({ ...prev, tts: { ...prev.tts, ...changes } }));
+  };
+
   const isDeviceAvailable = (deviceId: string): boolean => {
     return devices.some((d) => d.id === deviceId);
   };
@@ -164,6 +262,24 @@ export default function Settings() {
             onUpdateSetting={updateSetting}
           />
 
+          {/* Text to Speech */}
+          <TtsSettings settings={settings} onUpdateTts={updateTts} />
+
+          {/* MIDI Controller */}
+          <MidiSettings settings={settings} onUpdateSetting={updateSetting} />
+
+          {/* Remote Control API */}
+          <RemoteApiSettings
+            settings={settings}
+            onUpdateRemoteApi={updateRemoteApi}
+          />
+
+          {/* OBS Integration */}
+          <ObsSettings settings={settings} onUpdateObs={updateObs} />
+
+          {/* Twitch Chat */}
+          <TwitchSettings settings={settings} onUpdateTwitch={updateTwitch} />
+
           {/* System Tray & Startup */}
           <SystemTraySettings
             settings={settings}
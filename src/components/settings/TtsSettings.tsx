@@ -0,0 +1,59 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AppSettings, TtsConfig, TtsVoice } from "../../types";
+
+interface TtsSettingsProps {
+  settings: AppSettings;
+  onUpdateTts: (changes: Partial<TtsConfig>) => void;
+}
+
+const inputClass =
+  "bg-discord-darker text-discord-text rounded px-2 py-1 text-sm";
+
+export default function TtsSettings({ settings, onUpdateTts }: TtsSettingsProps) {
+  const [checkResult, setCheckResult] = useState<string | null>(null);
+
+  // Uses the saved settings, like rendering does
+  const handleCheck = async () => {
+    try {
+      const voices = await invoke<TtsVoice[]>("list_tts_voices");
+      setCheckResult(`Found ${voices.length} voices`);
+    } catch (error) {
+      setCheckResult(`Error: ${error}`);
+    }
+  };
+
+  return (
+    <div className="bg-discord-dark rounded-lg p-6 space-y-4">
+      <h2 className="text-xl font-semibold text-discord-text mb-4">
+        Text to Speech
+      </h2>
+
+      <div className="flex flex-wrap items-center gap-4">
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          espeak-ng program
+          <input
+            type="text"
+            value={settings.tts.program}
+            onChange={(e) => onUpdateTts({ program: e.target.value })}
+            className={`${inputClass} w-64`}
+          />
+        </label>
+        <button
+          onClick={handleCheck}
+          className="px-3 py-1 bg-discord-darker hover:bg-discord-darkest text-discord-text text-sm rounded"
+        >
+          Check
+        </button>
+      </div>
+      <p className="text-xs text-discord-text-muted">
+        Text-to-speech sounds are spoken by espeak-ng. Enter its name if it is on
+        your PATH, or the full path to the executable. Save settings before
+        checking.
+      </p>
+      {checkResult && (
+        <p className="text-xs text-discord-text-muted">{checkResult}</p>
+      )}
+    </div>
+  );
+}
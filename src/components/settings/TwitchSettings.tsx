@@ -0,0 +1,301 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AppSettings, TwitchConfig, TwitchStatus } from "../../types";
+import { useSoundLibrary } from "../../contexts/SoundLibraryContext";
+
+interface TwitchSettingsProps {
+  settings: AppSettings;
+  onUpdateTwitch: (changes: Partial<TwitchConfig>) => void;
+}
+
+const inputClass =
+  "bg-discord-darker text-discord-text rounded px-2 py-1 text-sm";
+
+export default function TwitchSettings({
+  settings,
+  onUpdateTwitch,
+}: TwitchSettingsProps) {
+  const twitch = settings.twitch;
+  const { soundLibrary } = useSoundLibrary();
+  const sounds = soundLibrary.sounds;
+  const [status, setStatus] = useState<TwitchStatus | null>(null);
+
+  // New entries being added
+  const [command, setCommand] = useState("");
+  const [commandSound, setCommandSound] = useState("");
+  const [rewardTitle, setRewardTitle] = useState("");
+  const [rewardId, setRewardId] = useState("");
+  const [rewardSound, setRewardSound] = useState("");
+
+  const refreshStatus = () =>
+    invoke<TwitchStatus>("get_twitch_status")
+      .then(setStatus)
+      .catch((error) => console.error("Failed to get Twitch status:", error));
+
+  // Connection, queue and kill switch change in the background
+  useEffect(() => {
+    refreshStatus();
+    const interval = setInterval(refreshStatus, 2000);
+    return () => clearInterval(interval);
+  }, []);
+
+  const handleSetPaused = async (paused: boolean) => {
+    try {
+      await invoke("set_twitch_paused", { paused });
+      await refreshStatus();
+    } catch (error) {
+      console.error("Failed to pause Twitch triggers:", error);
+    }
+  };
+
+  const soundName = (id: string) =>
+    sounds.find((s) => s.id === id)?.name ?? `${id} (missing)`;
+
+  const addCommand = () => {
+    const name = command.trim();
+    if (!name || !commandSound) return;
+    onUpdateTwitch({
+      commands: [
+        ...twitch.commands,
+        {
+          command: name.startsWith("!") ? name : `!${name}`,
+          sound_id: commandSound,
+        },
+      ],
+    });
+    setCommand("");
+  };
+
+  const addRedemption = () => {
+    if (!rewardId.trim() || !rewardSound) return;
+    onUpdateTwitch({
+      redemptions: [
+        ...twitch.redemptions,
+        {
+          title: rewardTitle.trim() || rewardId.trim(),
+          reward_id: rewardId.trim(),
+          sound_id: rewardSound,
+        },
+      ],
+    });
+    setRewardTitle("");
+    setRewardId("");
+  };
+
+  const soundSelect = (value: string, onChange: (id: string) => void) => (
+    <select
+      value={value}
+      onChange={(e) => onChange(e.target.value)}
+      className={inputClass}
+    >
+      <option value="">Sound...</option>
+      {sounds.map((sound) => (
+        <option key={sound.id} value={sound.id}>
+          {sound.name}
+        </option>
+      ))}
+    </select>
+  );
+
+  const numberField = (
+    label: string,
+    key: "user_cooldown_secs" | "global_cooldown_secs" | "max_queue"
+  ) => (
+    <label className="flex items-center gap-2 text-sm text-discord-text">
+      {label}
+      <input
+        type="number"
+        min="0"
+        value={twitch[key]}
+        onChange={(e) =>
+          onUpdateTwitch({
+            [key]: Math.max(0, parseInt(e.target.value) || 0),
+          } as Partial<TwitchConfig>)
+        }
+        className={`${inputClass} w-20`}
+      />
+    </label>
+  );
+
+  return (
+    <div className="bg-discord-dark rounded-lg p-6 space-y-4">
+      <h2 className="text-xl font-semibold text-discord-text mb-4">
+        Twitch Chat
+      </h2>
+
+      <label className="flex items-center gap-2 cursor-pointer">
+        <input
+          type="checkbox"
+          checked={twitch.enabled}
+          onChange={(e) => onUpdateTwitch({ enabled: e.target.checked })}
+          className="rounded border-discord-dark bg-discord-darker
+                     text-discord-primary focus:ring-discord-primary cursor-pointer"
+        />
+        <span className="text-sm text-discord-text">
+          Let chat commands and channel points play sounds
+        </span>
+      </label>
+
+      <div className="flex flex-wrap items-center gap-4">
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          Channel
+          <input
+            type="text"
+            value={twitch.channel}
+            onChange={(e) => onUpdateTwitch({ channel: e.target.value })}
+            className={`${inputClass} w-40`}
+          />
+        </label>
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          Bot account
+          <input
+            type="text"
+            placeholder="Anonymous"
+            value={twitch.username}
+            onChange={(e) => onUpdateTwitch({ username: e.target.value })}
+            className={`${inputClass} w-40`}
+          />
+        </label>
+        <label className="flex items-center gap-2 text-sm text-discord-text">
+          OAuth token
+          <input
+            type="password"
+            value={twitch.oauth_token}
+            onChange={(e) => onUpdateTwitch({ oauth_token: e.target.value })}
+            className={`${inputClass} w-40`}
+          />
+        </label>
+      </div>
+
+      <div className="flex flex-wrap items-center gap-4">
+        {numberField("Viewer cooldown (s)", "user_cooldown_secs")}
+        {numberField("Global cooldown (s)", "global_cooldown_secs")}
+        {numberField("Max queue", "max_queue")}
+      </div>
+      <p className="text-xs text-discord-text-muted">
+        Chat sounds play one at a time. Cooldowns apply to chat commands;
+        redemptions are already paid for. Moderators can type{" "}
+        <code>{twitch.kill_command}</code> to clear the queue and pause, and{" "}
+        <code>{twitch.resume_command}</code> to resume.
+      </p>
+
+      {status && twitch.enabled && (
+        <div className="flex items-center gap-4">
+          <p className="flex-1 text-xs text-discord-text-muted">
+            {status.connected
+              ? `Connected to #${twitch.channel}` +
+                (status.paused ? " (paused)" : "") +
+                (status.queued > 0 ? `, ${status.queued} queued` : "")
+              : status.error
+                ? `Error: ${status.error}`
+                : "Connecting..."}
+          </p>
+          <button
+            onClick={() => handleSetPaused(!status.paused)}
+            className="px-3 py-1 bg-discord-darker hover:bg-discord-darkest text-discord-text text-sm rounded"
+          >
+            {status.paused ? "Resume" : "Stop & pause"}
+          </button>
+        </div>
+      )}
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-medium text-discord-text">Chat commands</h3>
+        {twitch.commands.map((trigger, index) => (
+          <div key={index} className="flex items-center gap-2 text-sm">
+            <span className="flex-1 text-discord-text">
+              {trigger.command}: {soundName(trigger.sound_id)}
+            </span>
+            <button
+              onClick={() =>
+                onUpdateTwitch({
+                  commands: twitch.commands.filter((_, i) => i !== index),
+                })
+              }
+              className="text-discord-danger hover:underline text-xs"
+            >
+              Remove
+            </button>
+          </div>
+        ))}
+        <div className="flex flex-wrap items-center gap-2">
+          <input
+            type="text"
+            placeholder="!airhorn"
+            value={command}
+            onChange={(e) => setCommand(e.target.value)}
+            className={`${inputClass} w-32`}
+          />
+          {soundSelect(commandSound, setCommandSound)}
+          <button
+            onClick={addCommand}
+            className="px-3 py-1 bg-discord-darker hover:bg-discord-darkest text-discord-text text-sm rounded"
+          >
+            Add
+          </button>
+        </div>
+      </div>
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-medium text-discord-text">
+          Channel-point rewards
+        </h3>
+        <p className="text-xs text-discord-text-muted">
+          Twitch chat identifies rewards by ID, and only rewards that ask the
+          viewer for text. Redeem the reward once to see its ID here.
+        </p>
+        {twitch.redemptions.map((trigger, index) => (
+          <div key={index} className="flex items-center gap-2 text-sm">
+            <span className="flex-1 text-discord-text">
+              {trigger.title}: {soundName(trigger.sound_id)}
+            </span>
+            <button
+              onClick={() =>
+                onUpdateTwitch({
+                  redemptions: twitch.redemptions.filter((_, i) => i !== index),
+                })
+              }
+              className="text-discord-danger hover:underline text-xs"
+            >
+              Remove
+            </button>
+          </div>
+        ))}
+        {status?.last_reward_id && (
+          <p className="text-xs text-discord-text-muted">
+            Last redeemed:{" "}
+            <button
+              onClick={() => setRewardId(status.last_reward_id ?? "")}
+              className="text-discord-primary hover:underline"
+            >
+              {status.last_reward_id}
+            </button>
+          </p>
+        )}
+        <div className="flex flex-wrap items-center gap-2">
+          <input
+            type="text"
+            placeholder="Title"
+            value={rewardTitle}
+            onChange={(e) => setRewardTitle(e.target.value)}
+            className={`${inputClass} w-32`}
+          />
+          <input
+            type="text"
+            placeholder="Reward ID"
+            value={rewardId}
+            onChange={(e) => setRewardId(e.target.value)}
+            className={`${inputClass} w-48`}
+          />
+          {soundSelect(rewardSound, setRewardSound)}
+          <button
+            onClick={addRedemption}
+            className="px-3 py-1 bg-discord-darker hover:bg-discord-darkest text-discord-text text-sm rounded"
+          >
+            Add
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -11,15 +11,36 @@ vi.mock("@tauri-apps/api/core", () => ({
 // Mock contexts
 const mockSaveSettings = vi.fn();
 const mockRefreshDevices = vi.fn();
+const mockReloadSettings = vi.fn();
 
 vi.mock("../../contexts/SettingsContext", () => ({
   useSettings: () => ({
     settings: {
       microphone_routing_device_id: null,
       microphone_routing_enabled: false,
+      microphone_routing_mic_gain_db: 0,
+      microphone_routing_board_gain_db: 0,
+      microphone_processing: {
+        enabled: false,
+        gain_db: 0,
+        high_pass_enabled: true,
+        high_pass_hz: 80,
+        gate_enabled: true,
+        gate_threshold_db: -45,
+        gate_hysteresis_db: 6,
+        gate_hold_ms: 150,
+        gate_release_ms: 100,
+        compressor_enabled: true,
+        compressor_threshold_db: -18,
+        compressor_ratio: 3,
+        compressor_makeup_db: 3,
+      },
+      microphone_gate_mode: "always_on",
+      microphone_gate_hotkey: null,
       broadcast_device_id: null,
     },
     saveSettings: mockSaveSettings,
+    reloadSettings: mockReloadSettings,
   }),
 }));
 
@@ -197,7 +218,7 @@ describe("VbCableSettings", () => {
         expect(screen.getByText("Test Microphone")).toBeInTheDocument();
       });
 
-      const select = screen.getByRole("combobox");
+      const select = screen.getByDisplayValue("Select microphone...");
       fireEvent.change(select, { target: { value: "mic-1" } });
 
       const enableButton = screen.getByRole("button", { name: "Enable" });
@@ -211,7 +232,7 @@ describe("VbCableSettings", () => {
         expect(screen.getByText("Test Microphone")).toBeInTheDocument();
       });
 
-      const select = screen.getByRole("combobox");
+      const select = screen.getByDisplayValue("Select microphone...");
       fireEvent.change(select, { target: { value: "mic-1" } });
 
       const enableButton = screen.getByRole("button", { name: "Enable" });
@@ -263,6 +284,73 @@ describe("VbCableSettings", () => {
     });
   });
 
+  describe("push-to-talk", () => {
+    beforeEach(() => {
+      vi.mocked(invoke).mockImplementation((cmd: string) => {
+        if (cmd === "check_vb_cable_status") {
+          return Promise.resolve({
+            status: "installed",
+            info: { output_device: "CABLE Input" },
+          });
+        }
+        if (cmd === "list_microphones") {
+          return Promise.resolve([["mic-1", "Test Microphone"]]);
+        }
+        return Promise.resolve(null);
+      });
+    });
+
+    it("records a hotkey before enabling push-to-talk", async () => {
+      render(<VbCableSettings />);
+
+      await waitFor(() => {
+        expect(screen.getByDisplayValue("Always on")).toBeInTheDocument();
+      });
+
+      fireEvent.change(screen.getByDisplayValue("Always on"), {
+        target: { value: "push_to_talk" },
+      });
+      expect(screen.getByText("Press a key...")).toBeInTheDocument();
+      expect(invoke).not.toHaveBeenCalledWith(
+        "set_microphone_gate",
+        expect.anything()
+      );
+
+      // Modifiers alone don't finish recording
+      fireEvent.keyDown(window, { key: "Control", ctrlKey: true });
+      fireEvent.keyDown(window, { key: "F13", code: "F13", ctrlKey: true });
+
+      await waitFor(() => {
+        expect(invoke).toHaveBeenCalledWith("set_microphone_gate", {
+          mode: "push_to_talk",
+          hotkey: "Ctrl+F13",
+        });
+      });
+      expect(mockReloadSettings).toHaveBeenCalled();
+    });
+
+    it("cancels recording with Escape", async () => {
+      render(<VbCableSettings />);
+
+      await waitFor(() => {
+        expect(screen.getByDisplayValue("Always on")).toBeInTheDocument();
+      });
+
+      fireEvent.change(screen.getByDisplayValue("Always on"), {
+        target: { value: "push_to_mute" },
+      });
+      fireEvent.keyDown(window, { key: "Escape", code: "Escape" });
+
+      await waitFor(() => {
+        expect(screen.getByDisplayValue("Always on")).toBeInTheDocument();
+      });
+      expect(invoke).not.toHaveBeenCalledWith(
+        "set_microphone_gate",
+        expect.anything()
+      );
+    });
+  });
+
   describe("disable routing", () => {
     beforeEach(() => {
       vi.mocked(invoke).mockImplementation((cmd: string) => {
@@ -276,7 +364,12 @@ describe("VbCableSettings", () => {
           return Promise.resolve([["mic-1", "Test Microphone"]]);
         }
         if (cmd === "get_microphone_routing_status") {
-          return Promise.resolve("mic-1"); // Routing is active
+          // Routing is active
+          return Promise.resolve({
+            microphone_id: "mic-1",
+            gate_mode: "always_on",
+            mic_open: true,
+          });
         }
         if (cmd === "disable_microphone_routing") {
           return Promise.resolve(null);
@@ -524,7 +617,7 @@ describe("VbCableSettings", () => {
         expect(screen.getByText("Test Mic")).toBeInTheDocument();
       });
 
-      const select = screen.getByRole("combobox");
+      const select = screen.getByDisplayValue("Select microphone...");
       fireEvent.change(select, { target: { value: "mic-1" } });
 
       const enableButton = screen.getByRole("button", { name: "Enable" });
@@ -1,13 +1,23 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import {
   AudioDevice,
+  MicGateMode,
   RestoreResult,
+  RoutingStats,
+  RoutingStatus,
   SavedDefaults,
   VbCableStatus,
 } from "../../types";
 import { useSettings } from "../../contexts/SettingsContext";
 import { useAudio } from "../../contexts/AudioContext";
+import {
+  formatHotkeyForDisplay,
+  keysFromKeyboardEvent,
+} from "../../utils/hotkeyDisplay";
+
+const MODIFIER_KEYS = ["Ctrl", "Shift", "Alt", "Super"];
 
 interface VbCableSettingsProps {
   onDeviceChange?: () => void;
@@ -27,11 +37,17 @@ export default function VbCableSettings({
   const [selectedMicrophone, setSelectedMicrophone] = useState<string>("");
   const [isRoutingActive, setIsRoutingActive] = useState(false);
   const [isRoutingLoading, setIsRoutingLoading] = useState(false);
+  const [routingStats, setRoutingStats] = useState<RoutingStats | null>(null);
+  const [micOpen, setMicOpen] = useState(true);
+
+  // Push-to-talk: mode waiting for a hotkey while one is being recorded
+  const [recordingGateMode, setRecordingGateMode] =
+    useState<MicGateMode | null>(null);
 
   // Guard against race conditions from rapid clicks (state updates are async)
   const operationInProgress = useRef(false);
 
-  const { settings, saveSettings } = useSettings();
+  const { settings, saveSettings, reloadSettings } = useSettings();
   const { refreshDevices } = useAudio();
 
   // Load microphones and routing status when VB-Cable is installed
@@ -42,12 +58,13 @@ export default function VbCableSettings({
       setMicrophones(mics);
 
       // Check current routing status
-      const routingStatus = await invoke<string | null>(
+      const routingStatus = await invoke<RoutingStatus | null>(
         "get_microphone_routing_status"
       );
       if (routingStatus) {
         setIsRoutingActive(true);
-        setSelectedMicrophone(routingStatus);
+        setSelectedMicrophone(routingStatus.microphone_id);
+        setMicOpen(routingStatus.mic_open);
       } else {
         setIsRoutingActive(false);
         // Restore from settings if available AND microphone still exists
@@ -69,6 +86,81 @@ export default function VbCableSettings({
     checkStatus();
   }, []);
 
+  // Poll buffer statistics while routing is active
+  useEffect(() => {
+    if (!isRoutingActive) {
+      setRoutingStats(null);
+      return;
+    }
+    const interval = setInterval(() => {
+      invoke<RoutingStats | null>("get_microphone_routing_stats")
+        .then((stats) => setRoutingStats(stats ?? null))
+        .catch(() => setRoutingStats(null));
+    }, 1000);
+    return () => clearInterval(interval);
+  }, [isRoutingActive]);
+
+  // Poll the gate state faster so the open/muted indicator follows the key
+  const gateMode = settings?.microphone_gate_mode ?? "always_on";
+  useEffect(() => {
+    if (!isRoutingActive || gateMode === "always_on") {
+      setMicOpen(true);
+      return;
+    }
+    const interval = setInterval(() => {
+      invoke<RoutingStatus | null>("get_microphone_routing_status")
+        .then((status) => setMicOpen(status?.mic_open ?? true))
+        .catch(() => setMicOpen(true));
+    }, 150);
+    return () => clearInterval(interval);
+  }, [isRoutingActive, gateMode]);
+
+  const applyGate = useCallback(
+    async (mode: MicGateMode, hotkey: string | null) => {
+      setError(null);
+      try {
+        await invoke("set_microphone_gate", { mode, hotkey });
+      } catch (e) {
+        setError(`Push-to-talk setup failed: ${e}`);
+      }
+      await reloadSettings();
+    },
+    [reloadSettings]
+  );
+
+  // Record the push-to-talk key; Escape cancels
+  useEffect(() => {
+    if (!recordingGateMode) return;
+
+    const handleKeyDown = (e: globalThis.KeyboardEvent) => {
+      e.preventDefault();
+      e.stopPropagation();
+
+      if (e.key === "Escape") {
+        setRecordingGateMode(null);
+        return;
+      }
+      const keys = keysFromKeyboardEvent(e);
+      const key = keys[keys.length - 1];
+      if (!key || MODIFIER_KEYS.includes(key)) return;
+
+      setRecordingGateMode(null);
+      applyGate(recordingGateMode, keys.join("+"));
+    };
+
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [recordingGateMode, applyGate]);
+
+  const handleGateModeChange = (mode: MicGateMode) => {
+    if (mode !== "always_on" && !settings?.microphone_gate_hotkey) {
+      setRecordingGateMode(mode);
+      return;
+    }
+    setRecordingGateMode(null);
+    applyGate(mode, settings?.microphone_gate_hotkey ?? null);
+  };
+
   // Load microphone data when VB-Cable is installed
   useEffect(() => {
     if (status?.status === "installed") {
@@ -76,6 +168,17 @@ export default function VbCableSettings({
     }
   }, [status, loadMicrophoneData]);
 
+  // Routing pauses while the microphone is unplugged and restarts when it returns
+  useEffect(() => {
+    if (status?.status !== "installed") return;
+    const unlisten = listen("audio-devices-changed", () => {
+      loadMicrophoneData();
+    });
+    return () => {
+      unlisten.then((fn: () => void) => fn());
+    };
+  }, [status, loadMicrophoneData]);
+
   const checkStatus = async () => {
     try {
       const result = await invoke<VbCableStatus>("check_vb_cable_status");
@@ -386,6 +489,202 @@ export default function VbCableSettings({
               </p>
             )}
 
+            {isRoutingActive && gateMode !== "always_on" && (
+              <p
+                className={`mt-1 text-xs ${
+                  micOpen ? "text-discord-success" : "text-discord-warning"
+                }`}
+              >
+                {micOpen ? "Mic open" : "Mic muted"}
+              </p>
+            )}
+
+            {isRoutingActive && routingStats && (
+              <p className="mt-1 text-xs text-discord-text-muted">
+                Latency {routingStats.buffered_ms.toFixed(0)} ms (target{" "}
+                {routingStats.target_latency_ms.toFixed(0)} ms) ·{" "}
+                {routingStats.underruns} underruns · {routingStats.overruns}{" "}
+                overruns
+              </p>
+            )}
+
+            {/* Routed Mix Levels */}
+            {isRoutingActive && settings && (
+              <div className="mt-3 space-y-2">
+                {(
+                  [
+                    ["microphone_routing_mic_gain_db", "Microphone level"],
+                    ["microphone_routing_board_gain_db", "Soundboard level"],
+                  ] as const
+                ).map(([key, label]) => (
+                  <div key={key}>
+                    <label className="block text-xs font-medium text-discord-text mb-1">
+                      {label}: {settings[key] > 0 ? "+" : ""}
+                      {settings[key].toFixed(1)} dB
+                    </label>
+                    <input
+                      type="range"
+                      min="-24"
+                      max="12"
+                      step="0.5"
+                      value={settings[key]}
+                      onChange={(e) =>
+                        saveSettings({
+                          ...settings,
+                          [key]: parseFloat(e.target.value),
+                        })
+                      }
+                      className="w-full"
+                      style={{ accentColor: "#5865f2" }}
+                    />
+                  </div>
+                ))}
+              </div>
+            )}
+
+            {/* Push-to-talk */}
+            {settings && (
+              <div className="mt-3 space-y-2">
+                <label className="block text-xs font-medium text-discord-text">
+                  Microphone mode
+                </label>
+                <div className="flex items-center gap-3">
+                  <select
+                    value={recordingGateMode ?? gateMode}
+                    onChange={(e) =>
+                      handleGateModeChange(e.target.value as MicGateMode)
+                    }
+                    className="flex-1 bg-discord-darker text-discord-text rounded px-3 py-2
+                             border border-discord-darker hover:border-discord-text-muted
+                             focus:border-discord-primary focus:outline-none"
+                  >
+                    <option value="always_on">Always on</option>
+                    <option value="push_to_talk">Push-to-talk</option>
+                    <option value="push_to_mute">Push-to-mute</option>
+                  </select>
+
+                  {(recordingGateMode ?? gateMode) !== "always_on" && (
+                    <button
+                      onClick={() =>
+                        setRecordingGateMode(
+                          recordingGateMode ? null : gateMode
+                        )
+                      }
+                      className="px-3 py-2 rounded text-sm bg-discord-darker
+                               hover:bg-discord-dark text-discord-text transition-colors"
+                    >
+                      {recordingGateMode
+                        ? "Press a key..."
+                        : settings.microphone_gate_hotkey
+                          ? formatHotkeyForDisplay(
+                              settings.microphone_gate_hotkey
+                            )
+                          : "Set key"}
+                    </button>
+                  )}
+                </div>
+                {(recordingGateMode ?? gateMode) !== "always_on" && (
+                  <p className="text-xs text-discord-text-muted">
+                    Only your microphone is gated; sounds keep playing.
+                  </p>
+                )}
+              </div>
+            )}
+
+            {/* Microphone Processing */}
+            {settings && (
+              <div className="mt-3 space-y-2">
+                <label className="flex items-center gap-2 cursor-pointer">
+                  <input
+                    type="checkbox"
+                    checked={settings.microphone_processing.enabled}
+                    onChange={(e) =>
+                      saveSettings({
+                        ...settings,
+                        microphone_processing: {
+                          ...settings.microphone_processing,
+                          enabled: e.target.checked,
+                        },
+                      })
+                    }
+                    className="rounded border-discord-dark bg-discord-darker
+                     text-discord-primary focus:ring-discord-primary cursor-pointer"
+                  />
+                  <span className="text-sm text-discord-text">
+                    Voice processing (noise gate, rumble filter, compressor)
+                  </span>
+                </label>
+
+                {settings.microphone_processing.enabled && (
+                  <div className="ml-6 space-y-2">
+                    {(
+                      [
+                        ["high_pass_enabled", "Rumble filter"],
+                        ["gate_enabled", "Noise gate"],
+                        ["compressor_enabled", "Compressor"],
+                      ] as const
+                    ).map(([key, label]) => (
+                      <label
+                        key={key}
+                        className="flex items-center gap-2 cursor-pointer"
+                      >
+                        <input
+                          type="checkbox"
+                          checked={settings.microphone_processing[key]}
+                          onChange={(e) =>
+                            saveSettings({
+                              ...settings,
+                              microphone_processing: {
+                                ...settings.microphone_processing,
+                                [key]: e.target.checked,
+                              },
+                            })
+                          }
+                          className="rounded border-discord-dark bg-discord-darker
+                           text-discord-primary focus:ring-discord-primary cursor-pointer"
+                        />
+                        <span className="text-xs text-discord-text">
+                          {label}
+                        </span>
+                      </label>
+                    ))}
+
+                    {(
+                      [
+                        ["gain_db", "Input gain", -24, 24],
+                        ["gate_threshold_db", "Gate threshold", -80, -10],
+                      ] as const
+                    ).map(([key, label, min, max]) => (
+                      <div key={key}>
+                        <label className="block text-xs font-medium text-discord-text mb-1">
+                          {label}:{" "}
+                          {settings.microphone_processing[key].toFixed(1)} dB
+                        </label>
+                        <input
+                          type="range"
+                          min={min}
+                          max={max}
+                          step="0.5"
+                          value={settings.microphone_processing[key]}
+                          onChange={(e) =>
+                            saveSettings({
+                              ...settings,
+                              microphone_processing: {
+                                ...settings.microphone_processing,
+                                [key]: parseFloat(e.target.value),
+                              },
+                            })
+                          }
+                          className="w-full"
+                          style={{ accentColor: "#5865f2" }}
+                        />
+                      </div>
+                    ))}
+                  </div>
+                )}
+              </div>
+            )}
+
             {/* Communication Apps Setup Tip */}
             <div className="mt-3 p-3 bg-discord-darker rounded text-xs text-discord-text-muted">
               <p className="font-medium text-discord-text mb-1">
@@ -7,6 +7,7 @@ import {
   ReactNode,
 } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { AudioDevice } from "../types";
 
 interface AudioContextType {
@@ -40,6 +41,16 @@ export function AudioProvider({ children }: { children: ReactNode }) {
     loadDevices();
   }, [refreshDevices]);
 
+  // Devices were plugged in or removed
+  useEffect(() => {
+    const unlisten = listen("audio-devices-changed", () => {
+      refreshDevices().catch(() => {});
+    });
+    return () => {
+      unlisten.then((fn: () => void) => fn());
+    };
+  }, [refreshDevices]);
+
   return (
     <AudioContext.Provider value={{ devices, refreshDevices }}>
       {children}
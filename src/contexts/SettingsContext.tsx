@@ -7,6 +7,7 @@ import {
   ReactNode,
 } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { AppSettings } from "../types";
 
 interface SettingsContextType {
@@ -57,6 +58,26 @@ export function SettingsProvider({ children }: { children: ReactNode }) {
     loadSettings();
   }, [reloadSettings]);
 
+  // Saved device IDs are remapped when devices are plugged in or removed
+  useEffect(() => {
+    const unlisten = listen("audio-devices-changed", () => {
+      reloadSettings().catch(() => {});
+    });
+    return () => {
+      unlisten.then((fn: () => void) => fn());
+    };
+  }, [reloadSettings]);
+
+  // Settings changed outside the UI (remote API, generated tokens)
+  useEffect(() => {
+    const unlisten = listen("settings-changed", () => {
+      reloadSettings().catch(() => {});
+    });
+    return () => {
+      unlisten.then((fn: () => void) => fn());
+    };
+  }, [reloadSettings]);
+
   return (
     <SettingsContext.Provider
       value={{ settings, saveSettings, reloadSettings, isLoading }}
@@ -38,6 +38,7 @@ describe("useAudioPlayback", () => {
     is_favorite: false,
     trim_start_ms: null,
     trim_end_ms: null,
+    effects: [],
     ...overrides,
   });
 
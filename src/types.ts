@@ -23,6 +23,121 @@ export interface AppSettings {
   autostart_enabled: boolean; // Enable autostart on system boot
   microphone_routing_device_id: string | null; // Microphone device ID for VB-Cable routing
   microphone_routing_enabled: boolean; // Whether microphone routing is enabled
+  microphone_routing_mic_gain_db: number; // Mic gain in the routed CABLE Input mix
+  microphone_routing_board_gain_db: number; // Soundboard gain in the routed CABLE Input mix
+  microphone_processing: MicProcessingConfig; // Gain/high-pass/gate/compressor on the routed mic
+  microphone_gate_mode: MicGateMode; // Push-to-talk / push-to-mute for the routed mic
+  microphone_gate_hotkey: string | null; // Global shortcut driving the mic gate
+  ducking: DuckingConfig; // Mic/soundboard ducking (sidechain)
+  limiter_ceiling_db: number; // Output bus limiter ceiling in dBFS (-24 - 0), default -1
+  copy_sounds_to_library: boolean; // Copy added sounds into the app's media folder
+  decode_cache_max_mb: number; // Persistent decode cache cap in MB (0 = off), default 1024
+  decode_cache_resample: boolean; // Store decodes at the monitor device rate
+  remote_api: RemoteApiConfig;
+  midi_input_port: string | null; // null = MIDI off
+  obs: ObsConfig;
+  twitch: TwitchConfig;
+  tts: TtsConfig;
+}
+
+/** Local HTTP/WebSocket remote-control API */
+export interface RemoteApiConfig {
+  enabled: boolean;
+  bind: "localhost" | "lan"; // "lan" listens on all interfaces
+  port: number; // Default 7878
+  token: string; // Generated when first enabled
+}
+
+/** Remote API server state (get_remote_api_status) */
+export interface RemoteApiStatus {
+  running: boolean;
+  address: string | null;
+  error: string | null;
+  clients: number; // Connected WebSocket event clients
+}
+
+/** What OBS does when a sound plays */
+export type ObsAction =
+  | { type: "switch_scene"; scene: string }
+  | { type: "show_source"; scene: string; source: string }; // Shown while the sound plays
+
+/** OBS WebSocket (v5) integration */
+export interface ObsConfig {
+  enabled: boolean;
+  host: string; // Default "localhost"
+  port: number; // Default 4455
+  password: string; // Empty if authentication is off
+  sound_actions: { sound_id: string; action: ObsAction }[];
+  scene_triggers: { scene: string; sound_id: string }[]; // Played when the scene goes live
+}
+
+/** OBS connection state (get_obs_status) */
+export interface ObsStatus {
+  connected: boolean;
+  error: string | null;
+  scene: string | null;
+}
+
+/** Twitch chat commands and channel-point redemptions */
+export interface TwitchConfig {
+  enabled: boolean;
+  host: string; // Default "irc.chat.twitch.tv"
+  port: number; // Default 6697 (TLS; plain only for a loopback host)
+  channel: string;
+  username: string; // Empty = anonymous (read-only)
+  oauth_token: string;
+  commands: { command: string; sound_id: string }[]; // e.g. "!airhorn"
+  redemptions: { title: string; reward_id: string; sound_id: string }[];
+  user_cooldown_secs: number; // Per-viewer, chat commands only
+  global_cooldown_secs: number; // Chat commands only
+  max_queue: number; // Sounds waiting before requests are dropped
+  kill_command: string; // Moderators: clear the queue and pause
+  resume_command: string; // Moderators: resume
+}
+
+/** Twitch chat state (get_twitch_status) */
+export interface TwitchStatus {
+  connected: boolean;
+  error: string | null;
+  paused: boolean; // Kill switch active
+  queued: number;
+  last_reward_id: string | null; // Last redeemed reward (to map it)
+}
+
+/** Text-to-speech engine */
+export interface TtsConfig {
+  program: string; // espeak-ng executable, default "espeak-ng"
+}
+
+/** Processing chain applied to the routed microphone */
+export interface MicProcessingConfig {
+  enabled: boolean; // Master switch for the whole chain
+  gain_db: number;
+  high_pass_enabled: boolean; // Remove low-frequency rumble
+  high_pass_hz: number;
+  gate_enabled: boolean; // Silence the mic below the threshold
+  gate_threshold_db: number; // Level (dBFS) at which the gate opens
+  gate_hysteresis_db: number; // Gate closes this far below the threshold
+  gate_hold_ms: number;
+  gate_release_ms: number;
+  compressor_enabled: boolean;
+  compressor_threshold_db: number;
+  compressor_ratio: number;
+  compressor_makeup_db: number;
+}
+
+/** How the routed microphone is gated */
+export type MicGateMode = "always_on" | "push_to_talk" | "push_to_mute";
+
+/** Mic/soundboard ducking configuration */
+export interface DuckingConfig {
+  mic_ducking_enabled: boolean; // Lower the mic while soundboard audio plays
+  mic_duck_depth_db: number; // Mic attenuation while ducked (negative dB)
+  board_ducking_enabled: boolean; // Lower soundboard playback while speaking
+  board_duck_depth_db: number; // Soundboard attenuation while ducked (negative dB)
+  voice_threshold_db: number; // Mic level (dBFS) that counts as speaking
+  attack_ms: number;
+  release_ms: number;
 }
 
 // ============================================================================
@@ -36,6 +151,49 @@ export interface PlaybackResult {
   stopped_playback_id: string | null;
 }
 
+/** Microphone routing buffer statistics */
+export interface RoutingStats {
+  underruns: number; // Output callbacks that ran out of mic audio
+  overruns: number; // Input callbacks that found the buffer full
+  dropped_samples: number;
+  buffered_ms: number; // Mic audio currently buffered
+  target_latency_ms: number; // Adaptive latency target
+}
+
+/** Active microphone routing state */
+export interface RoutingStatus {
+  microphone_id: string;
+  gate_mode: MicGateMode;
+  mic_open: boolean; // Whether mic audio currently reaches CABLE Input
+}
+
+/** Role of a configured audio device watched for hot-plug */
+export type DeviceRole = "monitor" | "broadcast" | "microphone";
+
+/** Change to a configured device, from the `audio-devices-changed` event */
+export type DeviceChange =
+  | { kind: "lost"; role: DeviceRole; name: string; device_id: string }
+  | { kind: "restored"; role: DeviceRole; name: string; device_id: string }
+  | {
+      kind: "moved";
+      role: DeviceRole;
+      name: string;
+      from: string;
+      to: string;
+    };
+
+/** Payload of the `audio-devices-changed` event */
+export interface DevicesChangedEvent {
+  changes: DeviceChange[];
+}
+
+/** Limiter gain reduction meter for one output bus */
+export interface BusMeter {
+  device_id: string;
+  gain_reduction_db: number; // Peak reduction since the previous reading
+  active_voices: number;
+}
+
 // ============================================================================
 // Sound Library Types
 // ============================================================================
@@ -50,8 +208,82 @@ export interface Sound {
   is_favorite: boolean;
   trim_start_ms: number | null;
   trim_end_ms: number | null;
+  effects: EffectConfig[]; // Non-destructive effect chain, applied in order
+  original?: OriginalSource | null; // Set once edits have been applied (for revert)
+  tts?: TtsSpeech | null; // Set for text-to-speech sounds (file_path is the render)
+}
+
+/** What a text-to-speech sound says */
+export interface TtsSpeech {
+  text: string;
+  voice: string; // Engine voice ID, empty = default voice
+  rate: number; // 0.5 - 3.0, 1.0 = normal speed
+}
+
+/** A voice of the TTS engine (list_tts_voices) */
+export interface TtsVoice {
+  id: string;
+  name: string;
 }
 
+/** Where the recorder captures from (device IDs as in the device lists) */
+export type RecordSource =
+  | { type: "input"; device_id: string }
+  | { type: "loopback"; device_id: string }; // What an output device plays
+
+/** Record until saved, or keep the last `seconds` (instant replay) */
+export type RecordMode = { type: "record" } | { type: "replay"; seconds: number };
+
+/** A recorder source (list_recording_sources) */
+export interface RecordingSourceInfo {
+  source: RecordSource;
+  name: string;
+}
+
+/** Running recorder session (get_recorder_status, null when idle) */
+export interface RecorderStatus {
+  source: RecordSource;
+  mode: RecordMode;
+  recorded_secs: number;
+  max_secs: number;
+  full: boolean; // Recording hit its limit, or the replay window is filled
+  failed: boolean; // Stream error (e.g. device unplugged)
+}
+
+/** Disk usage of the managed media folder (get_media_storage_usage) */
+export interface MediaUsage {
+  file_count: number;
+  total_bytes: number;
+  unreferenced_count: number; // Files no sound uses (removed by collect_unused_media)
+  unreferenced_bytes: number;
+}
+
+/** File and trim of a sound before destructive edits (mirrors Rust OriginalSource) */
+export interface OriginalSource {
+  file_path: string;
+  trim_start_ms: number | null;
+  trim_end_ms: number | null;
+}
+
+/** One effect in a sound's processing chain (mirrors Rust EffectConfig) */
+export type EffectConfig =
+  | { type: "gain"; gain_db: number }
+  | { type: "pitch_shift"; semitones: number }
+  | { type: "speed"; factor: number }
+  | { type: "low_shelf"; frequency_hz: number; gain_db: number }
+  | { type: "high_shelf"; frequency_hz: number; gain_db: number }
+  | { type: "peak"; frequency_hz: number; gain_db: number; q: number }
+  | {
+      type: "compressor";
+      threshold_db: number;
+      ratio: number;
+      attack_ms: number;
+      release_ms: number;
+      makeup_db: number;
+    }
+  | { type: "reverb"; room_size: number; damping: number; mix: number }
+  | { type: "bitcrusher"; bits: number; downsample: number };
+
 export interface Category {
   id: string;
   name: string;
@@ -75,6 +307,27 @@ export interface SoundLibrary {
 
 export interface HotkeyMapping {
   mappings: Record<string, string>; // hotkey -> sound_id
+  midi?: MidiMappings;
+}
+
+/** MIDI input that triggers a sound (channels are 0-15) */
+export type MidiTrigger =
+  | { type: "note"; channel: number; note: number }
+  | { type: "control"; channel: number; controller: number };
+
+export interface MidiBinding {
+  trigger: MidiTrigger;
+  sound_id: string;
+}
+
+export interface MidiMappings {
+  bindings: MidiBinding[];
+}
+
+/** MIDI input connection (get_midi_status) */
+export interface MidiStatus {
+  connected: string | null; // Port name
+  error: string | null;
 }
 
 // ============================================================================
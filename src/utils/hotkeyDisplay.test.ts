@@ -1,5 +1,9 @@
 import { describe, it, expect } from "vitest";
-import { formatHotkeyForDisplay, parseDisplayHotkey } from "./hotkeyDisplay";
+import {
+  formatHotkeyForDisplay,
+  keysFromKeyboardEvent,
+  parseDisplayHotkey,
+} from "./hotkeyDisplay";
 
 describe("formatHotkeyForDisplay", () => {
   describe("modifier keys", () => {
@@ -185,3 +189,42 @@ describe("parseDisplayHotkey", () => {
     });
   });
 });
+
+describe("keysFromKeyboardEvent", () => {
+  const event = (
+    key: string,
+    code: string,
+    mods: Partial<KeyboardEvent> = {}
+  ) => ({
+    key,
+    code,
+    ctrlKey: false,
+    shiftKey: false,
+    altKey: false,
+    metaKey: false,
+    ...mods,
+  });
+
+  it("should put modifiers before the key", () => {
+    expect(
+      keysFromKeyboardEvent(
+        event("k", "KeyK", { ctrlKey: true, shiftKey: true })
+      )
+    ).toEqual(["Ctrl", "Shift", "k"]);
+  });
+
+  it("should return only modifiers while a modifier is pressed", () => {
+    expect(
+      keysFromKeyboardEvent(event("Control", "ControlLeft", { ctrlKey: true }))
+    ).toEqual(["Ctrl"]);
+  });
+
+  it("should distinguish NumPad digits from the number row", () => {
+    expect(keysFromKeyboardEvent(event("1", "Numpad1"))).toEqual(["NumPad1"]);
+    expect(keysFromKeyboardEvent(event("1", "Digit1"))).toEqual(["1"]);
+  });
+
+  it("should name the space bar Space", () => {
+    expect(keysFromKeyboardEvent(event(" ", "Space"))).toEqual(["Space"]);
+  });
+});
@@ -213,3 +213,56 @@ export function parseDisplayHotkey(displayHotkey: string): string {
     })
     .join("+");
 }
+
+/** Numpad key codes mapped to the hotkey names used by the backend */
+const NUMPAD_KEYS: Record<string, string> = {
+  Numpad0: "NumPad0",
+  Numpad1: "NumPad1",
+  Numpad2: "NumPad2",
+  Numpad3: "NumPad3",
+  Numpad4: "NumPad4",
+  Numpad5: "NumPad5",
+  Numpad6: "NumPad6",
+  Numpad7: "NumPad7",
+  Numpad8: "NumPad8",
+  Numpad9: "NumPad9",
+  NumpadDecimal: "NumPadDecimal",
+  NumpadEnter: "NumPadEnter",
+  NumpadAdd: "NumPadAdd",
+  NumpadSubtract: "NumPadSubtract",
+  NumpadMultiply: "NumPadMultiply",
+  NumpadDivide: "NumPadDivide",
+};
+
+/**
+ * Convert a key event to hotkey parts (modifiers first), e.g. ["Ctrl", "Shift", "F1"]
+ *
+ * While only modifiers are held the result contains just the modifiers.
+ */
+export function keysFromKeyboardEvent(
+  e: Pick<
+    KeyboardEvent,
+    "key" | "code" | "ctrlKey" | "shiftKey" | "altKey" | "metaKey"
+  >
+): string[] {
+  const keys: string[] = [];
+
+  // Add modifiers
+  if (e.ctrlKey) keys.push("Ctrl");
+  if (e.shiftKey) keys.push("Shift");
+  if (e.altKey) keys.push("Alt");
+  if (e.metaKey) keys.push("Super");
+
+  // Add actual key if it's not a modifier
+  if (!["Control", "Shift", "Alt", "Meta"].includes(e.key)) {
+    // Use e.code for NumPad keys so they differ from the main number row
+    const keyMap: Record<string, string> = {
+      Control: "Ctrl",
+      Meta: "Super",
+      " ": "Space",
+    };
+    keys.push(NUMPAD_KEYS[e.code] ?? keyMap[e.key] ?? e.key);
+  }
+
+  return keys;
+}